@@ -3,6 +3,9 @@
 /**
  * 从 Adoptium GitHub Releases 查询最新 Java 版本，
  * 生成简化的 config/java_versions.toml（LTS + 最新非 LTS）
+ *
+ * 每个资产的 SHA-256 写入 `[versions.checksums]`(取 GitHub 资产的 digest,
+ * 没有时读同名 `.sha256.txt`);任何资产拿不到校验和都不写文件,直接失败。
  */
 
 const https = require('https');
@@ -37,6 +40,41 @@ function fetchJSON(url) {
   });
 }
 
+/** 取文本,跟随 GitHub 下载地址的重定向。 */
+function fetchText(url, redirects = 5) {
+  return new Promise((resolve, reject) => {
+    https.get(url, { headers: { 'User-Agent': 'fnva-ci' } }, (res) => {
+      if (res.statusCode >= 300 && res.statusCode < 400 && res.headers.location && redirects > 0) {
+        res.resume();
+        resolve(fetchText(new URL(res.headers.location, url).toString(), redirects - 1));
+        return;
+      }
+      if (res.statusCode !== 200) {
+        res.resume();
+        reject(new Error(`HTTP ${res.statusCode} for ${url}`));
+        return;
+      }
+      let data = '';
+      res.on('data', (chunk) => data += chunk);
+      res.on('end', () => resolve(data));
+    }).on('error', reject);
+  });
+}
+
+/**
+ * 资产的 SHA-256(小写十六进制)。GitHub API 的 `digest` 形如 "sha256:<hex>";
+ * 老的 release 没有 digest,退回 release 里同名的 `.sha256.txt`("<hex>  <文件名>")。
+ */
+async function assetChecksum(release, asset) {
+  const digest = (asset.digest || '').match(/^sha256:([0-9a-f]{64})$/i);
+  if (digest) return digest[1].toLowerCase();
+  const sidecar = release.assets.find((a) => a.name === `${asset.name}.sha256.txt`);
+  if (!sidecar) throw new Error(`no checksum published for ${asset.name}`);
+  const hex = (await fetchText(sidecar.browser_download_url)).trim().split(/\s+/)[0];
+  if (!/^[0-9a-f]{64}$/i.test(hex)) throw new Error(`malformed checksum for ${asset.name}`);
+  return hex.toLowerCase();
+}
+
 function parseVersion(tagName) {
   // jdk-21.0.10+7 -> { version: "21.0.10", tag: "jdk-21.0.10+7" }
   // jdk8u482-b08 -> { version: "8u482b08", tag: "jdk8u482-b08" }
@@ -87,21 +125,31 @@ async function getLatestVersion(repo, major) {
     if (!parsed) continue;
 
     const assets = {};
+    const files = {};
     for (const asset of release.assets) {
       for (const p of PLATFORMS) {
         if (p.pattern.test(asset.name) && (asset.name.endsWith('.zip') || asset.name.endsWith('.tar.gz'))) {
           assets[p.key] = asset.name;
+          files[p.key] = asset;
         }
       }
     }
     if (Object.keys(assets).length < 3) continue;
 
-    const candidate = { ...parsed, major, assets };
+    const candidate = { ...parsed, major, assets, release, files };
     if (!best || compareVersionKeys(versionKey(candidate.version), versionKey(best.version)) > 0) {
       best = candidate;
     }
   }
-  return best;
+  if (!best) return null;
+
+  // 只为最终选中的 release 取校验和
+  const { release, files, ...entry } = best;
+  entry.checksums = {};
+  for (const [key, asset] of Object.entries(files)) {
+    entry.checksums[key] = await assetChecksum(release, asset);
+  }
+  return entry;
 }
 
 function toToml(entry) {
@@ -116,6 +164,10 @@ function toToml(entry) {
   for (const [key, name] of Object.entries(entry.assets)) {
     lines.push(`${key} = "${name}"`);
   }
+  lines.push(`[versions.checksums]`);
+  for (const key of Object.keys(entry.assets)) {
+    lines.push(`${key} = "${entry.checksums[key]}"`);
+  }
   return lines.join('\n');
 }
 
@@ -185,6 +237,7 @@ async function main() {
   console.log('🔍 查询 Adoptium 最新版本...');
 
   const entries = [];
+  let failed = false;
   for (const { repo, major, lts } of REPOS) {
     console.log(`  检查 ${repo}...`);
     try {
@@ -197,9 +250,16 @@ async function main() {
       }
     } catch (e) {
       console.log(`  ❌ Java ${major}: ${e.message}`);
+      failed = true;
     }
   }
 
+  // 少了某个版本或校验和时不覆盖注册表,避免发布没有校验和的条目
+  if (failed) {
+    console.error('\n❌ 部分版本查询失败,未更新注册表');
+    process.exit(1);
+  }
+
   entries.sort((a, b) => b.major - a.major);
 
   const toml = entries.map(toToml).join('\n\n') + '\n';
@@ -1,5 +1,5 @@
 use crate::core::environment_manager::EnvironmentType;
-use crate::infrastructure::shell::ShellType;
+use crate::core::presentation::OutputFormat;
 use clap::{Command, CommandFactory, Parser, Subcommand};
 
 /// fnva CLI application
@@ -8,6 +8,18 @@ use clap::{Command, CommandFactory, Parser, Subcommand};
 #[command(about = "Cross-platform environment switcher for Java / Maven / Claude Code", long_about = None)]
 #[command(version = env!("CARGO_PKG_VERSION"))]
 pub struct Cli {
+    /// Use this directory instead of ~/.fnva for config, state, cache and packages
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config_dir: Option<std::path::PathBuf>,
+
+    /// Diagnostic log level written to stderr (e.g. debug, trace; overrides FNVA_LOG)
+    #[arg(long, global = true, value_name = "LEVEL")]
+    pub log_level: Option<String>,
+
+    /// Never touch the network (also FNVA_OFFLINE=1); version lists come from the local registry
+    #[arg(long, global = true)]
+    pub offline: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -52,6 +64,8 @@ pub enum Commands {
         /// Shell type (bash/zsh/fish/powershell/cmd, auto-detected if omitted)
         #[arg(short, long)]
         shell: Option<String>,
+        #[command(subcommand)]
+        action: Option<EnvCommands>,
     },
     /// Manage configuration
     Config {
@@ -60,6 +74,8 @@ pub enum Commands {
     },
     /// Environment history
     History {
+        #[command(subcommand)]
+        action: Option<HistoryCommands>,
         /// Environment type
         #[arg(short, long)]
         env_type: Option<String>,
@@ -69,12 +85,54 @@ pub enum Commands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        /// Output format (text/json/table)
+        #[arg(long, conflicts_with = "json")]
+        format: Option<String>,
+        /// Show times in UTC instead of the local time zone
+        #[arg(long)]
+        utc: bool,
+    },
+    /// Print what fnva has set in this shell
+    Export {
+        #[command(subcommand)]
+        action: ExportCommands,
     },
     /// Diagnose installation and shell-integration issues
     Doctor {
         /// Also probe mirror reachability (uses the network)
         #[arg(long)]
         network: bool,
+        /// Reuse the last mirror probe if it is less than 5 minutes old
+        #[arg(long, requires = "network")]
+        cached: bool,
+    },
+    /// Commands about fnva itself
+    #[command(name = "self")]
+    SelfCmd {
+        #[command(subcommand)]
+        action: SelfCommands,
+    },
+}
+
+/// fnva export commands
+#[derive(Subcommand)]
+pub enum ExportCommands {
+    /// List FNVA_*, JAVA_HOME, ANTHROPIC_* etc. and compare them with the session state
+    ShellEnv {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// fnva self commands
+#[derive(Subcommand)]
+pub enum SelfCommands {
+    /// Check GitHub releases for a newer fnva (reports only, never downloads)
+    UpdateCheck {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
 }
 
@@ -86,17 +144,45 @@ pub enum JavaCommands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        /// Output format (text/json/table)
+        #[arg(long, conflicts_with = "json")]
+        format: Option<String>,
     },
     /// Switch to a Java environment
     Use {
-        /// Environment name
-        name: String,
+        /// Environment name, or `-` for the previous one (defaults to the project's
+        /// .java-version / .tool-versions, else asks in a terminal)
+        name: Option<String>,
         /// Shell type
         #[arg(short, long)]
         shell: Option<String>,
         /// Output format
         #[arg(long)]
         json: bool,
+        /// Warn when the target is a JRE (no javac)
+        #[arg(long)]
+        strict: bool,
+        /// Print only the environment's bin directory (for PATH prepending), don't switch
+        #[arg(long, conflicts_with_all = ["json", "shell"])]
+        print_path: bool,
+        /// Apply to this shell only; don't record it as current or in history
+        #[arg(long)]
+        temporary: bool,
+        /// Install the version first when no environment has this name
+        #[arg(long, requires = "name")]
+        create_if_missing: bool,
+        /// Don't print the "run eval ..." hint when run directly in a terminal
+        #[arg(long)]
+        no_hints: bool,
+        /// Print the environment names you can pick from, one per line, and exit
+        #[arg(long, conflicts_with = "name")]
+        list_only: bool,
+        /// Only record the environment as current (state and history); print no script
+        #[arg(long, conflicts_with_all = ["temporary", "print_path", "json", "list_only"])]
+        record_only: bool,
+        /// Only prepend the new bin directory; leave existing Java entries in PATH
+        #[arg(long, alias = "append-path", conflicts_with_all = ["print_path", "record_only", "list_only"])]
+        no_clean: bool,
     },
     /// Scan the system for Java installations
     Scan {
@@ -104,6 +190,11 @@ pub enum JavaCommands {
         #[arg(long)]
         path: Vec<String>,
     },
+    /// Manage extra directories searched by `java scan`
+    ScanPath {
+        #[command(subcommand)]
+        action: ScanPathCommands,
+    },
     /// Add a Java environment
     Add {
         /// Environment name
@@ -121,30 +212,178 @@ pub enum JavaCommands {
         /// Environment name
         name: String,
     },
+    /// Remove scanned environments whose JAVA_HOME no longer exists
+    Gc {
+        /// Only report what would be removed
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Copy a Java environment under a new name, optionally overriding fields
+    Copy {
+        /// Source environment name
+        from: String,
+        /// New environment name
+        to: String,
+        /// Override JAVA_HOME path
+        #[arg(long)]
+        home: Option<String>,
+        /// Override description
+        #[arg(short = 'd', long)]
+        description: Option<String>,
+    },
+    /// Show details of one Java environment (version, vendor, validity, size)
+    Info {
+        /// Environment name
+        name: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Write java/javac/jar wrapper scripts into DIR that always run the active JDK
+    InstallShims {
+        /// Directory to put on PATH
+        dir: String,
+    },
+    /// Print the path of a JDK tool in the active (else default) Java environment
+    Which {
+        /// Tool name
+        #[arg(default_value = "java")]
+        tool: String,
+    },
+    /// Compare JVM startup time (`java -version`) across environments
+    Benchmark {
+        /// Environment names (all environments when omitted)
+        names: Vec<String>,
+        /// Timed runs per environment
+        #[arg(long, default_value_t = 5)]
+        runs: usize,
+        /// Untimed warmup runs per environment
+        #[arg(long, default_value_t = 1)]
+        warmup: usize,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Set JVM options exported as JAVA_OPTS when switching to this environment
+    Set {
+        /// Environment name
+        name: String,
+        /// Set JAVA_OPTS (e.g. --java-opts "-Xmx2g -Dfile.encoding=UTF-8")
+        #[arg(long, allow_hyphen_values = true)]
+        java_opts: Option<String>,
+        /// Clear JAVA_OPTS
+        #[arg(long, conflicts_with = "java_opts")]
+        unset_java_opts: bool,
+        /// Extra directory prepended to PATH after bin (repeatable; replaces the list)
+        #[arg(long = "extra-path", value_name = "DIR")]
+        extra_paths: Vec<String>,
+        /// Clear the extra PATH directories
+        #[arg(long, conflicts_with = "extra_paths")]
+        clear_extra_paths: bool,
+        /// Set the description
+        #[arg(long)]
+        description: Option<String>,
+    },
+    /// Write JAVA_HOME / JAVA_OPTS as a dotenv file (stdout when no path; PATH is not included)
+    EnvFile {
+        /// Java environment name
+        name: String,
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+    },
     /// Query available remote versions
     LsRemote {
+        /// Version to inspect with --platforms (e.g. 21, 21.0.4+7, lts)
+        #[arg(value_name = "VERSION", requires = "platforms")]
+        spec: Option<String>,
         /// Major version filter
         #[arg(long, short = 'v')]
         version: Option<u32>,
         /// Show all versions (default: first 30)
         #[arg(long)]
         all: bool,
+        /// Only show LTS releases
+        #[arg(long)]
+        lts_only: bool,
+        /// List every platform VERSION is published for, with URLs and checksums
+        #[arg(long, requires = "spec")]
+        platforms: bool,
+        /// Output as JSON (all matches, with download URLs and checksums)
+        #[arg(long)]
+        json: bool,
     },
     /// Refresh the remote version cache
     Refresh,
+    /// Manage the local Java version registry
+    Registry {
+        #[command(subcommand)]
+        action: RegistryCommands,
+    },
     /// Install a Java version
     Install {
-        /// Java version
-        version: String,
+        /// Java version (defaults to the newest LTS with --lts-only)
+        #[arg(required_unless_present_any = ["lts_only", "from_archive"])]
+        version: Option<String>,
+        /// Install a local JDK archive (tar.gz/tar.xz/zip) offline; requires --name
+        #[arg(long, value_name = "PATH", requires = "name", conflicts_with_all = ["version", "lts_only", "keep_archive"])]
+        from_archive: Option<String>,
+        /// Environment name for --from-archive
+        #[arg(long, requires = "from_archive")]
+        name: Option<String>,
         /// Auto-switch after install
         #[arg(long)]
         auto_switch: bool,
+        /// Only accept LTS releases
+        #[arg(long)]
+        lts_only: bool,
+        /// Keep the verified archive and its .sha256 in ~/.fnva/cache/downloads for reinstalls (removed after install by default)
+        #[arg(long)]
+        keep_archive: bool,
+        /// Add shell integration to your shell rc if it's missing
+        #[arg(long)]
+        install_hook: bool,
+        /// INSECURE: skip SHA-256 and signature checks of the download (for mirrors
+        /// without published checksums). A corrupted or tampered JDK would be
+        /// installed undetected; the extracted JDK layout is still validated
+        #[arg(long)]
+        no_verify: bool,
+        /// Print the resolved version and the download URLs that would be tried,
+        /// in order, without downloading
+        #[arg(long, conflicts_with = "from_archive")]
+        explain: bool,
+        /// Name the environment `jdk<major>` and upgrade it in place when a newer
+        /// patch of that major is installed later
+        #[arg(long, conflicts_with_all = ["from_archive", "explain"])]
+        major_only: bool,
+        /// Connect/read timeout in seconds for this run (overrides download.*_timeout_sec)
+        #[arg(long, value_name = "SEC")]
+        timeout: Option<u64>,
+        /// Retries per download for this run (overrides download.retry_count, max 20)
+        #[arg(long, value_name = "N")]
+        retries: Option<u32>,
+    },
+    /// Print a manifest of fnva-installed JDKs (or apply one with `manifest apply`)
+    Manifest {
+        #[command(subcommand)]
+        action: Option<ManifestCommands>,
+        /// Write the manifest to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
     },
     /// Uninstall a Java version
     Uninstall {
         /// Java environment name
         name: String,
     },
+    /// Re-download an fnva-installed JDK under the same name (e.g. after corruption)
+    Reinstall {
+        /// Java environment name
+        name: String,
+        /// Download only from this mirror (a name from [[mirrors.java]])
+        #[arg(long)]
+        source: Option<String>,
+    },
     /// Set or show the default Java environment
     Default {
         /// Java environment name (shows current default when omitted)
@@ -164,6 +403,12 @@ pub enum JavaCommands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        /// Print only the environment name (exit 1 when none is active)
+        #[arg(short, long, visible_alias = "name-only", conflicts_with = "json")]
+        quiet: bool,
+        /// Re-emit the current environment's switch script for this shell
+        #[arg(short, long, conflicts_with_all = ["json", "quiet"])]
+        shell: Option<String>,
     },
 }
 
@@ -175,6 +420,9 @@ pub enum MavenCommands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        /// Output format (text/json/table)
+        #[arg(long, conflicts_with = "json")]
+        format: Option<String>,
     },
     /// Switch to a Maven environment
     Use {
@@ -186,6 +434,9 @@ pub enum MavenCommands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        /// Apply to this shell only; don't record it as current or in history
+        #[arg(long)]
+        temporary: bool,
     },
     /// Scan the system for Maven installations
     Scan {
@@ -226,6 +477,9 @@ pub enum MavenCommands {
         /// Auto-switch to the environment after install
         #[arg(long)]
         auto_switch: bool,
+        /// Keep the verified archive and its .sha256 in ~/.fnva/cache/downloads for reinstalls (removed after install by default)
+        #[arg(long)]
+        keep_archive: bool,
     },
     /// Uninstall a Maven version
     Uninstall {
@@ -248,6 +502,9 @@ pub enum MavenCommands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        /// Re-emit the current environment's switch script for this shell
+        #[arg(short, long, conflicts_with = "json")]
+        shell: Option<String>,
     },
     /// Set or show the default Maven environment
     Default {
@@ -301,6 +558,33 @@ pub enum CcCommands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        /// Output format (text/json/table)
+        #[arg(long, conflicts_with = "json")]
+        format: Option<String>,
+        /// Only environments of this provider (config `provider`, else the base_url domain)
+        #[arg(long)]
+        provider: Option<String>,
+        /// Only environments with a model containing this text (case-insensitive)
+        #[arg(long)]
+        model_contains: Option<String>,
+    },
+    /// List built-in provider presets (base_url and models)
+    #[command(visible_alias = "ls-remote")]
+    Presets {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Add a CC environment from a built-in provider preset
+    AddPreset {
+        /// Preset name (see `fnva cc presets`)
+        preset: String,
+        /// Environment name (defaults to `<preset>-cc`)
+        #[arg(short, long)]
+        name: Option<String>,
+        /// API Key (prompted for when omitted on a terminal; else `${<PROVIDER>_API_KEY}`)
+        #[arg(short = 'k', long)]
+        api_key: Option<String>,
     },
     /// Scan the system for CC environments
     Scan {
@@ -315,9 +599,15 @@ pub enum CcCommands {
         /// Shell type
         #[arg(short, long)]
         shell: Option<String>,
-        /// Output as JSON
+        /// Output as JSON (script plus the resolved environment, secrets masked in the environment)
         #[arg(long)]
         json: bool,
+        /// Show secrets unmasked in the JSON environment
+        #[arg(long, requires = "json")]
+        reveal: bool,
+        /// Apply to this shell only; don't record it as current or in history
+        #[arg(long)]
+        temporary: bool,
     },
     /// Add a CC environment
     Add {
@@ -342,6 +632,51 @@ pub enum CcCommands {
         /// Environment name
         name: String,
     },
+    /// Copy a CC environment under a new name, optionally overriding fields
+    Copy {
+        /// Source environment name
+        from: String,
+        /// New environment name
+        to: String,
+        /// Override API Key
+        #[arg(short = 'k', long)]
+        api_key: Option<String>,
+        /// Override Base URL
+        #[arg(short = 'u', long)]
+        base_url: Option<String>,
+        /// Override model name
+        #[arg(short, long)]
+        model: Option<String>,
+        /// Override description
+        #[arg(short = 'd', long)]
+        description: Option<String>,
+    },
+    /// List the models the environment's provider offers (GET {base_url}/v1/models)
+    Models {
+        /// CC environment name
+        name: String,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Set a single field of an existing CC environment
+    Set {
+        /// Environment name
+        name: String,
+        /// Field: base_url, model, opus_model, haiku_model, api_key, description, provider
+        field: String,
+        /// New value (empty clears opus_model/haiku_model/provider)
+        #[arg(allow_hyphen_values = true)]
+        value: String,
+    },
+    /// Write the environment's variables as a dotenv file (stdout when no path)
+    EnvFile {
+        /// CC environment name
+        name: String,
+        /// Output file path
+        #[arg(short, long)]
+        output: Option<String>,
+    },
     /// Set or show the default CC environment
     Default {
         /// CC environment name (shows current default when omitted)
@@ -356,11 +691,110 @@ pub enum CcCommands {
         #[arg(long)]
         json: bool,
     },
+    /// Print the switch script for the default CC environment (empty when none is set)
+    SwitchDefault {
+        /// Shell type
+        #[arg(short, long)]
+        shell: String,
+    },
     /// Show the current CC environment
     Current {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+        /// Re-emit the current environment's switch script for this shell
+        #[arg(short, long, conflicts_with = "json")]
+        shell: Option<String>,
+        /// Send an authenticated request to the provider to confirm the key works
+        #[arg(long, conflicts_with = "shell")]
+        check: bool,
+    },
+}
+
+/// Java version registry commands
+#[derive(Subcommand)]
+pub enum RegistryCommands {
+    /// Download the registry (versions + checksums) into the local registry file
+    Update {
+        /// Registry URL (defaults to `java_registry_url` in config)
+        #[arg(long)]
+        url: Option<String>,
+    },
+}
+
+/// Switch history commands
+#[derive(Subcommand)]
+pub enum HistoryCommands {
+    /// Delete recorded switches (all, or one environment type)
+    Clear {
+        /// Environment type to clear (java/maven/cc)
+        #[arg(short, long, visible_alias = "type")]
+        env_type: Option<String>,
+    },
+}
+
+/// Java scan path commands
+#[derive(Subcommand)]
+pub enum ScanPathCommands {
+    /// Add a directory to search (persisted in config)
+    Add {
+        /// Directory containing JDKs (e.g. a network share)
+        path: String,
+    },
+    /// Remove a directory from the search list
+    Remove {
+        /// Directory to remove
+        path: String,
+    },
+    /// List the configured scan directories
+    List,
+}
+
+/// Java manifest commands
+#[derive(Subcommand)]
+pub enum ManifestCommands {
+    /// Install every JDK listed in a manifest, skipping ones already installed
+    Apply {
+        /// Manifest file (TOML)
+        file: String,
+    },
+}
+
+/// Shell environment commands
+#[derive(Subcommand)]
+pub enum EnvCommands {
+    /// List managed environments of every type (or just one with --env-type)
+    List {
+        /// Environment type (java/maven/cc); all types when omitted
+        #[arg(short, long)]
+        env_type: Option<String>,
+        /// Output format (text/json/table/tsv)
+        #[arg(long)]
+        format: Option<String>,
+    },
+    /// Save an environment's switch script to a file that can be sourced without fnva
+    ExportScript {
+        /// Environment type (java/maven/cc)
+        env_type: String,
+        /// Environment name
+        name: String,
+        /// Output file
+        file: String,
+        /// Shell type (bash/zsh/fish/powershell/cmd, auto-detected if omitted)
+        #[arg(short, long)]
+        shell: Option<String>,
+        /// Write the current values of ${VAR} secrets instead of leaving the references
+        #[arg(long)]
+        resolve_secrets: bool,
+    },
+    /// Emit one script switching to every configured default (Java, CC, Maven)
+    Switch {
+        /// Apply every configured default (the only mode; may be omitted)
+        #[arg(long)]
+        all_defaults: bool,
+        /// Shell type (bash/zsh/fish/powershell/cmd, auto-detected if omitted)
+        #[arg(short, long)]
+        shell: Option<String>,
     },
 }
 
@@ -369,6 +803,43 @@ pub enum CcCommands {
 pub enum ConfigCommands {
     /// Complete and sync the configuration file
     Sync,
+    /// Print where fnva keeps its config, state and packages
+    Path {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print the value of a setting (dotted key, e.g. download.retry_count)
+    Get {
+        /// Setting key
+        key: String,
+    },
+    /// Change a setting (empty value clears optional settings)
+    Set {
+        /// Setting key
+        key: String,
+        /// New value
+        #[arg(allow_hyphen_values = true)]
+        value: String,
+    },
+    /// List all settable keys with their current values
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Check the configuration for dangling defaults, unknown hook types and duplicate names
+    Validate {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Normalize Java paths, drop duplicate entries and stale removed names, then rewrite the file
+    Compact {
+        /// Only report what would change
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 /// 解析环境类型字符串
@@ -383,16 +854,23 @@ pub fn parse_environment_type(env_type_str: &str) -> Result<EnvironmentType, Str
     }
 }
 
-/// 解析 Shell 类型字符串
-pub fn parse_shell_type(shell_str: &str) -> Result<ShellType, String> {
-    match shell_str.to_lowercase().as_str() {
-        "bash" => Ok(ShellType::Bash),
-        "zsh" => Ok(ShellType::Zsh),
-        "fish" => Ok(ShellType::Fish),
-        "powershell" | "ps1" => Ok(ShellType::PowerShell),
-        "cmd" => Ok(ShellType::Cmd),
+/// 解析输出格式字符串
+pub fn parse_output_format(format_str: &str) -> Result<OutputFormat, String> {
+    match format_str.to_lowercase().as_str() {
+        "text" => Ok(OutputFormat::Text),
+        "json" => Ok(OutputFormat::Json),
+        "table" => Ok(OutputFormat::Table),
         _ => Err(format!(
-            "Unknown shell '{shell_str}'. Valid: bash, zsh, fish, powershell, cmd"
+            "Unknown format '{format_str}'. Valid: text, json, table"
         )),
     }
 }
+
+/// 合并 `--json` 与 `--format`:显式 `--format` 优先,否则按 `--json` 选 Json/Text。
+pub fn resolve_output_format(json: bool, format: Option<&str>) -> Result<OutputFormat, String> {
+    match format {
+        Some(f) => parse_output_format(f),
+        None if json => Ok(OutputFormat::Json),
+        None => Ok(OutputFormat::Text),
+    }
+}
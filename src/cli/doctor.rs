@@ -1,15 +1,14 @@
 //! `fnva doctor` —— 环境自检,定位新用户最常见的安装 / 集成问题。
 //!
-//! 逐项检查配置可读、数据目录可写、shell 检测、shell 集成是否就位、
-//! fnva 是否在 PATH,以及(可选)镜像连通性。每项打印 ✓/✗ 并给修复建议,
+//! 逐项检查配置可读且自洽、数据目录可写、shell 检测、shell 集成是否就位、
+//! fnva 是否在 PATH,以及(可选)镜像连通性(`--cached` 复用 5 分钟内的探测结果)。每项打印 ✓/✗ 并给修复建议,
 //! 最后汇总;任一失败则整体返回失败(由调用方转成非零退出码)。
 
-use crate::cli::print;
+use crate::cli::{integration, print};
 use crate::infrastructure::config::Config;
 use crate::infrastructure::paths;
+use crate::infrastructure::remote::network_cache;
 use crate::infrastructure::shell::platform::detect_shell;
-use crate::infrastructure::shell::ShellType;
-use std::path::PathBuf;
 
 /// 检查结果计数。
 #[derive(Default)]
@@ -32,23 +31,32 @@ impl Outcome {
 }
 
 /// 运行全部自检。返回 `true` 表示全部通过(或仅有 skipped)。
-pub async fn run_doctor(network: bool) -> Result<bool, String> {
+pub async fn run_doctor(network: bool, cached: bool) -> Result<bool, String> {
     let mut out = Outcome::default();
 
-    println!("{}\n", print::bold("fnva doctor — environment self-check"));
+    println!(
+        "{}\n",
+        print::bold(&format!(
+            "fnva doctor {} environment self-check",
+            print::glyph("—", "-")
+        ))
+    );
 
     check_config(&mut out);
     check_data_dir(&mut out);
     check_shell(&mut out);
     check_path(&mut out);
-    check_network(network, &mut out).await;
+    check_partial_downloads(&mut out);
+    check_network(network, cached, &mut out).await;
 
     println!();
     let ok = out.failed == 0;
     if ok {
         print::success(&format!(
-            "{} passed, {} skipped — all checks OK",
-            out.passed, out.skipped
+            "{} passed, {} skipped {} all checks OK",
+            out.passed,
+            out.skipped,
+            print::glyph("—", "-")
         ));
     } else {
         print::failure(
@@ -72,9 +80,20 @@ fn check_config(out: &mut Outcome) {
         return;
     };
     match Config::load() {
-        Ok(_) => {
+        Ok(config) => {
             print::success(&format!("Config readable: {}", path.display()));
             out.pass();
+            let warnings = config.validate_semantics();
+            if warnings.is_empty() {
+                print::success("Config consistent");
+                out.pass();
+            } else {
+                // 只提示,不计为失败;需要非零退出码时用 `fnva config validate`
+                for warning in &warnings {
+                    print::warn(&format!("Config: {warning}"));
+                }
+                out.skip();
+            }
         }
         Err(e) => {
             print::failure(&format!("Config unreadable: {}", path.display()), Some(&e));
@@ -110,25 +129,38 @@ fn check_data_dir(out: &mut Outcome) {
 
 fn check_shell(out: &mut Outcome) {
     let shell = detect_shell();
-    print::success(&format!("Shell detected: {shell:?}"));
+    print::success(&format!("Shell detected: {shell}"));
     out.pass();
 
-    let candidates = shell_rc_candidates(&shell);
+    let candidates = integration::shell_rc_candidates(&shell);
     if candidates.is_empty() {
         print::warn(&format!(
-            "No rc profile known for {shell:?} — skipping integration check"
+            "No rc profile known for {shell} {} skipping integration check",
+            print::glyph("—", "-")
         ));
         out.skip();
         return;
     }
-    for rc in &candidates {
-        if let Ok(content) = std::fs::read_to_string(rc) {
-            if content.contains("fnva") {
-                print::success(&format!("Shell integration found: {}", rc.display()));
-                out.pass();
-                return;
-            }
-        }
+    if let Some(stale) = integration::find_stale_integration(&candidates) {
+        print::failure(
+            &format!(
+                "Shell integration in {} is from fnva {} (current {})",
+                stale.rc.display(),
+                stale.version,
+                env!("CARGO_PKG_VERSION")
+            ),
+            Some(&format!(
+                "The pasted script may not match this version's output; {}",
+                integration::refresh_hint(&shell)
+            )),
+        );
+        out.fail();
+        return;
+    }
+    if let Some(rc) = integration::find_integration(&candidates) {
+        print::success(&format!("Shell integration found: {}", rc.display()));
+        out.pass();
+        return;
     }
     let primary = candidates[0].display();
     print::failure(
@@ -141,36 +173,6 @@ fn check_shell(out: &mut Outcome) {
     out.fail();
 }
 
-/// 返回当前 shell 可能加载的 rc / profile 候选路径(存在多个时逐个检查)。
-fn shell_rc_candidates(shell: &ShellType) -> Vec<PathBuf> {
-    let Some(home) = dirs::home_dir() else {
-        return Vec::new();
-    };
-    match shell {
-        ShellType::Bash => vec![home.join(".bashrc"), home.join(".bash_profile")],
-        ShellType::Zsh => vec![home.join(".zshrc"), home.join(".zprofile")],
-        ShellType::Fish => vec![home.join(".config").join("fish").join("config.fish")],
-        ShellType::PowerShell => {
-            if cfg!(target_os = "windows") {
-                vec![
-                    home.join("Documents")
-                        .join("PowerShell")
-                        .join("Microsoft.PowerShell_profile.ps1"),
-                    home.join("Documents")
-                        .join("WindowsPowerShell")
-                        .join("Microsoft.PowerShell_profile.ps1"),
-                ]
-            } else {
-                vec![home
-                    .join(".config")
-                    .join("powershell")
-                    .join("Microsoft.PowerShell_profile.ps1")]
-            }
-        }
-        ShellType::Cmd | ShellType::Unknown => Vec::new(),
-    }
-}
-
 fn check_path(out: &mut Outcome) {
     match which::which("fnva") {
         Ok(p) => {
@@ -187,12 +189,53 @@ fn check_path(out: &mut Outcome) {
     }
 }
 
-async fn check_network(do_check: bool, out: &mut Outcome) {
+/// 列出留有 `*.fnva-part.json` 记录的未完成下载;不算失败,下次安装会续传。
+fn check_partial_downloads(out: &mut Outcome) {
+    use crate::infrastructure::remote::partial;
+    let Ok(dir) = paths::downloads_dir() else {
+        out.skip();
+        return;
+    };
+    let parts = partial::list(&dir);
+    if parts.is_empty() {
+        print::success("No interrupted downloads");
+        out.pass();
+        return;
+    }
+    print::warn(&format!(
+        "{} interrupted download(s) in {} (resumed on the next install; delete them to start over)",
+        parts.len(),
+        dir.display()
+    ));
+    for (archive, state) in &parts {
+        let name = archive.file_name().unwrap_or_default().to_string_lossy();
+        let on_disk = std::fs::metadata(partial::partial_file(archive))
+            .map(|m| m.len())
+            .unwrap_or(0);
+        print::detail(
+            &name,
+            &format!(
+                "{} / {} bytes from {}",
+                on_disk.max(state.bytes_written),
+                state.expected_size,
+                state.url
+            ),
+        );
+    }
+    out.skip();
+}
+
+async fn check_network(do_check: bool, cached: bool, out: &mut Outcome) {
     if !do_check {
         print::warn("Mirror reachability: skipped (pass --network to check)");
         out.skip();
         return;
     }
+    if crate::infrastructure::remote::offline::is_offline() {
+        print::warn("Mirror reachability: skipped (offline mode)");
+        out.skip();
+        return;
+    }
     let Some(config) = Config::load().ok() else {
         print::warn("Mirror reachability: skipped (config unreadable)");
         out.skip();
@@ -212,28 +255,41 @@ async fn check_network(do_check: bool, out: &mut Outcome) {
         return;
     };
     print::action(&format!("Checking mirror: {url}"));
-    let Ok(client) = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(8))
-        .build()
-    else {
-        print::failure(
-            "Cannot build HTTP client",
-            Some("Unknown TLS/backend error."),
-        );
-        out.fail();
-        return;
-    };
-    match client.get(&url).send().await {
-        Ok(resp) => {
-            print::success(&format!(
-                "Mirror reachable (HTTP {})",
-                resp.status().as_u16()
-            ));
+    let cache = network_cache::cache_file().unwrap_or_default();
+    let (result, age) =
+        network_cache::probe_with_cache(&cache, &url, cached, || probe_mirror(&config, &url)).await;
+    let from_cache = age
+        .map(|secs| format!(" (cached {secs}s ago)"))
+        .unwrap_or_default();
+    match (result.status, result.error) {
+        (Some(status), _) => {
+            print::success(&format!("Mirror reachable (HTTP {status}){from_cache}"));
             out.pass();
         }
-        Err(e) => {
-            print::failure(&format!("Mirror unreachable: {url}"), Some(&e.to_string()));
+        (None, error) => {
+            print::failure(
+                &format!("Mirror unreachable: {url}{from_cache}"),
+                error.as_deref(),
+            );
             out.fail();
         }
     }
 }
+
+/// 对镜像发一次 GET(8s 超时),记录状态码或失败原因。
+async fn probe_mirror(config: &Config, url: &str) -> network_cache::ProbeResult {
+    let response = match crate::infrastructure::remote::http::client_builder(&config.download)
+        .and_then(|b| {
+            b.timeout(std::time::Duration::from_secs(8))
+                .build()
+                .map_err(|e| e.to_string())
+        }) {
+        Ok(client) => client.get(url).send().await.map_err(|e| e.to_string()),
+        Err(e) => Err(format!("Cannot build HTTP client: {e}")),
+    };
+    network_cache::ProbeResult {
+        url: url.to_string(),
+        status: response.as_ref().ok().map(|r| r.status().as_u16()),
+        error: response.err(),
+    }
+}
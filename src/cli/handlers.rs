@@ -1,11 +1,13 @@
 use crate::cli::commands::*;
+use crate::cli::output::format_env_table;
 use crate::cli::output::FORMATTER;
 use crate::cli::print::format_envs;
 use crate::core::environment_manager::EnvironmentType;
-use crate::core::presentation::{EnvItem, OutputFormat};
+use crate::core::presentation::{EnvGroup, EnvItem, OutputFormat};
 use crate::core::switcher::EnvironmentSwitcher;
 use crate::error::AppError;
 use crate::infrastructure::shell::platform::detect_shell;
+use crate::infrastructure::shell::ShellType;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -17,6 +19,7 @@ fn render_envs(
 ) -> Result<String, AppError> {
     match fmt {
         OutputFormat::Text => Ok(format_envs(items)),
+        OutputFormat::Table => Ok(format_env_table(items)),
         OutputFormat::Json => {
             let json = serde_json::json!({"environment_type": env_type, "environments": items});
             serde_json::to_string_pretty(&json).map_err(AppError::from)
@@ -24,6 +27,66 @@ fn render_envs(
     }
 }
 
+/// 写出导出的切换脚本;含明文凭据时(Unix)只允许本人读写。
+fn write_script_file(path: &str, script: &str, has_secrets: bool) -> Result<(), AppError> {
+    let path_err = |e: std::io::Error| AppError::Path {
+        path: path.to_string(),
+        reason: e.to_string(),
+    };
+    // 先收紧权限再写内容,已存在的文件也不会有明文可读的窗口
+    #[cfg(unix)]
+    if has_secrets {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .mode(0o600)
+            .open(path)
+            .map_err(path_err)?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600)).map_err(path_err)?;
+    }
+    #[cfg(not(unix))]
+    let _ = has_secrets;
+    std::fs::write(path, script).map_err(path_err)
+}
+
+/// 离线模式下拒绝需要联网的命令。
+fn ensure_online(what: &str) -> Result<(), AppError> {
+    crate::infrastructure::remote::offline::ensure_online(what)
+        .map_err(|message| AppError::Network { message })
+}
+
+/// 安装成功后检查 shell 集成;写入失败只告警,不影响安装结果。
+fn post_install_hook(install: bool) {
+    if let Err(e) = crate::cli::integration::ensure_after_install(&detect_shell(), install) {
+        crate::cli::print::warn(&format!("Failed to add shell integration: {e}"));
+    }
+}
+
+/// `install --major-only` 遇到旧补丁时询问是否升级;非交互(CI)时直接升级。
+fn confirm_major_upgrade(name: &str, current: &str, latest: &str) -> bool {
+    if !crate::cli::picker::is_interactive() {
+        return true;
+    }
+    eprint!("Upgrade {name} from {current} to {latest}? [Y/n] ");
+    let mut input = String::new();
+    if std::io::stdin().read_line(&mut input).is_err() {
+        return false;
+    }
+    !input.trim().eq_ignore_ascii_case("n")
+}
+
+/// `--keep-archive`:保留在下载缓存目录,之后重装时命中缓存即可复用;不加时安装后删除。
+fn resolve_keep_archive(keep: bool) -> Result<Option<std::path::PathBuf>, AppError> {
+    if !keep {
+        return Ok(None);
+    }
+    crate::infrastructure::paths::downloads_dir()
+        .map(Some)
+        .map_err(|e| AppError::Config { message: e })
+}
+
 /// 命令处理器
 pub struct CommandHandler {
     switcher: EnvironmentSwitcher,
@@ -62,6 +125,7 @@ impl CommandHandler {
             let output = FORMATTER.format_switch_result(result, OutputFormat::Json)?;
             print!("{output}");
         } else if result.success {
+            crate::cli::integration::warn_if_stale(&detect_shell());
             if !result.script.is_empty() {
                 print!("{}", result.script);
             } else {
@@ -79,6 +143,41 @@ impl CommandHandler {
         Ok(())
     }
 
+    /// strict 模式:目标是 JRE(缺 javac)时提前警告,构建工具在 JRE 下的报错往往很难看懂。
+    fn warn_if_jre(name: &str) {
+        let Ok(config) = crate::infrastructure::config::Config::load() else {
+            return;
+        };
+        let Some(env) = config.get_java_env(name) else {
+            return;
+        };
+        if crate::utils::validate_java_home(&env.java_home) && !crate::utils::is_jdk(&env.java_home)
+        {
+            crate::cli::print::warn(&format!(
+                "'{name}' is a JRE (no javac); compile tasks (javac, Maven, Gradle) will fail"
+            ));
+        }
+    }
+
+    /// `java use --print-path`:解析环境的 bin 目录(不切换、不写 session)
+    fn java_bin_dir(name: &str) -> Result<std::path::PathBuf, AppError> {
+        let config = crate::infrastructure::config::Config::load()
+            .map_err(|e| AppError::Config { message: e })?;
+        let env = config
+            .get_java_env(name)
+            .ok_or_else(|| AppError::not_found(&format!("Java environment '{name}'")))?;
+        if !crate::utils::validate_java_home(&env.java_home) {
+            return Err(AppError::validation(
+                "java_home",
+                &format!(
+                    "Java installation at '{}' is missing or incomplete",
+                    env.java_home
+                ),
+            ));
+        }
+        Ok(std::path::Path::new(&env.java_home).join("bin"))
+    }
+
     async fn handle_default_command_helper(
         &mut self,
         env_type: EnvironmentType,
@@ -101,7 +200,7 @@ impl CommandHandler {
             match self.switcher.get_default_environment(env_type).await? {
                 Some(env_name) => {
                     if let Some(shell_name) = shell {
-                        let shell_type = parse_shell_type(&shell_name)?;
+                        let shell_type = shell_name.parse::<ShellType>()?;
                         let result = self
                             .switcher
                             .switch_environment(
@@ -110,7 +209,9 @@ impl CommandHandler {
                                 Some(shell_type),
                                 Some("Switch to default environment".to_string()),
                             )
-                            .await?;
+                            .await;
+                        let result =
+                            crate::cli::structured_status::observe(env_type, &env_name, result)?;
                         Self::handle_use_result(&result, &env_name, env_type_label, json)?;
                     } else {
                         crate::cli::print::success(&format!(
@@ -132,62 +233,212 @@ impl CommandHandler {
             Commands::Java { action } => self.handle_java_command(action).await,
             Commands::Cc { action } => self.handle_cc_command(action).await,
             Commands::Maven { action } => self.handle_maven_command(action).await,
-            Commands::Env { shell } => {
-                let shell_type = shell
-                    .map(|s| parse_shell_type(&s))
-                    .transpose()?
-                    .unwrap_or_else(detect_shell);
-                let script = self.switcher.generate_shell_integration(shell_type).await?;
-                print!("{script}");
+            Commands::Env { shell, action } => match action {
+                Some(EnvCommands::List { env_type, format }) => {
+                    self.handle_env_list(env_type.as_deref(), format.as_deref())
+                        .await
+                }
+                Some(EnvCommands::ExportScript {
+                    env_type,
+                    name,
+                    file,
+                    shell: export_shell,
+                    resolve_secrets,
+                }) => {
+                    let env_type = parse_environment_type(&env_type)?;
+                    let shell_type = export_shell
+                        .or(shell)
+                        .map(|s| s.parse::<ShellType>())
+                        .transpose()?
+                        .unwrap_or_else(detect_shell);
+                    let (script, has_secrets) = self
+                        .switcher
+                        .export_script(env_type, &name, shell_type, resolve_secrets)
+                        .await?;
+                    write_script_file(&file, &script, has_secrets)?;
+                    crate::cli::print::success(&format!(
+                        "Wrote {env_type} '{name}' script: {file}"
+                    ));
+                    Ok(())
+                }
+                Some(EnvCommands::Switch {
+                    all_defaults: _,
+                    shell: switch_shell,
+                }) => {
+                    let shell_type = switch_shell
+                        .or(shell)
+                        .map(|s| s.parse::<ShellType>())
+                        .transpose()?
+                        .unwrap_or_else(detect_shell);
+                    let script = self.switcher.switch_all_defaults(shell_type).await?;
+                    print!("{script}");
+                    Ok(())
+                }
+                None => {
+                    let shell_type = shell
+                        .map(|s| s.parse::<ShellType>())
+                        .transpose()?
+                        .unwrap_or_else(detect_shell);
+                    let script = self.switcher.generate_shell_integration(shell_type).await?;
+                    print!("{script}");
+                    Ok(())
+                }
+            },
+            Commands::Config { action } => self.handle_config_command(action).await,
+            Commands::History {
+                action: Some(HistoryCommands::Clear { env_type }),
+                ..
+            } => {
+                let env_type = env_type.map(|t| parse_environment_type(&t)).transpose()?;
+                let removed = self.switcher.clear_switch_history(env_type).await?;
+                let scope = env_type.map_or(String::new(), |t| format!("{t} "));
+                crate::cli::print::success(&format!("Cleared {removed} {scope}history entries"));
                 Ok(())
             }
-            Commands::Config { action } => self.handle_config_command(action).await,
             Commands::History {
+                action: None,
                 env_type,
                 limit,
                 json,
-            } => self.handle_history_command(env_type, limit, json).await,
-            Commands::Doctor { network } => {
-                let ok = crate::cli::doctor::run_doctor(network).await?;
+                format,
+                utc,
+            } => {
+                let fmt = resolve_output_format(json, format.as_deref())?;
+                self.handle_history_command(env_type, limit, fmt, utc).await
+            }
+            Commands::Export {
+                action: ExportCommands::ShellEnv { json },
+            } => Ok(crate::cli::shell_env::run(json)?),
+            Commands::Doctor { network, cached } => {
+                let ok = crate::cli::doctor::run_doctor(network, cached).await?;
                 if !ok {
                     return Err("doctor: one or more checks failed".to_string().into());
                 }
                 Ok(())
             }
+            Commands::SelfCmd {
+                action: SelfCommands::UpdateCheck { json },
+            } => {
+                use crate::infrastructure::remote::self_update;
+                ensure_online("Checking for fnva updates")?;
+                let check = self_update::check_for_update(
+                    &self_update::releases_url(),
+                    self_update::CURRENT_VERSION,
+                )
+                .await
+                .map_err(|e| AppError::Network { message: e })?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&check)?);
+                } else if check.update_available {
+                    crate::cli::print::action(&format!(
+                        "fnva {} is available (current {})",
+                        check.latest, check.current
+                    ));
+                    crate::cli::print::detail("Release", &check.url);
+                } else {
+                    crate::cli::print::success(&format!(
+                        "fnva {} is up to date (latest {})",
+                        check.current, check.latest
+                    ));
+                }
+                Ok(())
+            }
         }
     }
 
     /// 处理 Java 命令
     async fn handle_java_command(&mut self, action: JavaCommands) -> Result<(), AppError> {
         match action {
-            JavaCommands::List { json } => {
+            JavaCommands::List { json, format } => {
                 let items = self
                     .switcher
                     .list_environments_with_default(EnvironmentType::Java)
                     .await?;
-                let fmt = if json {
-                    OutputFormat::Json
-                } else {
-                    OutputFormat::Text
-                };
+                let fmt = resolve_output_format(json, format.as_deref())?;
                 print!("{}", render_envs(&items, EnvironmentType::Java, fmt)?);
             }
-            JavaCommands::Use { name, shell, json } => {
+            JavaCommands::Use {
+                name,
+                shell,
+                json,
+                strict,
+                print_path,
+                temporary,
+                create_if_missing,
+                no_hints,
+                list_only,
+                record_only,
+                no_clean,
+            } => {
+                if list_only {
+                    for name in self.java_candidates().await?.0 {
+                        println!("{name}");
+                    }
+                    return Ok(());
+                }
+                let name = match name {
+                    Some(name) if name == "-" => {
+                        self.switcher.previous_environment(EnvironmentType::Java)?
+                    }
+                    Some(name) if create_if_missing => Self::install_missing_java(&name).await?,
+                    Some(name) => name,
+                    None => match Self::project_java_env()? {
+                        Some(name) => name,
+                        None => self.pick_java_env().await?,
+                    },
+                };
+                if record_only {
+                    // 只写状态,stdout 保持为空:shell wrapper 照常 source 也不会有副作用
+                    return self
+                        .switcher
+                        .record_environment(
+                            EnvironmentType::Java,
+                            &name,
+                            Some("Recorded via --record-only".to_string()),
+                        )
+                        .await;
+                }
+                if print_path {
+                    if strict {
+                        Self::warn_if_jre(&name);
+                    }
+                    println!("{}", Self::java_bin_dir(&name)?.display());
+                    return Ok(());
+                }
                 let shell_type = match shell {
-                    Some(s) => Some(parse_shell_type(&s)?),
+                    Some(s) => Some(s.parse::<ShellType>()?),
                     None => Some(crate::infrastructure::shell::platform::detect_shell()),
                 };
 
-                let result = match self
-                    .switcher
-                    .switch_environment(
-                        EnvironmentType::Java,
-                        &name,
-                        shell_type,
-                        Some("Manual switch via command".to_string()),
-                    )
-                    .await
-                {
+                if strict {
+                    Self::warn_if_jre(&name);
+                }
+                let options = crate::core::environment_manager::SwitchOptions {
+                    keep_path: no_clean,
+                };
+                let switched = if temporary {
+                    self.switcher
+                        .switch_environment_temporary_with(
+                            EnvironmentType::Java,
+                            &name,
+                            shell_type,
+                            options,
+                        )
+                        .await
+                } else {
+                    self.switcher
+                        .switch_environment_with(
+                            EnvironmentType::Java,
+                            &name,
+                            shell_type,
+                            Some("Manual switch via command".to_string()),
+                            options,
+                        )
+                        .await
+                };
+                let switched =
+                    crate::cli::structured_status::observe(EnvironmentType::Java, &name, switched);
+                let result = match switched {
                     Ok(res) => res,
                     Err(ctx_err) => {
                         // java_home 失效时交互式询问是否从配置中删除该环境
@@ -229,8 +480,28 @@ impl CommandHandler {
                 };
 
                 Self::handle_use_result(&result, &name, "java", json)?;
+                if !json && !no_hints && !result.script.is_empty() {
+                    if let Some(shell) = &shell_type {
+                        crate::cli::integration::hint_if_not_applied(shell, &name);
+                    }
+                }
             }
-            JavaCommands::Current { json } => {
+            JavaCommands::Current { json, quiet, shell } => {
+                if let Some(shell) = shell {
+                    self.print_current_script(EnvironmentType::Java, &shell)
+                        .await?;
+                } else if quiet {
+                    // 只输出裸环境名,给 prompt/脚本用;无当前环境时静默以退出码 1 结束
+                    match self
+                        .switcher
+                        .get_current_environment_name(EnvironmentType::Java)
+                        .await?
+                    {
+                        Some(name) => println!("{name}"),
+                        None => std::process::exit(1),
+                    }
+                    return Ok(());
+                }
                 let output = self
                     .switcher
                     .get_current_environment(
@@ -251,11 +522,40 @@ impl CommandHandler {
                     .await?;
                 print!("{output}");
             }
-            JavaCommands::LsRemote { version, all } => {
-                let output = self.handle_java_ls_remote(version, all).await?;
+            JavaCommands::ScanPath { action } => Self::handle_java_scan_path(action)?,
+            JavaCommands::LsRemote {
+                spec,
+                version,
+                all,
+                lts_only,
+                platforms,
+                json,
+            } => {
+                if let (true, Some(spec)) = (platforms, spec) {
+                    use crate::environments::java::installer::JavaInstaller;
+                    let builds = JavaInstaller::list_platform_builds(&spec)
+                        .await
+                        .map_err(|e| AppError::Network { message: e })?;
+                    if json {
+                        println!("{}", serde_json::to_string_pretty(&builds)?);
+                    } else {
+                        print!("{}", Self::format_platform_builds(&builds));
+                    }
+                    return Ok(());
+                }
+                if json {
+                    use crate::environments::java::installer::JavaInstaller;
+                    let versions = JavaInstaller::list_remote_versions(lts_only, version)
+                        .await
+                        .map_err(|e| AppError::Network { message: e })?;
+                    println!("{}", serde_json::to_string_pretty(&versions)?);
+                    return Ok(());
+                }
+                let output = self.handle_java_ls_remote(version, all, lts_only).await?;
                 print!("{output}");
             }
             JavaCommands::Refresh => {
+                ensure_online("Refreshing Java versions")?;
                 use crate::environments::java::downloader::JavaDownloader;
                 use crate::infrastructure::config::Config;
                 let config = Config::load().map_err(|e| AppError::Config { message: e })?;
@@ -265,22 +565,176 @@ impl CommandHandler {
                 })?;
                 crate::cli::print::success("Java version cache refreshed");
             }
+            JavaCommands::Registry {
+                action: RegistryCommands::Update { url },
+            } => {
+                use crate::environments::java::version_discovery::{
+                    update_registry, DEFAULT_REGISTRY_URL,
+                };
+                use crate::infrastructure::config::Config;
+
+                ensure_online("Updating the Java registry")?;
+                let config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                let url = url
+                    .or(config.java_registry_url)
+                    .unwrap_or_else(|| DEFAULT_REGISTRY_URL.to_string());
+                crate::cli::print::action(&format!("Updating Java registry from {url}"));
+                let count = update_registry(&url)
+                    .await
+                    .map_err(|e| AppError::Network { message: e })?;
+                crate::cli::print::success(&format!("Java registry updated ({count} versions)"));
+            }
+            JavaCommands::Manifest { action, output } => {
+                use crate::environments::java::installer::JavaInstaller;
+                use crate::environments::java::manifest::JavaManifest;
+                use crate::infrastructure::config::Config;
+
+                let mut config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                match action {
+                    None => {
+                        let manifest = JavaInstaller::export_manifest(&config)
+                            .await
+                            .map_err(|e| AppError::Config { message: e })?;
+                        let text = manifest
+                            .to_toml()
+                            .map_err(|e| AppError::Config { message: e })?;
+                        match output {
+                            Some(path) => {
+                                std::fs::write(&path, text).map_err(|e| AppError::Config {
+                                    message: format!("Failed to write {path}: {e}"),
+                                })?;
+                                crate::cli::print::success(&format!(
+                                    "Wrote {} java entries to {path}",
+                                    manifest.java.len()
+                                ));
+                            }
+                            None => print!("{text}"),
+                        }
+                    }
+                    Some(ManifestCommands::Apply { file }) => {
+                        ensure_online("Applying a Java manifest")?;
+                        let manifest = JavaManifest::load(std::path::Path::new(&file))
+                            .map_err(|e| AppError::validation("file", &e))?;
+                        let (installed, skipped) =
+                            JavaInstaller::apply_manifest(&manifest, &mut config)
+                                .await
+                                .map_err(|e| AppError::Network { message: e })?;
+                        crate::cli::print::success(&format!(
+                            "Manifest applied: {} installed, {} skipped",
+                            installed.len(),
+                            skipped.len()
+                        ));
+                    }
+                }
+            }
             JavaCommands::Install {
                 version,
                 auto_switch,
+                lts_only,
+                keep_archive,
+                from_archive,
+                name,
+                install_hook,
+                no_verify,
+                explain,
+                major_only,
+                timeout,
+                retries,
             } => {
                 use crate::environments::java::installer::JavaInstaller;
-                use crate::infrastructure::config::Config;
+                use crate::infrastructure::config::{Config, DownloadOverrides};
+
+                let overrides = DownloadOverrides {
+                    timeout_sec: timeout,
+                    retries,
+                    no_verify,
+                };
+                overrides
+                    .validate()
+                    .map_err(|e| AppError::validation("download", &e))?;
 
+                if let (Some(archive), Some(name)) = (from_archive, name) {
+                    // 名称会成为安装目录名:拒绝路径分隔符与 `.` / `..`
+                    crate::utils::validation::ValidationUtils::validate_environment_name(&name)
+                        .map_err(|e| AppError::validation("name", &e))?;
+                    let archive = crate::utils::path::resolve_input_path(&archive)
+                        .map_err(|e| AppError::validation("from_archive", &e))?;
+                    let mut config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                    let java_home = JavaInstaller::install_from_archive(
+                        std::path::Path::new(&archive),
+                        &name,
+                        &mut config,
+                        auto_switch,
+                    )
+                    .await
+                    .map_err(|e| AppError::Installation { message: e })?;
+                    crate::cli::print::success(&format!("java {name} installed"));
+                    crate::cli::print::detail("Path", &java_home);
+                    post_install_hook(install_hook);
+                    return Ok(());
+                }
+
+                if explain {
+                    // 只解析不下载:离线时按本地注册表解析
+                    let version = version.unwrap_or_else(|| "lts".to_string());
+                    let config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                    let plan = JavaInstaller::explain_install(&version, &config, lts_only)
+                        .await
+                        .map_err(|e| AppError::Installation { message: e })?;
+                    crate::cli::print::step(
+                        "Resolved",
+                        &format!("{} ({})", plan.version, plan.display),
+                    );
+                    crate::cli::print::step("Platform", &plan.platform);
+                    crate::cli::print::step("File", &plan.filename);
+                    if plan.download_urls.is_empty() {
+                        crate::cli::print::warn("No enabled mirrors in [[mirrors.java]]");
+                    }
+                    let width = plan
+                        .download_urls
+                        .iter()
+                        .map(|m| m.mirror.len())
+                        .max()
+                        .unwrap_or(0);
+                    for (i, m) in plan.download_urls.iter().enumerate() {
+                        println!("{}. {:<width$}  {}", i + 1, m.mirror, m.url);
+                    }
+                    return Ok(());
+                }
+
+                ensure_online("Installing Java (use --from-archive offline)")?;
+                let version = version.unwrap_or_else(|| "lts".to_string());
+                let keep_archive = resolve_keep_archive(keep_archive)?;
                 let mut config = Config::load().map_err(|e| AppError::Config { message: e })?;
-                match JavaInstaller::install_java(&version, &mut config, auto_switch).await {
+                let installed = if major_only {
+                    JavaInstaller::install_major_only(
+                        &version,
+                        &mut config,
+                        auto_switch,
+                        lts_only,
+                        keep_archive.as_deref(),
+                        overrides,
+                        &confirm_major_upgrade,
+                    )
+                    .await
+                } else {
+                    JavaInstaller::install_java(
+                        &version,
+                        &mut config,
+                        auto_switch,
+                        lts_only,
+                        keep_archive.as_deref(),
+                        overrides,
+                    )
+                    .await
+                };
+                match installed {
                     Ok(java_home) => {
                         crate::cli::print::success(&format!("java {version} installed"));
                         crate::cli::print::detail("Path", &java_home);
+                        post_install_hook(install_hook);
                     }
-                    Err(e) => {
-                        return Err(format!("Install failed: {e}").into());
-                    }
+                    Err(e) => return Err(AppError::Installation { message: e }),
                 }
             }
             JavaCommands::Add {
@@ -288,6 +742,8 @@ impl CommandHandler {
                 home,
                 description,
             } => {
+                let home = crate::utils::path::resolve_input_path(&home)
+                    .map_err(|e| AppError::validation("java_home", &e))?;
                 let mut config_value = serde_json::json!({
                     "java_home": home
                 });
@@ -300,6 +756,188 @@ impl CommandHandler {
                     .await?;
                 crate::cli::print::success(&output);
             }
+            JavaCommands::EnvFile { name, output } => {
+                self.write_env_file(EnvironmentType::Java, &name, output)
+                    .await?;
+            }
+            JavaCommands::Copy {
+                from,
+                to,
+                home,
+                description,
+            } => {
+                use crate::infrastructure::config::Config;
+
+                let mut config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                let env = config
+                    .copy_java_env(&from, &to)
+                    .map_err(|e| AppError::validation("name", &e))?;
+                if let Some(h) = home {
+                    let h = crate::utils::path::resolve_input_path(&h)
+                        .map_err(|e| AppError::validation("java_home", &e))?;
+                    env.java_home = crate::utils::path::clean_home(&h);
+                }
+                if let Some(d) = description {
+                    env.description = d;
+                }
+                config.save().map_err(|e| AppError::Config { message: e })?;
+                crate::cli::print::success(&format!("Copied java environment: {from} -> {to}"));
+            }
+            JavaCommands::InstallShims { dir } => {
+                use crate::environments::java::shims;
+                let fnva = std::env::current_exe()?;
+                let dir = std::path::PathBuf::from(dir);
+                let written = shims::install_shims(&dir, &fnva, cfg!(windows))?;
+                for path in &written {
+                    crate::cli::print::step("Shim", &path.display().to_string());
+                }
+                crate::cli::print::success(&format!(
+                    "Installed {} shims; put {} first on PATH",
+                    written.len(),
+                    dir.display()
+                ));
+            }
+            JavaCommands::Which { tool } => {
+                use crate::infrastructure::config::Config;
+                let config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                let current = self
+                    .switcher
+                    .get_current_environment_name(EnvironmentType::Java)
+                    .await?;
+                let path = crate::environments::java::shims::resolve_tool(
+                    &config,
+                    current.as_deref(),
+                    &tool,
+                )
+                .map_err(|e| AppError::validation("tool", &e))?;
+                println!("{}", path.display());
+            }
+            JavaCommands::Info { name, json } => {
+                use crate::infrastructure::config::Config;
+
+                let config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                let env = config
+                    .get_java_env(&name)
+                    .ok_or_else(|| AppError::not_found(&format!("Java environment '{name}'")))?;
+                let current = self
+                    .switcher
+                    .get_current_environment_name(EnvironmentType::Java)
+                    .await?;
+                let info =
+                    crate::environments::java::info::java_info(env, &config, current.as_deref());
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&info)?);
+                } else {
+                    println!("{}", info.to_text());
+                }
+            }
+            JavaCommands::Benchmark {
+                names,
+                runs,
+                warmup,
+                json,
+            } => {
+                use crate::environments::java::benchmark;
+                use crate::infrastructure::config::Config;
+
+                let config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                let envs = if names.is_empty() {
+                    config.java_environments.iter().collect::<Vec<_>>()
+                } else {
+                    names
+                        .iter()
+                        .map(|name| {
+                            config.get_java_env(name).ok_or_else(|| {
+                                AppError::not_found(&format!("Java environment '{name}'"))
+                            })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?
+                };
+                if envs.is_empty() {
+                    return Err(AppError::not_found("Java environments to benchmark"));
+                }
+                if !json {
+                    crate::cli::print::action(&format!(
+                        "Timing `java -version` ({warmup} warmup + {runs} runs per environment)..."
+                    ));
+                }
+                let results =
+                    benchmark::benchmark(&envs, runs, warmup, benchmark::time_java_version);
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&results)?);
+                } else {
+                    println!("{}", benchmark::to_table(&results));
+                }
+            }
+            JavaCommands::Set {
+                name,
+                java_opts,
+                unset_java_opts,
+                extra_paths,
+                clear_extra_paths,
+                description,
+            } => {
+                use crate::infrastructure::config::Config;
+
+                let mut config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                let env = config
+                    .java_environments
+                    .iter_mut()
+                    .find(|e| e.name == name)
+                    .ok_or_else(|| AppError::not_found(&format!("Java environment '{name}'")))?;
+                if unset_java_opts {
+                    env.java_opts = None;
+                } else if let Some(opts) = java_opts {
+                    env.java_opts = Some(opts).filter(|s| !s.is_empty());
+                }
+                if clear_extra_paths {
+                    env.extra_paths.clear();
+                } else if !extra_paths.is_empty() {
+                    env.extra_paths = extra_paths
+                        .iter()
+                        .map(|p| crate::utils::path::clean_home(p))
+                        .collect();
+                }
+                if let Some(d) = description {
+                    env.description = d;
+                }
+                config.save().map_err(|e| AppError::Config { message: e })?;
+                crate::cli::print::success(&format!("Updated Java environment: {name}"));
+            }
+            JavaCommands::Gc { dry_run } => {
+                use crate::infrastructure::config::Config;
+
+                let mut config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                let (removed, stale_manual) =
+                    config.prune_stale_java_envs(crate::utils::validate_java_home, dry_run);
+                let verb = if dry_run { "Would remove" } else { "Removed" };
+                for name in &removed {
+                    crate::cli::print::step(verb, name);
+                }
+                for name in &stale_manual {
+                    crate::cli::print::warn(&format!(
+                        "Manual environment '{name}' points to a missing JAVA_HOME; remove it with `fnva java remove {name}`"
+                    ));
+                }
+                if removed.is_empty() {
+                    crate::cli::print::success("No stale scanned environments");
+                } else if !dry_run {
+                    config.save().map_err(|e| AppError::Config { message: e })?;
+                    use crate::infrastructure::shell::current_envs::CurrentEnvsFile;
+                    let current = CurrentEnvsFile::read().ok().and_then(|f| f.java);
+                    if current.is_some_and(|n| removed.contains(&n)) {
+                        if let Err(e) = CurrentEnvsFile::clear(EnvironmentType::Java) {
+                            crate::cli::print::warn(&format!(
+                                "Failed to clear current_envs.toml: {e}"
+                            ));
+                        }
+                    }
+                    crate::cli::print::success(&format!(
+                        "Removed {} stale java environment(s)",
+                        removed.len()
+                    ));
+                }
+            }
             JavaCommands::Remove { name } => {
                 let output = self
                     .switcher
@@ -314,6 +952,19 @@ impl CommandHandler {
                 let mut config = Config::load().map_err(|e| AppError::Config { message: e })?;
                 JavaInstaller::uninstall_java(&name, &mut config)?;
             }
+            JavaCommands::Reinstall { name, source } => {
+                ensure_online("Reinstalling Java")?;
+                use crate::environments::java::installer::JavaInstaller;
+                use crate::infrastructure::config::Config;
+
+                let mut config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                let java_home =
+                    JavaInstaller::reinstall_java(&name, source.as_deref(), &mut config)
+                        .await
+                        .map_err(|e| AppError::Installation { message: e })?;
+                crate::cli::print::success(&format!("java {name} reinstalled"));
+                crate::cli::print::detail("Path", &java_home);
+            }
             JavaCommands::Default {
                 name,
                 unset,
@@ -339,41 +990,58 @@ impl CommandHandler {
         use crate::environments::maven::{MavenInstaller, MirrorDirectoryDiscovery};
         use crate::infrastructure::tool_protocol::VersionDiscovery;
         match action {
-            MavenCommands::List { json } => {
+            MavenCommands::List { json, format } => {
                 let items = self
                     .switcher
                     .list_environments_with_default(EnvironmentType::Maven)
                     .await?;
-                let fmt = if json {
-                    OutputFormat::Json
-                } else {
-                    OutputFormat::Text
-                };
+                let fmt = resolve_output_format(json, format.as_deref())?;
                 print!("{}", render_envs(&items, EnvironmentType::Maven, fmt)?);
             }
-            MavenCommands::Use { name, shell, json } => {
+            MavenCommands::Use {
+                name,
+                shell,
+                json,
+                temporary,
+            } => {
                 let shell_type = match shell {
-                    Some(s) => Some(parse_shell_type(&s)?),
+                    Some(s) => Some(s.parse::<ShellType>()?),
                     None => None,
                 };
-                let result = self
-                    .switcher
-                    .switch_environment(
-                        EnvironmentType::Maven,
-                        &name,
-                        shell_type,
-                        Some("Manual switch via command".to_string()),
-                    )
-                    .await?;
+                let result = if temporary {
+                    self.switcher
+                        .switch_environment_temporary(EnvironmentType::Maven, &name, shell_type)
+                        .await
+                } else {
+                    self.switcher
+                        .switch_environment(
+                            EnvironmentType::Maven,
+                            &name,
+                            shell_type,
+                            Some("Manual switch via command".to_string()),
+                        )
+                        .await
+                };
+                let result =
+                    crate::cli::structured_status::observe(EnvironmentType::Maven, &name, result)?;
                 Self::handle_use_result(&result, &name, "maven", json)?;
             }
             MavenCommands::Install {
                 version,
                 auto_switch,
+                keep_archive,
             } => {
+                ensure_online("Installing Maven")?;
+                let keep_archive = resolve_keep_archive(keep_archive)?;
                 let mut config = crate::infrastructure::config::Config::load()
                     .map_err(|e| AppError::Config { message: e })?;
-                MavenInstaller::install_maven(&version, &mut config, auto_switch).await?;
+                MavenInstaller::install_maven(
+                    &version,
+                    &mut config,
+                    auto_switch,
+                    keep_archive.as_deref(),
+                )
+                .await?;
             }
             MavenCommands::Scan { path } => {
                 let output = self
@@ -422,14 +1090,17 @@ impl CommandHandler {
                 MavenInstaller::uninstall_maven(&name, &mut config)?;
             }
             MavenCommands::Refresh => {
-                let discovery = MirrorDirectoryDiscovery::new();
+                ensure_online("Refreshing Maven versions")?;
+                let config = crate::infrastructure::config::Config::load().unwrap_or_default();
+                let discovery = MirrorDirectoryDiscovery::from_mirrors(&config.mirrors.maven);
                 discovery.refresh().await.map_err(|e| AppError::Network {
                     message: format!("{e:?}"),
                 })?;
                 crate::cli::print::success("Maven version cache refreshed");
             }
             MavenCommands::LsRemote { version, all } => {
-                let discovery = MirrorDirectoryDiscovery::new();
+                let config = crate::infrastructure::config::Config::load().unwrap_or_default();
+                let discovery = MirrorDirectoryDiscovery::from_mirrors(&config.mirrors.maven);
                 let versions = discovery.list().await.map_err(|e| AppError::Network {
                     message: format!("{e:?}"),
                 })?;
@@ -451,13 +1122,22 @@ impl CommandHandler {
                 if !all && total > 30 {
                     crate::cli::print::step(
                         "Status",
-                        &format!("(30 of {total} shown — pass --all to see everything)"),
+                        &format!(
+                            "(30 of {total} shown {} pass --all to see everything)",
+                            crate::cli::print::glyph("—", "-")
+                        ),
                     );
                 } else {
                     crate::cli::print::step("Status", &format!("({total} versions shown)"));
                 }
             }
-            MavenCommands::Current { json } => {
+            MavenCommands::Current {
+                shell: Some(shell), ..
+            } => {
+                self.print_current_script(EnvironmentType::Maven, &shell)
+                    .await?;
+            }
+            MavenCommands::Current { json, shell: None } => {
                 let output = self
                     .switcher
                     .get_current_environment(
@@ -532,17 +1212,67 @@ impl CommandHandler {
     /// 处理 CC 命令
     async fn handle_cc_command(&mut self, action: CcCommands) -> Result<(), AppError> {
         match action {
-            CcCommands::List { json } => {
+            CcCommands::List {
+                json,
+                format,
+                provider,
+                model_contains,
+            } => {
                 let items = self
                     .switcher
-                    .list_environments_with_default(EnvironmentType::Cc)
+                    .list_cc_environments(provider.as_deref(), model_contains.as_deref())
                     .await?;
-                let fmt = if json {
-                    OutputFormat::Json
+                let fmt = resolve_output_format(json, format.as_deref())?;
+                print!("{}", render_envs(&items, EnvironmentType::Cc, fmt)?);
+            }
+            CcCommands::Presets { json } => {
+                use crate::environments::cc::presets::PRESETS;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(PRESETS)?);
                 } else {
-                    OutputFormat::Text
+                    let width = PRESETS.iter().map(|p| p.name.len()).max().unwrap_or(0);
+                    for p in PRESETS {
+                        println!("{:<width$}  {:<40}  {}", p.name, p.base_url, p.sonnet_model);
+                    }
+                }
+            }
+            CcCommands::AddPreset {
+                preset,
+                name,
+                api_key,
+            } => {
+                use std::io::IsTerminal;
+                let preset =
+                    crate::environments::cc::presets::find_preset(&preset).ok_or_else(|| {
+                        AppError::validation(
+                            "preset",
+                            &format!(
+                                "Unknown preset '{preset}'; run `fnva cc presets` to list them"
+                            ),
+                        )
+                    })?;
+                let api_key = match api_key {
+                    Some(key) => Some(key),
+                    None if std::io::stdin().is_terminal() => {
+                        eprint!(
+                            "API key for {} (Enter for ${{{}}}): ",
+                            preset.name, preset.key_var
+                        );
+                        let mut input = String::new();
+                        std::io::stdin().read_line(&mut input)?;
+                        Some(input.trim().to_string()).filter(|k| !k.is_empty())
+                    }
+                    None => None,
                 };
-                print!("{}", render_envs(&items, EnvironmentType::Cc, fmt)?);
+                let name = name.unwrap_or_else(|| format!("{}-cc", preset.name));
+                let env = preset.instantiate(&name, api_key.as_deref());
+                crate::utils::validation::ValidationUtils::validate_api_key(&env.api_key)
+                    .map_err(|e| AppError::validation("api_key", &e))?;
+                let output = self
+                    .switcher
+                    .add_environment(EnvironmentType::Cc, &name, serde_json::to_value(&env)?)
+                    .await?;
+                crate::cli::print::success(&output);
             }
             CcCommands::Scan { path } => {
                 let output = self
@@ -551,21 +1281,46 @@ impl CommandHandler {
                     .await?;
                 print!("{output}");
             }
-            CcCommands::Use { name, shell, json } => {
+            CcCommands::Use {
+                name,
+                shell,
+                json,
+                reveal,
+                temporary,
+            } => {
                 let shell_type = match shell {
-                    Some(s) => Some(parse_shell_type(&s)?),
+                    Some(s) => Some(s.parse::<ShellType>()?),
                     None => Some(crate::infrastructure::shell::platform::detect_shell()),
                 };
-                let result = self
-                    .switcher
-                    .switch_environment(
-                        EnvironmentType::Cc,
-                        &name,
-                        shell_type,
-                        Some("Manual switch via command".to_string()),
-                    )
-                    .await?;
-                Self::handle_use_result(&result, &name, "cc", json)?;
+                let result = if temporary {
+                    self.switcher
+                        .switch_environment_temporary(EnvironmentType::Cc, &name, shell_type)
+                        .await
+                } else {
+                    self.switcher
+                        .switch_environment(
+                            EnvironmentType::Cc,
+                            &name,
+                            shell_type,
+                            Some("Manual switch via command".to_string()),
+                        )
+                        .await
+                };
+                let result =
+                    crate::cli::structured_status::observe(EnvironmentType::Cc, &name, result)?;
+                if json && result.success {
+                    // 结构化的环境参数随脚本一起输出,编排工具无需再解析脚本;
+                    // 脚本与 `--shell` 输出一致(要能直接 eval),只在 environment 里打码
+                    let config = crate::infrastructure::config::Config::load()
+                        .map_err(|e| AppError::Config { message: e })?;
+                    let mut output = serde_json::to_value(&result)?;
+                    if let Some(env) = config.get_cc_env(&result.name) {
+                        output["environment"] = env.to_public_json(reveal);
+                    }
+                    println!("{}", serde_json::to_string_pretty(&output)?);
+                } else {
+                    Self::handle_use_result(&result, &name, "cc", json)?;
+                }
             }
             CcCommands::Default {
                 name,
@@ -583,7 +1338,74 @@ impl CommandHandler {
                 )
                 .await?;
             }
-            CcCommands::Current { json } => {
+            CcCommands::SwitchDefault { shell } => {
+                let shell_type = shell.parse::<ShellType>()?;
+                // 未设置默认环境时输出空脚本,集成脚本照常 eval
+                let Some(name) = self
+                    .switcher
+                    .get_default_environment(EnvironmentType::Cc)
+                    .await?
+                else {
+                    return Ok(());
+                };
+                let result = self
+                    .switcher
+                    .switch_environment(
+                        EnvironmentType::Cc,
+                        &name,
+                        Some(shell_type),
+                        Some("Switch to default environment".to_string()),
+                    )
+                    .await;
+                let result =
+                    crate::cli::structured_status::observe(EnvironmentType::Cc, &name, result)?;
+                Self::handle_use_result(&result, &name, "cc", false)?;
+            }
+            CcCommands::Models { name, json } => {
+                use crate::environments::cc::environment_manager::mask_secret;
+                let config = crate::infrastructure::config::Config::load()
+                    .map_err(|e| AppError::Config { message: e })?;
+                let env = config
+                    .cc_environments
+                    .iter()
+                    .find(|e| e.name == name)
+                    .ok_or_else(|| AppError::not_found(&format!("CC environment '{name}'")))?;
+                let models = crate::environments::cc::models::list_models(env)
+                    .await
+                    .map_err(|message| AppError::Network { message })?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&models)?);
+                    return Ok(());
+                }
+                crate::cli::print::detail("Provider", &env.resolve_env_var(&env.base_url));
+                crate::cli::print::detail("Key", &mask_secret(&env.resolve_env_var(&env.api_key)));
+                println!();
+                for model in &models {
+                    let marker = if model.used_as.is_empty() { " " } else { "*" };
+                    let mut line = format!("{marker} {}", model.id);
+                    if let Some(display) = &model.display_name {
+                        line.push_str(&format!("  {}", crate::cli::print::dim(display)));
+                    }
+                    if !model.used_as.is_empty() {
+                        line.push_str(&format!(" ({})", model.used_as.join(", ")));
+                    }
+                    println!("{line}");
+                }
+                if models.is_empty() {
+                    crate::cli::print::warn("The provider returned no models");
+                }
+            }
+            CcCommands::Current {
+                shell: Some(shell), ..
+            } => {
+                self.print_current_script(EnvironmentType::Cc, &shell)
+                    .await?;
+            }
+            CcCommands::Current {
+                json,
+                shell: None,
+                check,
+            } => {
                 let output = self
                     .switcher
                     .get_current_environment(
@@ -595,7 +1417,35 @@ impl CommandHandler {
                         },
                     )
                     .await?;
-                print!("{output}");
+                if !check || !json {
+                    print!("{output}");
+                }
+                if !check {
+                    return Ok(());
+                }
+                let result = self.check_current_cc().await?;
+                if json {
+                    let mut value: serde_json::Value = serde_json::from_str(&output)?;
+                    value["check"] = serde_json::to_value(&result)?;
+                    println!("{}", serde_json::to_string_pretty(&value)?);
+                }
+                match result {
+                    Some(check) if check.ok => {
+                        let line = format!("{}: {}", check.url, check.message);
+                        match (json, check.verified) {
+                            (true, _) => {}
+                            (false, true) => crate::cli::print::success(&line),
+                            // 服务商没有 /v1/models:可达,但 key 没有验证
+                            (false, false) => crate::cli::print::warn(&line),
+                        }
+                    }
+                    Some(check) => {
+                        return Err(AppError::Network {
+                            message: format!("{}: {}", check.url, check.message),
+                        })
+                    }
+                    None => crate::cli::print::warn("Skipping endpoint check in offline mode"),
+                }
             }
             CcCommands::Add {
                 name,
@@ -613,6 +1463,11 @@ impl CommandHandler {
                          --api-key ${ANTHROPIC_API_KEY}`",
                     ));
                 }
+                if let Some(warning) =
+                    crate::environments::cc::presets::base_url_warning(&base_url_val)
+                {
+                    crate::cli::print::warn(&warning);
+                }
                 let mut json = serde_json::json!({
                     "base_url": base_url_val,
                 });
@@ -631,10 +1486,67 @@ impl CommandHandler {
                     .await?;
                 crate::cli::print::success(&output);
                 if api_key.as_deref().map(str::trim).unwrap_or("").is_empty() {
-                    crate::cli::print::warn(
-                        "no api-key set — CC will not authenticate. \
+                    crate::cli::print::warn(&format!(
+                        "no api-key set {} CC will not authenticate. \
                          Re-add with --api-key, or set api_key in config.",
-                    );
+                        crate::cli::print::glyph("—", "-")
+                    ));
+                }
+            }
+            CcCommands::EnvFile { name, output } => {
+                self.write_env_file(EnvironmentType::Cc, &name, output)
+                    .await?;
+            }
+            CcCommands::Copy {
+                from,
+                to,
+                api_key,
+                base_url,
+                model,
+                description,
+            } => {
+                use crate::infrastructure::config::Config;
+
+                let mut config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                let env = config
+                    .copy_cc_env(&from, &to)
+                    .map_err(|e| AppError::validation("name", &e))?;
+                if let Some(k) = api_key {
+                    env.api_key = k;
+                }
+                if let Some(u) = base_url {
+                    if let Some(warning) = crate::environments::cc::presets::base_url_warning(&u) {
+                        crate::cli::print::warn(&warning);
+                    }
+                    env.base_url = u;
+                }
+                if let Some(m) = model {
+                    env.sonnet_model = m;
+                }
+                if let Some(d) = description {
+                    env.description = d;
+                }
+                config.save().map_err(|e| AppError::Config { message: e })?;
+                crate::cli::print::success(&format!("Copied cc environment: {from} -> {to}"));
+            }
+            CcCommands::Set { name, field, value } => {
+                use crate::infrastructure::config::Config;
+
+                let mut config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                let env = config
+                    .cc_environments
+                    .iter_mut()
+                    .find(|e| e.name == name)
+                    .ok_or_else(|| AppError::not_found(&format!("CC environment '{name}'")))?;
+                env.set_field(&field, &value)?;
+                config.save().map_err(|e| AppError::Config { message: e })?;
+                crate::cli::print::success(&format!("Updated cc environment {name}: {field}"));
+                if field == "base_url" {
+                    if let Some(warning) =
+                        crate::environments::cc::presets::base_url_warning(&value)
+                    {
+                        crate::cli::print::warn(&warning);
+                    }
                 }
             }
             CcCommands::Remove { name } => {
@@ -650,9 +1562,9 @@ impl CommandHandler {
 
     /// 处理配置命令
     async fn handle_config_command(&mut self, action: ConfigCommands) -> Result<(), AppError> {
+        use crate::infrastructure::config::Config;
         match action {
             ConfigCommands::Sync => {
-                use crate::infrastructure::config::Config;
                 let updated = Config::sync()?;
                 if updated {
                     crate::cli::print::success("Configuration synced");
@@ -660,21 +1572,401 @@ impl CommandHandler {
                     crate::cli::print::success("Configuration is up to date");
                 }
             }
+            ConfigCommands::Path { json } => {
+                use crate::infrastructure::paths;
+                let mut entries = vec![
+                    ("config_file", paths::config_path()?),
+                    ("config_dir", paths::fnva_dir()?),
+                    ("java_packages_dir", paths::tool_packages_dir("java")?),
+                    ("history_file", paths::history_path()?),
+                    ("current_envs_file", paths::current_envs_path()?),
+                ];
+                if let Some(system) = crate::infrastructure::config_layers::system_config_path()
+                    .filter(|p| p.is_file())
+                {
+                    entries.push(("system_config_file", system));
+                }
+                if json {
+                    let map: serde_json::Map<_, _> = entries
+                        .iter()
+                        .map(|(k, p)| (k.to_string(), p.display().to_string().into()))
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&map)?);
+                } else {
+                    for (key, path) in &entries {
+                        println!("{key:<18} {}", path.display());
+                    }
+                }
+            }
+            ConfigCommands::Get { key } => {
+                let config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                let value = config
+                    .get_setting(&key)
+                    .map_err(|e| AppError::validation(&key, &e))?;
+                println!("{}", value.unwrap_or_default());
+            }
+            ConfigCommands::Set { key, value } => {
+                let mut config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                config
+                    .set_setting(&key, &value)
+                    .map_err(|e| AppError::validation(&key, &e))?;
+                config
+                    .save_setting(&key)
+                    .map_err(|e| AppError::Config { message: e })?;
+                match config.get_setting(&key)? {
+                    Some(value) => crate::cli::print::success(&format!("{key} = {value}")),
+                    None => crate::cli::print::success(&format!("{key} cleared")),
+                }
+            }
+            ConfigCommands::List { json } => {
+                use crate::infrastructure::config_keys::SETTABLE_KEYS;
+                let config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                let mut settings = Vec::new();
+                for key in SETTABLE_KEYS {
+                    settings.push((*key, config.get_setting(key)?));
+                }
+                if json {
+                    let map: serde_json::Map<_, _> = settings
+                        .into_iter()
+                        .map(|(k, v)| (k.to_string(), v.into()))
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&map)?);
+                } else {
+                    for (key, value) in &settings {
+                        println!("{key:<37} {}", value.as_deref().unwrap_or("(unset)"));
+                    }
+                }
+            }
+            ConfigCommands::Validate { json } => {
+                let config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                let warnings = config.validate_semantics();
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&warnings)?);
+                } else if warnings.is_empty() {
+                    crate::cli::print::success("Configuration is valid");
+                } else {
+                    for warning in &warnings {
+                        crate::cli::print::warn(&warning.to_string());
+                    }
+                }
+                if !warnings.is_empty() {
+                    return Err(
+                        format!("config validate: {} problem(s) found", warnings.len()).into(),
+                    );
+                }
+            }
+            ConfigCommands::Compact { dry_run } => {
+                use crate::cli::print;
+                let mut config = Config::load().map_err(|e| AppError::Config { message: e })?;
+                let report = config.compact_java(cfg!(windows));
+                for (name, from, to) in &report.normalized {
+                    print::step("Normalize", &format!("{name}: {from} -> {to}"));
+                }
+                for (dropped, kept) in &report.duplicates {
+                    print::step("Duplicate", &format!("{dropped} (kept {kept})"));
+                }
+                for name in &report.pruned_removed_names {
+                    print::step("Unblock", name);
+                }
+                if dry_run {
+                    if report.is_empty() {
+                        print::success("Nothing to compact");
+                    }
+                    return Ok(());
+                }
+                // 即使没有改动也重写一遍,顺带得到规范格式的文件
+                config.save().map_err(|e| AppError::Config { message: e })?;
+                if report.is_empty() {
+                    print::success("Nothing to compact; configuration rewritten");
+                } else {
+                    print::success(&format!(
+                        "Compacted configuration: {} path(s) normalized, {} duplicate(s) removed, {} removed name(s) pruned",
+                        report.normalized.len(),
+                        report.duplicates.len(),
+                        report.pruned_removed_names.len()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `env list`:按 java / maven / cc 分组列出全部(或指定类型的)环境。
+    async fn handle_env_list(
+        &self,
+        env_type: Option<&str>,
+        format: Option<&str>,
+    ) -> Result<(), AppError> {
+        let groups = match env_type {
+            Some(t) => {
+                let t = parse_environment_type(t)?;
+                vec![EnvGroup {
+                    environment_type: t,
+                    environments: self.switcher.list_environments_with_default(t).await?,
+                    error: None,
+                }]
+            }
+            None => self.switcher.list_all().await?,
+        };
+        for group in &groups {
+            if let Some(e) = &group.error {
+                crate::cli::print::warn(&format!(
+                    "Failed to list {} environments: {e}",
+                    group.environment_type
+                ));
+            }
+        }
+
+        if format.is_some_and(|f| f.eq_ignore_ascii_case("tsv")) {
+            let rows: Vec<_> = groups
+                .into_iter()
+                .map(|g| (g.environment_type, g.environments))
+                .collect();
+            print!("{}", crate::cli::output::format_env_tsv(&rows));
+            return Ok(());
+        }
+        match resolve_output_format(false, format)? {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&groups)?),
+            fmt => {
+                for group in groups.iter().filter(|g| g.error.is_none()) {
+                    let t = group.environment_type;
+                    println!("{}", crate::cli::print::bold(&t.to_string()));
+                    print!("{}", render_envs(&group.environments, t, fmt)?);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `current --shell`:重新输出当前环境的切换脚本,供新终端 `eval` 恢复。
+    async fn print_current_script(
+        &self,
+        env_type: EnvironmentType,
+        shell: &str,
+    ) -> Result<(), AppError> {
+        let shell_type = shell.parse::<ShellType>()?;
+        print!(
+            "{}",
+            self.switcher
+                .current_environment_script(env_type, shell_type)
+                .await?
+        );
+        Ok(())
+    }
+
+    /// `cc current --check`:检查当前 CC 环境的服务商;离线模式下跳过,返回 `None`。
+    async fn check_current_cc(
+        &self,
+    ) -> Result<Option<crate::environments::cc::check::EndpointCheck>, AppError> {
+        let name = self
+            .switcher
+            .get_current_environment_name(EnvironmentType::Cc)
+            .await?
+            .ok_or_else(|| AppError::not_found("current CC environment"))?;
+        if crate::infrastructure::remote::offline::is_offline() {
+            return Ok(None);
+        }
+        let config = crate::infrastructure::config::Config::load()
+            .map_err(|e| AppError::Config { message: e })?;
+        let env = config
+            .cc_environments
+            .iter()
+            .find(|e| e.name == name)
+            .ok_or_else(|| AppError::not_found(&format!("CC environment '{name}'")))?;
+        crate::environments::cc::check::check_environment(env)
+            .await
+            .map(Some)
+            .map_err(|message| AppError::Network { message })
+    }
+
+    /// `env-file`:无路径时写 stdout(便于重定向),否则写文件并提示
+    async fn write_env_file(
+        &self,
+        env_type: EnvironmentType,
+        name: &str,
+        output: Option<String>,
+    ) -> Result<(), AppError> {
+        let content = self.switcher.render_env_file(env_type, name).await?;
+        match output {
+            Some(path) => {
+                std::fs::write(&path, content).map_err(|e| AppError::Path {
+                    path: path.clone(),
+                    reason: e.to_string(),
+                })?;
+                crate::cli::print::success(&format!("Wrote env file: {path}"));
+            }
+            None => print!("{content}"),
         }
         Ok(())
     }
 
+    /// `--create-if-missing`:环境不存在时先安装,返回实际的环境名。
+    /// 安装进度写 stderr,stdout 只留给要被 source 的切换脚本。
+    async fn install_missing_java(spec: &str) -> Result<String, AppError> {
+        use crate::environments::java::installer::JavaInstaller;
+        use crate::infrastructure::config::Config;
+
+        let mut config = Config::load().map_err(|e| AppError::Config { message: e })?;
+        crate::cli::print::status_to_stderr(true);
+        let installed = JavaInstaller::ensure_java_env(spec, &mut config).await;
+        crate::cli::print::status_to_stderr(false);
+        installed.map_err(|e| AppError::Network { message: e })
+    }
+
+    /// `java scan-path add/remove/list`:维护 `custom_java_scan_paths`。
+    fn handle_java_scan_path(action: ScanPathCommands) -> Result<(), AppError> {
+        use crate::infrastructure::config::Config;
+
+        let mut config = Config::load().map_err(|e| AppError::Config { message: e })?;
+        match action {
+            ScanPathCommands::Add { path } => {
+                // 相对路径按当前目录展开;不存在的目录仍允许加入(之后才挂载的网络盘等)
+                let cwd = std::env::current_dir()?;
+                let path =
+                    crate::utils::path::absolutize_against(std::path::Path::new(path.trim()), &cwd)
+                        .to_string_lossy()
+                        .into_owned();
+                if !std::path::Path::new(&path).is_dir() {
+                    crate::cli::print::warn(&format!(
+                        "{path} is not a directory (yet); it will be skipped until it exists"
+                    ));
+                }
+                if !config
+                    .add_java_scan_path(&path)
+                    .map_err(|e| AppError::validation("path", &e))?
+                {
+                    crate::cli::print::success(&format!("Scan path already present: {path}"));
+                    return Ok(());
+                }
+                config.save().map_err(|e| AppError::Config { message: e })?;
+                crate::cli::print::success(&format!("Added scan path: {path}"));
+                crate::cli::print::step("Next", "run `fnva java scan` to pick up its JDKs");
+            }
+            ScanPathCommands::Remove { path } => {
+                if !config.remove_java_scan_path(&path) {
+                    return Err(AppError::not_found(&format!("Scan path '{path}'")));
+                }
+                config.save().map_err(|e| AppError::Config { message: e })?;
+                crate::cli::print::success(&format!("Removed scan path: {path}"));
+            }
+            ScanPathCommands::List => {
+                for path in &config.custom_java_scan_paths {
+                    println!("{path}");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 未给名称时按项目的 .java-version / .tool-versions 选环境;没有版本文件时为 `None`。
+    fn project_java_env() -> Result<Option<String>, AppError> {
+        use crate::environments::java::project_version::{find_project_version, match_java_env};
+        use crate::infrastructure::config::Config;
+
+        let cwd = std::env::current_dir()?;
+        let Some(project) = find_project_version(&cwd) else {
+            return Ok(None);
+        };
+        let config = Config::load().map_err(|e| AppError::Config { message: e })?;
+        project
+            .specs
+            .iter()
+            .find_map(|spec| match_java_env(spec, &config.java_environments))
+            .map(|env| Some(env.name.clone()))
+            .ok_or_else(|| {
+                AppError::not_found(&format!(
+                    "Java environment matching '{}' (from {})",
+                    project.specs.join(" "),
+                    project.file.display()
+                ))
+            })
+    }
+
+    /// 可选的 Java 环境名(按名称排序)与当前环境
+    async fn java_candidates(&self) -> Result<(Vec<String>, Option<String>), AppError> {
+        let envs = self
+            .switcher
+            .list_environments_with_default(EnvironmentType::Java)
+            .await?;
+        let current = envs.iter().find(|e| e.is_current).map(|e| e.name.clone());
+        let mut names: Vec<String> = envs.into_iter().map(|e| e.name).collect();
+        names.sort();
+        Ok((names, current))
+    }
+
+    /// 既没给名称也没有项目版本文件:终端里让用户选,否则报错并列出可用名称。
+    async fn pick_java_env(&self) -> Result<String, AppError> {
+        let (names, current) = self.java_candidates().await?;
+        if names.is_empty() {
+            return Err(AppError::validation(
+                "name",
+                "No Java environments configured; add one with `fnva java add` or `fnva java scan`",
+            ));
+        }
+        if !crate::cli::picker::is_interactive() {
+            return Err(AppError::validation(
+                "name",
+                &format!(
+                    "No environment name given and no .java-version / .tool-versions found; available: {}",
+                    names.join(", ")
+                ),
+            ));
+        }
+        crate::cli::picker::pick(
+            "Java environments:",
+            &names,
+            current.as_deref(),
+            std::io::stdin().lock(),
+            std::io::stderr(),
+        )
+        .map_err(|e| AppError::validation("name", &e))?
+        .ok_or_else(|| AppError::validation("name", "No environment selected"))
+    }
+
+    /// `ls-remote <version> --platforms` 的文本输出:每个平台的文件名、校验和与镜像地址。
+    fn format_platform_builds(
+        builds: &[crate::environments::java::installer::RemoteJavaVersion],
+    ) -> String {
+        let mut output = String::new();
+        let Some(first) = builds.first() else {
+            return output;
+        };
+        output.push_str(&format!(
+            "Java {} platforms ({}):\n",
+            first.version,
+            builds.len()
+        ));
+        for build in builds {
+            output.push_str(&format!("  {}  {}\n", build.platform, build.filename));
+            output.push_str(&format!(
+                "    sha256: {}\n",
+                build.sha256.as_deref().unwrap_or("(not published)")
+            ));
+            for m in &build.download_urls {
+                output.push_str(&format!("    {}: {}\n", m.mirror, m.url));
+            }
+        }
+        output
+    }
+
     /// Handle Java remote version listing.
     async fn handle_java_ls_remote(
         &self,
         version: Option<u32>,
         all: bool,
+        lts_only: bool,
     ) -> Result<String, AppError> {
         use crate::environments::java::installer::JavaInstaller;
 
-        crate::cli::print::action("Querying available Java versions...");
+        if crate::infrastructure::remote::offline::is_offline() {
+            crate::cli::print::action(
+                "Listing Java versions from the local registry (offline mode)...",
+            );
+        } else {
+            crate::cli::print::action("Querying available Java versions...");
+        }
 
-        match JavaInstaller::list_installable_versions().await {
+        match JavaInstaller::list_installable_versions(lts_only).await {
             Ok(versions) => {
                 let filtered: Vec<String> = if let Some(major) = version {
                     versions
@@ -689,9 +1981,10 @@ impl CommandHandler {
                 let shown: Vec<String> = filtered.into_iter().take(limit).collect();
 
                 let mut output = String::new();
+                let kind = if lts_only { "LTS versions" } else { "versions" };
                 let header = match version {
-                    Some(major) => format!("Available Java {major} versions"),
-                    None => "Available Java versions".to_string(),
+                    Some(major) => format!("Available Java {major} {kind}"),
+                    None => format!("Available Java {kind}"),
                 };
                 if shown.is_empty() {
                     output.push_str(&format!("{header}: none found\n"));
@@ -702,14 +1995,17 @@ impl CommandHandler {
                     }
                     if !all && total > 30 {
                         output.push_str(&format!(
-                            "  ... ({} more — pass --all to see everything)\n",
-                            total - shown.len()
+                            "  ... ({} more {} pass --all to see everything)\n",
+                            total - shown.len(),
+                            crate::cli::print::glyph("—", "-")
                         ));
                     }
                 }
                 Ok(output)
             }
-            Err(e) => Err(format!("Failed to query versions: {e}").into()),
+            Err(e) => Err(AppError::Network {
+                message: format!("Failed to query versions: {e}"),
+            }),
         }
     }
 
@@ -718,15 +2014,25 @@ impl CommandHandler {
         &self,
         env_type: Option<String>,
         limit: usize,
-        json: bool,
+        fmt: OutputFormat,
+        utc: bool,
     ) -> Result<(), AppError> {
         let env_type = env_type.map(|t| parse_environment_type(&t)).transpose()?;
-        let items = self.switcher.get_switch_history(env_type, limit).await?;
-        if json {
-            let payload = serde_json::json!({ "history": items, "count": items.len() });
-            print!("{}", serde_json::to_string_pretty(&payload)?);
-        } else {
-            print!("{}", crate::cli::print::format_history(&items));
+        let items = self
+            .switcher
+            .get_switch_history(env_type, limit, utc)
+            .await?;
+        match fmt {
+            OutputFormat::Json => {
+                let payload = serde_json::json!({ "history": items, "count": items.len() });
+                print!("{}", serde_json::to_string_pretty(&payload)?);
+            }
+            OutputFormat::Table => {
+                print!("{}", crate::cli::output::format_history_table(&items));
+            }
+            OutputFormat::Text => {
+                print!("{}", crate::cli::print::format_history(&items));
+            }
         }
         Ok(())
     }
@@ -0,0 +1,261 @@
+//! shell 集成(`fnva env`)在 rc 文件里是否就位的检测与一键写入。
+//!
+//! 与 `scripts/install.sh` 写入的 `# >>> fnva >>>` 块保持同一格式,
+//! 便于 `scripts/uninstall.sh` 统一移除。
+//!
+//! `fnva env` 生成的脚本带 `fnva-integration-version:` 标记;rc 里是
+//! `eval "$(fnva env)"` 时每次启动都重新生成,不会过期。只有把生成结果
+//! 直接粘贴进 rc 的旧副本才带着标记,标记与当前版本不符即视为过期。
+
+use crate::infrastructure::shell::ShellType;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 返回当前 shell 可能加载的 rc / profile 候选路径(存在多个时逐个检查)。
+pub fn shell_rc_candidates(shell: &ShellType) -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    match shell {
+        ShellType::Bash => vec![home.join(".bashrc"), home.join(".bash_profile")],
+        ShellType::Zsh => vec![home.join(".zshrc"), home.join(".zprofile")],
+        ShellType::Fish => vec![home.join(".config").join("fish").join("config.fish")],
+        ShellType::PowerShell => {
+            if cfg!(target_os = "windows") {
+                vec![
+                    home.join("Documents")
+                        .join("PowerShell")
+                        .join("Microsoft.PowerShell_profile.ps1"),
+                    home.join("Documents")
+                        .join("WindowsPowerShell")
+                        .join("Microsoft.PowerShell_profile.ps1"),
+                ]
+            } else {
+                vec![home
+                    .join(".config")
+                    .join("powershell")
+                    .join("Microsoft.PowerShell_profile.ps1")]
+            }
+        }
+        ShellType::Cmd | ShellType::Unknown => Vec::new(),
+    }
+}
+
+/// 第一个已包含 fnva 配置的 rc 文件。
+pub fn find_integration(candidates: &[PathBuf]) -> Option<PathBuf> {
+    candidates
+        .iter()
+        .find(|rc| fs::read_to_string(rc).is_ok_and(|c| c.contains("fnva")))
+        .cloned()
+}
+
+/// 生成脚本里的版本标记(bash/fish/powershell 为 `#` 注释,cmd 为 `REM`)。
+pub const VERSION_MARKER: &str = "fnva-integration-version:";
+
+/// 内容中粘贴的集成脚本所标记的 fnva 版本。
+pub fn embedded_version(content: &str) -> Option<&str> {
+    content.lines().find_map(|line| {
+        let (_, rest) = line.split_once(VERSION_MARKER)?;
+        Some(rest.trim()).filter(|v| !v.is_empty())
+    })
+}
+
+/// 过期的集成副本:所在 rc 文件与其标记的版本。
+#[derive(Debug, PartialEq)]
+pub struct StaleIntegration {
+    pub rc: PathBuf,
+    pub version: String,
+}
+
+/// 第一个粘贴了旧版本集成脚本的 rc 文件。
+pub fn find_stale_integration(candidates: &[PathBuf]) -> Option<StaleIntegration> {
+    candidates.iter().find_map(|rc| {
+        let content = fs::read_to_string(rc).ok()?;
+        let version = embedded_version(&content)?;
+        (version != env!("CARGO_PKG_VERSION")).then(|| StaleIntegration {
+            rc: rc.clone(),
+            version: version.to_string(),
+        })
+    })
+}
+
+/// 切换命令的输出由 shell 集成解析;rc 里是旧版本副本时提示替换。
+pub fn warn_if_stale(shell: &ShellType) {
+    let Some(stale) = find_stale_integration(&shell_rc_candidates(shell)) else {
+        return;
+    };
+    crate::cli::print::warn(&format!(
+        "{} has a pasted fnva {} integration script (current {}); {}",
+        stale.rc.display(),
+        stale.version,
+        env!("CARGO_PKG_VERSION"),
+        refresh_hint(shell)
+    ));
+}
+
+/// 替换过期副本的建议:换成每次启动重新生成的那一行。
+pub fn refresh_hint(shell: &ShellType) -> String {
+    match integration_line(shell) {
+        Some(line) => format!("replace it with: {line}"),
+        None => "regenerate it with `fnva env`".to_string(),
+    }
+}
+
+/// 加载 shell 集成的那一行;cmd 等没有 rc 文件的 shell 返回 `None`。
+pub fn integration_line(shell: &ShellType) -> Option<&'static str> {
+    match shell {
+        ShellType::Bash | ShellType::Zsh => Some("eval \"$(fnva env)\""),
+        ShellType::Fish => Some("fnva env --shell fish | source"),
+        ShellType::PowerShell => {
+            Some("fnva env --shell powershell | Out-String | Invoke-Expression")
+        }
+        ShellType::Cmd | ShellType::Unknown => None,
+    }
+}
+
+/// 把 `# >>> fnva >>>` 集成块追加到 `rc`(必要时创建父目录)。
+pub fn append_integration(rc: &Path, line: &str) -> Result<(), String> {
+    if let Some(parent) = rc.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(rc)
+        .map_err(|e| format!("Failed to open {}: {e}", rc.display()))?;
+    write!(file, "\n# >>> fnva >>>\n{line}\n# <<< fnva <<<\n")
+        .map_err(|e| format!("Failed to write {}: {e}", rc.display()))
+}
+
+/// 安装完成后的集成检查:已就位则静默;`install` 时写入首选 rc,否则只提示。
+pub fn ensure_after_install(shell: &ShellType, install: bool) -> Result<(), String> {
+    let candidates = shell_rc_candidates(shell);
+    let (Some(line), Some(primary)) = (integration_line(shell), candidates.first()) else {
+        return Ok(());
+    };
+    if find_integration(&candidates).is_some() {
+        return Ok(());
+    }
+    if !install {
+        crate::cli::print::warn(&format!(
+            "Shell integration not found; `fnva java use` needs it. Re-run with --install-hook or add to {}: {line}",
+            primary.display()
+        ));
+        return Ok(());
+    }
+    append_integration(primary, line)?;
+    crate::cli::print::step("Hook", &format!("added to {}", primary.display()));
+    crate::cli::print::step("Next", "open a new terminal (or source it) to activate");
+    Ok(())
+}
+
+/// 直接在终端里运行 `java use` 只会打印脚本:stdout 是终端(没被集成或 `eval`
+/// 捕获)且 rc 里找不到集成时,给出应用脚本的写法。
+pub fn use_hint(
+    shell: &ShellType,
+    name: &str,
+    stdout_is_terminal: bool,
+    integration_installed: bool,
+) -> Option<String> {
+    if !stdout_is_terminal || integration_installed {
+        return None;
+    }
+    let apply = match shell {
+        ShellType::Bash | ShellType::Zsh => format!("eval \"$(fnva java use {name})\""),
+        ShellType::Fish => format!("fnva java use {name} | source"),
+        ShellType::PowerShell => {
+            format!("fnva java use {name} | Out-String | Invoke-Expression")
+        }
+        ShellType::Cmd | ShellType::Unknown => return None,
+    };
+    Some(format!(
+        "This only printed the script; run `{apply}` or add shell integration: {}",
+        integration_line(shell).unwrap_or("fnva env")
+    ))
+}
+
+/// 按当前终端与 rc 文件状态输出 [`use_hint`](stderr,不影响 stdout 的脚本)。
+pub fn hint_if_not_applied(shell: &ShellType, name: &str) {
+    use std::io::IsTerminal;
+    let stdout_is_terminal = std::io::stdout().is_terminal();
+    if !stdout_is_terminal {
+        return;
+    }
+    let installed = find_integration(&shell_rc_candidates(shell)).is_some();
+    if let Some(hint) = use_hint(shell, name, stdout_is_terminal, installed) {
+        crate::cli::print::warn(&hint);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn use_hint_only_for_bare_terminal_without_integration() {
+        let hint = use_hint(&ShellType::Bash, "17", true, false).unwrap();
+        assert!(hint.contains(r#"eval "$(fnva java use 17)""#), "{hint}");
+        assert!(hint.contains(r#"eval "$(fnva env)""#), "{hint}");
+        assert!(use_hint(&ShellType::Fish, "17", true, false)
+            .unwrap()
+            .contains("fnva java use 17 | source"));
+
+        // 被管道 / 集成捕获,或已装集成:不提示
+        assert_eq!(use_hint(&ShellType::Bash, "17", false, false), None);
+        assert_eq!(use_hint(&ShellType::Bash, "17", true, true), None);
+        assert_eq!(use_hint(&ShellType::Cmd, "17", true, false), None);
+    }
+
+    #[test]
+    fn append_integration_writes_detectable_block() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let rc = tmp.path().join(".config").join("fish").join("config.fish");
+        let candidates = vec![rc.clone()];
+        assert_eq!(find_integration(&candidates), None);
+
+        append_integration(&rc, integration_line(&ShellType::Fish).unwrap()).unwrap();
+        assert_eq!(
+            fs::read_to_string(&rc).unwrap(),
+            "\n# >>> fnva >>>\nfnva env --shell fish | source\n# <<< fnva <<<\n"
+        );
+        assert_eq!(find_integration(&candidates), Some(rc));
+    }
+
+    #[test]
+    fn pasted_script_with_old_marker_is_stale() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let dynamic = tmp.path().join(".bashrc");
+        let pasted = tmp.path().join(".bash_profile");
+        append_integration(&dynamic, integration_line(&ShellType::Bash).unwrap()).unwrap();
+        let candidates = vec![dynamic, pasted.clone()];
+        // eval "$(fnva env)" 每次重新生成,没有标记,不算过期
+        assert_eq!(find_stale_integration(&candidates), None);
+
+        let current = crate::infrastructure::shell::ScriptGenerator::new()
+            .unwrap()
+            .generate_integration_script(&Default::default(), Some(ShellType::Bash))
+            .unwrap();
+        assert_eq!(embedded_version(&current), Some(env!("CARGO_PKG_VERSION")));
+        fs::write(&pasted, &current).unwrap();
+        assert_eq!(find_stale_integration(&candidates), None);
+
+        fs::write(
+            &pasted,
+            "# fnva-integration-version: 0.0.1\nfnva() { :; }\n",
+        )
+        .unwrap();
+        assert_eq!(
+            find_stale_integration(&candidates),
+            Some(StaleIntegration {
+                rc: pasted,
+                version: "0.0.1".to_string()
+            })
+        );
+        assert_eq!(
+            embedded_version("REM fnva-integration-version: 0.0.2\r\n"),
+            Some("0.0.2")
+        );
+    }
+}
@@ -1,8 +1,12 @@
 pub mod commands;
 pub mod doctor;
 pub mod handlers;
+pub mod integration;
 pub mod output;
+pub mod picker;
 pub mod print;
+pub mod shell_env;
+pub mod structured_status;
 
 pub use commands::*;
 pub use handlers::*;
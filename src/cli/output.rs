@@ -1,4 +1,5 @@
-use crate::core::presentation::OutputFormat;
+use crate::core::environment_manager::EnvironmentType;
+use crate::core::presentation::{EnvItem, HistoryItem, OutputFormat};
 
 /// 输出格式化器(目前仅用于 use 命令的输出)。
 pub struct OutputFormatter;
@@ -11,7 +12,7 @@ impl OutputFormatter {
         format: OutputFormat,
     ) -> Result<String, String> {
         match format {
-            OutputFormat::Text => {
+            OutputFormat::Text | OutputFormat::Table => {
                 if result.success {
                     Ok(format!(
                         "Successfully switched to {}: {}\n",
@@ -31,3 +32,281 @@ impl OutputFormatter {
 
 /// 默认输出格式化器实例
 pub static FORMATTER: OutputFormatter = OutputFormatter;
+
+/// 本次调用是否要求 JSON 输出:任一层子命令带 `--json` 或 `--format json`。
+///
+/// main 据此决定失败时输出 JSON 错误还是普通文本。
+pub fn json_requested(matches: &clap::ArgMatches) -> bool {
+    let mut current = Some(matches);
+    while let Some(m) = current {
+        // 未定义该参数的子命令返回 Err,视为未请求
+        if m.try_get_one::<bool>("json").ok().flatten() == Some(&true)
+            || m.try_get_one::<String>("format")
+                .ok()
+                .flatten()
+                .is_some_and(|f| f.eq_ignore_ascii_case("json"))
+        {
+            return true;
+        }
+        current = m.subcommand().map(|(_, sub)| sub);
+    }
+    false
+}
+
+// ─── Table Rendering ────────────────────────────────────────────────────
+
+/// 终端宽度未知(管道/重定向且无 `COLUMNS`)时的兜底列宽。
+const DEFAULT_TABLE_WIDTH: usize = 120;
+/// 可截断列被压缩后的最小宽度,再窄就失去可读性了。
+const MIN_SHRINK_WIDTH: usize = 12;
+const COLUMN_GAP: &str = "  ";
+
+/// 当前终端宽度:优先 `COLUMNS`(shell 通常会导出),否则用兜底值。
+pub fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.trim().parse::<usize>().ok())
+        .filter(|w| *w > 0)
+        .unwrap_or(DEFAULT_TABLE_WIDTH)
+}
+
+/// 从左侧截断到 `width` 个字符,保留尾部(路径最有辨识度的部分),前缀 `…`。
+fn truncate_left(s: &str, width: usize) -> String {
+    let len = s.chars().count();
+    if len <= width {
+        return s.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let tail: String = s.chars().skip(len - (width - 1)).collect();
+    format!("{}{tail}", crate::cli::print::glyph("…", "~"))
+}
+
+/// 渲染对齐表格。
+///
+/// 总宽超过 `max_width` 时只压缩 `shrink_col` 指定的列(通常是 Path),
+/// 超长单元格用 [`truncate_left`] 截断;其余列保持完整。
+pub fn render_table(
+    headers: &[&str],
+    rows: &[Vec<String>],
+    shrink_col: Option<usize>,
+    max_width: usize,
+) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate().take(widths.len()) {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    let total = widths.iter().sum::<usize>() + COLUMN_GAP.len() * widths.len().saturating_sub(1);
+    if let Some(col) = shrink_col.filter(|c| *c < widths.len()) {
+        if total > max_width {
+            let overflow = total - max_width;
+            let floor = MIN_SHRINK_WIDTH.max(headers[col].chars().count());
+            widths[col] = widths[col].saturating_sub(overflow).max(floor);
+        }
+    }
+
+    let render_row = |cells: Vec<String>| -> String {
+        let line = cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| {
+                let cell = if Some(i) == shrink_col {
+                    truncate_left(cell, widths[i])
+                } else {
+                    cell.clone()
+                };
+                let pad = widths[i].saturating_sub(cell.chars().count());
+                format!("{cell}{}", " ".repeat(pad))
+            })
+            .collect::<Vec<_>>()
+            .join(COLUMN_GAP);
+        format!("{}\n", line.trim_end())
+    };
+
+    let mut out = render_row(headers.iter().map(|h| h.to_string()).collect());
+    let rule_width = widths.iter().sum::<usize>() + COLUMN_GAP.len() * (widths.len() - 1);
+    let rule = crate::cli::print::glyph("─", "-");
+    out.push_str(&format!("{}\n", rule.repeat(rule_width)));
+    for row in rows {
+        out.push_str(&render_row(row.clone()));
+    }
+    out
+}
+
+/// 环境列表表格:Name / Version / Path / Flags。
+pub fn format_env_table(items: &[EnvItem]) -> String {
+    if items.is_empty() {
+        return "(no environments found)\n".to_string();
+    }
+    let rows: Vec<Vec<String>> = items
+        .iter()
+        .map(|item| {
+            vec![
+                item.name.clone(),
+                item.version.clone().unwrap_or_else(|| "-".to_string()),
+                item.path.clone(),
+                env_flags(item).join(","),
+            ]
+        })
+        .collect();
+    render_table(
+        &["Name", "Version", "Path", "Flags"],
+        &rows,
+        Some(2),
+        terminal_width(),
+    )
+}
+
+/// `env list --format tsv`:每行 `type<TAB>name<TAB>path-or-model<TAB>flags`,
+/// 供 `awk -F'\t'` / `cut` 解析。CC 的第三列是模型,Java/Maven 是安装路径;
+/// 字段内的制表符/换行替换为空格,保证一行一条。
+pub fn format_env_tsv(groups: &[(EnvironmentType, Vec<EnvItem>)]) -> String {
+    let clean = |s: &str| s.replace(['\t', '\n', '\r'], " ");
+    let mut out = String::new();
+    for (env_type, items) in groups {
+        for item in items {
+            let location = if *env_type == EnvironmentType::Cc {
+                item.version.clone().unwrap_or_default()
+            } else {
+                item.path.clone()
+            };
+            out.push_str(&format!(
+                "{env_type}\t{}\t{}\t{}\n",
+                clean(&item.name),
+                clean(&location),
+                env_flags(item).join(",")
+            ));
+        }
+    }
+    out
+}
+
+/// 条目的状态标记(表格 Flags 列与 TSV 共用)。
+fn env_flags(item: &EnvItem) -> Vec<&'static str> {
+    let mut flags = Vec::new();
+    if item.is_current {
+        flags.push("current");
+    }
+    if item.is_default {
+        flags.push("default");
+    }
+    if item.missing_key {
+        flags.push("no-key");
+    }
+    if item.is_jre {
+        flags.push("jre");
+    }
+    flags
+}
+
+/// 切换历史表格:Time / Type / From / To / Reason。
+pub fn format_history_table(items: &[HistoryItem]) -> String {
+    if items.is_empty() {
+        return "(no history found)\n".to_string();
+    }
+    let rows: Vec<Vec<String>> = items
+        .iter()
+        .map(|item| {
+            vec![
+                item.timestamp.clone(),
+                item.env_type.clone(),
+                item.from.clone().unwrap_or_else(|| "-".to_string()),
+                item.to.clone(),
+                item.reason.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+    render_table(
+        &["Time", "Type", "From", "To", "Reason"],
+        &rows,
+        Some(4),
+        terminal_width(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_requested_checks_every_subcommand_level() {
+        let requested = |args: &[&str]| {
+            let matches = crate::cli::Cli::command()
+                .try_get_matches_from(args)
+                .unwrap();
+            json_requested(&matches)
+        };
+        assert!(requested(&["fnva", "java", "info", "jdk17", "--json"]));
+        assert!(requested(&["fnva", "history", "--json"]));
+        assert!(requested(&["fnva", "env", "list", "--format", "JSON"]));
+        assert!(!requested(&["fnva", "env", "list", "--format", "table"]));
+        assert!(!requested(&["fnva", "java", "info", "jdk17"]));
+        assert!(!requested(&["fnva", "doctor"]));
+    }
+
+    #[test]
+    fn test_render_table_aligns_columns() {
+        let rows = vec![
+            vec!["j17".to_string(), "/usr/lib/jvm/17".to_string()],
+            vec!["temurin-21".to_string(), "/opt/21".to_string()],
+        ];
+        let out = render_table(&["Name", "Path"], &rows, None, 120);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "Name        Path");
+        assert_eq!(lines[2], "j17         /usr/lib/jvm/17");
+        assert_eq!(lines[3], "temurin-21  /opt/21");
+    }
+
+    #[test]
+    fn test_render_table_truncates_shrink_column_in_narrow_terminal() {
+        let long = "/home/user/.fnva/packages/java/temurin-21/Contents/Home".to_string();
+        let rows = vec![vec!["j21".to_string(), long.clone()]];
+        let out = render_table(&["Name", "Path"], &rows, Some(1), 30);
+        let row = out.lines().nth(2).unwrap();
+        assert!(row.chars().count() <= 30, "row too wide: {row}");
+        assert!(row.contains('…'));
+        // 保留路径尾部
+        assert!(row.ends_with("Contents/Home"));
+    }
+
+    #[test]
+    fn test_truncate_left_keeps_short_values() {
+        assert_eq!(truncate_left("abc", 5), "abc");
+        assert_eq!(truncate_left("abcdef", 4), "…def");
+    }
+
+    #[test]
+    fn test_env_tsv_uses_model_for_cc_and_path_otherwise() {
+        let item = |name: &str, path: &str, version: Option<&str>| EnvItem {
+            name: name.to_string(),
+            description: String::new(),
+            path: path.to_string(),
+            version: version.map(str::to_string),
+            extra: None,
+            is_current: false,
+            is_default: false,
+            missing_key: false,
+            is_jre: false,
+        };
+        let mut j17 = item("j17", "/opt/jdk\t17", Some("17.0.9"));
+        j17.is_current = true;
+        j17.is_default = true;
+        let mut glm = item("glm", "https://api.example", Some("glm-4.6"));
+        glm.missing_key = true;
+        let out = format_env_tsv(&[
+            (EnvironmentType::Java, vec![j17]),
+            (
+                EnvironmentType::Cc,
+                vec![glm, item("bare", "https://x", None)],
+            ),
+        ]);
+        assert_eq!(
+            out,
+            "java\tj17\t/opt/jdk 17\tcurrent,default\ncc\tglm\tglm-4.6\tno-key\ncc\tbare\t\t\n"
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! 交互式选择环境(`fnva java use` 不带名称时)。
+//!
+//! 列表与提示写到 stderr:shell 集成的 wrapper 会捕获 stdout 去 `eval`,
+//! 所以是否可交互看 stdin / stderr 是否为终端,而不是 stdout。
+
+use std::io::{BufRead, Write};
+
+/// stdin 与 stderr 都是终端时才弹出选择。
+pub fn is_interactive() -> bool {
+    use std::io::IsTerminal;
+    std::io::stdin().is_terminal() && std::io::stderr().is_terminal()
+}
+
+/// 列出带序号的候选项并读取一行:序号或完整名称都可以,空行表示取消(`None`)。
+pub fn pick(
+    title: &str,
+    names: &[String],
+    current: Option<&str>,
+    mut input: impl BufRead,
+    mut out: impl Write,
+) -> Result<Option<String>, String> {
+    let io_err = |e: std::io::Error| e.to_string();
+    writeln!(out, "{title}").map_err(io_err)?;
+    for (i, name) in names.iter().enumerate() {
+        let marker = if current == Some(name.as_str()) {
+            "*"
+        } else {
+            " "
+        };
+        writeln!(out, "{marker} {:>2}) {name}", i + 1).map_err(io_err)?;
+    }
+    write!(out, "Select [1-{}] (Enter to cancel): ", names.len()).map_err(io_err)?;
+    out.flush().map_err(io_err)?;
+
+    let mut line = String::new();
+    input.read_line(&mut line).map_err(io_err)?;
+    let answer = line.trim();
+    if answer.is_empty() {
+        return Ok(None);
+    }
+    if let Some(name) = names.iter().find(|n| n.as_str() == answer) {
+        return Ok(Some(name.clone()));
+    }
+    answer
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|i| names.get(i))
+        .cloned()
+        .map(Some)
+        .ok_or_else(|| format!("Invalid selection '{answer}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> Vec<String> {
+        vec!["jdk11".into(), "jdk17".into(), "21".into()]
+    }
+
+    fn run(answer: &str) -> (Result<Option<String>, String>, String) {
+        let mut out = Vec::new();
+        let picked = pick(
+            "Java environments:",
+            &names(),
+            Some("jdk17"),
+            answer.as_bytes(),
+            &mut out,
+        );
+        (picked, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn lists_numbered_candidates_and_marks_current() {
+        let (_, shown) = run("\n");
+        assert!(shown.contains("   1) jdk11\n"), "{shown}");
+        assert!(shown.contains("*  2) jdk17\n"), "{shown}");
+        assert!(shown.contains("Select [1-3]"), "{shown}");
+    }
+
+    #[test]
+    fn accepts_index_or_name_and_cancels_on_empty_line() {
+        assert_eq!(run("2\n").0, Ok(Some("jdk17".to_string())));
+        // 名称优先于序号:名为 "21" 的环境不会被当成第 21 项
+        assert_eq!(run("21\n").0, Ok(Some("21".to_string())));
+        assert_eq!(run("jdk11\n").0, Ok(Some("jdk11".to_string())));
+        assert_eq!(run("\n").0, Ok(None));
+        assert_eq!(run("").0, Ok(None));
+        assert!(run("0\n").0.is_err());
+        assert!(run("4\n").0.is_err());
+        assert!(run("jdk8\n").0.is_err());
+    }
+}
@@ -4,11 +4,18 @@ use std::io::Write;
 // ─── Color Basics ────────────────────────────────────────────────────
 fn use_color() -> bool {
     use std::io::IsTerminal;
-    // NO_COLOR (https://no-color.org) 或非终端输出(管道/重定向)时禁用颜色,
-    // 避免 `fnva list | grep` / `out=$(fnva cc list)` 混入 ANSI 转义码。
-    std::env::var("NO_COLOR").is_err()
-        && std::env::var("TERM").map(|t| t != "dumb").unwrap_or(true)
-        && std::io::stdout().is_terminal()
+    color_enabled(
+        std::env::var_os("NO_COLOR").as_deref(),
+        std::env::var("TERM").ok().as_deref(),
+        std::io::stdout().is_terminal(),
+    )
+}
+
+/// NO_COLOR (https://no-color.org,非空即生效)、`TERM=dumb` 或非终端输出
+/// (管道/重定向)时禁用颜色,避免 `fnva list | grep` / `out=$(fnva cc list)`
+/// 混入 ANSI 转义码。
+fn color_enabled(no_color: Option<&std::ffi::OsStr>, term: Option<&str>, is_tty: bool) -> bool {
+    no_color.is_none_or(|v| v.is_empty()) && term != Some("dumb") && is_tty
 }
 
 pub fn green(s: &str) -> String {
@@ -54,15 +61,67 @@ pub fn dim(s: &str) -> String {
     }
 }
 
+// ─── Glyphs ────────────────────────────────────────────────────────────
+/// 是否退回 ASCII 字形:`FNVA_ASCII=1` 强制开启;Windows 上控制台输出代码页
+/// 不是 UTF-8(65001)时自动开启,否则旧控制台会把 ✓/⚠/╭─ 显示成乱码。
+pub fn use_ascii() -> bool {
+    static ASCII: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *ASCII.get_or_init(|| match std::env::var("FNVA_ASCII") {
+        Ok(v) => !matches!(v.as_str(), "" | "0" | "false"),
+        Err(_) => !console_is_utf8(),
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn console_is_utf8() -> bool {
+    extern "system" {
+        fn GetConsoleOutputCP() -> u32;
+    }
+    const CP_UTF8: u32 = 65001;
+    // 0 表示没有附着控制台(重定向到文件/管道),此时输出按 UTF-8 字节写出
+    let cp = unsafe { GetConsoleOutputCP() };
+    cp == 0 || cp == CP_UTF8
+}
+
+#[cfg(not(target_os = "windows"))]
+fn console_is_utf8() -> bool {
+    true
+}
+
+/// 按 [`use_ascii`] 在 Unicode 字形与 ASCII 替代之间选择(与模板里的 `[OK]` 标记一致)。
+pub fn glyph(unicode: &'static str, ascii: &'static str) -> &'static str {
+    if use_ascii() {
+        ascii
+    } else {
+        unicode
+    }
+}
+
 // ─── Main Output Functions (stdout) ──────────────────────────────────────
+static STATUS_TO_STDERR: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// stdout 留给会被 shell `source` 的切换脚本时(`java use --create-if-missing`
+/// 先安装),把 success/action/step/detail 改写到 stderr。
+pub fn status_to_stderr(enabled: bool) {
+    STATUS_TO_STDERR.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+fn status_line(line: std::fmt::Arguments) {
+    if STATUS_TO_STDERR.load(std::sync::atomic::Ordering::Relaxed) {
+        let _ = writeln!(std::io::stderr(), "{line}");
+    } else {
+        println!("{line}");
+    }
+}
+
 /// ✓ Success
 pub fn success(msg: &str) {
-    println!("{} {}", green("✓"), msg);
+    status_line(format_args!("{} {}", green(glyph("✓", "[OK]")), msg));
 }
 
 /// ✗ Failure
 pub fn failure(title: &str, reason: Option<&str>) {
-    eprintln!("{} {}", red("✗"), bold(title));
+    eprintln!("{} {}", red(glyph("✗", "[FAIL]")), bold(title));
     if let Some(r) = reason {
         eprintln!("  {}", dim(r));
     }
@@ -70,32 +129,46 @@ pub fn failure(title: &str, reason: Option<&str>) {
 
 /// → Action start
 pub fn action(msg: &str) {
-    println!("{} {}", cyan("→"), bold(msg));
+    status_line(format_args!("{} {}", cyan(glyph("→", "->")), bold(msg)));
 }
 
 /// · Step detail
 pub fn step(key: &str, val: &str) {
-    println!("  {} {:<10} {}", dim("·"), dim(key), val);
+    status_line(format_args!(
+        "  {} {:<10} {}",
+        dim(glyph("·", "-")),
+        dim(key),
+        val
+    ));
 }
 
 /// Key-Value detail
 pub fn detail(key: &str, val: &str) {
-    println!("  {:<8} {}", dim(key), val);
+    status_line(format_args!("  {:<8} {}", dim(key), val));
 }
 
 /// ⚠ Warning (stderr)
 pub fn warn(msg: &str) {
-    let _ = writeln!(std::io::stderr(), "{} {}", yellow("⚠"), dim(msg));
+    let _ = writeln!(
+        std::io::stderr(),
+        "{} {}",
+        yellow(glyph("⚠", "[WARN]")),
+        dim(msg)
+    );
 }
 
 // ─── List Formatting ────────────────────────────────────────────────────
 pub fn format_envs(items: &[EnvItem]) -> String {
     let mut out = String::new();
-    out.push_str(&format!("\n{} {}\n", dim("╭─"), bold("Environments")));
+    out.push_str(&format!(
+        "\n{} {}\n",
+        dim(glyph("╭─", "+-")),
+        bold("Environments")
+    ));
     if items.is_empty() {
         out.push_str(&format!(
             "{} {}\n",
-            dim("│ "),
+            dim(glyph("│ ", "| ")),
             dim("  (no environments found)")
         ));
     } else {
@@ -110,13 +183,16 @@ pub fn format_envs(items: &[EnvItem]) -> String {
 
             let mut tags = Vec::new();
             if item.is_current {
-                tags.push(cyan("◉ current"));
+                tags.push(cyan(glyph("◉ current", "* current")));
             }
             if item.is_default {
-                tags.push(yellow("★ default"));
+                tags.push(yellow(glyph("★ default", "+ default")));
             }
             if item.missing_key {
-                tags.push(yellow("⚠ no key"));
+                tags.push(yellow(glyph("⚠ no key", "! no key")));
+            }
+            if item.is_jre {
+                tags.push(yellow(glyph("⚠ jre", "! jre")));
             }
             let tag_str = if tags.is_empty() {
                 String::new()
@@ -133,7 +209,7 @@ pub fn format_envs(items: &[EnvItem]) -> String {
 
             out.push_str(&format!(
                 "{}   {}{}{}{}\n",
-                dim("│"),
+                dim(glyph("│", "|")),
                 name_str,
                 dim(&item.description),
                 extra_display,
@@ -143,7 +219,10 @@ pub fn format_envs(items: &[EnvItem]) -> String {
     }
     out.push_str(&format!(
         "{}\n",
-        dim("╰────────────────────────────────────────")
+        dim(glyph(
+            "╰────────────────────────────────────────",
+            "+----------------------------------------"
+        ))
     ));
     out
 }
@@ -153,28 +232,51 @@ pub fn format_history(items: &[HistoryItem]) -> String {
     let mut out = String::new();
     out.push_str(&format!(
         "\n{} {}\n",
-        dim("╭─"),
+        dim(glyph("╭─", "+-")),
         bold("Recent switch history")
     ));
     if items.is_empty() {
-        out.push_str(&format!("{} {}\n", dim("│ "), dim("  (no history found)")));
+        out.push_str(&format!(
+            "{} {}\n",
+            dim(glyph("│ ", "| ")),
+            dim("  (no history found)")
+        ));
     } else {
         for item in items {
             let from_str = item.from.as_deref().unwrap_or("None");
             out.push_str(&format!(
                 "{}  {}  {:<8}  {} {} {}\n",
-                dim("│"),
+                dim(glyph("│", "|")),
                 dim(&item.timestamp),
                 cyan(&item.env_type),
                 from_str,
-                dim("→"),
+                dim(glyph("→", "->")),
                 bold(&item.to)
             ));
         }
     }
     out.push_str(&format!(
         "{}\n",
-        dim("╰────────────────────────────────────────")
+        dim(glyph(
+            "╰────────────────────────────────────────",
+            "+----------------------------------------"
+        ))
     ));
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn no_color_disables_color_even_on_a_tty() {
+        assert!(color_enabled(None, Some("xterm-256color"), true));
+        assert!(!color_enabled(Some(OsStr::new("1")), Some("xterm"), true));
+        // 规范:空值不算设置
+        assert!(color_enabled(Some(OsStr::new("")), Some("xterm"), true));
+        assert!(!color_enabled(None, Some("dumb"), true));
+        assert!(!color_enabled(None, Some("xterm"), false));
+    }
+}
@@ -0,0 +1,305 @@
+//! `fnva export shell-env` —— 列出当前进程里 fnva 相关的环境变量,并与会话记录对账。
+//!
+//! 会话记录(current_envs.toml)是 fnva 认为激活的环境,`FNVA_CURRENT_*` / `JAVA_HOME`
+//! 是 shell 里实际生效的值;两者不一致(另一个终端切换过、手动改了变量等)即为漂移。
+
+use crate::core::environment_manager::EnvironmentType;
+use crate::environments::cc::environment_manager::{is_secret_name, mask_secret};
+use crate::environments::java::environment_manager::same_java_home;
+use crate::infrastructure::config::Config;
+use crate::infrastructure::shell::current_envs::CurrentEnvsFile;
+use serde::Serialize;
+
+/// 按名称收录的变量(前缀 `FNVA_` / `ANTHROPIC_` 之外)
+const EXACT_VARS: &[&str] = &["JAVA_HOME", "MAVEN_HOME", "M2_HOME", "MAVEN_OPTS"];
+const PREFIXES: &[&str] = &["FNVA_", "ANTHROPIC_"];
+
+#[derive(Debug, Serialize)]
+pub struct Variable {
+    pub name: String,
+    /// 密钥类变量已掩码
+    pub value: String,
+}
+
+/// 一种环境类型的对账结果
+#[derive(Debug, Serialize)]
+pub struct Tracking {
+    pub env_type: EnvironmentType,
+    /// current_envs.toml 记录的环境
+    pub session: Option<String>,
+    /// shell 里的 `FNVA_CURRENT_*`
+    pub shell: Option<String>,
+    pub in_sync: bool,
+    /// 漂移原因
+    pub drift: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShellEnvReport {
+    pub variables: Vec<Variable>,
+    pub tracking: Vec<Tracking>,
+}
+
+impl ShellEnvReport {
+    pub fn has_drift(&self) -> bool {
+        self.tracking.iter().any(|t| !t.in_sync)
+    }
+}
+
+fn is_tracked(name: &str) -> bool {
+    EXACT_VARS.contains(&name) || PREFIXES.iter().any(|p| name.starts_with(p))
+}
+
+fn current_var(env_type: EnvironmentType) -> &'static str {
+    match env_type {
+        EnvironmentType::Java => "FNVA_CURRENT_JAVA",
+        EnvironmentType::Maven => "FNVA_CURRENT_MAVEN",
+        EnvironmentType::Cc => "FNVA_CURRENT_CC",
+    }
+}
+
+/// 由进程环境变量、会话记录和配置生成报告。
+pub fn build(
+    vars: &[(String, String)],
+    session: &CurrentEnvsFile,
+    config: &Config,
+) -> ShellEnvReport {
+    let get = |name: &str| {
+        vars.iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.clone())
+            .filter(|v| !v.is_empty())
+    };
+
+    let mut variables: Vec<Variable> = vars
+        .iter()
+        .filter(|(name, _)| is_tracked(name))
+        .map(|(name, value)| Variable {
+            name: name.clone(),
+            value: if is_secret_name(name) {
+                mask_secret(value)
+            } else {
+                value.clone()
+            },
+        })
+        .collect();
+    variables.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let tracking = [
+        (EnvironmentType::Java, &session.java),
+        (EnvironmentType::Maven, &session.maven),
+        (EnvironmentType::Cc, &session.cc),
+    ]
+    .into_iter()
+    .map(|(env_type, recorded)| {
+        let shell = get(current_var(env_type));
+        let drift = match (recorded, &shell) {
+            (Some(s), Some(e)) if s != e => Some(format!(
+                "session says '{s}', shell has {}='{e}'",
+                current_var(env_type)
+            )),
+            (Some(s), None) => Some(format!(
+                "session says '{s}', but {} is not set in this shell",
+                current_var(env_type)
+            )),
+            (None, Some(e)) => Some(format!(
+                "{}='{e}' is set, but the session has no current {env_type} environment",
+                current_var(env_type)
+            )),
+            _ if env_type == EnvironmentType::Java => recorded
+                .as_deref()
+                .and_then(|name| java_home_drift(name, get("JAVA_HOME"), config)),
+            _ => None,
+        };
+        Tracking {
+            env_type,
+            session: recorded.clone(),
+            shell,
+            in_sync: drift.is_none(),
+            drift,
+        }
+    })
+    .collect();
+
+    ShellEnvReport {
+        variables,
+        tracking,
+    }
+}
+
+/// 名称一致时再核对 `JAVA_HOME` 是否指向该环境的目录。
+fn java_home_drift(name: &str, java_home: Option<String>, config: &Config) -> Option<String> {
+    let expected = &config.get_java_env(name)?.java_home;
+    match java_home {
+        Some(actual) if same_java_home(expected, &actual) => None,
+        Some(actual) => Some(format!(
+            "JAVA_HOME is '{actual}', but '{name}' lives at '{expected}'"
+        )),
+        None => Some(format!("JAVA_HOME is not set, expected '{expected}'")),
+    }
+}
+
+/// 读取当前进程环境并打印报告。
+pub fn run(json: bool) -> Result<(), String> {
+    let vars: Vec<(String, String)> = std::env::vars().collect();
+    let session = CurrentEnvsFile::read()?;
+    let config = Config::load()?;
+    let report = build(&vars, &session, &config);
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?
+        );
+        return Ok(());
+    }
+
+    println!("{}", crate::cli::print::bold("Variables"));
+    if report.variables.is_empty() {
+        println!("  {}", crate::cli::print::dim("(none set)"));
+    }
+    let width = report
+        .variables
+        .iter()
+        .map(|v| v.name.len())
+        .max()
+        .unwrap_or(0);
+    for var in &report.variables {
+        println!("  {:<width$}  {}", var.name, var.value);
+    }
+
+    println!("\n{}", crate::cli::print::bold("Session vs shell"));
+    for t in &report.tracking {
+        let shown = t.session.as_deref().unwrap_or("-");
+        match &t.drift {
+            None => println!(
+                "  {} {:<6} {shown}",
+                crate::cli::print::green(crate::cli::print::glyph("✓", "[OK]")),
+                t.env_type
+            ),
+            Some(reason) => println!(
+                "  {} {:<6} drift: {reason}",
+                crate::cli::print::red(crate::cli::print::glyph("✗", "[DRIFT]")),
+                t.env_type
+            ),
+        }
+    }
+    if report.has_drift() {
+        println!(
+            "\n{}",
+            crate::cli::print::dim(
+                "Re-run `fnva <type> use <name>` in this shell to bring it back in sync."
+            )
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn config() -> Config {
+        toml::from_str(
+            "[[java_environments]]\nname = \"jdk17\"\njava_home = \"/opt/jdk17\"\n\
+             [[java_environments]]\nname = \"jdk11\"\njava_home = \"/opt/jdk11\"\n",
+        )
+        .unwrap()
+    }
+
+    fn tracking(report: &ShellEnvReport, env_type: EnvironmentType) -> &Tracking {
+        report
+            .tracking
+            .iter()
+            .find(|t| t.env_type == env_type)
+            .unwrap()
+    }
+
+    #[test]
+    fn lists_only_fnva_related_variables_with_secrets_masked() {
+        let report = build(
+            &vars(&[
+                ("PATH", "/usr/bin"),
+                ("JAVA_HOME", "/opt/jdk17"),
+                ("FNVA_CURRENT_JAVA", "jdk17"),
+                ("ANTHROPIC_AUTH_TOKEN", "sk-0123456789abcd"),
+            ]),
+            &CurrentEnvsFile::default(),
+            &config(),
+        );
+        let names: Vec<(&str, &str)> = report
+            .variables
+            .iter()
+            .map(|v| (v.name.as_str(), v.value.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("ANTHROPIC_AUTH_TOKEN", "****abcd"),
+                ("FNVA_CURRENT_JAVA", "jdk17"),
+                ("JAVA_HOME", "/opt/jdk17"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_drift_between_session_and_environment() {
+        let session = CurrentEnvsFile {
+            java: Some("jdk17".to_string()),
+            cc: Some("glm".to_string()),
+            maven: None,
+        };
+
+        let in_sync = build(
+            &vars(&[
+                ("FNVA_CURRENT_JAVA", "jdk17"),
+                ("JAVA_HOME", "/opt/jdk17/"),
+                ("FNVA_CURRENT_CC", "glm"),
+            ]),
+            &session,
+            &config(),
+        );
+        assert!(!in_sync.has_drift(), "{:?}", in_sync.tracking);
+
+        let drifted = build(
+            &vars(&[
+                ("FNVA_CURRENT_JAVA", "jdk11"),
+                ("JAVA_HOME", "/opt/jdk11"),
+                ("FNVA_CURRENT_MAVEN", "mvn3"),
+            ]),
+            &session,
+            &config(),
+        );
+        let java = tracking(&drifted, EnvironmentType::Java);
+        assert_eq!(java.shell.as_deref(), Some("jdk11"));
+        assert!(java.drift.as_deref().unwrap().contains("'jdk17'"));
+        assert!(!tracking(&drifted, EnvironmentType::Maven).in_sync);
+        assert!(tracking(&drifted, EnvironmentType::Cc)
+            .drift
+            .as_deref()
+            .unwrap()
+            .contains("not set"));
+
+        // 名称一致但 JAVA_HOME 被手动改过
+        let moved = build(
+            &vars(&[("FNVA_CURRENT_JAVA", "jdk17"), ("JAVA_HOME", "/opt/jdk11")]),
+            &CurrentEnvsFile {
+                java: Some("jdk17".to_string()),
+                ..Default::default()
+            },
+            &config(),
+        );
+        assert!(tracking(&moved, EnvironmentType::Java)
+            .drift
+            .as_deref()
+            .unwrap()
+            .contains("JAVA_HOME"));
+    }
+}
@@ -0,0 +1,126 @@
+//! `FNVA_STRUCTURED_STATUS`:切换后在旁路输出一行 JSON 状态,供 prompt hook 等工具判断成败。
+//!
+//! stdout 仍是给 `eval` 的脚本,状态行不混入其中:
+//! - `FNVA_STRUCTURED_STATUS=3`:写到 fd 3(Unix,父进程需已打开,否则退回 stderr)
+//! - 其他非空且非 `0` 的值:写到 stderr
+
+use crate::core::environment_manager::{EnvironmentType, SwitchResult};
+use crate::error::AppError;
+use serde::Serialize;
+use std::io::Write;
+
+/// 开关环境变量
+pub const STRUCTURED_STATUS_ENV_VAR: &str = "FNVA_STRUCTURED_STATUS";
+
+/// 状态行内容(不含脚本)
+#[derive(Serialize)]
+struct StatusLine<'a> {
+    name: &'a str,
+    env_type: EnvironmentType,
+    success: bool,
+    error: Option<&'a str>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Channel {
+    Fd3,
+    Stderr,
+}
+
+fn channel(value: Option<&str>) -> Option<Channel> {
+    match value.map(str::trim) {
+        None | Some("") | Some("0") => None,
+        Some("3") => Some(Channel::Fd3),
+        Some(_) => Some(Channel::Stderr),
+    }
+}
+
+fn render(result: &SwitchResult) -> String {
+    let line = StatusLine {
+        name: &result.name,
+        env_type: result.env_type,
+        success: result.success,
+        error: result.error.as_deref(),
+    };
+    serde_json::to_string(&line).unwrap_or_default()
+}
+
+/// 按 `FNVA_STRUCTURED_STATUS` 输出状态行;未开启时什么也不做,写入失败静默忽略。
+pub fn emit(result: &SwitchResult) {
+    let Some(channel) = channel(std::env::var(STRUCTURED_STATUS_ENV_VAR).ok().as_deref()) else {
+        return;
+    };
+    let line = format!("{}\n", render(result));
+    if channel == Channel::Fd3 && write_fd3(&line) {
+        return;
+    }
+    let _ = std::io::stderr().write_all(line.as_bytes());
+}
+
+/// 输出一次切换的状态(出错时记为失败)后原样返回结果。
+pub fn observe(
+    env_type: EnvironmentType,
+    name: &str,
+    result: Result<SwitchResult, AppError>,
+) -> Result<SwitchResult, AppError> {
+    match &result {
+        Ok(switched) => emit(switched),
+        Err(e) => emit(&SwitchResult {
+            name: name.to_string(),
+            env_type,
+            script: String::new(),
+            success: false,
+            error: Some(e.to_string()),
+        }),
+    }
+    result
+}
+
+#[cfg(unix)]
+fn write_fd3(line: &str) -> bool {
+    std::fs::OpenOptions::new()
+        .append(true)
+        .open("/dev/fd/3")
+        .and_then(|mut f| f.write_all(line.as_bytes()))
+        .is_ok()
+}
+
+#[cfg(not(unix))]
+fn write_fd3(_line: &str) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_is_opt_in() {
+        assert_eq!(channel(None), None);
+        assert_eq!(channel(Some("0")), None);
+        assert_eq!(channel(Some(" ")), None);
+        assert_eq!(channel(Some("1")), Some(Channel::Stderr));
+        assert_eq!(channel(Some("3")), Some(Channel::Fd3));
+    }
+
+    #[test]
+    fn status_line_omits_the_script() {
+        let result = SwitchResult {
+            name: "jdk17".to_string(),
+            env_type: EnvironmentType::Java,
+            script: "export JAVA_HOME=/opt/jdk17\n".to_string(),
+            success: true,
+            error: None,
+        };
+        let line: serde_json::Value = serde_json::from_str(&render(&result)).unwrap();
+        assert_eq!(
+            line,
+            serde_json::json!({
+                "name": "jdk17",
+                "env_type": "Java",
+                "success": true,
+                "error": null
+            })
+        );
+    }
+}
@@ -21,6 +21,13 @@ impl std::fmt::Display for EnvironmentType {
     }
 }
 
+/// 单次切换的命令行选项,不写回配置;不适用的环境忽略。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SwitchOptions {
+    /// 不清理 PATH 里原有的 Java 条目,只把新的 bin 放到最前(`java use --no-clean`)
+    pub keep_path: bool,
+}
+
 /// 环境信息动态类型
 #[derive(Debug, Clone, Serialize)]
 pub struct DynEnvironment {
@@ -52,6 +59,16 @@ pub trait EnvironmentManager: Send + Sync {
     /// 使用环境（生成 shell 脚本）
     fn use_env(&mut self, name: &str, shell_type: Option<ShellType>) -> Result<String, AppError>;
 
+    /// 带本次切换选项生成脚本;默认忽略选项。
+    fn use_env_with(
+        &mut self,
+        name: &str,
+        shell_type: Option<ShellType>,
+        _options: SwitchOptions,
+    ) -> Result<String, AppError> {
+        self.use_env(name, shell_type)
+    }
+
     /// 获取当前环境名称
     fn get_current(&self) -> Result<Option<String>, AppError>;
 
@@ -66,6 +83,21 @@ pub trait EnvironmentManager: Send + Sync {
 
     /// 获取环境的详细信息
     fn get_details(&self, name: &str) -> Result<Option<DynEnvironment>, AppError>;
+
+    /// 校验环境是否真正可用(路径/二进制/凭据),返回不可用的原因。
+    ///
+    /// 切换前调用,避免生成指向已删除目录的脚本;默认不做额外校验。
+    fn validate(&self, _name: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// 与 shell 无关的变量表(`env-file` 用),按输出顺序排列;默认不支持。
+    fn env_vars(&self, _name: &str) -> Result<Vec<(String, String)>, AppError> {
+        Err(AppError::validation(
+            &format!("{}", self.environment_type()),
+            "dotenv export is not supported for this environment type",
+        ))
+    }
 }
 
 /// 环境信息的通用接口
@@ -1,6 +1,7 @@
 pub mod environment_manager;
 pub mod presentation;
 pub mod session;
+pub mod switch_hook;
 pub mod switcher;
 
 pub use environment_manager::*;
@@ -4,6 +4,7 @@
 //! (`print::format_envs`/`format_history`)消费渲染。下沉到 core 是为了消除
 //! core → cli 的反向依赖。
 
+use crate::core::environment_manager::EnvironmentType;
 use serde::Serialize;
 
 /// 输出格式
@@ -11,6 +12,8 @@ use serde::Serialize;
 pub enum OutputFormat {
     Text,
     Json,
+    /// 按列对齐的表格(`--format table`)
+    Table,
 }
 
 /// 环境列表的一个条目(供 cli 层 `format_envs` 渲染,或 Json 序列化)。
@@ -18,11 +21,26 @@ pub enum OutputFormat {
 pub struct EnvItem {
     pub name: String,
     pub description: String,
+    /// 安装路径(Java/Maven)或 base_url(CC),供表格 Path 列使用。
+    pub path: String,
+    /// 版本(Java/Maven 已知时)或模型(CC),供表格 Version 列使用。
+    pub version: Option<String>,
     pub extra: Option<String>,
     pub is_current: bool,
     pub is_default: bool,
     /// 缺少必要凭据(CC 没配 api_key)→ 渲染成 `⚠ no key` 标签,提醒该环境导出后无法鉴权。
     pub missing_key: bool,
+    /// Java 环境只有 `bin/java` 没有 `bin/javac`(JRE)→ 渲染成 `⚠ jre` 标签,编译类任务会失败。
+    pub is_jre: bool,
+}
+
+/// `list_all` 的一个类型分组;该类型 `list` 失败时 `environments` 为空、`error` 记录原因。
+#[derive(Serialize, Clone, Debug)]
+pub struct EnvGroup {
+    pub environment_type: EnvironmentType,
+    pub environments: Vec<EnvItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
 /// 切换历史的一个条目(供 cli 层 `format_history` 渲染)。
@@ -32,6 +50,7 @@ pub struct HistoryItem {
     pub env_type: String,
     pub from: Option<String>,
     pub to: String,
+    pub reason: Option<String>,
 }
 
 /// 扫描结果项(供 switcher 打印;各 scanner 自带 import 命令)。
@@ -199,7 +199,7 @@ impl HistoryManager {
 
         // 加载现有历史
         if let Err(e) = history_manager.load_history() {
-            eprintln!("⚠  Failed to load history: {e}");
+            crate::cli::print::warn(&format!("Failed to load history: {e}"));
         }
 
         Ok(history_manager)
@@ -252,7 +252,9 @@ impl HistoryManager {
         }) {
             Ok(content) => content,
             Err(e) => {
-                eprintln!("⚠  Failed to serialize history: {e}. Skipping history save.");
+                crate::cli::print::warn(&format!(
+                    "Failed to serialize history: {e}. Skipping history save."
+                ));
                 return Ok(());
             }
         };
@@ -288,7 +290,7 @@ impl HistoryManager {
 
         // 尝试保存历史，但不影响主要功能
         if let Err(e) = self.save_history() {
-            eprintln!("⚠  Failed to save history: {e}");
+            crate::cli::print::warn(&format!("Failed to save history: {e}"));
         }
 
         Ok(())
@@ -312,11 +314,36 @@ impl HistoryManager {
             .collect()
     }
 
+    /// 上一个环境(`use -`):最近一次真正换了环境的切换之前的那个;
+    /// 跳过重复切到同一环境的记录(`record_noop_switches`)。
+    pub fn previous_env(&self, env_type: EnvironmentType) -> Option<&str> {
+        self.history
+            .iter()
+            .rev()
+            .filter(|record| record.env_type == env_type)
+            .find(|record| record.old_env.as_deref() != Some(record.new_env.as_str()))
+            .and_then(|record| record.old_env.as_deref())
+    }
+
     /// 清除历史记录
     pub fn clear_history(&mut self) -> Result<(), String> {
         self.history.clear();
         self.save_history()
     }
+
+    /// 清除全部(`None`)或某一类型的历史,返回删除的条数。
+    pub fn clear_history_for(
+        &mut self,
+        env_type: Option<EnvironmentType>,
+    ) -> Result<usize, String> {
+        let before = self.history.len();
+        match env_type {
+            Some(t) => self.history.retain(|record| record.env_type != t),
+            None => self.history.clear(),
+        }
+        self.save_history()?;
+        Ok(before - self.history.len())
+    }
 }
 
 #[cfg(test)]
@@ -383,4 +410,67 @@ mod tests {
         let java_only = h.get_history_for_env(EnvironmentType::Java);
         assert_eq!(java_only.len(), 2);
     }
+
+    #[test]
+    fn previous_env_toggles_and_skips_noop_records() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let mut h = HistoryManager::new(100).unwrap();
+        assert_eq!(h.previous_env(EnvironmentType::Java), None);
+
+        h.record_switch(EnvironmentType::Java, None, "j17".to_string(), None)
+            .unwrap();
+        assert_eq!(h.previous_env(EnvironmentType::Java), None);
+
+        let switch = |h: &mut HistoryManager, from: &str, to: &str| {
+            h.record_switch(
+                EnvironmentType::Java,
+                Some(from.to_string()),
+                to.to_string(),
+                None,
+            )
+            .unwrap();
+        };
+        switch(&mut h, "j17", "j21");
+        assert_eq!(h.previous_env(EnvironmentType::Java), Some("j17"));
+        switch(&mut h, "j21", "j17");
+        assert_eq!(h.previous_env(EnvironmentType::Java), Some("j21"));
+        // 重复切到当前环境、其他类型的切换都不影响
+        switch(&mut h, "j17", "j17");
+        h.record_switch(
+            EnvironmentType::Cc,
+            Some("a".to_string()),
+            "b".to_string(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(h.previous_env(EnvironmentType::Java), Some("j21"));
+    }
+
+    #[test]
+    fn history_clear_by_type_keeps_other_types() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let mut h = HistoryManager::new(100).unwrap();
+        for (t, name) in [
+            (EnvironmentType::Java, "j17"),
+            (EnvironmentType::Cc, "cc1"),
+            (EnvironmentType::Java, "j21"),
+        ] {
+            h.record_switch(t, None, name.to_string(), None).unwrap();
+        }
+
+        assert_eq!(h.clear_history_for(Some(EnvironmentType::Java)).unwrap(), 2);
+        let h = HistoryManager::new(100).unwrap();
+        let recent = h.get_recent_history(10);
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].new_env, "cc1");
+
+        let mut h = h;
+        assert_eq!(h.clear_history_for(None).unwrap(), 1);
+        assert!(HistoryManager::new(100)
+            .unwrap()
+            .get_recent_history(10)
+            .is_empty());
+    }
 }
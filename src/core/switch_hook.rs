@@ -0,0 +1,128 @@
+//! 切换成功后的用户钩子(`on_switch_command` / `[on_switch_commands.<type>]`)。
+//!
+//! 命令交给系统 shell 执行,新环境通过 `FNVA_SWITCH_*` 变量传入。钩子的输出转到
+//! stderr(stdout 会被 shell 集成 source);超时即结束进程,失败只警告,不影响切换。
+
+use crate::core::environment_manager::EnvironmentType;
+use crate::infrastructure::config::Config;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// 钩子最长运行时间,超时后结束进程
+pub const SWITCH_HOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 按配置执行切换钩子;未配置时什么也不做,出错只打印警告。
+pub fn fire_switch_hook(env_type: EnvironmentType, name: &str, previous: Option<&str>) {
+    let Ok(config) = Config::load() else {
+        return;
+    };
+    let Some(command) = config.on_switch_command_for(&env_type.to_string(), name) else {
+        return;
+    };
+    if let Err(e) = run_switch_hook(command, env_type, name, previous, SWITCH_HOOK_TIMEOUT) {
+        crate::cli::print::warn(&format!("on_switch_command failed: {e}"));
+    }
+}
+
+/// 执行钩子命令并等待至多 `timeout`。
+pub fn run_switch_hook(
+    command: &str,
+    env_type: EnvironmentType,
+    name: &str,
+    previous: Option<&str>,
+    timeout: Duration,
+) -> Result<(), String> {
+    let mut cmd = if cfg!(windows) {
+        let mut c = Command::new("cmd");
+        c.arg("/C").arg(command);
+        c
+    } else {
+        let mut c = Command::new("sh");
+        c.arg("-c").arg(command);
+        c
+    };
+    cmd.env("FNVA_SWITCH_ENV_TYPE", env_type.to_string())
+        .env("FNVA_SWITCH_ENV_NAME", name)
+        .env_remove("FNVA_SWITCH_PREVIOUS_ENV")
+        .stdin(Stdio::null())
+        .stdout(Stdio::from(std::io::stderr()))
+        .stderr(Stdio::inherit());
+    if let Some(previous) = previous {
+        cmd.env("FNVA_SWITCH_PREVIOUS_ENV", previous);
+    }
+
+    let mut child = cmd.spawn().map_err(|e| format!("failed to start: {e}"))?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => return Err(format!("exited with {status}")),
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {}s", timeout.as_secs_f32()));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(20)),
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hook_receives_switch_env_vars() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let out = tmp.path().join("hook.out");
+        let command = format!(
+            "printf '%s|%s|%s' \"$FNVA_SWITCH_ENV_TYPE\" \"$FNVA_SWITCH_ENV_NAME\" \"${{FNVA_SWITCH_PREVIOUS_ENV-none}}\" > '{}'",
+            out.display()
+        );
+        run_switch_hook(
+            &command,
+            EnvironmentType::Java,
+            "jdk21",
+            Some("jdk17"),
+            SWITCH_HOOK_TIMEOUT,
+        )
+        .unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "java|jdk21|jdk17");
+
+        run_switch_hook(
+            &command,
+            EnvironmentType::Maven,
+            "mvn39",
+            None,
+            SWITCH_HOOK_TIMEOUT,
+        )
+        .unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "maven|mvn39|none");
+    }
+
+    #[test]
+    fn failing_and_slow_hooks_are_reported() {
+        let err = run_switch_hook(
+            "exit 3",
+            EnvironmentType::Java,
+            "jdk21",
+            None,
+            SWITCH_HOOK_TIMEOUT,
+        )
+        .unwrap_err();
+        assert!(err.contains("exit"), "{err}");
+
+        let started = Instant::now();
+        let err = run_switch_hook(
+            "sleep 10",
+            EnvironmentType::Java,
+            "jdk21",
+            None,
+            Duration::from_millis(200),
+        )
+        .unwrap_err();
+        assert!(err.contains("timed out"), "{err}");
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -1,12 +1,15 @@
-use crate::core::environment_manager::{EnvironmentManager, EnvironmentType, SwitchResult};
-use crate::core::presentation::{EnvItem, HistoryItem, OutputFormat};
+use crate::cli::print;
+use crate::core::environment_manager::{
+    DynEnvironment, EnvironmentManager, EnvironmentType, SwitchOptions, SwitchResult,
+};
+use crate::core::presentation::{EnvGroup, EnvItem, HistoryItem, OutputFormat};
 use crate::core::session::{HistoryManager, SessionManager, SwitchHistory};
+use crate::environments::cc::environment_manager::is_secret_name;
 use crate::error::{
     option_with_context, safe_to_json, safe_to_json_pretty, AppError, AppResult, ResultExt,
     SafeMutex,
 };
 use crate::infrastructure::config::Config;
-use crate::infrastructure::shell::current_envs::CurrentEnvsFile;
 use crate::infrastructure::shell::{script_factory::ScriptGenerator, ShellType};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -28,9 +31,13 @@ impl EnvironmentSwitcher {
         let session_manager = SessionManager::new().map_err(|e| AppError::Config {
             message: format!("Failed to create session manager: {e}"),
         })?;
-        let history_manager = HistoryManager::new(100).map_err(|e| AppError::Internal {
-            message: format!("Failed to create history manager: {e}"),
-        })?;
+        let history_limit = Config::load()
+            .map(|c| c.history_limit)
+            .unwrap_or(crate::infrastructure::config::DEFAULT_HISTORY_LIMIT);
+        let history_manager =
+            HistoryManager::new(history_limit).map_err(|e| AppError::Internal {
+                message: format!("Failed to create history manager: {e}"),
+            })?;
 
         Ok(Self {
             managers: HashMap::new(),
@@ -55,6 +62,119 @@ impl EnvironmentSwitcher {
         name: &str,
         shell_type: Option<ShellType>,
         reason: Option<String>,
+    ) -> AppResult<SwitchResult> {
+        self.switch_inner(
+            env_type,
+            name,
+            shell_type,
+            reason,
+            SwitchOptions::default(),
+            true,
+        )
+        .await
+    }
+
+    /// 带本次切换选项的 [`switch_environment`](Self::switch_environment)。
+    pub async fn switch_environment_with(
+        &self,
+        env_type: EnvironmentType,
+        name: &str,
+        shell_type: Option<ShellType>,
+        reason: Option<String>,
+        options: SwitchOptions,
+    ) -> AppResult<SwitchResult> {
+        self.switch_inner(env_type, name, shell_type, reason, options, true)
+            .await
+    }
+
+    /// 临时切换:只生成当前 shell 的脚本,不写 session / current_envs / 历史,
+    /// 新 shell 仍恢复原来的环境(`use --temporary`)。
+    pub async fn switch_environment_temporary(
+        &self,
+        env_type: EnvironmentType,
+        name: &str,
+        shell_type: Option<ShellType>,
+    ) -> AppResult<SwitchResult> {
+        self.switch_inner(
+            env_type,
+            name,
+            shell_type,
+            None,
+            SwitchOptions::default(),
+            false,
+        )
+        .await
+    }
+
+    /// 带本次切换选项的 [`switch_environment_temporary`](Self::switch_environment_temporary)。
+    pub async fn switch_environment_temporary_with(
+        &self,
+        env_type: EnvironmentType,
+        name: &str,
+        shell_type: Option<ShellType>,
+        options: SwitchOptions,
+    ) -> AppResult<SwitchResult> {
+        self.switch_inner(env_type, name, shell_type, None, options, false)
+            .await
+    }
+
+    /// 只记录:把 `name` 写为当前环境(session / current_envs,换了环境时记历史),
+    /// 不生成脚本、不触发切换钩子;由之后的 `use` 或新 shell 的恢复真正生效(`use --record-only`)。
+    pub async fn record_environment(
+        &self,
+        env_type: EnvironmentType,
+        name: &str,
+        reason: Option<String>,
+    ) -> AppResult<()> {
+        let manager = option_with_context(
+            self.managers.get(&env_type),
+            AppError::env_not_found(&format!("{env_type:?}")),
+            "finding environment manager when recording environment",
+        )?;
+        let old_env = {
+            let manager_guard = manager.lock().await;
+            if manager_guard
+                .get(name)
+                .with_context(&format!("looking up {env_type} environment '{name}'"))?
+                .is_none()
+            {
+                return Err(AppError::not_found(&format!(
+                    "{env_type} environment '{name}'"
+                )));
+            }
+            manager_guard
+                .get_current()
+                .with_context("getting current environment")?
+        };
+
+        {
+            let mut session_manager = self.session_manager.lock()?;
+            session_manager
+                .set_current_environment(env_type, name)
+                .map_err(|e| AppError::Config {
+                    message: format!("Failed to update session state: {e}"),
+                })?;
+        }
+        if old_env.as_deref() != Some(name) {
+            let mut history_manager = self.history_manager.lock()?;
+            history_manager
+                .record_switch(env_type, old_env, name.to_string(), reason)
+                .map_err(|e| AppError::Internal {
+                    message: format!("Failed to record switch history: {e}"),
+                })?;
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self, shell_type, reason, options), fields(env_type = %env_type))]
+    async fn switch_inner(
+        &self,
+        env_type: EnvironmentType,
+        name: &str,
+        shell_type: Option<ShellType>,
+        reason: Option<String>,
+        options: SwitchOptions,
+        persist: bool,
     ) -> AppResult<SwitchResult> {
         // 获取环境管理器
         let manager = option_with_context(
@@ -89,15 +209,66 @@ impl EnvironmentSwitcher {
             });
         }
 
+        // 已是当前环境且变量都已生效(on-cd hook 每个提示符都会触发):跳过模板渲染
+        if old_env.as_deref() == Some(name) {
+            let active = {
+                let manager_guard = manager.lock().await;
+                manager_guard
+                    .env_vars(name)
+                    .is_ok_and(|vars| env_vars_active(&vars, |k| std::env::var(k).ok()))
+            };
+            if active {
+                tracing::debug!("environment already active, skipping switch script");
+                let record = Config::load().is_ok_and(|c| c.record_noop_switches);
+                if persist && record {
+                    let mut history_manager = self.history_manager.lock()?;
+                    history_manager
+                        .record_switch(env_type, old_env, name.to_string(), reason)
+                        .map_err(|e| AppError::Internal {
+                            message: format!("Failed to record switch history: {e}"),
+                        })?;
+                }
+                return Ok(SwitchResult {
+                    name: name.to_string(),
+                    env_type,
+                    script: noop_script(shell_type, env_type, name),
+                    success: true,
+                    error: None,
+                });
+            }
+        }
+
+        // 校验环境确实可用(路径/二进制/凭据),不可用时不生成脚本
+        {
+            let manager_guard = manager.lock().await;
+            if let Err(reason) = manager_guard.validate(name) {
+                tracing::debug!(%reason, "environment validation failed");
+                return Err(AppError::validation(
+                    &format!("{env_type} environment '{name}'"),
+                    &reason,
+                ));
+            }
+        }
+
         // 生成切换脚本（需要可变借用）
         let script = {
             let mut manager_guard = manager.lock().await;
             manager_guard
-                .use_env(name, shell_type)
+                .use_env_with(name, shell_type, options)
                 .with_context(&format!("switching to {env_type} environment '{name}'"))?
         };
 
-        // 更新会话状态
+        if !persist {
+            return Ok(SwitchResult {
+                name: name.to_string(),
+                env_type,
+                script,
+                success: true,
+                error: None,
+            });
+        }
+
+        // 更新会话状态(同时写 current_envs.toml,shell hook 恢复也读这里)
         {
             let mut session_manager = self.session_manager.lock()?;
             session_manager
@@ -107,11 +278,9 @@ impl EnvironmentSwitcher {
                 })?;
         }
 
-        // Persist to current_envs.toml for shell hook auto-restore
-        {
-            if let Err(e) = CurrentEnvsFile::write(env_type, name) {
-                eprintln!("⚠  Failed to update current_envs.toml: {e}");
-            }
+        // 真正换了环境才触发钩子(新 shell 恢复同一环境时不触发)
+        if old_env.as_deref() != Some(name) {
+            crate::core::switch_hook::fire_switch_hook(env_type, name, old_env.as_deref());
         }
 
         // 记录历史
@@ -192,9 +361,6 @@ impl EnvironmentSwitcher {
                         .map_err(|e| AppError::Config {
                             message: format!("Failed to clear current environment: {e}"),
                         })?;
-                    if let Err(e) = CurrentEnvsFile::clear(env_type) {
-                        eprintln!("⚠  Failed to clear current_envs.toml: {e}");
-                    }
                 }
             }
         }
@@ -225,7 +391,7 @@ impl EnvironmentSwitcher {
         };
 
         match output_format {
-            OutputFormat::Text => {
+            OutputFormat::Text | OutputFormat::Table => {
                 if let Some(env_name) = current_env {
                     if let Some(env_info) = manager_guard
                         .get(&env_name)
@@ -234,24 +400,31 @@ impl EnvironmentSwitcher {
                         Ok(format!(
                             "Current {} environment: {}\n{}\n",
                             env_type,
-                            env_name,
-                            env_info.description.clone().unwrap_or_default()
+                            print::bold(&print::cyan(&env_name)),
+                            print::dim(&env_info.description.clone().unwrap_or_default())
                         ))
                     } else {
                         Ok(format!(
-                            "Current {env_type} environment: {env_name} (details unavailable)\n"
+                            "Current {env_type} environment: {} {}\n",
+                            print::bold(&print::cyan(&env_name)),
+                            print::dim("(details unavailable)")
                         ))
                     }
                 } else {
-                    Ok(format!("No current {env_type} environment\n"))
+                    Ok(format!(
+                        "{}\n",
+                        print::dim(&format!("No current {env_type} environment"))
+                    ))
                 }
             }
             OutputFormat::Json => {
                 let json_output = if let Some(env_name) = current_env {
-                    if let Some(env_info) = manager_guard
+                    if let Some(mut env_info) = manager_guard
                         .get(&env_name)
                         .with_context("getting environment info")?
                     {
+                        // 这里就是当前环境,不取管理器按 JAVA_HOME 的判断
+                        env_info.is_active = true;
                         serde_json::json!({
                             "environment_type": env_type,
                             "name": env_name,
@@ -276,12 +449,152 @@ impl EnvironmentSwitcher {
         }
     }
 
+    /// 获取当前环境名(无当前环境时为 `None`),供 `current --quiet` 脚本化输出
+    pub async fn get_current_environment_name(
+        &self,
+        env_type: EnvironmentType,
+    ) -> AppResult<Option<String>> {
+        let manager = option_with_context(
+            self.managers.get(&env_type),
+            AppError::env_not_found(&format!("{env_type:?}")),
+            "finding environment manager when getting current environment",
+        )?;
+        let manager_guard = manager.lock().await;
+        manager_guard
+            .get_current()
+            .with_context("getting current environment")
+    }
+
+    /// 重新生成当前环境的切换脚本(`current --shell`),新终端 `eval` 后恢复上次使用的
+    /// 环境;不写会话和历史。没有当前环境时返回只含注释的脚本。
+    pub async fn current_environment_script(
+        &self,
+        env_type: EnvironmentType,
+        shell_type: ShellType,
+    ) -> AppResult<String> {
+        let Some(name) = self.get_current_environment_name(env_type).await? else {
+            return Ok(comment_script(
+                Some(shell_type),
+                &format!("no current {env_type} environment"),
+            ));
+        };
+        let result = self
+            .switch_environment_temporary(env_type, &name, Some(shell_type))
+            .await?;
+        if !result.success {
+            return Err(AppError::Environment {
+                message: result.error.unwrap_or_default(),
+            });
+        }
+        Ok(result.script)
+    }
+
+    /// 生成可脱离 fnva 直接 source 的切换脚本(`env export-script`),不改变当前环境。
+    ///
+    /// 凭据里的 `${VAR}` 引用默认原样保留,由 source 时的 shell 展开;`resolve_secrets`
+    /// 时替换为当前进程里的值(变量未设置则报错)。返回脚本及其是否含有明文凭据
+    /// (配置里直接写的 api_key 等,或展开后的引用)。
+    pub async fn export_script(
+        &self,
+        env_type: EnvironmentType,
+        name: &str,
+        shell_type: ShellType,
+        resolve_secrets: bool,
+    ) -> AppResult<(String, bool)> {
+        let manager = option_with_context(
+            self.managers.get(&env_type),
+            AppError::env_not_found(&format!("{env_type:?}")),
+            "finding environment manager when exporting script",
+        )?;
+        let mut script = manager
+            .lock()
+            .await
+            .use_env(name, Some(shell_type))
+            .with_context(&format!("exporting {env_type} environment '{name}'"))?;
+
+        let config = Config::load().map_err(|e| AppError::Config { message: e })?;
+        let cc_env = match env_type {
+            EnvironmentType::Cc => config.get_cc_env(name),
+            _ => None,
+        };
+        let secrets: Vec<&str> = cc_env
+            .map(|e| {
+                std::iter::once(e.api_key.as_str())
+                    .chain(e.extra_env.values().map(String::as_str))
+                    .collect()
+            })
+            .unwrap_or_default();
+        // 决定文件权限的凭据:api_key 与名称像密钥的 extra_env
+        let sensitive: Vec<&str> = cc_env
+            .map(|e| {
+                std::iter::once(e.api_key.as_str())
+                    .chain(
+                        e.extra_env
+                            .iter()
+                            .filter(|(k, _)| is_secret_name(k))
+                            .map(|(_, v)| v.as_str()),
+                    )
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut resolved = HashMap::new();
+        if resolve_secrets {
+            for var in secrets.iter().flat_map(|v| env_var_refs(v)) {
+                let value = std::env::var(var).map_err(|_| {
+                    AppError::validation(
+                        "resolve-secrets",
+                        &format!("environment variable {var} is not set"),
+                    )
+                })?;
+                script = script.replace(&format!("${{{var}}}"), &value);
+                resolved.insert(var, value);
+            }
+        } else if secrets
+            .first()
+            .is_some_and(|key| !key.trim().is_empty() && !key.contains("${"))
+        {
+            print::warn(&format!(
+                "the script contains the plain api_key of '{name}'; \
+                 set api_key = \"${{VAR}}\" in the config to keep it out of the file"
+            ));
+        }
+        let has_secrets = sensitive.iter().any(|value| {
+            let value = env_var_refs(value)
+                .into_iter()
+                .fold(value.to_string(), |v, var| match resolved.get(var) {
+                    Some(r) => v.replace(&format!("${{{var}}}"), r),
+                    None => v,
+                });
+            !value.trim().is_empty() && !value.contains("${") && script.contains(&value)
+        });
+        Ok((script, has_secrets))
+    }
+
+    /// 渲染环境的 dotenv 文本(`env-file` 命令),不改变当前环境。
+    ///
+    /// dotenv 消费方不会展开 `${PATH}`,追加型的 `PATH` 不输出,只留字面值变量。
+    pub async fn render_env_file(
+        &self,
+        env_type: EnvironmentType,
+        name: &str,
+    ) -> AppResult<String> {
+        let manager = option_with_context(
+            self.managers.get(&env_type),
+            AppError::env_not_found(&format!("{env_type:?}")),
+            "finding environment manager when exporting env file",
+        )?;
+        let mut vars = manager.lock().await.env_vars(name)?;
+        vars.retain(|(key, _)| key != "PATH");
+        Ok(crate::infrastructure::shell::dotenv::format_dotenv(&vars))
+    }
+
     /// 生成 shell 集成脚本
     pub async fn generate_shell_integration(&self, shell_type: ShellType) -> AppResult<String> {
         let current_envs = self.session_manager.lock()?.get_all_current().clone();
 
         let generator = ScriptGenerator::new().map_err(|e| AppError::ScriptGeneration {
-            shell_type: format!("{shell_type:?}"),
+            shell_type: shell_type.to_string(),
             reason: e.to_string(),
         })?;
 
@@ -330,11 +643,23 @@ impl EnvironmentSwitcher {
         Ok(output)
     }
 
-    /// 获取切换历史
+    /// `use -` 的目标:切换历史里当前环境之前的那个环境
+    pub fn previous_environment(&self, env_type: EnvironmentType) -> AppResult<String> {
+        let history_manager = self.history_manager.lock()?;
+        history_manager
+            .previous_env(env_type)
+            .map(str::to_string)
+            .ok_or_else(|| AppError::Environment {
+                message: format!("No previous {env_type} environment to switch back to"),
+            })
+    }
+
+    /// 获取切换历史;时间为带时区偏移的 RFC 3339(`utc` 时统一换算成 UTC)
     pub async fn get_switch_history(
         &self,
         env_type: Option<EnvironmentType>,
         limit: usize,
+        utc: bool,
     ) -> AppResult<Vec<HistoryItem>> {
         let history: Vec<SwitchHistory> = {
             let history_manager = self.history_manager.lock()?;
@@ -360,15 +685,29 @@ impl EnvironmentSwitcher {
         let mut items = Vec::new();
         for record in history {
             items.push(HistoryItem {
-                timestamp: record.timestamp.format("%Y-%m-%d %H:%M").to_string(),
+                timestamp: history_timestamp(record.timestamp, utc),
                 env_type: format!("{}", record.env_type),
                 from: record.old_env.clone(),
                 to: record.new_env.clone(),
+                reason: record.reason.clone(),
             });
         }
         Ok(items)
     }
 
+    /// 清除全部或某一类型的切换历史,返回删除的条数
+    pub async fn clear_switch_history(
+        &self,
+        env_type: Option<EnvironmentType>,
+    ) -> AppResult<usize> {
+        let mut history_manager = self.history_manager.lock()?;
+        history_manager
+            .clear_history_for(env_type)
+            .map_err(|e| AppError::Internal {
+                message: format!("Failed to clear switch history: {e}"),
+            })
+    }
+
     /// 设置默认环境
     pub async fn set_default_environment(
         &self,
@@ -464,70 +803,304 @@ impl EnvironmentSwitcher {
         Ok(default_env)
     }
 
+    /// 依次切换到每个已配置的默认环境(Java → CC → Maven),拼成一份脚本。
+    ///
+    /// 未注册管理器或未设置默认值的类型跳过;单个默认环境切换失败只告警,不影响其余类型。
+    pub async fn switch_all_defaults(&self, shell_type: ShellType) -> AppResult<String> {
+        let mut script = String::new();
+        for env_type in [
+            EnvironmentType::Java,
+            EnvironmentType::Cc,
+            EnvironmentType::Maven,
+        ] {
+            if !self.managers.contains_key(&env_type) {
+                continue;
+            }
+            let Some(name) = self.get_default_environment(env_type).await? else {
+                continue;
+            };
+            match self
+                .switch_environment(
+                    env_type,
+                    &name,
+                    Some(shell_type),
+                    Some("Switch to default environment".to_string()),
+                )
+                .await
+            {
+                Ok(result) if result.success => {
+                    script.push_str(&result.script);
+                    if !script.ends_with('\n') {
+                        script.push('\n');
+                    }
+                }
+                Ok(result) => crate::cli::print::warn(&format!(
+                    "Skipping default {env_type} environment '{name}': {}",
+                    result.error.unwrap_or_default()
+                )),
+                Err(e) => crate::cli::print::warn(&format!(
+                    "Skipping default {env_type} environment '{name}': {e}"
+                )),
+            }
+        }
+        Ok(script)
+    }
+
     /// 列出环境时显示默认环境标记
     pub async fn list_environments_with_default(
         &self,
         env_type: EnvironmentType,
     ) -> AppResult<Vec<EnvItem>> {
+        // 一次性加载配置，避免重复读取
+        let config = Config::load().map_err(|e| AppError::Config {
+            message: format!("Failed to load config: {e}"),
+        })?;
+        self.list_items(env_type, &config).await
+    }
+
+    /// `cc list --provider / --model-contains`:只保留服务商与模型都匹配的 CC 环境。
+    pub async fn list_cc_environments(
+        &self,
+        provider: Option<&str>,
+        model_contains: Option<&str>,
+    ) -> AppResult<Vec<EnvItem>> {
+        let config = Config::load().map_err(|e| AppError::Config {
+            message: format!("Failed to load config: {e}"),
+        })?;
+        let mut items = self.list_items(EnvironmentType::Cc, &config).await?;
+        items.retain(|item| {
+            config
+                .cc_environments
+                .iter()
+                .find(|e| e.name == item.name)
+                .is_some_and(|e| e.matches_filter(provider, model_contains))
+        });
+        Ok(items)
+    }
+
+    async fn list_items(
+        &self,
+        env_type: EnvironmentType,
+        config: &Config,
+    ) -> AppResult<Vec<EnvItem>> {
+        let environments = self.list_environments(env_type).await?;
+        Ok(env_items(env_type, environments, config))
+    }
+
+    /// 列出某类型的环境;`is_active` 以会话的当前环境为准,而非各管理器自行判断。
+    async fn list_environments(&self, env_type: EnvironmentType) -> AppResult<Vec<DynEnvironment>> {
         let manager = option_with_context(
             self.managers.get(&env_type),
             AppError::env_not_found(&format!("{env_type:?}")),
             "finding environment manager when listing environments",
         )?;
+        let mut environments = manager
+            .lock()
+            .await
+            .list()
+            .with_context("listing environments")?;
+        mark_active(
+            &mut environments,
+            self.session_current(env_type)?.as_deref(),
+        );
+        Ok(environments)
+    }
 
-        // 一次性加载配置，避免重复读取
+    /// 会话记录的当前环境名
+    fn session_current(&self, env_type: EnvironmentType) -> AppResult<Option<String>> {
+        Ok(self
+            .session_manager
+            .lock()?
+            .get_current_environment(env_type)
+            .cloned())
+    }
+
+    /// 按 java / maven / cc 汇总所有已注册管理器的环境(带当前/默认标记)。
+    ///
+    /// 配置和会话各只读取一次;某个类型 `list` 失败只记录在该分组的 `error` 里,
+    /// 不影响其余类型。
+    pub async fn list_all(&self) -> AppResult<Vec<EnvGroup>> {
         let config = Config::load().map_err(|e| AppError::Config {
             message: format!("Failed to load config: {e}"),
         })?;
+        let currents: HashMap<EnvironmentType, String> = {
+            let session_manager = self.session_manager.lock()?;
+            ALL_TYPES
+                .iter()
+                .filter_map(|t| {
+                    session_manager
+                        .get_current_environment(*t)
+                        .map(|name| (*t, name.clone()))
+                })
+                .collect()
+        };
 
-        let (environments, current_env) = {
-            let manager_guard = manager.lock().await;
-            let environments = manager_guard.list().with_context("listing environments")?;
-            let current_env = {
-                let session_manager = self.session_manager.lock()?;
-                session_manager.get_current_environment(env_type).cloned()
+        let mut groups = Vec::new();
+        for env_type in ALL_TYPES {
+            let Some(manager) = self.managers.get(&env_type) else {
+                continue;
             };
-            (environments, current_env)
+            let listed = manager.lock().await.list();
+            groups.push(match listed {
+                Ok(mut environments) => {
+                    mark_active(
+                        &mut environments,
+                        currents.get(&env_type).map(String::as_str),
+                    );
+                    EnvGroup {
+                        environment_type: env_type,
+                        environments: env_items(env_type, environments, &config),
+                        error: None,
+                    }
+                }
+                Err(e) => EnvGroup {
+                    environment_type: env_type,
+                    environments: Vec::new(),
+                    error: Some(e.to_string()),
+                },
+            });
+        }
+        Ok(groups)
+    }
+}
+
+/// `list_all` / `env list` 的分组顺序。
+const ALL_TYPES: [EnvironmentType; 3] = [
+    EnvironmentType::Java,
+    EnvironmentType::Maven,
+    EnvironmentType::Cc,
+];
+
+/// 用会话的当前环境覆盖管理器给出的 `is_active`:管理器只能看到本进程的
+/// `JAVA_HOME` 等变量,与 `fnva use` 记录的状态可能不一致。
+fn mark_active(environments: &mut [DynEnvironment], current: Option<&str>) {
+    for env in environments {
+        env.is_active = current == Some(env.name.as_str());
+    }
+}
+
+/// 把管理器返回的环境(已经 [`mark_active`])转换成带当前/默认/告警标记的列表条目。
+fn env_items(
+    env_type: EnvironmentType,
+    environments: Vec<DynEnvironment>,
+    config: &Config,
+) -> Vec<EnvItem> {
+    let default_env = match env_type {
+        EnvironmentType::Java => config.default_java_env.clone(),
+        EnvironmentType::Cc => config.default_cc_env.clone(),
+        EnvironmentType::Maven => config.default_maven_env.clone(),
+    };
+
+    let mut items = Vec::new();
+    for env in environments {
+        let name = env.name.clone();
+        let is_current = env.is_active;
+        let is_default = default_env.as_ref() == Some(&name);
+        // CC 环境把模型显示在 extra
+        let extra = if env_type == EnvironmentType::Cc {
+            env.version.clone().filter(|m| !m.is_empty())
+        } else {
+            None
         };
-        let default_env = match env_type {
-            EnvironmentType::Java => config.default_java_env.clone(),
-            EnvironmentType::Cc => config.default_cc_env.clone(),
-            EnvironmentType::Maven => config.default_maven_env.clone(),
+        // CC 缺 api_key 时标记,提醒该环境导出后无法鉴权
+        let missing_key = if env_type == EnvironmentType::Cc {
+            config
+                .cc_environments
+                .iter()
+                .find(|e| e.name == name)
+                .map(|e| e.api_key.trim().is_empty())
+                .unwrap_or(false)
+        } else {
+            false
         };
+        // Java 路径有效但缺 javac → JRE
+        let is_jre = env_type == EnvironmentType::Java
+            && crate::utils::validate_java_home(&env.path)
+            && !crate::utils::is_jdk(&env.path);
+        items.push(EnvItem {
+            name,
+            description: env.description.clone().unwrap_or_default(),
+            path: env.path.clone(),
+            version: env.version.clone().filter(|v| !v.is_empty()),
+            extra,
+            is_current,
+            is_default,
+            missing_key,
+            is_jre,
+        });
+    }
+    items
+}
 
-        let mut items = Vec::new();
-        for env in environments {
-            let name = env.name.clone();
-            let is_current = current_env.as_ref() == Some(&name);
-            let is_default = default_env.as_ref() == Some(&name);
-            // CC 环境把模型显示在 extra
-            let extra = if env_type == EnvironmentType::Cc {
-                env.version.filter(|m| !m.is_empty())
-            } else {
-                None
+/// 环境变量表是否已在当前进程中生效。字面值须完全相同;`PATH=<bin>:${PATH}`
+/// 这类追加型的值,只要求其中的目录都已在该变量里(rc 重置过 PATH 时不能跳过)。
+/// 至少要有一个字面值变量,否则无法判定。
+fn env_vars_active(vars: &[(String, String)], lookup: impl Fn(&str) -> Option<String>) -> bool {
+    let has_literal = vars.iter().any(|(_, v)| !v.contains("${"));
+    has_literal
+        && vars.iter().all(|(k, v)| {
+            let Some(current) = lookup(k) else {
+                return false;
             };
-            // CC 缺 api_key 时标记,提醒该环境导出后无法鉴权
-            let missing_key = if env_type == EnvironmentType::Cc {
-                config
-                    .cc_environments
-                    .iter()
-                    .find(|e| e.name == name)
-                    .map(|e| e.api_key.trim().is_empty())
-                    .unwrap_or(false)
-            } else {
-                false
-            };
-            items.push(EnvItem {
-                name,
-                description: env.description.clone().unwrap_or_default(),
-                extra,
-                is_current,
-                is_default,
-                missing_key,
-            });
+            if !v.contains("${") {
+                return current == *v;
+            }
+            let present: Vec<_> = std::env::split_paths(&current).collect();
+            let literal = v
+                .split("${")
+                .enumerate()
+                .map(|(i, part)| {
+                    if i == 0 {
+                        part
+                    } else {
+                        part.split_once('}').map_or("", |(_, rest)| rest)
+                    }
+                })
+                .collect::<String>();
+            std::env::split_paths(&literal)
+                .filter(|p| !p.as_os_str().is_empty())
+                .all(|p| present.contains(&p))
+        })
+}
+
+/// 历史记录时间:RFC 3339,精确到秒;默认本地时区(带偏移),`utc` 时为 `Z` 结尾。
+fn history_timestamp(ts: chrono::DateTime<chrono::Utc>, utc: bool) -> String {
+    use chrono::SecondsFormat;
+    if utc {
+        ts.to_rfc3339_opts(SecondsFormat::Secs, true)
+    } else {
+        ts.with_timezone(&chrono::Local)
+            .to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+}
+
+/// 值里的 `${VAR}` 引用名。
+fn env_var_refs(value: &str) -> Vec<&str> {
+    let mut refs = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        if end > 0 {
+            refs.push(&rest[..end]);
         }
-        Ok(items)
+        rest = &rest[end + 1..];
     }
+    refs
+}
+
+/// 无操作的切换脚本:只有一行注释,被 wrapper eval / source 时什么也不做。
+fn noop_script(shell_type: Option<ShellType>, env_type: EnvironmentType, name: &str) -> String {
+    comment_script(shell_type, &format!("{env_type} '{name}' already active"))
+}
+
+/// 只含一行注释的脚本,`eval` / `source` 后什么也不做。
+fn comment_script(shell_type: Option<ShellType>, text: &str) -> String {
+    let shell = shell_type.unwrap_or_else(crate::infrastructure::shell::platform::detect_shell);
+    let comment = if shell == ShellType::Cmd { "REM" } else { "#" };
+    format!("{comment} fnva: {text}\n")
 }
 
 #[cfg(test)]
@@ -535,6 +1108,7 @@ mod tests {
     use super::*;
     use crate::environments::java::JavaEnvironmentManager;
     use crate::infrastructure::config::{Config, EnvironmentSource, JavaEnvironment};
+    use crate::infrastructure::shell::current_envs::CurrentEnvsFile;
     use crate::testutil::FnvaHomeGuard;
 
     fn make_switcher() -> EnvironmentSwitcher {
@@ -583,6 +1157,333 @@ mod tests {
             .expect("list should resolve");
     }
 
+    #[tokio::test]
+    async fn test_temporary_switch_does_not_persist() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _guard = FnvaHomeGuard::new(tmp.path());
+
+        let home = tmp.path().join("jdk8");
+        std::fs::create_dir_all(home.join("bin")).unwrap();
+        let java = if cfg!(windows) { "java.exe" } else { "java" };
+        std::fs::write(home.join("bin").join(java), b"").unwrap();
+        {
+            let mut config = Config::new();
+            config
+                .add_java_env(JavaEnvironment {
+                    name: "8".to_string(),
+                    java_home: home.to_string_lossy().into_owned(),
+                    description: "test".to_string(),
+                    source: EnvironmentSource::Manual,
+                    java_opts: None,
+                    extra_paths: Vec::new(),
+                    version: None,
+                })
+                .expect("add java env");
+            config.save().expect("save config");
+        }
+
+        let switcher = make_switcher();
+        let result = switcher
+            .switch_environment_temporary(EnvironmentType::Java, "8", Some(ShellType::Bash))
+            .await
+            .expect("temporary switch");
+        assert!(result.success);
+        assert!(result.script.contains("JAVA_HOME"));
+
+        assert!(switcher
+            .session_manager
+            .lock()
+            .unwrap()
+            .get_current_environment(EnvironmentType::Java)
+            .is_none());
+        assert!(!CurrentEnvsFile::read()
+            .unwrap()
+            .to_map()
+            .contains_key("java"));
+        assert!(switcher
+            .history_manager
+            .lock()
+            .unwrap()
+            .get_history_for_env(EnvironmentType::Java)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_history_timestamp_is_rfc3339_with_offset() {
+        let ts = chrono::DateTime::parse_from_rfc3339("2026-03-01T08:30:05Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(history_timestamp(ts, true), "2026-03-01T08:30:05Z");
+        // 本地时区:无论偏移是多少,都能无歧义地解析回同一时刻
+        let local = history_timestamp(ts, false);
+        assert_eq!(chrono::DateTime::parse_from_rfc3339(&local).unwrap(), ts);
+    }
+
+    #[test]
+    fn test_env_vars_active_checks_literal_and_path_entries() {
+        let sep = if cfg!(windows) { ";" } else { ":" };
+        let vars = vec![
+            ("JAVA_HOME".to_string(), "/opt/jdk".to_string()),
+            ("PATH".to_string(), format!("/opt/jdk/bin{sep}${{PATH}}")),
+        ];
+        let env = |path: String| {
+            move |k: &str| match k {
+                "JAVA_HOME" => Some("/opt/jdk".to_string()),
+                "PATH" => Some(path.clone()),
+                _ => None,
+            }
+        };
+        assert!(env_vars_active(
+            &vars,
+            env(format!("/opt/jdk/bin{sep}/usr/bin"))
+        ));
+        // rc 重置了 PATH:JAVA_HOME 虽一致也不能跳过
+        assert!(!env_vars_active(&vars, env("/usr/bin".to_string())));
+        assert!(!env_vars_active(&vars, |_| None));
+        assert!(!env_vars_active(&[], |_| None));
+    }
+
+    #[tokio::test]
+    async fn test_switch_writes_current_envs_file_read_back_by_session() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _guard = FnvaHomeGuard::new(tmp.path());
+
+        let mut config = Config::new();
+        for name in ["17", "21"] {
+            let home = tmp.path().join(name);
+            std::fs::create_dir_all(home.join("bin")).unwrap();
+            let java = if cfg!(windows) { "java.exe" } else { "java" };
+            std::fs::write(home.join("bin").join(java), b"").unwrap();
+            config
+                .add_java_env(JavaEnvironment {
+                    name: name.to_string(),
+                    java_home: home.to_string_lossy().into_owned(),
+                    description: String::new(),
+                    source: EnvironmentSource::Manual,
+                    java_opts: None,
+                    extra_paths: Vec::new(),
+                    version: None,
+                })
+                .unwrap();
+        }
+        config.save().unwrap();
+
+        let switcher = make_switcher();
+        for name in ["17", "21"] {
+            let result = switcher
+                .switch_environment(EnvironmentType::Java, name, Some(ShellType::Bash), None)
+                .await
+                .unwrap();
+            assert!(result.success);
+            assert_eq!(CurrentEnvsFile::read().unwrap().java.as_deref(), Some(name));
+            // 新进程的会话从同一文件读出
+            let session = SessionManager::new().unwrap();
+            assert_eq!(
+                session
+                    .get_current_environment(EnvironmentType::Java)
+                    .map(String::as_str),
+                Some(name)
+            );
+        }
+        // 原子写不留临时文件
+        let state = crate::infrastructure::paths::current_envs_path().unwrap();
+        let leftovers: Vec<_> = std::fs::read_dir(state.parent().unwrap())
+            .unwrap()
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().contains(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty(), "{leftovers:?}");
+    }
+
+    #[tokio::test]
+    async fn test_active_flag_follows_session_after_switch() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _guard = FnvaHomeGuard::new(tmp.path());
+
+        let mut config = Config::new();
+        for name in ["11", "17", "21"] {
+            let home = tmp.path().join(name);
+            std::fs::create_dir_all(home.join("bin")).unwrap();
+            let java = if cfg!(windows) { "java.exe" } else { "java" };
+            std::fs::write(home.join("bin").join(java), b"").unwrap();
+            config
+                .add_java_env(JavaEnvironment {
+                    name: name.to_string(),
+                    java_home: home.to_string_lossy().into_owned(),
+                    description: String::new(),
+                    source: EnvironmentSource::Manual,
+                    java_opts: None,
+                    extra_paths: Vec::new(),
+                    version: None,
+                })
+                .unwrap();
+        }
+        config.save().unwrap();
+
+        let switcher = make_switcher();
+        switcher
+            .switch_environment(EnvironmentType::Java, "17", Some(ShellType::Bash), None)
+            .await
+            .unwrap();
+
+        let listed = switcher
+            .list_environments(EnvironmentType::Java)
+            .await
+            .unwrap();
+        let active: Vec<_> = listed
+            .iter()
+            .filter(|e| e.is_active)
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(active, ["17"]);
+
+        let items = switcher
+            .list_environments_with_default(EnvironmentType::Java)
+            .await
+            .unwrap();
+        let current: Vec<_> = items
+            .iter()
+            .filter(|e| e.is_current)
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(current, ["17"]);
+    }
+
+    #[tokio::test]
+    async fn test_switch_to_active_env_is_noop() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _guard = FnvaHomeGuard::new(tmp.path());
+
+        let home = tmp.path().join("jdk11");
+        std::fs::create_dir_all(home.join("bin")).unwrap();
+        let java = if cfg!(windows) { "java.exe" } else { "java" };
+        std::fs::write(home.join("bin").join(java), b"").unwrap();
+        let home_str = home.to_string_lossy().into_owned();
+        {
+            let mut config = Config::new();
+            config
+                .add_java_env(JavaEnvironment {
+                    name: "11".to_string(),
+                    java_home: home_str.clone(),
+                    description: "test".to_string(),
+                    source: EnvironmentSource::Manual,
+                    java_opts: None,
+                    extra_paths: Vec::new(),
+                    version: None,
+                })
+                .expect("add java env");
+            config.save().expect("save config");
+        }
+
+        let switcher = make_switcher();
+        let history_len = || {
+            switcher
+                .history_manager
+                .lock()
+                .unwrap()
+                .get_history_for_env(EnvironmentType::Java)
+                .len()
+        };
+        let first = switcher
+            .switch_environment(EnvironmentType::Java, "11", Some(ShellType::Bash), None)
+            .await
+            .unwrap();
+        assert!(first.script.contains("JAVA_HOME"));
+        assert_eq!(history_len(), 1);
+
+        // 模拟 shell 已 source 过脚本(PATH 只在前面追加,不影响并行的其他测试)
+        let prev = std::env::var_os("JAVA_HOME");
+        let prev_path = std::env::var_os("PATH").unwrap_or_default();
+        let mut paths = vec![home.join("bin")];
+        paths.extend(std::env::split_paths(&prev_path));
+        std::env::set_var("PATH", std::env::join_paths(paths).unwrap());
+        std::env::set_var("JAVA_HOME", &home_str);
+        let again = switcher
+            .switch_environment(EnvironmentType::Java, "11", Some(ShellType::Bash), None)
+            .await
+            .unwrap();
+        assert!(again.success);
+        assert_eq!(again.script, "# fnva: java '11' already active\n");
+        assert_eq!(history_len(), 1);
+
+        let mut config = Config::load().unwrap();
+        config.record_noop_switches = true;
+        config.save().unwrap();
+        switcher
+            .switch_environment(EnvironmentType::Java, "11", Some(ShellType::Bash), None)
+            .await
+            .unwrap();
+        assert_eq!(history_len(), 2);
+
+        std::env::set_var("PATH", prev_path);
+        match prev {
+            Some(v) => std::env::set_var("JAVA_HOME", v),
+            None => std::env::remove_var("JAVA_HOME"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_switch_fires_on_switch_command_with_env_vars() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _guard = FnvaHomeGuard::new(tmp.path());
+        let out = tmp.path().join("hook.out");
+        {
+            let mut config = Config::new();
+            for name in ["11", "17"] {
+                let home = tmp.path().join(format!("jdk{name}"));
+                std::fs::create_dir_all(home.join("bin")).unwrap();
+                std::fs::write(home.join("bin").join("java"), b"").unwrap();
+                config
+                    .add_java_env(JavaEnvironment {
+                        name: name.to_string(),
+                        java_home: home.to_string_lossy().into_owned(),
+                        description: "test".to_string(),
+                        source: EnvironmentSource::Manual,
+                        java_opts: None,
+                        extra_paths: Vec::new(),
+                        version: None,
+                    })
+                    .expect("add java env");
+            }
+            config.on_switch_command = Some(format!(
+                "printf '%s %s' \"$FNVA_SWITCH_ENV_TYPE\" \"$FNVA_SWITCH_ENV_NAME\" > '{}'",
+                out.display()
+            ));
+            config.on_switch_commands.insert(
+                "java".to_string(),
+                [(
+                    "17".to_string(),
+                    format!(
+                        "printf 'from %s' \"$FNVA_SWITCH_PREVIOUS_ENV\" > '{}'",
+                        out.display()
+                    ),
+                )]
+                .into(),
+            );
+            config.save().expect("save config");
+        }
+
+        let switcher = make_switcher();
+        switcher
+            .switch_environment_temporary(EnvironmentType::Java, "11", Some(ShellType::Bash))
+            .await
+            .unwrap();
+        assert!(!out.exists(), "temporary switch must not fire the hook");
+
+        switcher
+            .switch_environment(EnvironmentType::Java, "11", Some(ShellType::Bash), None)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "java 11");
+
+        switcher
+            .switch_environment(EnvironmentType::Java, "17", Some(ShellType::Bash), None)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "from 11");
+    }
+
     #[tokio::test]
     async fn test_switch_invalid_java_home_errors() {
         let tmp = tempfile::TempDir::new().unwrap();
@@ -598,6 +1499,9 @@ mod tests {
                     java_home: "/nonexistent/path/to/java".to_string(),
                     description: "test".to_string(),
                     source: EnvironmentSource::Manual,
+                    java_opts: None,
+                    extra_paths: Vec::new(),
+                    version: None,
                 })
                 .expect("add bad java env");
             config.save().expect("save config");
@@ -671,4 +1575,202 @@ mod tests {
             None
         );
     }
+
+    #[tokio::test]
+    async fn test_switch_all_defaults_emits_java_and_cc() {
+        use crate::environments::cc::CcEnvironmentManager;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _guard = FnvaHomeGuard::new(tmp.path());
+
+        // 伪造一个含 bin/java 的 JAVA_HOME 设为默认;CC 默认沿用 Config::new 的 anthropic-cc
+        let java_home = tmp.path().join("jdk17");
+        std::fs::create_dir_all(java_home.join("bin")).unwrap();
+        let java_exe = if cfg!(target_os = "windows") {
+            "java.exe"
+        } else {
+            "java"
+        };
+        std::fs::write(java_home.join("bin").join(java_exe), "").unwrap();
+        {
+            let mut config = Config::new();
+            config
+                .add_java_env(JavaEnvironment {
+                    name: "jdk17".to_string(),
+                    java_home: java_home.to_string_lossy().to_string(),
+                    description: String::new(),
+                    source: EnvironmentSource::Manual,
+                    java_opts: None,
+                    extra_paths: Vec::new(),
+                    version: None,
+                })
+                .unwrap();
+            config.set_default_java_env("jdk17".to_string()).unwrap();
+            config.save().unwrap();
+        }
+
+        let mut switcher = make_switcher();
+        switcher
+            .register_manager(
+                EnvironmentType::Cc,
+                Arc::new(Mutex::new(CcEnvironmentManager::new())),
+            )
+            .unwrap();
+
+        let script = switcher
+            .switch_all_defaults(ShellType::Bash)
+            .await
+            .expect("switch all defaults");
+        assert!(script.contains("JAVA_HOME"), "{script}");
+        assert!(script.contains("ANTHROPIC_BASE_URL"), "{script}");
+    }
+
+    /// `list` 总是失败的管理器,用于验证 `list_all` 的按类型容错。
+    struct BrokenManager;
+
+    #[async_trait::async_trait]
+    impl EnvironmentManager for BrokenManager {
+        fn environment_type(&self) -> EnvironmentType {
+            EnvironmentType::Maven
+        }
+        fn list(&self) -> Result<Vec<DynEnvironment>, AppError> {
+            Err(AppError::not_found("maven registry"))
+        }
+        fn get(&self, _name: &str) -> Result<Option<DynEnvironment>, AppError> {
+            Ok(None)
+        }
+        fn add(&mut self, _name: &str, _config_str: &str) -> Result<(), AppError> {
+            Ok(())
+        }
+        fn remove(&mut self, _name: &str) -> Result<(), AppError> {
+            Ok(())
+        }
+        fn use_env(&mut self, _name: &str, _shell: Option<ShellType>) -> Result<String, AppError> {
+            Ok(String::new())
+        }
+        fn get_current(&self) -> Result<Option<String>, AppError> {
+            Ok(None)
+        }
+        fn set_current(&mut self, _name: &str) -> Result<(), AppError> {
+            Ok(())
+        }
+        async fn scan(
+            &self,
+            _extra_paths: &[String],
+        ) -> Result<Vec<crate::core::presentation::ScanHit>, AppError> {
+            Ok(Vec::new())
+        }
+        fn is_available(&self, _name: &str) -> Result<bool, AppError> {
+            Ok(false)
+        }
+        fn get_details(&self, _name: &str) -> Result<Option<DynEnvironment>, AppError> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_all_reports_per_type_errors() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _guard = FnvaHomeGuard::new(tmp.path());
+        {
+            let mut config = Config::new();
+            config
+                .add_java_env(JavaEnvironment {
+                    name: "jdk17".to_string(),
+                    java_home: "/opt/jdk17".to_string(),
+                    description: String::new(),
+                    source: EnvironmentSource::Manual,
+                    java_opts: None,
+                    extra_paths: Vec::new(),
+                    version: None,
+                })
+                .unwrap();
+            config.set_default_java_env("jdk17".to_string()).unwrap();
+            config.save().unwrap();
+        }
+
+        let mut switcher = make_switcher();
+        switcher
+            .register_manager(EnvironmentType::Maven, Arc::new(Mutex::new(BrokenManager)))
+            .unwrap();
+
+        // 未注册 CC 管理器 → 不出现 cc 分组;maven 失败不影响 java
+        let groups = switcher.list_all().await.expect("list all");
+        let types: Vec<_> = groups.iter().map(|g| g.environment_type).collect();
+        assert_eq!(types, [EnvironmentType::Java, EnvironmentType::Maven]);
+
+        let java = &groups[0];
+        assert!(java.error.is_none());
+        let jdk17 = java
+            .environments
+            .iter()
+            .find(|e| e.name == "jdk17")
+            .unwrap();
+        assert!(jdk17.is_default);
+
+        assert!(groups[1].environments.is_empty());
+        assert!(groups[1]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("maven registry"));
+    }
+
+    #[tokio::test]
+    async fn test_current_environment_script_reemits_current_env() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _guard = FnvaHomeGuard::new(tmp.path());
+
+        let java_home = tmp.path().join("jdk17");
+        std::fs::create_dir_all(java_home.join("bin")).unwrap();
+        let java = if cfg!(windows) { "java.exe" } else { "java" };
+        std::fs::write(java_home.join("bin").join(java), "").unwrap();
+        {
+            let mut config = Config::new();
+            config
+                .add_java_env(JavaEnvironment {
+                    name: "jdk17".to_string(),
+                    java_home: java_home.to_string_lossy().to_string(),
+                    description: String::new(),
+                    source: EnvironmentSource::Manual,
+                    java_opts: None,
+                    extra_paths: Vec::new(),
+                    version: None,
+                })
+                .unwrap();
+            config.save().unwrap();
+        }
+        let switcher = make_switcher();
+        let none = switcher
+            .current_environment_script(EnvironmentType::Java, ShellType::Cmd)
+            .await
+            .unwrap();
+        assert_eq!(none, "REM fnva: no current java environment\n");
+
+        switcher
+            .switch_environment(EnvironmentType::Java, "jdk17", Some(ShellType::Bash), None)
+            .await
+            .unwrap();
+        let history_len = switcher
+            .get_switch_history(None, 100, false)
+            .await
+            .unwrap()
+            .len();
+
+        // 新的 switcher 模拟新终端:从持久化的当前环境重新生成脚本,且不追加历史
+        let switcher = make_switcher();
+        let script = switcher
+            .current_environment_script(EnvironmentType::Java, ShellType::Bash)
+            .await
+            .unwrap();
+        assert!(script.contains("JAVA_HOME"), "{script}");
+        assert_eq!(
+            switcher
+                .get_switch_history(None, 100, false)
+                .await
+                .unwrap()
+                .len(),
+            history_len
+        );
+    }
 }
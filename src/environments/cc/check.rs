@@ -0,0 +1,165 @@
+//! `fnva cc current --check`:用环境的 key 向服务商发一个轻量的鉴权请求。
+//!
+//! 请求 `GET {base_url}/v1/models`,同时带 `x-api-key` 与 `Authorization: Bearer`,
+//! 兼容官方接口和只认其中一种头的兼容服务商。只看状态码,不解析响应体。
+//! 只有连不上和 401/403 算失败;不提供 `/v1/models` 的兼容服务商(404/405 等)
+//! 视为可达但 key 无法验证。
+
+use crate::infrastructure::config::CcEnvironment;
+use serde::Serialize;
+
+/// Anthropic API 版本头
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// 检查结果;`ok` 为 false 仅表示 key 被拒绝,`verified` 表示 key 确实通过了鉴权。
+#[derive(Debug, Serialize)]
+pub struct EndpointCheck {
+    pub url: String,
+    pub status: u16,
+    pub ok: bool,
+    pub verified: bool,
+    pub message: String,
+}
+
+/// 解析环境的 base_url / api_key(`${VAR}` 展开)后检查;变量未设置时直接报错。
+pub async fn check_environment(env: &CcEnvironment) -> Result<EndpointCheck, String> {
+    let (base_url, api_key) = resolve_credentials(env)?;
+    check_endpoint(&base_url, &api_key).await
+}
+
+/// 展开后的 `(base_url, api_key)`;为空或引用的变量未设置时报错。
+pub fn resolve_credentials(env: &CcEnvironment) -> Result<(String, String), String> {
+    let base_url = env.resolve_env_var(&env.base_url);
+    let api_key = env.resolve_env_var(&env.api_key);
+    for (field, value) in [("base_url", &base_url), ("api_key", &api_key)] {
+        if value.is_empty() {
+            return Err(format!("CC environment '{}' has no {field}", env.name));
+        }
+        if value.starts_with("${") {
+            return Err(format!(
+                "{field} of CC environment '{}' references {value}, which is not set",
+                env.name
+            ));
+        }
+    }
+    Ok((base_url, api_key))
+}
+
+/// `{base_url}/v1/models` 的鉴权 GET 请求(两种鉴权头都带)。
+pub fn models_request(base_url: &str, api_key: &str, query: &str) -> reqwest::RequestBuilder {
+    let url = format!("{}/v1/models{query}", base_url.trim_end_matches('/'));
+    crate::infrastructure::remote::http::http_client()
+        .get(url)
+        .header("x-api-key", api_key)
+        .bearer_auth(api_key)
+        .header("anthropic-version", ANTHROPIC_VERSION)
+        .timeout(std::time::Duration::from_secs(10))
+}
+
+/// 向 `base_url` 发送鉴权请求并按状态码给出结论;连不上时返回错误。
+pub async fn check_endpoint(base_url: &str, api_key: &str) -> Result<EndpointCheck, String> {
+    crate::infrastructure::remote::offline::ensure_online("Checking the CC endpoint")?;
+    let url = format!("{}/v1/models", base_url.trim_end_matches('/'));
+    let resp = models_request(base_url, api_key, "")
+        .send()
+        .await
+        .map_err(|e| format!("Cannot reach {url}: {e}"))?;
+    let status = resp.status();
+    let rejected =
+        status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN;
+    let message = if status.is_success() {
+        "reachable and authenticated".to_string()
+    } else if rejected {
+        format!("API key rejected (HTTP {})", status.as_u16())
+    } else if status == reqwest::StatusCode::NOT_FOUND
+        || status == reqwest::StatusCode::METHOD_NOT_ALLOWED
+    {
+        format!(
+            "reachable, but the provider has no /v1/models (HTTP {}); API key not verified",
+            status.as_u16()
+        )
+    } else {
+        format!(
+            "reachable, unexpected response (HTTP {}); API key not verified",
+            status.as_u16()
+        )
+    };
+    Ok(EndpointCheck {
+        url,
+        status: status.as_u16(),
+        ok: !rejected,
+        verified: status.is_success(),
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+
+    #[tokio::test]
+    async fn accepted_and_rejected_keys_are_reported_by_status() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/v1/models")
+                .header("x-api-key", "good-key")
+                .header("authorization", "Bearer good-key");
+            then.status(200)
+                .json_body(serde_json::json!({ "data": [] }));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/v1/models");
+            then.status(401);
+        });
+
+        let ok = check_endpoint(&server.url("/"), "good-key").await.unwrap();
+        assert!(ok.ok);
+        assert_eq!(ok.status, 200);
+        assert!(ok.url.ends_with("/v1/models") && !ok.url.contains("//v1"));
+
+        let rejected = check_endpoint(&server.base_url(), "bad-key").await.unwrap();
+        assert!(!rejected.ok);
+        assert_eq!(rejected.status, 401);
+        assert!(
+            rejected.message.contains("rejected"),
+            "{}",
+            rejected.message
+        );
+    }
+
+    #[tokio::test]
+    async fn providers_without_models_endpoint_are_reachable_but_unverified() {
+        for status in [404, 405] {
+            let server = MockServer::start();
+            server.mock(|when, then| {
+                when.method(GET).path("/v1/models");
+                then.status(status);
+            });
+            let check = check_endpoint(&server.base_url(), "key").await.unwrap();
+            assert!(check.ok, "HTTP {status}: {}", check.message);
+            assert!(!check.verified);
+            assert_eq!(check.status, status);
+            assert!(check.message.contains("not verified"), "{}", check.message);
+        }
+    }
+
+    #[tokio::test]
+    async fn unreachable_endpoint_is_an_error() {
+        // 端口 1 上没有服务
+        let err = check_endpoint("http://127.0.0.1:1", "key")
+            .await
+            .unwrap_err();
+        assert!(err.starts_with("Cannot reach"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn unset_key_variable_fails_before_any_request() {
+        let env = crate::environments::cc::presets::find_preset("deepseek")
+            .unwrap()
+            .instantiate("ds", Some("${FNVA_TEST_SURELY_UNSET_KEY}"));
+        let err = check_environment(&env).await.unwrap_err();
+        assert!(err.contains("FNVA_TEST_SURELY_UNSET_KEY"), "{err}");
+    }
+}
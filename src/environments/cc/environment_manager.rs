@@ -52,6 +52,7 @@ impl CcEnvironmentManager {
                 description: env.description.clone(),
                 api_timeout_ms: env.api_timeout_ms.clone(),
                 extra_env: env.extra_env.clone(),
+                provider: env.provider.clone(),
             };
 
             self.environments.insert(env.name.clone(), cc_env);
@@ -136,6 +137,10 @@ impl EnvironmentManager for CcEnvironmentManager {
                 .and_then(|v| v.as_str())
                 .map(String::from),
             extra_env: std::collections::HashMap::new(),
+            provider: config
+                .get("provider")
+                .and_then(|v| v.as_str())
+                .map(String::from),
         };
 
         // 持久化到配置文件
@@ -151,6 +156,7 @@ impl EnvironmentManager for CcEnvironmentManager {
             existing.description = description.to_string();
             existing.opus_model = opus_model.map(String::from);
             existing.haiku_model = haiku_model.map(String::from);
+            existing.provider = cc_environment.provider.clone();
             existing.api_timeout_ms = config
                 .get("api_timeout_ms")
                 .and_then(|v| v.as_str())
@@ -205,6 +211,14 @@ impl EnvironmentManager for CcEnvironmentManager {
         generator.generate_switch_script(EnvironmentType::Cc, name, &config, Some(shell_type))
     }
 
+    fn env_vars(&self, name: &str) -> Result<Vec<(String, String)>, AppError> {
+        let cc_env = self
+            .environments
+            .get(name)
+            .ok_or_else(|| AppError::not_found(&format!("CC environment '{name}'")))?;
+        Ok(crate::environments::cc::setup::anthropic_env_vars(cc_env))
+    }
+
     fn get_current(&self) -> Result<Option<String>, AppError> {
         // Session 优先
         if let Ok(session) = SessionManager::new() {
@@ -346,6 +360,22 @@ impl EnvironmentManager for CcEnvironmentManager {
     fn get_details(&self, name: &str) -> Result<Option<DynEnvironment>, AppError> {
         self.get(name)
     }
+
+    fn validate(&self, name: &str) -> Result<(), String> {
+        let cc_env = self
+            .environments
+            .get(name)
+            .ok_or_else(|| format!("CC environment '{name}' not found"))?;
+        if cc_env.base_url.trim().is_empty() {
+            return Err(format!(
+                "CC environment '{name}' has no base_url configured"
+            ));
+        }
+        if cc_env.api_key.trim().is_empty() {
+            return Err(format!("CC environment '{name}' has no api_key configured"));
+        }
+        Ok(())
+    }
 }
 
 /// Returns all candidate paths for Claude Code's settings.json on the current platform.
@@ -380,20 +410,47 @@ fn claude_settings_candidates() -> Vec<std::path::PathBuf> {
 /// e.g. "https://open.bigmodel.cn/api/anthropic" → "bigmodel-cc"
 ///      "https://api.anthropic.com"              → "anthropic-cc"
 fn url_to_env_name(base_url: &str) -> String {
+    format!("{}-cc", host_label(base_url).unwrap_or("cc"))
+}
+
+/// Second-level domain segment of a base URL's host
+/// (e.g. "bigmodel" from "https://open.bigmodel.cn/api").
+fn host_label(base_url: &str) -> Option<&str> {
     // Strip scheme
     let without_scheme = base_url
         .trim_start_matches("https://")
         .trim_start_matches("http://");
-    // Take only the hostname portion
-    let host = without_scheme.split('/').next().unwrap_or(without_scheme);
-    // Take the second-level domain segment (e.g. "bigmodel" from "open.bigmodel.cn")
-    let parts: Vec<&str> = host.split('.').collect();
-    let label = if parts.len() >= 2 {
-        parts[parts.len() - 2]
+    // Take only the hostname portion (without port)
+    let host = without_scheme.split(['/', ':']).next()?;
+    let parts: Vec<&str> = host.split('.').filter(|p| !p.is_empty()).collect();
+    if parts.len() >= 2 {
+        Some(parts[parts.len() - 2])
     } else {
-        parts.first().copied().unwrap_or("cc")
-    };
-    format!("{label}-cc")
+        parts.first().copied()
+    }
+}
+
+/// 掩码:只保留末 4 位,短值或未展开的 `${VAR}` 引用整体打码(空值保持为空)。
+pub(crate) fn mask_secret(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.is_empty() {
+        String::new()
+    } else if chars.len() < 12 || value.starts_with("${") {
+        "****".to_string()
+    } else {
+        format!(
+            "****{}",
+            chars[chars.len() - 4..].iter().collect::<String>()
+        )
+    }
+}
+
+/// 变量名看起来像密钥(KEY / TOKEN / SECRET / PASSWORD)。
+pub(crate) fn is_secret_name(name: &str) -> bool {
+    let upper = name.to_ascii_uppercase();
+    ["KEY", "TOKEN", "SECRET", "PASSWORD"]
+        .iter()
+        .any(|w| upper.contains(w))
 }
 
 // 为 ConfigCcEnvironment 添加扩展方法
@@ -402,6 +459,111 @@ impl ConfigCcEnvironment {
         crate::environments::cc::setup::is_anthropic_active()
     }
 
+    /// 服务商:配置里的 `provider`,没写则取 base_url 的主域名(`api.moonshot.cn` → `moonshot`)。
+    pub fn provider_name(&self) -> String {
+        self.provider
+            .clone()
+            .filter(|p| !p.trim().is_empty())
+            .or_else(|| host_label(&self.base_url).map(str::to_string))
+            .unwrap_or_default()
+    }
+
+    /// `cc list` 过滤:服务商不区分大小写全匹配;模型为 sonnet/opus/haiku 任一包含子串。
+    pub fn matches_filter(&self, provider: Option<&str>, model_contains: Option<&str>) -> bool {
+        let provider_ok = provider.is_none_or(|p| self.provider_name().eq_ignore_ascii_case(p));
+        let model_ok = model_contains.is_none_or(|needle| {
+            let needle = needle.to_lowercase();
+            std::iter::once(&self.sonnet_model)
+                .chain(self.opus_model.as_ref())
+                .chain(self.haiku_model.as_ref())
+                .any(|m| m.to_lowercase().contains(&needle))
+        });
+        provider_ok && model_ok
+    }
+
+    /// `cc set` 可修改的字段;`model` 即 sonnet 模型。
+    pub const SETTABLE_FIELDS: &'static [&'static str] = &[
+        "base_url",
+        "model",
+        "opus_model",
+        "haiku_model",
+        "api_key",
+        "description",
+        "provider",
+    ];
+
+    /// 校验并修改单个字段;可选字段(opus/haiku/provider)传空串即清除。
+    pub fn set_field(&mut self, field: &str, value: &str) -> Result<(), AppError> {
+        use crate::utils::validation::ValidationUtils;
+
+        let invalid = |e: String| AppError::validation(field, &e);
+        let optional = |v: &str| Some(v.to_string()).filter(|v| !v.is_empty());
+        match field {
+            "base_url" => {
+                ValidationUtils::validate_url(value).map_err(invalid)?;
+                self.base_url = value.to_string();
+            }
+            "model" | "sonnet_model" => {
+                if value.trim().is_empty() {
+                    return Err(invalid("Model cannot be empty".to_string()));
+                }
+                self.sonnet_model = value.to_string();
+            }
+            "opus_model" => self.opus_model = optional(value),
+            "haiku_model" => self.haiku_model = optional(value),
+            "api_key" => {
+                ValidationUtils::validate_api_key(value).map_err(invalid)?;
+                self.api_key = value.to_string();
+            }
+            "description" => self.description = value.to_string(),
+            "provider" => self.provider = optional(value),
+            _ => {
+                return Err(invalid(format!(
+                    "Unknown field; settable fields: {}",
+                    Self::SETTABLE_FIELDS.join(", ")
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// `cc use --json` 附带的结构化配置:`${VAR}` 引用展开为实际值;密钥(api_key、
+    /// 名称像密钥的 extra_env)只给掩码,`reveal` 时才给出展开后的原值。
+    pub fn to_public_json(&self, reveal: bool) -> serde_json::Value {
+        let secret = |value: &str| {
+            let resolved = self.resolve_env_var(value);
+            if reveal {
+                resolved
+            } else {
+                mask_secret(&resolved)
+            }
+        };
+        let extra_env: serde_json::Map<String, serde_json::Value> = self
+            .extra_env
+            .iter()
+            .map(|(k, v)| {
+                let value = if is_secret_name(k) {
+                    secret(v)
+                } else {
+                    self.resolve_env_var(v)
+                };
+                (k.clone(), value.into())
+            })
+            .collect();
+        serde_json::json!({
+            "name": self.name,
+            "provider": self.provider_name(),
+            "base_url": self.resolve_env_var(&self.base_url),
+            "sonnet_model": self.resolve_env_var(&self.sonnet_model),
+            "opus_model": self.opus_model.as_deref().map(|m| self.resolve_env_var(m)),
+            "haiku_model": self.haiku_model.as_deref().map(|m| self.resolve_env_var(m)),
+            "description": self.description,
+            "api_timeout_ms": self.api_timeout_ms,
+            "api_key": secret(&self.api_key),
+            "extra_env": extra_env,
+        })
+    }
+
     pub fn resolve_env_var(&self, value: &str) -> String {
         if value.starts_with("${") && value.ends_with('}') {
             let var_name = &value[2..value.len() - 1];
@@ -471,4 +633,115 @@ mod tests {
         assert!(script.contains("https://api.x.com"));
         assert!(script.contains("ANTHROPIC_AUTH_TOKEN"));
     }
+
+    #[test]
+    fn provider_falls_back_to_base_url_host_and_filters_match() {
+        let env = |base_url: &str, provider: Option<&str>| ConfigCcEnvironment {
+            name: "x".to_string(),
+            api_key: String::new(),
+            base_url: base_url.to_string(),
+            sonnet_model: "kimi-k2".to_string(),
+            opus_model: None,
+            haiku_model: Some("Kimi-Lite".to_string()),
+            description: String::new(),
+            api_timeout_ms: None,
+            extra_env: HashMap::new(),
+            provider: provider.map(str::to_string),
+        };
+        let moonshot = env("https://api.moonshot.cn/anthropic", None);
+        assert_eq!(moonshot.provider_name(), "moonshot");
+        assert_eq!(
+            env("http://localhost:4000", None).provider_name(),
+            "localhost"
+        );
+        assert_eq!(
+            env("https://gw.corp.example", Some("deepseek")).provider_name(),
+            "deepseek"
+        );
+
+        assert!(moonshot.matches_filter(None, None));
+        assert!(moonshot.matches_filter(Some("Moonshot"), Some("lite")));
+        assert!(!moonshot.matches_filter(Some("anthropic"), None));
+        assert!(!moonshot.matches_filter(None, Some("claude")));
+    }
+
+    #[test]
+    fn set_field_validates_and_restricts_fields() {
+        let mut env = ConfigCcEnvironment {
+            name: "x".to_string(),
+            api_key: "${ANTHROPIC_API_KEY}".to_string(),
+            base_url: "https://api.x.com".to_string(),
+            sonnet_model: "claude-sonnet-4-5".to_string(),
+            opus_model: Some("claude-opus-4-5".to_string()),
+            haiku_model: None,
+            description: String::new(),
+            api_timeout_ms: None,
+            extra_env: HashMap::new(),
+            provider: None,
+        };
+        env.set_field("model", "kimi-k2").unwrap();
+        env.set_field("opus_model", "").unwrap();
+        env.set_field("base_url", "https://api.moonshot.cn/anthropic")
+            .unwrap();
+        assert_eq!(env.sonnet_model, "kimi-k2");
+        assert_eq!(env.opus_model, None);
+        assert_eq!(env.base_url, "https://api.moonshot.cn/anthropic");
+
+        let err = env.set_field("base_url", "api.x.com").unwrap_err();
+        assert!(matches!(err, AppError::Validation { ref field, .. } if field == "base_url"));
+        assert!(env.set_field("model", " ").is_err());
+        assert!(env.set_field("name", "y").is_err());
+        assert_eq!(env.base_url, "https://api.moonshot.cn/anthropic");
+    }
+
+    #[test]
+    fn public_json_masks_secrets_unless_revealed() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let env = ConfigCcEnvironment {
+            name: "kimi".to_string(),
+            api_key: "sk-moonshot-0123456789abcd".to_string(),
+            base_url: "https://api.moonshot.cn/anthropic".to_string(),
+            sonnet_model: "kimi-k2".to_string(),
+            opus_model: None,
+            haiku_model: None,
+            description: String::new(),
+            api_timeout_ms: None,
+            extra_env: HashMap::from([
+                (
+                    "CLAUDE_CODE_AUTO_COMPACT_WINDOW".to_string(),
+                    "200000".to_string(),
+                ),
+                ("GATEWAY_TOKEN".to_string(), "short".to_string()),
+            ]),
+            provider: None,
+        };
+
+        let masked = env.to_public_json(false);
+        assert_eq!(masked["provider"], "moonshot");
+        assert_eq!(masked["sonnet_model"], "kimi-k2");
+        assert_eq!(masked["api_key"], "****abcd");
+        assert_eq!(masked["extra_env"]["GATEWAY_TOKEN"], "****");
+        assert_eq!(
+            masked["extra_env"]["CLAUDE_CODE_AUTO_COMPACT_WINDOW"],
+            "200000"
+        );
+        assert!(masked["opus_model"].is_null());
+
+        let revealed = env.to_public_json(true);
+        assert_eq!(revealed["api_key"], "sk-moonshot-0123456789abcd");
+        assert_eq!(revealed["extra_env"]["GATEWAY_TOKEN"], "short");
+    }
+
+    #[test]
+    fn cc_validate_requires_api_key() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let mut m = CcEnvironmentManager::new();
+        m.add("ok", &cc_json("https://api.x.com")).unwrap();
+        m.add("no-key", r#"{"base_url":"https://api.x.com","api_key":""}"#)
+            .unwrap();
+        assert!(m.validate("ok").is_ok());
+        assert!(m.validate("no-key").unwrap_err().contains("api_key"));
+    }
 }
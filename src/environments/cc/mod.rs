@@ -1,4 +1,7 @@
+pub mod check;
 pub mod environment_manager;
+pub mod models;
+pub mod presets;
 pub mod setup;
 
 pub use environment_manager::*;
@@ -0,0 +1,123 @@
+//! `fnva cc models <name>`:列出服务商 `GET {base_url}/v1/models` 返回的模型。
+//!
+//! 与 `cc current --check` 用同一个请求(共享 HTTP 客户端、两种鉴权头);响应按
+//! Anthropic 格式解析,`display_name` 缺失时(OpenAI 风格的兼容服务商)只给 id。
+
+use super::check::{models_request, resolve_credentials};
+use crate::infrastructure::config::CcEnvironment;
+use serde::{Deserialize, Serialize};
+
+/// 单页最多取这么多条;服务商的模型数远小于此
+const PAGE_LIMIT: u32 = 1000;
+
+/// 一个可用模型
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// 环境里用它的字段(model / opus_model / haiku_model)
+    #[serde(default, skip_deserializing, skip_serializing_if = "Vec::is_empty")]
+    pub used_as: Vec<&'static str>,
+}
+
+#[derive(Deserialize)]
+struct ModelsPage {
+    #[serde(default)]
+    data: Vec<ModelInfo>,
+}
+
+/// 查询 `env` 服务商的模型列表,并标出环境当前配置的模型。
+pub async fn list_models(env: &CcEnvironment) -> Result<Vec<ModelInfo>, String> {
+    crate::infrastructure::remote::offline::ensure_online("Listing CC provider models")?;
+    let (base_url, api_key) = resolve_credentials(env)?;
+    let mut models = fetch_models(&base_url, &api_key).await?;
+    mark_configured(&mut models, env);
+    Ok(models)
+}
+
+/// 请求并解析模型列表;非 2xx 时报错(401/403 提示 key 被拒)。
+pub async fn fetch_models(base_url: &str, api_key: &str) -> Result<Vec<ModelInfo>, String> {
+    let resp = models_request(base_url, api_key, &format!("?limit={PAGE_LIMIT}"))
+        .send()
+        .await
+        .map_err(|e| format!("Cannot reach {base_url}: {e}"))?;
+    let status = resp.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Err(format!("API key rejected (HTTP {})", status.as_u16()));
+    }
+    if !status.is_success() {
+        return Err(format!(
+            "Provider does not list models (HTTP {})",
+            status.as_u16()
+        ));
+    }
+    let page: ModelsPage = resp
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected models response: {e}"))?;
+    Ok(page.data)
+}
+
+fn mark_configured(models: &mut [ModelInfo], env: &CcEnvironment) {
+    let fields = [
+        ("model", Some(&env.sonnet_model)),
+        ("opus_model", env.opus_model.as_ref()),
+        ("haiku_model", env.haiku_model.as_ref()),
+    ];
+    for model in models {
+        model.used_as = fields
+            .iter()
+            .filter(|(_, value)| value.is_some_and(|v| *v == model.id))
+            .map(|(field, _)| *field)
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+
+    #[tokio::test]
+    async fn lists_models_and_marks_configured_ones() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/v1/models")
+                .query_param("limit", "1000")
+                .header("x-api-key", "good-key");
+            then.status(200).json_body(serde_json::json!({
+                "data": [
+                    {"type": "model", "id": "claude-sonnet-4-5", "display_name": "Claude Sonnet 4.5"},
+                    {"type": "model", "id": "claude-haiku-4-5", "display_name": "Claude Haiku 4.5"},
+                    {"id": "glm-4.6", "object": "model"}
+                ],
+                "has_more": false
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/v1/models");
+            then.status(401);
+        });
+
+        let mut env = crate::environments::cc::presets::find_preset("anthropic")
+            .unwrap()
+            .instantiate("claude", Some("good-key"));
+        env.base_url = server.base_url();
+        env.sonnet_model = "claude-sonnet-4-5".to_string();
+        env.haiku_model = Some("claude-sonnet-4-5".to_string());
+
+        let models = list_models(&env).await.unwrap();
+        let ids: Vec<&str> = models.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["claude-sonnet-4-5", "claude-haiku-4-5", "glm-4.6"]);
+        assert_eq!(models[0].used_as, ["model", "haiku_model"]);
+        assert_eq!(models[0].display_name.as_deref(), Some("Claude Sonnet 4.5"));
+        assert!(models[2].used_as.is_empty() && models[2].display_name.is_none());
+
+        let err = fetch_models(&server.base_url(), "bad-key")
+            .await
+            .unwrap_err();
+        assert!(err.contains("rejected"), "{err}");
+    }
+}
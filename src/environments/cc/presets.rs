@@ -0,0 +1,229 @@
+//! 内置的 CC 服务商预设(`fnva cc presets` / `fnva cc add-preset`)。
+//!
+//! 只收录提供 Anthropic 兼容接口的服务商;预设里的 `key_var` 是未给出
+//! `--api-key` 时写入配置的 `${VAR}` 引用。
+
+use crate::infrastructure::config::{CcEnvironment, DEFAULT_SONNET_MODEL};
+use serde::Serialize;
+
+/// 一个服务商预设
+#[derive(Debug, Clone, Serialize)]
+pub struct CcPreset {
+    pub name: &'static str,
+    pub provider: &'static str,
+    pub base_url: &'static str,
+    pub sonnet_model: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opus_model: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub haiku_model: Option<&'static str>,
+    /// 默认的 API key 环境变量名
+    pub key_var: &'static str,
+    pub description: &'static str,
+}
+
+/// 全部内置预设,按名称排列。
+pub const PRESETS: &[CcPreset] = &[
+    CcPreset {
+        name: "anthropic",
+        provider: "anthropic",
+        base_url: "https://api.anthropic.com",
+        sonnet_model: DEFAULT_SONNET_MODEL,
+        opus_model: Some("claude-opus-4-5"),
+        haiku_model: Some("claude-haiku-4-5"),
+        key_var: "ANTHROPIC_API_KEY",
+        description: "Anthropic (official)",
+    },
+    CcPreset {
+        name: "deepseek",
+        provider: "deepseek",
+        base_url: "https://api.deepseek.com/anthropic",
+        sonnet_model: "deepseek-chat",
+        opus_model: None,
+        haiku_model: None,
+        key_var: "DEEPSEEK_API_KEY",
+        description: "DeepSeek",
+    },
+    CcPreset {
+        name: "glm",
+        provider: "bigmodel",
+        base_url: "https://open.bigmodel.cn/api/anthropic",
+        sonnet_model: "glm-4.6",
+        opus_model: None,
+        haiku_model: Some("glm-4.5-air"),
+        key_var: "GLM_API_KEY",
+        description: "Zhipu GLM (open.bigmodel.cn)",
+    },
+    CcPreset {
+        name: "kimi",
+        provider: "moonshot",
+        base_url: "https://api.moonshot.cn/anthropic",
+        sonnet_model: "kimi-k2-turbo-preview",
+        opus_model: None,
+        haiku_model: None,
+        key_var: "MOONSHOT_API_KEY",
+        description: "Moonshot Kimi",
+    },
+    CcPreset {
+        name: "zai",
+        provider: "z",
+        base_url: "https://api.z.ai/api/anthropic",
+        sonnet_model: "glm-4.6",
+        opus_model: None,
+        haiku_model: Some("glm-4.5-air"),
+        key_var: "ZAI_API_KEY",
+        description: "Z.ai GLM (international)",
+    },
+];
+
+/// 按名称查找预设(忽略大小写)。
+pub fn find_preset(name: &str) -> Option<&'static CcPreset> {
+    PRESETS.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+}
+
+/// base_url 已带 API 路径时的提示;没问题(或无法解析,如 `${VAR}`)时为 `None`。
+///
+/// Claude Code 会在 base_url 后自己拼 `/v1/messages`,`.../api/paas/v4` 这类地址
+/// 最终会请求 `.../v4/v1/messages`。只做提示,不拒绝:兼容网关的路径千奇百怪。
+pub fn base_url_warning(base_url: &str) -> Option<String> {
+    let url = url::Url::parse(base_url.trim()).ok()?;
+    let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+    let at = segments.iter().position(|s| is_api_path_segment(s))?;
+    let host = url.host_str()?;
+
+    let advice = match PRESETS.iter().find(|p| {
+        url::Url::parse(p.base_url)
+            .ok()
+            .is_some_and(|u| u.host_str() == Some(host))
+    }) {
+        Some(p) => format!(
+            "For {} use `{}` (or `fnva cc add-preset {}`).",
+            p.description, p.base_url, p.name
+        ),
+        None => {
+            let mut trimmed = url.clone();
+            trimmed.set_path(&segments[..at].join("/"));
+            trimmed.set_query(None);
+            format!("Try `{}` instead.", trimmed.as_str().trim_end_matches('/'))
+        }
+    };
+    Some(format!(
+        "base_url `{}` contains the API path `/{}`; Claude Code appends /v1/messages itself, \
+         so requests would go to `{}/v1/messages`. {advice}",
+        base_url.trim(),
+        segments[at..].join("/"),
+        base_url.trim().trim_end_matches('/'),
+    ))
+}
+
+/// `v1`、`v4`、`v1beta` 这类版本段,以及 `messages` / `chat` / `completions` 接口段
+fn is_api_path_segment(segment: &str) -> bool {
+    let lower = segment.to_ascii_lowercase();
+    let is_version = lower
+        .strip_prefix('v')
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(|c| c.is_ascii_digit())
+        && lower[1..]
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .chars()
+            .all(|c| c.is_ascii_alphabetic());
+    is_version || matches!(lower.as_str(), "messages" | "chat" | "completions")
+}
+
+impl CcPreset {
+    /// 生成 CC 环境;`api_key` 为 `None` 时引用 `${key_var}`。
+    pub fn instantiate(&self, name: &str, api_key: Option<&str>) -> CcEnvironment {
+        CcEnvironment {
+            name: name.to_string(),
+            api_key: api_key
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("${{{}}}", self.key_var)),
+            base_url: self.base_url.to_string(),
+            sonnet_model: self.sonnet_model.to_string(),
+            opus_model: self.opus_model.map(str::to_string),
+            haiku_model: self.haiku_model.map(str::to_string),
+            description: self.description.to_string(),
+            api_timeout_ms: None,
+            extra_env: std::collections::HashMap::new(),
+            provider: Some(self.provider.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::validation::ValidationUtils;
+
+    #[test]
+    fn every_preset_instantiates_into_a_valid_environment() {
+        for preset in PRESETS {
+            for key in [None, Some("sk-test-0123456789")] {
+                let env = preset.instantiate(&format!("{}-cc", preset.name), key);
+                ValidationUtils::validate_url(&env.base_url).unwrap();
+                ValidationUtils::validate_api_key(&env.api_key).unwrap();
+                assert!(!env.sonnet_model.is_empty(), "{}", preset.name);
+                assert_eq!(env.provider_name(), preset.provider);
+            }
+        }
+        assert_eq!(
+            PRESETS[0].instantiate("a", None).api_key,
+            "${ANTHROPIC_API_KEY}"
+        );
+    }
+
+    #[test]
+    fn presets_are_sorted_unique_and_found_case_insensitively() {
+        let names: Vec<_> = PRESETS.iter().map(|p| p.name).collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(names, sorted);
+        assert_eq!(find_preset("GLM").unwrap().provider, "bigmodel");
+        assert!(find_preset("nope").is_none());
+    }
+
+    #[test]
+    fn preset_base_urls_pass_and_versioned_paths_are_flagged() {
+        for preset in PRESETS {
+            assert_eq!(base_url_warning(preset.base_url), None, "{}", preset.name);
+        }
+
+        let glm = base_url_warning("https://open.bigmodel.cn/api/paas/v4").unwrap();
+        assert!(glm.contains("`/v4`"), "{glm}");
+        assert!(glm.contains("/api/paas/v4/v1/messages"), "{glm}");
+        assert!(
+            glm.contains("https://open.bigmodel.cn/api/anthropic")
+                && glm.contains("add-preset glm"),
+            "{glm}"
+        );
+        let anthropic = base_url_warning("https://api.anthropic.com/v1/").unwrap();
+        assert!(
+            anthropic.contains("`https://api.anthropic.com`"),
+            "{anthropic}"
+        );
+        let deepseek = base_url_warning("https://api.deepseek.com/v1").unwrap();
+        assert!(
+            deepseek.contains("https://api.deepseek.com/anthropic"),
+            "{deepseek}"
+        );
+
+        // 未知网关:去掉 API 路径作为建议
+        let gateway = base_url_warning("https://llm.corp.example/gateway/v1/messages").unwrap();
+        assert!(gateway.contains("`/v1/messages`"), "{gateway}");
+        assert!(
+            gateway.contains("Try `https://llm.corp.example/gateway`"),
+            "{gateway}"
+        );
+        let beta = base_url_warning("https://llm.corp.example/v1beta").unwrap();
+        assert!(beta.contains("Try `https://llm.corp.example`"), "{beta}");
+
+        for fine in [
+            "https://proxy.example/anthropic",
+            "https://proxy.example/vault/anthropic",
+            "${CC_BASE_URL}",
+        ] {
+            assert_eq!(base_url_warning(fine), None, "{fine}");
+        }
+    }
+}
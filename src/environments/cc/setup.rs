@@ -45,6 +45,35 @@ pub fn apply_anthropic_config(env: &ConfigCcEnvironment, config: &mut serde_json
     }
 }
 
+/// dotenv 导出用的变量表:变量名与 CC 切换模板一致,另附 `extra_env`。
+pub fn anthropic_env_vars(env: &ConfigCcEnvironment) -> Vec<(String, String)> {
+    let mut config = serde_json::json!({});
+    apply_anthropic_config(env, &mut config);
+    let field = |key: &str| config[key].as_str().map(str::to_string);
+
+    let mut vars = Vec::new();
+    let mut push = |name: &str, value: Option<String>| {
+        if let Some(v) = value.filter(|v| !v.is_empty()) {
+            vars.push((name.to_string(), v));
+        }
+    };
+    push("ANTHROPIC_AUTH_TOKEN", field("anthropic_auth_token"));
+    push("ANTHROPIC_BASE_URL", field("anthropic_base_url"));
+    push("ANTHROPIC_DEFAULT_OPUS_MODEL", field("opus_model"));
+    push("ANTHROPIC_DEFAULT_SONNET_MODEL", field("sonnet_model"));
+    push("ANTHROPIC_DEFAULT_HAIKU_MODEL", field("haiku_model"));
+    if !env.api_key.is_empty() {
+        push("CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC", Some("1".into()));
+        push("API_TIMEOUT_MS", field("api_timeout_ms"));
+    }
+    let mut extra: Vec<_> = env.extra_env.iter().collect();
+    extra.sort();
+    for (k, v) in extra {
+        push(k, Some(v.clone()));
+    }
+    vars
+}
+
 /// 当前 shell 是否已经激活了某个 Anthropic 协议的 CC 环境。
 pub fn is_anthropic_active() -> bool {
     std::env::var("ANTHROPIC_AUTH_TOKEN").is_ok()
@@ -0,0 +1,176 @@
+//! JVM 启动耗时对比(`fnva java benchmark`)。
+//!
+//! 对每个环境顺序执行 `java -version`:先跑 `warmup` 次预热(不计入,让磁盘
+//! 缓存就位),再计时 `runs` 次,报告平均值与中位数,按平均值升序排列。
+//! 环境之间不并发,避免互相抢 CPU 影响结果。
+
+use crate::infrastructure::config::JavaEnvironment;
+use serde::Serialize;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// 单个环境的测量结果;启动失败时 `error` 非空,不参与排序比较。
+#[derive(Debug, Serialize)]
+pub struct BenchResult {
+    pub name: String,
+    pub runs: usize,
+    pub mean_ms: f64,
+    pub median_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 执行一次 `$JAVA_HOME/bin/java -version` 并计时。
+pub fn time_java_version(java_home: &Path) -> Result<Duration, String> {
+    let java = if cfg!(windows) { "java.exe" } else { "java" };
+    let bin = java_home.join("bin").join(java);
+    let started = Instant::now();
+    let status = Command::new(&bin)
+        .arg("-version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("Failed to run {}: {e}", bin.display()))?;
+    let elapsed = started.elapsed();
+    if !status.success() {
+        return Err(format!("{} -version exited with {status}", bin.display()));
+    }
+    Ok(elapsed)
+}
+
+/// 逐个环境预热 + 计时;`run` 是单次测量(测试里可替换),结果按平均耗时排序,失败的排最后。
+pub fn benchmark<F>(
+    envs: &[&JavaEnvironment],
+    runs: usize,
+    warmup: usize,
+    mut run: F,
+) -> Vec<BenchResult>
+where
+    F: FnMut(&Path) -> Result<Duration, String>,
+{
+    let runs = runs.max(1);
+    let mut results: Vec<BenchResult> = envs
+        .iter()
+        .map(|env| {
+            let home = Path::new(&env.java_home);
+            let measured = (0..warmup)
+                .try_for_each(|_| run(home).map(drop))
+                .and_then(|_| (0..runs).map(|_| run(home)).collect());
+            match measured {
+                Ok(samples) => summarize(&env.name, samples),
+                Err(e) => BenchResult {
+                    name: env.name.clone(),
+                    runs: 0,
+                    mean_ms: 0.0,
+                    median_ms: 0.0,
+                    error: Some(e),
+                },
+            }
+        })
+        .collect();
+    results.sort_by(|a, b| {
+        a.error
+            .is_some()
+            .cmp(&b.error.is_some())
+            .then(a.mean_ms.total_cmp(&b.mean_ms))
+    });
+    results
+}
+
+fn summarize(name: &str, mut samples: Vec<Duration>) -> BenchResult {
+    samples.sort();
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    let mid = samples.len() / 2;
+    let median = if samples.len().is_multiple_of(2) {
+        (ms(samples[mid - 1]) + ms(samples[mid])) / 2.0
+    } else {
+        ms(samples[mid])
+    };
+    BenchResult {
+        name: name.to_string(),
+        runs: samples.len(),
+        mean_ms: samples.iter().map(|d| ms(*d)).sum::<f64>() / samples.len() as f64,
+        median_ms: median,
+        error: None,
+    }
+}
+
+/// 对齐的文本表格,最快的在前。
+pub fn to_table(results: &[BenchResult]) -> String {
+    let width = results
+        .iter()
+        .map(|r| r.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("Name".len());
+    let mut lines = vec![format!(
+        "{:<width$}  {:>10}  {:>10}  {:>4}",
+        "Name", "Mean(ms)", "Median(ms)", "Runs"
+    )];
+    for r in results {
+        match &r.error {
+            Some(e) => lines.push(format!("{:<width$}  failed: {e}", r.name)),
+            None => lines.push(format!(
+                "{:<width$}  {:>10.1}  {:>10.1}  {:>4}",
+                r.name, r.mean_ms, r.median_ms, r.runs
+            )),
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::config::EnvironmentSource;
+    use std::collections::HashMap;
+
+    fn env(name: &str) -> JavaEnvironment {
+        JavaEnvironment {
+            name: name.to_string(),
+            java_home: format!("/opt/{name}"),
+            description: String::new(),
+            source: EnvironmentSource::Manual,
+            java_opts: None,
+            extra_paths: Vec::new(),
+            version: None,
+        }
+    }
+
+    #[test]
+    fn harness_warms_up_then_times_each_env_and_sorts_by_mean() {
+        let (slow, fast, broken) = (env("slow"), env("fast"), env("broken"));
+        let cost: HashMap<&str, u64> = [("/opt/slow", 30), ("/opt/fast", 10)].into();
+        let mut calls: HashMap<String, usize> = HashMap::new();
+
+        let results = benchmark(&[&slow, &broken, &fast], 3, 1, |home| {
+            let home = home.to_string_lossy().into_owned();
+            *calls.entry(home.clone()).or_default() += 1;
+            cost.get(home.as_str())
+                .map(|ms| Duration::from_millis(*ms))
+                .ok_or_else(|| "bin/java is missing".to_string())
+        });
+
+        // 预热 1 次 + 计时 3 次;失败的环境在预热时就停下
+        assert_eq!(calls["/opt/slow"], 4);
+        assert_eq!(calls["/opt/fast"], 4);
+        assert_eq!(calls["/opt/broken"], 1);
+
+        let names: Vec<_> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["fast", "slow", "broken"]);
+        assert_eq!(results[0].runs, 3);
+        assert!((results[0].mean_ms - 10.0).abs() < 1e-9);
+        assert!((results[1].median_ms - 30.0).abs() < 1e-9);
+        assert!(results[2].error.is_some());
+        assert!(to_table(&results).contains("broken  failed: bin/java is missing"));
+    }
+
+    #[test]
+    fn median_of_even_sample_count_averages_middle_pair() {
+        let samples = [40, 10, 30, 20].map(Duration::from_millis).to_vec();
+        let result = summarize("x", samples);
+        assert!((result.median_ms - 25.0).abs() < 1e-9);
+        assert!((result.mean_ms - 25.0).abs() < 1e-9);
+    }
+}
@@ -1,5 +1,7 @@
 //! Java 下载器:`AdoptiumDiscovery` + `MirrorResolver` 组合,
 //! 复用通用的 [`GenericDownloader`](crate::infrastructure::tool_protocol::GenericDownloader)。
+//! Temurin 在每个归档旁发布 `.sha256.txt` 校验文件与 `.sig` 分离签名;注册表没给 SHA-256 时
+//! 从镜像取校验文件,仍然没有则拒绝下载(`--no-verify` 放行)。
 
 use crate::environments::java::version_discovery::AdoptiumDiscovery;
 use crate::infrastructure::config::MirrorConfig;
@@ -12,8 +14,11 @@ impl JavaDownloader {
     pub fn new(mirrors: Vec<MirrorConfig>) -> Self {
         Self::with_file_name(AdoptiumDiscovery::new(), mirrors, |version, mirror| {
             let vars = &version.template_vars;
+            // 缓存文件名保留原扩展名,解压按扩展名分派
             let ext = if vars.filename.ends_with(".zip") {
                 "zip"
+            } else if vars.filename.ends_with(".tar.xz") {
+                "tar.xz"
             } else {
                 "tar.gz"
             };
@@ -22,5 +27,85 @@ impl JavaDownloader {
                 version.version, vars.os, vars.arch, mirror, ext
             )
         })
+        .with_checksum_suffix(".sha256.txt")
+        .with_signature_suffix(".sig")
+        .requiring_checksum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::config::Config;
+    use crate::infrastructure::remote::java_downloader::DownloadTarget;
+    use crate::infrastructure::remote::platform::Platform;
+    use crate::infrastructure::tool_protocol::{ToolDownloader, VersionDiscovery};
+    use crate::testutil::FnvaHomeGuard;
+    use httpmock::prelude::*;
+
+    const ARCHIVE: &str = "OpenJDK21U-jdk_x64_linux_hotspot_21.0.5_11.tar.gz";
+
+    /// 与在线发现一致:镜像目录只给文件名,版本不带 SHA-256,校验和取自旁边的 `.sha256.txt`
+    #[tokio::test]
+    async fn online_version_is_verified_against_published_checksum_file() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let mut config = Config::new();
+        config.download.retry_count = 0;
+        config.save().unwrap();
+
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path_contains("/21/jdk/");
+            then.status(200)
+                .body(format!(r#"<a href="{ARCHIVE}">{ARCHIVE}</a>"#));
+        });
+        let archive = server.mock(|when, then| {
+            when.method(GET).path(format!("/good/{ARCHIVE}"));
+            then.status(200).body("jdk payload");
+        });
+        let published = server.mock(|when, then| {
+            when.method(GET).path(format!("/good/{ARCHIVE}.sha256.txt"));
+            then.status(200).body(format!(
+                "{}  {ARCHIVE}\n",
+                hex::encode(<sha2::Sha256 as sha2::Digest>::digest(b"jdk payload"))
+            ));
+        });
+        let mirror = |name: &str| MirrorConfig {
+            name: name.to_string(),
+            priority: 1,
+            base_url: server.url(format!("/{name}")),
+            url_template: "{base_url}/{filename}".to_string(),
+            enabled: true,
+        };
+
+        let version = AdoptiumDiscovery::with_base_url(&server.base_url())
+            .offline(false)
+            .find("21")
+            .await
+            .unwrap();
+        assert_eq!(version.version, "21.0.5+11");
+        assert_eq!(version.template_vars.sha256, None);
+        let platform = Platform::current();
+
+        let target = JavaDownloader::new(vec![mirror("good")])
+            .download(&version, &platform, Box::new(|_, _| {}))
+            .await
+            .unwrap();
+        let DownloadTarget::File(path) = target else {
+            panic!("expected a file");
+        };
+        assert_eq!(std::fs::read(path).unwrap(), b"jdk payload");
+        published.assert();
+        archive.assert();
+
+        // 镜像上没有校验文件:拒绝不校验地安装
+        let Err(err) = JavaDownloader::new(vec![mirror("bare")])
+            .download(&version, &platform, Box::new(|_, _| {}))
+            .await
+        else {
+            panic!("a download without any checksum should be refused");
+        };
+        assert!(format!("{err}").contains("--no-verify"), "{err}");
     }
 }
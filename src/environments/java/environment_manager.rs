@@ -1,13 +1,14 @@
 use crate::core::environment_manager::{
-    DynEnvironment, EnvironmentInfo, EnvironmentManager, EnvironmentType,
+    DynEnvironment, EnvironmentInfo, EnvironmentManager, EnvironmentType, SwitchOptions,
 };
 use crate::core::presentation::ScanHit;
 use crate::core::session::SessionManager;
 use crate::environments::java::scanner::JavaScanner;
 use crate::error::AppError;
+use crate::infrastructure::config::PathStrategy;
 use crate::infrastructure::shell::ScriptGenerator;
 use crate::infrastructure::shell::ShellType;
-use crate::utils::path::normalize_path;
+use crate::utils::path::{clean_home, normalize_path};
 use serde_json;
 use std::collections::HashMap;
 
@@ -87,6 +88,9 @@ impl JavaEnvironmentManager {
                 java_home: java_home.to_string(),
                 description: description.to_string(),
                 source: crate::infrastructure::config::EnvironmentSource::Manual,
+                java_opts: None,
+                extra_paths: Vec::new(),
+                version: None,
             };
             config.java_environments.push(new_env);
         }
@@ -188,7 +192,9 @@ impl EnvironmentManager for JavaEnvironmentManager {
         let java_home = config
             .get("java_home")
             .and_then(|v| v.as_str())
+            .map(crate::utils::path::clean_home)
             .ok_or_else(|| AppError::validation("java_home", "missing in config"))?;
+        let java_home = java_home.as_str();
 
         // Validate that it's a valid Java installation
         if !crate::environments::java::scanner::JavaScanner::is_valid_java_installation(java_home) {
@@ -255,28 +261,44 @@ impl EnvironmentManager for JavaEnvironmentManager {
     }
 
     fn use_env(&mut self, name: &str, shell_type: Option<ShellType>) -> Result<String, AppError> {
+        self.use_env_with(name, shell_type, SwitchOptions::default())
+    }
+
+    fn use_env_with(
+        &mut self,
+        name: &str,
+        shell_type: Option<ShellType>,
+        options: SwitchOptions,
+    ) -> Result<String, AppError> {
         let java_installation = self
             .installations
             .get(name)
             .ok_or_else(|| AppError::not_found(&format!("Java environment '{name}'")))?;
 
         // 验证 java_home 路径是否真实存在且包含有效的 Java 安装
-        if !crate::utils::validate_java_home(&java_installation.java_home) {
-            let java_home = &java_installation.java_home;
-            let path_exists = std::path::Path::new(java_home).exists();
-            let reason = if path_exists {
-                format!("Java installation at '{java_home}' is incomplete or corrupted")
-            } else {
-                format!("Java installation path does not exist: {java_home}")
-            };
-            return Err(AppError::validation("java_home", &reason));
-        }
+        self.validate(name)
+            .map_err(|reason| AppError::validation("java_home", &reason))?;
 
         let shell_type =
             shell_type.unwrap_or_else(crate::infrastructure::shell::platform::detect_shell);
 
+        // 读不到配置时退回默认的 fnva_only,只影响 PATH 清理方式
+        let app_config = crate::infrastructure::config::Config::load().unwrap_or_default();
+        let env = app_config.get_java_env(name);
+        let java_opts = env.and_then(|e| e.java_opts.clone());
+        let extra_paths = env.map(|e| e.extra_paths.clone()).unwrap_or_default();
+
+        let strategy = if options.keep_path {
+            PathStrategy::Keep
+        } else {
+            app_config.path_strategy
+        };
         let config = serde_json::json!({
             "java_home": java_installation.java_home,
+            "aggressive_path": strategy == PathStrategy::Aggressive,
+            "keep_path": strategy == PathStrategy::Keep,
+            "java_opts": java_opts,
+            "extra_paths": extra_paths,
         });
 
         let generator = ScriptGenerator::new()?;
@@ -293,13 +315,9 @@ impl EnvironmentManager for JavaEnvironmentManager {
 
         // Check environment variable JAVA_HOME to determine current
         if let Ok(java_home) = std::env::var("JAVA_HOME") {
-            // Normalize the JAVA_HOME path for comparison
-            let normalized_current = normalize_path(&java_home);
-
             // Find which environment matches this JAVA_HOME
             for (name, installation) in &self.installations {
-                let normalized_installation = normalize_path(&installation.java_home);
-                if normalized_installation == normalized_current {
+                if same_java_home(&installation.java_home, &java_home) {
                     return Ok(Some(name.clone()));
                 }
             }
@@ -335,6 +353,60 @@ impl EnvironmentManager for JavaEnvironmentManager {
     fn get_details(&self, name: &str) -> Result<Option<DynEnvironment>, AppError> {
         self.get(name)
     }
+
+    fn validate(&self, name: &str) -> Result<(), String> {
+        let java_home = &self
+            .installations
+            .get(name)
+            .ok_or_else(|| format!("Java environment '{name}' not found"))?
+            .java_home;
+        if !std::path::Path::new(java_home).exists() {
+            return Err(format!(
+                "Java installation path does not exist: {java_home}"
+            ));
+        }
+        if !crate::utils::validate_java_home(java_home) {
+            return Err(format!(
+                "Java installation at '{java_home}' is incomplete or corrupted (bin/java missing)"
+            ));
+        }
+        Ok(())
+    }
+
+    fn env_vars(&self, name: &str) -> Result<Vec<(String, String)>, AppError> {
+        let java_home = &self
+            .installations
+            .get(name)
+            .ok_or_else(|| AppError::not_found(&format!("Java environment '{name}'")))?
+            .java_home;
+        let bin = std::path::Path::new(java_home).join("bin");
+        let sep = if cfg!(windows) { ";" } else { ":" };
+        let config = crate::infrastructure::config::Config::load().ok();
+        let env = config.as_ref().and_then(|c| c.get_java_env(name));
+        let mut path = vec![bin.display().to_string()];
+        path.extend(env.iter().flat_map(|e| e.extra_paths.iter().cloned()));
+        path.push("${PATH}".to_string());
+        let mut vars = vec![
+            ("JAVA_HOME".to_string(), java_home.clone()),
+            ("PATH".to_string(), path.join(sep)),
+        ];
+        if let Some(opts) = env.and_then(|e| e.java_opts.clone()) {
+            vars.push(("JAVA_OPTS".to_string(), opts));
+        }
+        Ok(vars)
+    }
+}
+
+/// `JAVA_HOME` 是否指向环境 `java_home`:归一化后相同,或是其内嵌的 `jre`
+/// (JDK 8 及更早,不少工具把 `JAVA_HOME` 设成 `<jdk>/jre`)。
+pub(crate) fn same_java_home(env_home: &str, java_home: &str) -> bool {
+    let env_home = normalize_path(&clean_home(env_home));
+    let current = normalize_path(&clean_home(java_home));
+    if env_home == current {
+        return true;
+    }
+    let jre = std::path::Path::new(&env_home).join("jre");
+    normalize_path(&jre.to_string_lossy()) == current
 }
 
 #[cfg(test)]
@@ -364,6 +436,9 @@ mod tests {
                     java_home: "/nonexistent/java".to_string(),
                     description: String::new(),
                     source: EnvironmentSource::Manual,
+                    java_opts: None,
+                    extra_paths: Vec::new(),
+                    version: None,
                 })
                 .unwrap();
             config.save().unwrap();
@@ -376,6 +451,46 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn java_use_env_with_keep_path_skips_path_cleaning() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let home = tmp.path().join("jdk17");
+        let java = if cfg!(windows) { "java.exe" } else { "java" };
+        std::fs::create_dir_all(home.join("bin")).unwrap();
+        std::fs::write(home.join("bin").join(java), b"").unwrap();
+        {
+            let mut config = Config::new();
+            config
+                .add_java_env(JavaEnvironment {
+                    name: "j17".to_string(),
+                    java_home: home.to_string_lossy().into_owned(),
+                    description: String::new(),
+                    source: EnvironmentSource::Manual,
+                    java_opts: None,
+                    extra_paths: Vec::new(),
+                    version: None,
+                })
+                .unwrap();
+            config.save().unwrap();
+        }
+        let mut m = JavaEnvironmentManager::new();
+        let cleaned = m.use_env("j17", Some(ShellType::Bash)).unwrap();
+        assert!(cleaned.contains("${PATH//"), "{cleaned}");
+        let kept = m
+            .use_env_with(
+                "j17",
+                Some(ShellType::Bash),
+                SwitchOptions { keep_path: true },
+            )
+            .unwrap();
+        assert!(!kept.contains("${PATH//"), "{kept}");
+        assert!(
+            kept.contains(r#"export PATH="$FNVA_JAVA_BIN:$PATH""#),
+            "{kept}"
+        );
+    }
+
     #[test]
     fn java_list_loads_from_config() {
         let tmp = tempfile::TempDir::new().unwrap();
@@ -388,6 +503,9 @@ mod tests {
                     java_home: "/x/java".to_string(),
                     description: String::new(),
                     source: EnvironmentSource::Manual,
+                    java_opts: None,
+                    extra_paths: Vec::new(),
+                    version: None,
                 })
                 .unwrap();
             config.save().unwrap();
@@ -396,4 +514,50 @@ mod tests {
         let list = m.list().unwrap();
         assert!(list.iter().any(|e| e.name == "j17"));
     }
+
+    #[test]
+    fn java_get_current_matches_nested_jre_java_home() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let jdk = tmp.path().join("jdk8");
+        std::fs::create_dir_all(jdk.join("jre").join("bin")).unwrap();
+        {
+            let mut config = Config::new();
+            config
+                .add_java_env(JavaEnvironment {
+                    name: "jdk8".to_string(),
+                    java_home: jdk.to_string_lossy().into_owned(),
+                    description: String::new(),
+                    source: EnvironmentSource::Manual,
+                    java_opts: None,
+                    extra_paths: Vec::new(),
+                    version: None,
+                })
+                .unwrap();
+            config.save().unwrap();
+        }
+
+        let prev = std::env::var("JAVA_HOME").ok();
+        std::env::set_var("JAVA_HOME", jdk.join("jre"));
+        let current = JavaEnvironmentManager::new().get_current().unwrap();
+        match prev {
+            Some(v) => std::env::set_var("JAVA_HOME", v),
+            None => std::env::remove_var("JAVA_HOME"),
+        }
+        assert_eq!(current.as_deref(), Some("jdk8"));
+
+        assert!(same_java_home(
+            &jdk.to_string_lossy(),
+            &format!("{}/jre/", jdk.display())
+        ));
+        // 反过来(环境是 jre、JAVA_HOME 是 JDK)或兄弟目录都不算
+        assert!(!same_java_home(
+            &jdk.join("jre").to_string_lossy(),
+            &jdk.to_string_lossy()
+        ));
+        assert!(!same_java_home(
+            &jdk.to_string_lossy(),
+            &tmp.path().join("jdk8-jre").to_string_lossy()
+        ));
+    }
 }
@@ -0,0 +1,190 @@
+//! 单个 Java 环境的详细信息(`fnva java info <name>`)。
+//!
+//! 版本与供应商优先读 `$JAVA_HOME/release`(不用启动 JVM),读不到再退回
+//! `java -version` / 路径猜测。fnva 安装的环境额外统计占用空间。
+
+use crate::environments::java::scanner::JavaScanner;
+use crate::infrastructure::config::{Config, EnvironmentSource, JavaEnvironment};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Serialize)]
+pub struct JavaInfo {
+    pub name: String,
+    pub java_home: String,
+    pub description: String,
+    pub version: Option<String>,
+    pub vendor: Option<String>,
+    /// manual / scanned
+    pub source: String,
+    pub is_default: bool,
+    pub is_current: bool,
+    /// `bin/java` 存在
+    pub valid: bool,
+    /// 不可用的原因(`valid` 为 false 时)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub problem: Option<String>,
+    /// 含 `bin/javac`(否则是 JRE)
+    pub is_jdk: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub java_opts: Option<String>,
+    /// 安装在 `~/.fnva/packages/java` 下
+    pub managed: bool,
+    /// 仅 fnva 安装的环境统计
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+}
+
+/// 收集环境详情;`current` 为当前激活的环境名。
+pub fn java_info(env: &JavaEnvironment, config: &Config, current: Option<&str>) -> JavaInfo {
+    let home = Path::new(&env.java_home);
+    let problem = if !home.exists() {
+        Some(format!("path does not exist: {}", env.java_home))
+    } else if !crate::utils::validate_java_home(&env.java_home) {
+        Some("bin/java is missing".to_string())
+    } else {
+        None
+    };
+    let valid = problem.is_none();
+
+    let version = release_field(home, "JAVA_VERSION").or_else(|| {
+        valid
+            .then(|| {
+                JavaScanner::detect_java_version(&env.java_home)
+                    .ok()
+                    .flatten()
+            })
+            .flatten()
+    });
+    let vendor = release_field(home, "IMPLEMENTOR")
+        .or_else(|| JavaScanner::detect_vendor(&env.java_home).ok().flatten());
+
+    let managed = crate::infrastructure::paths::tool_packages_dir("java")
+        .is_ok_and(|dir| home.starts_with(dir));
+
+    JavaInfo {
+        name: env.name.clone(),
+        java_home: env.java_home.clone(),
+        description: env.description.clone(),
+        version,
+        vendor,
+        source: match env.source {
+            EnvironmentSource::Manual => "manual",
+            EnvironmentSource::Scanned => "scanned",
+        }
+        .to_string(),
+        is_default: config.default_java_env.as_deref() == Some(env.name.as_str()),
+        is_current: current == Some(env.name.as_str()),
+        valid,
+        problem,
+        is_jdk: valid && crate::utils::is_jdk(&env.java_home),
+        java_opts: env.java_opts.clone(),
+        managed,
+        size_bytes: (managed && valid).then(|| crate::utils::path::dir_size(home)),
+    }
+}
+
+/// 读 `release` 文件里的 `KEY="value"`。
+pub(crate) fn release_field(java_home: &Path, key: &str) -> Option<String> {
+    let content = std::fs::read_to_string(java_home.join("release")).ok()?;
+    content.lines().find_map(|line| {
+        let value = line.strip_prefix(key)?.strip_prefix('=')?;
+        let value = value.trim().trim_matches('"');
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+impl JavaInfo {
+    /// 与 `maven show` 相同的 `键 : 值` 文本格式。
+    pub fn to_text(&self) -> String {
+        let or_unknown = |v: &Option<String>| v.clone().unwrap_or_else(|| "unknown".to_string());
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        let mut lines = vec![
+            format!("Name        : {}", self.name),
+            format!("JAVA_HOME   : {}", self.java_home),
+            format!("Description : {}", self.description),
+            format!("Version     : {}", or_unknown(&self.version)),
+            format!("Vendor      : {}", or_unknown(&self.vendor)),
+            format!("Source      : {}", self.source),
+            format!("Current     : {}", yes_no(self.is_current)),
+            format!("Default     : {}", yes_no(self.is_default)),
+        ];
+        match &self.problem {
+            Some(p) => lines.push(format!("Valid       : no ({p})")),
+            None if !self.is_jdk => lines.push("Valid       : yes (JRE, no javac)".to_string()),
+            None => lines.push("Valid       : yes".to_string()),
+        }
+        if let Some(opts) = &self.java_opts {
+            lines.push(format!("JAVA_OPTS   : {opts}"));
+        }
+        lines.push(format!("Managed     : {}", yes_no(self.managed)));
+        if let Some(size) = self.size_bytes {
+            lines.push(format!("Size        : {} MB", size / (1024 * 1024)));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::FnvaHomeGuard;
+
+    fn env(name: &str, home: &Path) -> JavaEnvironment {
+        JavaEnvironment {
+            name: name.to_string(),
+            java_home: home.to_string_lossy().into_owned(),
+            description: String::new(),
+            source: EnvironmentSource::Manual,
+            java_opts: None,
+            extra_paths: Vec::new(),
+            version: None,
+        }
+    }
+
+    fn fake_jdk(home: &Path, release: &str) {
+        std::fs::create_dir_all(home.join("bin")).unwrap();
+        let java = if cfg!(windows) { "java.exe" } else { "java" };
+        std::fs::write(home.join("bin").join(java), b"").unwrap();
+        std::fs::write(home.join("release"), release).unwrap();
+    }
+
+    #[test]
+    fn managed_env_reports_release_info_and_size() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let home = crate::infrastructure::paths::tool_packages_dir("java")
+            .unwrap()
+            .join("21.0.5+11");
+        fake_jdk(
+            &home,
+            "IMPLEMENTOR=\"Eclipse Adoptium\"\nJAVA_VERSION=\"21.0.5\"\n",
+        );
+        std::fs::write(home.join("lib.bin"), vec![0u8; 4096]).unwrap();
+
+        let mut config = Config::new();
+        config.default_java_env = Some("21".to_string());
+        let info = java_info(&env("21", &home), &config, Some("21"));
+        assert_eq!(info.version.as_deref(), Some("21.0.5"));
+        assert_eq!(info.vendor.as_deref(), Some("Eclipse Adoptium"));
+        assert!(info.valid && info.managed && info.is_default && info.is_current);
+        // JRE:没有 javac
+        assert!(!info.is_jdk);
+        assert!(info.size_bytes.unwrap() >= 4096);
+    }
+
+    #[test]
+    fn unmanaged_missing_env_reports_problem_without_size() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let info = java_info(
+            &env("ghost", &tmp.path().join("nope")),
+            &Config::new(),
+            None,
+        );
+        assert!(!info.valid && !info.managed && !info.is_current);
+        assert!(info.problem.unwrap().contains("does not exist"));
+        assert_eq!(info.size_bytes, None);
+        assert_eq!(info.version, None);
+    }
+}
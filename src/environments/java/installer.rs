@@ -1,16 +1,23 @@
 use super::downloader::JavaDownloader;
-use crate::config::Config;
+use super::manifest::{self, JavaManifest, ManifestEntry};
+use super::version_discovery::{parse_version_spec, registry_platform_builds, VersionSpec};
+use crate::config::{Config, DownloadOverrides};
 use crate::infrastructure::installer::generic;
 use crate::infrastructure::remote::platform::Platform;
 use crate::infrastructure::tool_protocol::{
     AssetModel, ResolvedVersion, ToolDescriptor, ToolDownloader,
 };
+use serde::Serialize;
 use std::fs;
 use std::path::Path;
+use std::time::Duration;
 
 /// Java 安装管理器
 pub struct JavaInstaller;
 
+/// 清单条目的来源标识(当前所有远程版本都来自 Adoptium 发布)。
+const MANIFEST_SOURCE: &str = "adoptium";
+
 /// Java 工具描述符(供通用 installer 骨架参数化)
 pub const JAVA_DESCRIPTOR: ToolDescriptor = ToolDescriptor {
     id: "java",
@@ -21,12 +28,36 @@ pub const JAVA_DESCRIPTOR: ToolDescriptor = ToolDescriptor {
     locate_home: JavaInstaller::find_installed_java,
 };
 
+/// `ls-remote --json` 的单个版本条目。
+#[derive(Debug, Serialize)]
+pub struct RemoteJavaVersion {
+    pub version: String,
+    pub major: Option<u32>,
+    pub is_lts: bool,
+    pub display: String,
+    /// `{os}-{arch}`,与注册表的平台键一致
+    pub platform: String,
+    pub filename: String,
+    pub sha256: Option<String>,
+    pub download_urls: Vec<MirrorUrl>,
+}
+
+/// 某个镜像上的下载地址。
+#[derive(Debug, Serialize)]
+pub struct MirrorUrl {
+    pub mirror: String,
+    pub url: String,
+}
+
 impl JavaInstaller {
     /// 安装指定版本的 Java（使用模板化下载器）
     pub async fn install_java(
         version_spec: &str,
         config: &mut Config,
         auto_switch: bool,
+        lts_only: bool,
+        keep_archive: Option<&Path>,
+        overrides: DownloadOverrides,
     ) -> Result<String, String> {
         crate::cli::print::action(&format!("Installing java {version_spec}"));
 
@@ -38,11 +69,54 @@ impl JavaInstaller {
                 auto_switch,
                 &java_home,
                 "local",
-                "local",
+                None,
             )
             .await;
         }
 
+        let (downloader, deadline) = Self::install_downloader(config, &overrides)?;
+        Self::install_within_deadline(
+            &downloader,
+            deadline,
+            version_spec,
+            config,
+            auto_switch,
+            lts_only,
+            keep_archive,
+        )
+        .await
+    }
+
+    /// `install --major-only`:环境名固定为 `jdk<major>`;同一大版本已装过旧补丁时,
+    /// `confirm(环境名, 当前版本, 新版本)` 同意后原名升级到新补丁并删除旧目录。
+    pub async fn install_major_only(
+        version_spec: &str,
+        config: &mut Config,
+        auto_switch: bool,
+        lts_only: bool,
+        keep_archive: Option<&Path>,
+        overrides: DownloadOverrides,
+        confirm: &dyn Fn(&str, &str, &str) -> bool,
+    ) -> Result<String, String> {
+        crate::cli::print::action(&format!("Installing java {version_spec} (major only)"));
+        let (downloader, deadline) = Self::install_downloader(config, &overrides)?;
+        let install = Self::install_major_with(
+            &downloader,
+            version_spec,
+            config,
+            auto_switch,
+            lts_only,
+            keep_archive,
+            confirm,
+        );
+        Self::run_within_deadline(&downloader, deadline, install).await
+    }
+
+    /// 按配置的镜像与超时构造下载器,叠加本次命令的覆盖值;`--no-verify` 时跳过校验。
+    fn install_downloader(
+        config: &Config,
+        overrides: &DownloadOverrides,
+    ) -> Result<(JavaDownloader, Option<Duration>), String> {
         let mirrors = config.mirrors.java.clone();
         let mirror_names: Vec<&str> = mirrors
             .iter()
@@ -51,66 +125,331 @@ impl JavaInstaller {
             .collect();
         crate::cli::print::step("Mirrors", &mirror_names.join(" -> "));
 
-        let downloader = JavaDownloader::new(mirrors);
-        let res =
-            Self::install_with_downloader(&downloader, version_spec, config, auto_switch).await;
+        let deadline = match config.download.install_timeout_sec {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        };
+        let mut downloader = JavaDownloader::new(mirrors)
+            .with_download_config(config.download.clone().with_overrides(overrides))?;
+        if overrides.no_verify {
+            crate::cli::print::warn(
+                "--no-verify: skipping checksum and signature checks; \
+                 a corrupted or tampered JDK will not be detected",
+            );
+            downloader = downloader.without_verification();
+        }
+        Ok((downloader, deadline))
+    }
+
+    /// 给整次安装(所有镜像 × 重试)加总时限;超时即取消,返回最近一次来源失败的原因。
+    async fn install_within_deadline(
+        downloader: &dyn ToolDownloader,
+        deadline: Option<Duration>,
+        version_spec: &str,
+        config: &mut Config,
+        auto_switch: bool,
+        lts_only: bool,
+        keep_archive: Option<&Path>,
+    ) -> Result<String, String> {
+        let install = Self::install_with_downloader(
+            downloader,
+            version_spec,
+            config,
+            auto_switch,
+            lts_only,
+            true,
+            keep_archive,
+        );
+        Self::run_within_deadline(downloader, deadline, install).await
+    }
+
+    async fn run_within_deadline(
+        downloader: &dyn ToolDownloader,
+        deadline: Option<Duration>,
+        install: impl std::future::Future<Output = Result<String, String>>,
+    ) -> Result<String, String> {
+        let res = match deadline {
+            Some(deadline) => match tokio::time::timeout(deadline, install).await {
+                Ok(res) => res,
+                Err(_) => {
+                    return Err(match downloader.last_error() {
+                        Some(e) => format!("Install timed out after {deadline:?}; last error: {e}"),
+                        None => format!("Install timed out after {deadline:?}"),
+                    })
+                }
+            },
+            None => install.await,
+        };
+        res.map_err(|e| format!("All mirrors failed: {e}"))
+    }
+
+    /// `java use --create-if-missing`:同名环境已存在时直接返回;否则按版本规格安装,
+    /// 返回新环境名(`lts` / `latest` 会解析成具体版本名)。
+    pub async fn ensure_java_env(spec: &str, config: &mut Config) -> Result<String, String> {
+        let downloader = JavaDownloader::new(config.mirrors.java.clone());
+        Self::ensure_with_downloader(&downloader, spec, config).await
+    }
+
+    async fn ensure_with_downloader(
+        downloader: &dyn ToolDownloader,
+        spec: &str,
+        config: &mut Config,
+    ) -> Result<String, String> {
+        if config.get_java_env(spec).is_some() {
+            return Ok(spec.to_string());
+        }
+        crate::cli::print::action(&format!("java {spec} is not installed, installing"));
+        // 规格必须能解析:拼错的名字不能装成最新版再以错名注册
+        let java_home =
+            Self::install_with_downloader(downloader, spec, config, false, false, false, None)
+                .await?;
+        config
+            .java_environments
+            .iter()
+            .find(|e| e.java_home == java_home)
+            .map(|e| e.name.clone())
+            .ok_or_else(|| format!("Installed java {spec} but it is not registered"))
+    }
 
-        match res {
-            Ok(java_home) => Ok(java_home),
-            Err(e) => Err(format!("All mirrors failed: {e}")),
+    /// 离线安装本地 JDK 归档(tar.gz / zip),注册为 `name`;全程不访问网络。
+    pub async fn install_from_archive(
+        archive: &Path,
+        name: &str,
+        config: &mut Config,
+        auto_switch: bool,
+    ) -> Result<String, String> {
+        crate::cli::print::action(&format!("Installing java {name} from archive"));
+        if config.get_java_env(name).is_some() {
+            return Err(format!("Java environment '{name}' already exists"));
         }
+        crate::cli::print::step("Source", &archive.display().to_string());
+        let java_home = generic::install_local_archive(archive, name, &JAVA_DESCRIPTOR)?;
+        Self::complete_installation_simple(name, config, auto_switch, &java_home, name, None).await
     }
 
+    /// 解析并安装 `version_spec`;`fallback_to_latest` 见 [`Self::resolve_install_target`]。
     async fn install_with_downloader(
         downloader: &dyn ToolDownloader,
         version_spec: &str,
         config: &mut Config,
         auto_switch: bool,
+        lts_only: bool,
+        fallback_to_latest: bool,
+        keep_archive: Option<&Path>,
     ) -> Result<String, String> {
-        let resolved = match downloader.find_version_by_spec(version_spec).await {
-            Ok(version) => {
-                crate::cli::print::step(
-                    "Resolved",
-                    &format!("{} ({})", version.version, version.display),
-                );
-                version
-            }
-            Err(_) => {
-                crate::cli::print::step("Resolved", "using latest...");
-                downloader
-                    .list_available_versions()
-                    .await
-                    .map_err(|e| format!("{e:?}"))?
-                    .into_iter()
-                    .next()
-                    .ok_or_else(|| "No versions available".to_string())?
-            }
-        };
+        let (resolved, install_name) =
+            Self::resolve_install_target(downloader, version_spec, lts_only, fallback_to_latest)
+                .await?;
+        crate::cli::print::step(
+            "Resolved",
+            &format!("{} ({})", resolved.version, resolved.display),
+        );
 
         let platform = Platform::current();
-        let java_home =
-            Self::download_and_install(downloader, &resolved, &platform, version_spec).await?;
+        let java_home = Self::download_and_install(
+            downloader,
+            &resolved,
+            &platform,
+            &install_name,
+            keep_archive,
+        )
+        .await?;
         Self::complete_installation_simple(
-            version_spec,
+            &install_name,
             config,
             auto_switch,
             &java_home,
             &resolved.version,
-            &resolved.display,
+            Some(&resolved.version),
         )
         .await
     }
 
+    async fn install_major_with(
+        downloader: &dyn ToolDownloader,
+        version_spec: &str,
+        config: &mut Config,
+        auto_switch: bool,
+        lts_only: bool,
+        keep_archive: Option<&Path>,
+        confirm: &dyn Fn(&str, &str, &str) -> bool,
+    ) -> Result<String, String> {
+        let (resolved, _) =
+            Self::resolve_install_target(downloader, version_spec, lts_only, true).await?;
+        crate::cli::print::step(
+            "Resolved",
+            &format!("{} ({})", resolved.version, resolved.display),
+        );
+        let major = resolved
+            .major
+            .or_else(|| resolved.version.split(['.', '+', '-']).next()?.parse().ok())
+            .ok_or_else(|| {
+                format!(
+                    "Cannot tell the major version of {}; install it without --major-only",
+                    resolved.version
+                )
+            })?;
+        let name = format!("jdk{major}");
+
+        let Some(existing) = config.get_java_env(&name).cloned() else {
+            let java_home = Self::download_and_install(
+                downloader,
+                &resolved,
+                &Platform::current(),
+                &name,
+                keep_archive,
+            )
+            .await?;
+            return Self::complete_installation_simple(
+                &name,
+                config,
+                auto_switch,
+                &java_home,
+                &resolved.version,
+                Some(&resolved.version),
+            )
+            .await;
+        };
+
+        // 只替换环境名自己的目录,别的环境的安装目录不动
+        let packages_dir = crate::infrastructure::paths::tool_packages_dir("java")?;
+        let target_dir = packages_dir.join(&name);
+        if !Path::new(&existing.java_home).starts_with(&target_dir) {
+            return Err(format!(
+                "'{name}' is not installed in {} ({}); remove or rename it first",
+                target_dir.display(),
+                existing.java_home
+            ));
+        }
+        let current = manifest::installed_version(&existing)
+            .unwrap_or_else(|| "an unknown version".to_string());
+        match (
+            java_version_key(&resolved.version),
+            java_version_key(&current),
+        ) {
+            (Some(new), Some(old)) if new == old => {
+                crate::cli::print::step("Status", &format!("{name} is up to date ({current})"));
+                return Ok(existing.java_home);
+            }
+            (Some(new), Some(old)) if new < old => {
+                crate::cli::print::step(
+                    "Status",
+                    &format!(
+                        "{name} is at {current}, newer than {}; not downgrading",
+                        resolved.version
+                    ),
+                );
+                return Ok(existing.java_home);
+            }
+            _ => {}
+        }
+        if !confirm(&name, &current, &resolved.version) {
+            crate::cli::print::step("Status", &format!("Kept {name} at {current}"));
+            return Ok(existing.java_home);
+        }
+
+        // 新版本先装到旁边的目录,成功后才删除旧目录并接管环境名对应的目录
+        let staging = format!("{name}.upgrade");
+        let staged_home = Self::download_and_install(
+            downloader,
+            &resolved,
+            &Platform::current(),
+            &staging,
+            keep_archive,
+        )
+        .await?;
+        let staging_dir = packages_dir.join(&staging);
+        if target_dir.exists() {
+            fs::remove_dir_all(&target_dir)
+                .map_err(|e| format!("Failed to remove old install: {e}"))?;
+        }
+        fs::rename(&staging_dir, &target_dir)
+            .map_err(|e| format!("Failed to move the new install into place: {e}"))?;
+        let java_home = Path::new(&staged_home)
+            .strip_prefix(&staging_dir)
+            .map(|rel| target_dir.join(rel))
+            .map_err(|_| format!("Unexpected install location: {staged_home}"))?
+            .to_string_lossy()
+            .to_string();
+
+        if let Some(env) = config.java_environments.iter_mut().find(|e| e.name == name) {
+            env.description = format!("Java {} ({java_home})", resolved.version);
+            env.java_home = java_home.clone();
+            env.version = Some(resolved.version.clone());
+        }
+        if auto_switch {
+            config.set_current_java_env(name.clone())?;
+        }
+        config.save()?;
+        crate::cli::print::step(
+            "Upgraded",
+            &format!("{name}: {current} -> {}", resolved.version),
+        );
+        Ok(java_home)
+    }
+
+    /// 解析安装目标,返回 `(具体版本, 环境名)`。
+    ///
+    /// `lts` / `latest` 关键字必须解析成具体版本,环境名取解析结果而非关键字本身,
+    /// 否则下次 `lts` 指向新版本时会与旧安装撞名;其余规格保持原名,`fallback_to_latest` 时
+    /// 解析失败回退到最新版本。`lts_only` 时不回退:解析不到或解析结果不是 LTS 都直接报错。
+    async fn resolve_install_target(
+        downloader: &dyn ToolDownloader,
+        version_spec: &str,
+        lts_only: bool,
+        fallback_to_latest: bool,
+    ) -> Result<(ResolvedVersion, String), String> {
+        let (resolved, install_name) = match parse_version_spec(version_spec) {
+            Ok(VersionSpec::LatestLts) | Ok(VersionSpec::Latest) => {
+                let resolved = downloader
+                    .find_version_by_spec(version_spec)
+                    .await
+                    .map_err(|e| format!("Failed to resolve '{version_spec}': {e}"))?;
+                let install_name = resolved.version.clone();
+                (resolved, install_name)
+            }
+            _ => {
+                let resolved = match downloader.find_version_by_spec(version_spec).await {
+                    Ok(version) => version,
+                    Err(_) if lts_only => {
+                        return Err(format!("No LTS release matches '{version_spec}'"));
+                    }
+                    Err(e) if !fallback_to_latest => {
+                        return Err(format!("Failed to resolve '{version_spec}': {e}"));
+                    }
+                    Err(_) => {
+                        crate::cli::print::step("Resolved", "using latest...");
+                        downloader
+                            .list_available_versions()
+                            .await
+                            .map_err(|e| format!("{e:?}"))?
+                            .into_iter()
+                            .next()
+                            .ok_or_else(|| "No versions available".to_string())?
+                    }
+                };
+                (resolved, version_spec.to_string())
+            }
+        };
+        if lts_only && !resolved.is_lts {
+            return Err(format!(
+                "'{version_spec}' resolves to {} which is not an LTS release",
+                resolved.version
+            ));
+        }
+        Ok((resolved, install_name))
+    }
+
     /// 完成安装流程（简单下载器）
     async fn complete_installation_simple(
-        version_spec: &str,
+        install_name: &str,
         config: &mut Config,
         auto_switch: bool,
         java_home: &str,
         version: &str,
-        _release_name: &str,
+        resolved_version: Option<&str>,
     ) -> Result<String, String> {
-        let install_name = version_spec.to_string();
+        let install_name = install_name.to_string();
 
         // Already installed - return success with info message
         if let Some(existing) = config.get_java_env(&install_name) {
@@ -124,6 +463,9 @@ impl JavaInstaller {
             java_home: java_home.to_string(),
             description,
             source: crate::config::EnvironmentSource::Manual,
+            java_opts: None,
+            extra_paths: Vec::new(),
+            version: resolved_version.map(str::to_string),
         })?;
         config.save()?;
 
@@ -145,9 +487,17 @@ impl JavaInstaller {
         version: &ResolvedVersion,
         platform: &Platform,
         env_name: &str,
+        keep_archive: Option<&Path>,
     ) -> Result<String, String> {
-        generic::download_and_install(downloader, version, platform, env_name, &JAVA_DESCRIPTOR)
-            .await
+        generic::download_and_install(
+            downloader,
+            version,
+            platform,
+            env_name,
+            &JAVA_DESCRIPTOR,
+            keep_archive,
+        )
+        .await
     }
 
     /// 查找已安装的 Java 目录
@@ -194,17 +544,105 @@ impl JavaInstaller {
         Err("No valid Java installation found".to_string())
     }
 
+    /// 列出可安装版本的结构化数据(`ls-remote --json`):含当前平台的文件名、
+    /// 注册表校验和与各镜像下载 URL。
+    pub async fn list_remote_versions(
+        lts_only: bool,
+        major: Option<u32>,
+    ) -> Result<Vec<RemoteJavaVersion>, String> {
+        let config = crate::infrastructure::config::Config::load()
+            .map_err(|e| format!("Failed to load config: {e}"))?;
+        let downloader = JavaDownloader::new(config.mirrors.java.clone());
+        let platform = Platform::current();
+
+        let versions = ToolDownloader::list_available_versions(&downloader)
+            .await
+            .map_err(|e| format!("{e:?}"))?;
+        Ok(versions
+            .iter()
+            .filter(|v| !lts_only || v.is_lts)
+            .filter(|v| major.is_none() || v.major == major)
+            .map(|v| Self::remote_entry(&downloader, v, &platform))
+            .collect())
+    }
+
+    /// `ls-remote <version> --platforms`:按安装规则解析版本,列出注册表中它在各平台的
+    /// 构建;注册表里没有该版本时只有当前平台一项。
+    pub async fn list_platform_builds(
+        version_spec: &str,
+    ) -> Result<Vec<RemoteJavaVersion>, String> {
+        let config = crate::infrastructure::config::Config::load()
+            .map_err(|e| format!("Failed to load config: {e}"))?;
+        let downloader = JavaDownloader::new(config.mirrors.java.clone());
+        let resolved = ToolDownloader::find_version_by_spec(&downloader, version_spec)
+            .await
+            .map_err(|e| format!("Failed to resolve '{version_spec}': {e}"))?;
+        let builds = registry_platform_builds(&resolved.version);
+        if builds.is_empty() {
+            return Ok(vec![Self::remote_entry(
+                &downloader,
+                &resolved,
+                &Platform::current(),
+            )]);
+        }
+        Ok(builds
+            .iter()
+            .map(|(platform, v)| Self::remote_entry(&downloader, v, platform))
+            .collect())
+    }
+
+    /// `install --explain`:按安装时的规则解析版本,返回当前平台依次尝试的下载 URL;
+    /// 不下载、不做可用性探测。
+    pub async fn explain_install(
+        version_spec: &str,
+        config: &Config,
+        lts_only: bool,
+    ) -> Result<RemoteJavaVersion, String> {
+        let downloader = JavaDownloader::new(config.mirrors.java.clone());
+        let (resolved, _) =
+            Self::resolve_install_target(&downloader, version_spec, lts_only, true).await?;
+        Ok(Self::remote_entry(
+            &downloader,
+            &resolved,
+            &Platform::current(),
+        ))
+    }
+
+    fn remote_entry(
+        downloader: &JavaDownloader,
+        v: &ResolvedVersion,
+        platform: &Platform,
+    ) -> RemoteJavaVersion {
+        RemoteJavaVersion {
+            version: v.version.clone(),
+            major: v.major,
+            is_lts: v.is_lts,
+            display: v.display.clone(),
+            platform: format!("{}-{}", platform.os, platform.arch),
+            filename: v.template_vars.filename.clone(),
+            sha256: v.template_vars.sha256.clone(),
+            download_urls: downloader
+                .candidate_urls(v)
+                .into_iter()
+                .map(|(mirror, url)| MirrorUrl { mirror, url })
+                .collect(),
+        }
+    }
+
     /// 列出可安装的 Java 版本
-    pub async fn list_installable_versions() -> Result<Vec<String>, String> {
+    pub async fn list_installable_versions(lts_only: bool) -> Result<Vec<String>, String> {
         let config = crate::infrastructure::config::Config::load()
             .map_err(|e| format!("Failed to load config: {e}"))?;
 
         let mirrors = config.mirrors.java.clone();
         let downloader = JavaDownloader::new(mirrors);
 
-        let versions = ToolDownloader::list_available_versions(&downloader)
+        let mut versions = ToolDownloader::list_available_versions(&downloader)
             .await
             .map_err(|e| format!("{e:?}"))?;
+        if lts_only {
+            versions.retain(|v| v.is_lts);
+        }
 
         let mut result = Vec::new();
 
@@ -226,7 +664,11 @@ impl JavaInstaller {
         let mut major_versions: Vec<_> = versions_by_major.keys().cloned().collect();
         major_versions.sort_by(|a, b| b.cmp(a));
 
-        result.push("Available versions (* = LTS):".to_string());
+        result.push(if lts_only {
+            "Available LTS versions:".to_string()
+        } else {
+            "Available versions (* = LTS):".to_string()
+        });
         result.push("".to_string());
 
         for major in major_versions.iter().take(15) {
@@ -258,6 +700,217 @@ impl JavaInstaller {
         Ok(result)
     }
 
+    /// 导出 fnva 托管 JDK 的清单:按已安装版本重新解析,附带首选镜像的下载地址与校验和。
+    /// 注册表里已找不到的版本仍然列出,只是没有 `url`。
+    pub async fn export_manifest(config: &Config) -> Result<JavaManifest, String> {
+        let packages_dir = crate::infrastructure::paths::tool_packages_dir("java")?;
+        let downloader = JavaDownloader::new(config.mirrors.java.clone());
+        let mut manifest = JavaManifest::default();
+        for env in manifest::managed_envs(&config.java_environments, &packages_dir) {
+            let Some(version) = manifest::installed_version(env) else {
+                crate::cli::print::warn(&format!(
+                    "java {}: installed version unknown (no recorded version or release file), skipped",
+                    env.name
+                ));
+                continue;
+            };
+            let resolved = downloader.find_version_by_spec(&version).await.ok();
+            if resolved.is_none() {
+                crate::cli::print::warn(&format!(
+                    "java {}: version {version} not found in registry, no download URL",
+                    env.name
+                ));
+            }
+            manifest.java.push(ManifestEntry {
+                name: env.name.clone(),
+                version: resolved
+                    .as_ref()
+                    .map_or(version.clone(), |r| r.version.clone()),
+                source: MANIFEST_SOURCE.to_string(),
+                url: resolved.as_ref().and_then(|r| {
+                    downloader
+                        .candidate_urls(r)
+                        .into_iter()
+                        .next()
+                        .map(|(_, url)| url)
+                }),
+                sha256: resolved.and_then(|r| r.template_vars.sha256),
+            });
+        }
+        Ok(manifest)
+    }
+
+    /// 按清单逐个安装,已存在同名环境的跳过;返回 `(已安装, 已跳过)` 名单。
+    /// 单个条目失败不中断,汇总后报错。
+    pub async fn apply_manifest(
+        manifest: &JavaManifest,
+        config: &mut Config,
+    ) -> Result<(Vec<String>, Vec<String>), String> {
+        let downloader = JavaDownloader::new(config.mirrors.java.clone());
+        Self::apply_manifest_with(&downloader, manifest, config).await
+    }
+
+    async fn apply_manifest_with(
+        downloader: &dyn ToolDownloader,
+        manifest: &JavaManifest,
+        config: &mut Config,
+    ) -> Result<(Vec<String>, Vec<String>), String> {
+        // 环境名会成为安装目录名:有一条不合法就整份拒绝,不装任何条目
+        let invalid = manifest::invalid_entry_names(manifest);
+        if !invalid.is_empty() {
+            return Err(format!(
+                "Invalid environment names in manifest: {}",
+                invalid.join("; ")
+            ));
+        }
+        let (mut installed, mut skipped, mut failed) = (Vec::new(), Vec::new(), Vec::new());
+        for entry in &manifest.java {
+            if config.get_java_env(&entry.name).is_some() {
+                crate::cli::print::step("Skip", &format!("{} (already installed)", entry.name));
+                skipped.push(entry.name.clone());
+                continue;
+            }
+            crate::cli::print::action(&format!(
+                "Installing java {} as {}",
+                entry.version, entry.name
+            ));
+            match Self::install_exact(downloader, entry, config).await {
+                Ok(_) => installed.push(entry.name.clone()),
+                Err(e) => {
+                    crate::cli::print::failure(&entry.name, Some(&e));
+                    failed.push(entry.name.clone());
+                }
+            }
+        }
+        if !failed.is_empty() {
+            return Err(format!("Failed to install: {}", failed.join(", ")));
+        }
+        Ok((installed, skipped))
+    }
+
+    /// 严格按版本安装清单条目(不回退到最新版),注册为条目的环境名。
+    async fn install_exact(
+        downloader: &dyn ToolDownloader,
+        entry: &ManifestEntry,
+        config: &mut Config,
+    ) -> Result<String, String> {
+        let resolved = downloader
+            .find_version_by_spec(&entry.version)
+            .await
+            .map_err(|e| format!("Failed to resolve '{}': {e}", entry.version))?;
+        crate::cli::print::step(
+            "Resolved",
+            &format!("{} ({})", resolved.version, resolved.display),
+        );
+        let java_home = Self::download_and_install(
+            downloader,
+            &resolved,
+            &Platform::current(),
+            &entry.name,
+            None,
+        )
+        .await?;
+        Self::complete_installation_simple(
+            &entry.name,
+            config,
+            false,
+            &java_home,
+            &resolved.version,
+            Some(&resolved.version),
+        )
+        .await
+    }
+
+    /// 重装 fnva 安装的环境:按原版本重新下载,沿用环境名、JAVA_OPTS 与默认设置。
+    /// `source` 指定只用某个 `[[mirrors.java]]` 镜像。
+    pub async fn reinstall_java(
+        name: &str,
+        source: Option<&str>,
+        config: &mut Config,
+    ) -> Result<String, String> {
+        let mut mirrors = config.mirrors.java.clone();
+        if let Some(source) = source {
+            mirrors.retain(|m| m.name == source);
+            let Some(mirror) = mirrors.first_mut() else {
+                return Err(format!(
+                    "Unknown source '{source}'; use a name from [[mirrors.java]] in the config"
+                ));
+            };
+            mirror.enabled = true;
+        }
+        let downloader = JavaDownloader::new(mirrors);
+        Self::reinstall_with(&downloader, name, config).await
+    }
+
+    async fn reinstall_with(
+        downloader: &dyn ToolDownloader,
+        name: &str,
+        config: &mut Config,
+    ) -> Result<String, String> {
+        let env = config
+            .get_java_env(name)
+            .cloned()
+            .ok_or_else(|| format!("Java environment '{name}' not found"))?;
+        let packages_dir = crate::infrastructure::paths::tool_packages_dir("java")?;
+        if !Path::new(&env.java_home).starts_with(&packages_dir) {
+            return Err(format!(
+                "'{name}' was not installed by fnva ({}); reinstall that JDK yourself, \
+                 or install a fresh one with `fnva java install <version>`",
+                env.java_home
+            ));
+        }
+        let version = manifest::installed_version(&env).ok_or_else(|| {
+            format!(
+                "Cannot reinstall '{name}': installed version unknown (no recorded version or \
+                 release file in {}); install it again with `fnva java install <version>`",
+                env.java_home
+            )
+        })?;
+        let entry = ManifestEntry {
+            name: name.to_string(),
+            version,
+            source: MANIFEST_SOURCE.to_string(),
+            url: None,
+            sha256: None,
+        };
+        // 先确认版本还能解析,再动已有安装
+        downloader
+            .find_version_by_spec(&entry.version)
+            .await
+            .map_err(|e| {
+                format!(
+                    "Cannot reinstall '{name}': failed to resolve {}: {e}",
+                    entry.version
+                )
+            })?;
+        let was_default = config.default_java_env.as_deref() == Some(name);
+
+        Self::uninstall_java(name, config)?;
+        crate::cli::print::action(&format!("Installing java {} as {name}", entry.version));
+        let java_home = Self::install_exact(downloader, &entry, config)
+            .await
+            .map_err(|e| {
+                format!(
+                    "{e}; '{name}' was removed, run `fnva java install {}` to retry",
+                    entry.version
+                )
+            })?;
+
+        // 除安装目录外原样保留用户对环境的设置(JAVA_OPTS、额外 PATH、描述、版本)
+        if let Some(restored) = config.java_environments.iter_mut().find(|e| e.name == name) {
+            *restored = crate::config::JavaEnvironment {
+                java_home: std::mem::take(&mut restored.java_home),
+                version: env.version.clone().or(restored.version.take()),
+                ..env
+            };
+        }
+        if was_default {
+            config.default_java_env = Some(name.to_string());
+        }
+        config.save()?;
+        Ok(java_home)
+    }
+
     /// 卸载 Java 版本
     pub fn uninstall_java(version_name: &str, config: &mut Config) -> Result<(), String> {
         let java_env = config
@@ -312,21 +965,198 @@ impl JavaInstaller {
             return Ok(None);
         }
 
-        let java_home = fnva_dir.join(version_spec);
-
-        // 如果本地包目录存在，则查找实际的Java安装目录
-        if java_home.exists() {
-            // 查找实际的Java安装目录（可能在其子目录中）
-            let actual_java_home = Self::find_installed_java(&java_home)?;
-            return Ok(Some(actual_java_home));
-        }
+        // 仅复用带完成标记的完整安装;中断留下的半成品会被清理后重新安装
+        generic::reuse_complete_install(
+            &generic::install_dir_for(&fnva_dir, version_spec)?,
+            &JAVA_DESCRIPTOR,
+        )
+    }
+}
 
-        Ok(None)
+/// Java 版本的比较键:`17.0.11+9` → `([17, 0, 11], 9)`,版本号段数值比较,缺省段补 0;
+/// 不是以数字开头的版本(如退回的环境名)返回 `None`。
+fn java_version_key(version: &str) -> Option<(Vec<u64>, u64)> {
+    let (number, build) = version.split_once('+').unwrap_or((version, "0"));
+    let mut parts: Vec<u64> = number
+        .split(['.', '-'])
+        .map_while(|p| p.parse().ok())
+        .collect();
+    if parts.is_empty() {
+        return None;
     }
+    parts.resize(parts.len().max(3), 0);
+    let build = build.split(['.', '-']).next()?.parse().unwrap_or(0);
+    Some((parts, build))
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crate::infrastructure::remote::java_downloader::{DownloadError, DownloadTarget};
+    use crate::infrastructure::tool_protocol::TemplateVars;
+    use crate::testutil::FnvaHomeGuard;
+    use std::future::Future;
+    use std::pin::Pin;
+
+    /// 固定版本表的桩下载器:21 为最新 LTS,24 为最新非 LTS。
+    struct StubDownloader;
+
+    fn stub_version(version: &str, major: u32, is_lts: bool) -> ResolvedVersion {
+        ResolvedVersion {
+            version: version.to_string(),
+            major: Some(major),
+            is_lts,
+            display: format!("Eclipse Temurin JDK {version}"),
+            template_vars: TemplateVars::default(),
+        }
+    }
+
+    impl ToolDownloader for StubDownloader {
+        fn list_available_versions(
+            &self,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<ResolvedVersion>, DownloadError>> + Send + '_>>
+        {
+            Box::pin(async {
+                Ok(vec![
+                    stub_version("24.0.1+9", 24, false),
+                    stub_version("21.0.5+11", 21, true),
+                ])
+            })
+        }
+
+        fn find_version_by_spec(
+            &self,
+            spec: &str,
+        ) -> Pin<Box<dyn Future<Output = Result<ResolvedVersion, DownloadError>> + Send + '_>>
+        {
+            let spec = parse_version_spec(spec);
+            Box::pin(async move {
+                match spec {
+                    Ok(VersionSpec::Latest) => Ok(stub_version("24.0.1+9", 24, false)),
+                    Ok(VersionSpec::LatestLts) => Ok(stub_version("21.0.5+11", 21, true)),
+                    Ok(VersionSpec::Exact(v)) if v == "21.0.5+11" => {
+                        Ok(stub_version("21.0.5+11", 21, true))
+                    }
+                    _ => Err(DownloadError::NotFound),
+                }
+            })
+        }
+
+        fn get_download_url(
+            &self,
+            _version: &ResolvedVersion,
+            _platform: &Platform,
+        ) -> Pin<Box<dyn Future<Output = Result<String, DownloadError>> + Send + '_>> {
+            Box::pin(async { Err(DownloadError::NotFound) })
+        }
+
+        fn download(
+            &self,
+            _version: &ResolvedVersion,
+            _platform: &Platform,
+            _progress_callback: Box<dyn Fn(u64, u64) + Send + Sync>,
+        ) -> Pin<Box<dyn Future<Output = Result<DownloadTarget, DownloadError>> + Send + '_>>
+        {
+            Box::pin(async { Err(DownloadError::NotFound) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_install_lts_names_env_after_resolved_version() {
+        let (resolved, name) =
+            JavaInstaller::resolve_install_target(&StubDownloader, "lts", false, true)
+                .await
+                .unwrap();
+        assert!(resolved.is_lts);
+        assert_eq!(name, "21.0.5+11");
+    }
+
+    #[tokio::test]
+    async fn test_install_latest_names_env_after_resolved_version() {
+        let (resolved, name) =
+            JavaInstaller::resolve_install_target(&StubDownloader, "Latest", false, true)
+                .await
+                .unwrap();
+        assert_eq!(resolved.major, Some(24));
+        assert_eq!(name, "24.0.1+9");
+    }
+
+    #[tokio::test]
+    async fn test_install_other_spec_keeps_spec_as_name() {
+        let (_, name) = JavaInstaller::resolve_install_target(&StubDownloader, "17", false, true)
+            .await
+            .unwrap();
+        assert_eq!(name, "17");
+    }
+
+    #[tokio::test]
+    async fn test_install_lts_only_rejects_unresolvable_spec() {
+        let err = JavaInstaller::resolve_install_target(&StubDownloader, "17", true, true)
+            .await
+            .unwrap_err();
+        assert_eq!(err, "No LTS release matches '17'");
+    }
+
+    #[tokio::test]
+    async fn test_install_lts_only_rejects_non_lts() {
+        let err = JavaInstaller::resolve_install_target(&StubDownloader, "latest", true, true)
+            .await
+            .unwrap_err();
+        assert!(err.contains("not an LTS"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn apply_manifest_skips_installed_and_reports_unresolvable() {
+        let mut config = Config::default();
+        config
+            .add_java_env(crate::config::JavaEnvironment {
+                name: "21".to_string(),
+                java_home: "/opt/jdk21".to_string(),
+                description: String::new(),
+                source: crate::config::EnvironmentSource::Manual,
+                java_opts: None,
+                extra_paths: Vec::new(),
+                version: None,
+            })
+            .unwrap();
+        let entry = |name: &str, version: &str| ManifestEntry {
+            name: name.to_string(),
+            version: version.to_string(),
+            source: MANIFEST_SOURCE.to_string(),
+            url: None,
+            sha256: None,
+        };
+
+        let manifest = JavaManifest {
+            java: vec![entry("21", "21.0.5+11")],
+        };
+        let (installed, skipped) =
+            JavaInstaller::apply_manifest_with(&StubDownloader, &manifest, &mut config)
+                .await
+                .unwrap();
+        assert!(installed.is_empty());
+        assert_eq!(skipped, ["21"]);
+
+        // 精确版本解析不到时报错,而不是像 `install` 那样回退到最新版
+        let manifest = JavaManifest {
+            java: vec![entry("21", "21.0.5+11"), entry("old", "17.0.1+12")],
+        };
+        let err = JavaInstaller::apply_manifest_with(&StubDownloader, &manifest, &mut config)
+            .await
+            .unwrap_err();
+        assert_eq!(err, "Failed to install: old");
+        assert!(config.get_java_env("old").is_none());
+
+        // 不合法的环境名在安装任何条目之前就整份拒绝
+        let manifest = JavaManifest {
+            java: vec![entry("21", "21.0.5+11"), entry("..", "21.0.5+11")],
+        };
+        let err = JavaInstaller::apply_manifest_with(&StubDownloader, &manifest, &mut config)
+            .await
+            .unwrap_err();
+        assert!(err.contains("Invalid environment names"), "{err}");
+    }
+
     #[test]
     fn test_parse_version_spec() {
         assert!(matches!(
@@ -346,4 +1176,561 @@ mod tests {
             crate::environments::java::VersionSpec::Major(21)
         ));
     }
+
+    /// 在 `dir` 下生成一个最小的 JDK 21 zip 归档(只有 `bin/java` 与 `release`)。
+    fn jdk21_zip(dir: &Path) -> std::path::PathBuf {
+        jdk_zip(dir, "21.0.5+11")
+    }
+
+    /// 同上,顶层目录为 `jdk-<version>`。
+    fn jdk_zip(dir: &Path, version: &str) -> std::path::PathBuf {
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let archive = dir.join(format!("jdk-{}.zip", version.replace('+', "_")));
+        let mut zip = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+        let java = if cfg!(windows) { "java.exe" } else { "java" };
+        for entry in [
+            format!("jdk-{version}/bin/{java}"),
+            format!("jdk-{version}/release"),
+        ] {
+            zip.start_file(entry, SimpleFileOptions::default()).unwrap();
+            zip.write_all(b"").unwrap();
+        }
+        zip.finish().unwrap();
+        archive
+    }
+
+    /// 版本表同 [`StubDownloader`],下载直接返回本地归档。
+    struct ArchiveDownloader(std::path::PathBuf);
+
+    impl ToolDownloader for ArchiveDownloader {
+        fn list_available_versions(
+            &self,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<ResolvedVersion>, DownloadError>> + Send + '_>>
+        {
+            StubDownloader.list_available_versions()
+        }
+
+        fn find_version_by_spec(
+            &self,
+            spec: &str,
+        ) -> Pin<Box<dyn Future<Output = Result<ResolvedVersion, DownloadError>> + Send + '_>>
+        {
+            let spec = spec.to_string();
+            Box::pin(async move { StubDownloader.find_version_by_spec(&spec).await })
+        }
+
+        fn get_download_url(
+            &self,
+            _version: &ResolvedVersion,
+            _platform: &Platform,
+        ) -> Pin<Box<dyn Future<Output = Result<String, DownloadError>> + Send + '_>> {
+            Box::pin(async { Err(DownloadError::NotFound) })
+        }
+
+        fn download(
+            &self,
+            _version: &ResolvedVersion,
+            _platform: &Platform,
+            _progress_callback: Box<dyn Fn(u64, u64) + Send + Sync>,
+        ) -> Pin<Box<dyn Future<Output = Result<DownloadTarget, DownloadError>> + Send + '_>>
+        {
+            let path = self.0.to_string_lossy().to_string();
+            Box::pin(async move { Ok(DownloadTarget::File(path)) })
+        }
+    }
+
+    /// 版本解析正常,但每个来源都要等一会儿才失败,且无限重试。
+    struct FailingSources {
+        last_error: std::sync::Mutex<Option<String>>,
+    }
+
+    impl ToolDownloader for FailingSources {
+        fn list_available_versions(
+            &self,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<ResolvedVersion>, DownloadError>> + Send + '_>>
+        {
+            StubDownloader.list_available_versions()
+        }
+
+        fn find_version_by_spec(
+            &self,
+            spec: &str,
+        ) -> Pin<Box<dyn Future<Output = Result<ResolvedVersion, DownloadError>> + Send + '_>>
+        {
+            let spec = spec.to_string();
+            Box::pin(async move { StubDownloader.find_version_by_spec(&spec).await })
+        }
+
+        fn get_download_url(
+            &self,
+            _version: &ResolvedVersion,
+            _platform: &Platform,
+        ) -> Pin<Box<dyn Future<Output = Result<String, DownloadError>> + Send + '_>> {
+            Box::pin(async { Err(DownloadError::NotFound) })
+        }
+
+        fn download(
+            &self,
+            _version: &ResolvedVersion,
+            _platform: &Platform,
+            _progress_callback: Box<dyn Fn(u64, u64) + Send + Sync>,
+        ) -> Pin<Box<dyn Future<Output = Result<DownloadTarget, DownloadError>> + Send + '_>>
+        {
+            Box::pin(async move {
+                for attempt in 0.. {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    *self.last_error.lock().unwrap() =
+                        Some(format!("source {}: connection reset", attempt % 3));
+                }
+                unreachable!()
+            })
+        }
+
+        fn last_error(&self) -> Option<String> {
+            self.last_error.lock().unwrap().clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn install_gives_up_at_deadline_with_last_source_error() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let downloader = FailingSources {
+            last_error: std::sync::Mutex::new(None),
+        };
+        let mut config = Config::new();
+
+        let started = std::time::Instant::now();
+        let err = JavaInstaller::install_within_deadline(
+            &downloader,
+            Some(Duration::from_millis(300)),
+            "lts",
+            &mut config,
+            false,
+            false,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert!(err.starts_with("Install timed out after 300ms"), "{err}");
+        assert!(err.contains("connection reset"), "{err}");
+        assert!(config.java_environments.is_empty());
+    }
+
+    #[tokio::test]
+    async fn ensure_java_env_returns_existing_env_without_installing() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let mut config = Config::new();
+        config
+            .add_java_env(crate::config::JavaEnvironment {
+                name: "21".to_string(),
+                java_home: "/opt/jdk21".to_string(),
+                description: String::new(),
+                source: crate::config::EnvironmentSource::Manual,
+                java_opts: None,
+                extra_paths: Vec::new(),
+                version: None,
+            })
+            .unwrap();
+
+        // StubDownloader 的下载总是失败,走到安装就会报错
+        let name = JavaInstaller::ensure_with_downloader(&StubDownloader, "21", &mut config)
+            .await
+            .unwrap();
+        assert_eq!(name, "21");
+    }
+
+    #[tokio::test]
+    async fn ensure_java_env_rejects_unresolvable_spec() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let downloader = ArchiveDownloader(jdk21_zip(tmp.path()));
+        let mut config = Config::new();
+
+        // 拼错的名字不会装成最新版
+        let err = JavaInstaller::ensure_with_downloader(&downloader, "jdk1y", &mut config)
+            .await
+            .unwrap_err();
+        assert!(err.contains("Failed to resolve 'jdk1y'"), "{err}");
+        assert!(config.get_java_env("jdk1y").is_none());
+        assert!(config.java_environments.is_empty());
+    }
+
+    #[tokio::test]
+    async fn ensure_java_env_installs_missing_env_under_resolved_name() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let downloader = ArchiveDownloader(jdk21_zip(tmp.path()));
+        let mut config = Config::new();
+
+        let name = JavaInstaller::ensure_with_downloader(&downloader, "lts", &mut config)
+            .await
+            .unwrap();
+        assert_eq!(name, "21.0.5+11");
+        let env = config.get_java_env(&name).unwrap();
+        assert!(
+            env.java_home.ends_with("jdk-21.0.5+11"),
+            "{}",
+            env.java_home
+        );
+        // 已写入配置文件,随后的切换能找到它
+        assert!(Config::load().unwrap().get_java_env(&name).is_some());
+    }
+
+    #[tokio::test]
+    async fn reinstall_restores_corrupt_env_under_same_name() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let downloader = ArchiveDownloader(jdk21_zip(tmp.path()));
+        let mut config = Config::new();
+        let name = JavaInstaller::ensure_with_downloader(&downloader, "lts", &mut config)
+            .await
+            .unwrap();
+        config.java_environments[0].java_opts = Some("-Xmx1g".to_string());
+        config.java_environments[0].extra_paths = vec!["/opt/tools/bin".to_string()];
+        config.java_environments[0].description = "team JDK".to_string();
+        config.default_java_env = Some(name.clone());
+        config.save().unwrap();
+
+        // 损坏安装
+        let old_home = config.get_java_env(&name).unwrap().java_home.clone();
+        fs::remove_dir_all(Path::new(&old_home).join("bin")).unwrap();
+        assert!(!crate::utils::validate_java_home(&old_home));
+
+        let home = JavaInstaller::reinstall_with(&downloader, &name, &mut config)
+            .await
+            .unwrap();
+        assert!(crate::utils::validate_java_home(&home));
+        let config = Config::load().unwrap();
+        let env = config.get_java_env(&name).unwrap();
+        assert_eq!(env.java_home, home);
+        assert_eq!(env.java_opts.as_deref(), Some("-Xmx1g"));
+        assert_eq!(env.extra_paths, ["/opt/tools/bin"]);
+        assert_eq!(env.description, "team JDK");
+        assert_eq!(env.version.as_deref(), Some("21.0.5+11"));
+        assert_eq!(config.default_java_env.as_deref(), Some(name.as_str()));
+    }
+
+    #[tokio::test]
+    async fn reinstall_refuses_manually_added_env() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let mut config = Config::new();
+        config
+            .add_java_env(crate::config::JavaEnvironment {
+                name: "corp".to_string(),
+                java_home: "/opt/corp-jdk".to_string(),
+                description: String::new(),
+                source: crate::config::EnvironmentSource::Manual,
+                java_opts: None,
+                extra_paths: Vec::new(),
+                version: None,
+            })
+            .unwrap();
+        let err = JavaInstaller::reinstall_with(&StubDownloader, "corp", &mut config)
+            .await
+            .unwrap_err();
+        assert!(err.contains("not installed by fnva"), "{err}");
+        assert!(config.get_java_env("corp").is_some());
+    }
+
+    #[tokio::test]
+    async fn install_from_archive_registers_extracted_jdk() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+
+        let archive = jdk21_zip(tmp.path());
+
+        let mut config = Config::new();
+        let home = JavaInstaller::install_from_archive(&archive, "offline21", &mut config, false)
+            .await
+            .unwrap();
+
+        assert!(home.ends_with("jdk-21.0.5+11"), "{home}");
+        assert!(std::path::Path::new(&home)
+            .parent()
+            .unwrap()
+            .join(generic::INSTALL_COMPLETE_MARKER)
+            .is_file());
+        assert_eq!(
+            config
+                .get_java_env("offline21")
+                .map(|e| e.java_home.as_str()),
+            Some(home.as_str())
+        );
+        // 重名拒绝
+        assert!(
+            JavaInstaller::install_from_archive(&archive, "offline21", &mut config, false)
+                .await
+                .is_err()
+        );
+    }
+
+    /// 任何规格都解析成同一个版本,下载直接返回对应的本地归档。
+    struct PatchDownloader {
+        version: ResolvedVersion,
+        archive: std::path::PathBuf,
+    }
+
+    impl PatchDownloader {
+        fn new(dir: &Path, version: &str) -> Self {
+            Self {
+                version: stub_version(version, 17, true),
+                archive: jdk_zip(dir, version),
+            }
+        }
+    }
+
+    impl ToolDownloader for PatchDownloader {
+        fn list_available_versions(
+            &self,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<ResolvedVersion>, DownloadError>> + Send + '_>>
+        {
+            let versions = vec![self.version.clone()];
+            Box::pin(async move { Ok(versions) })
+        }
+
+        fn find_version_by_spec(
+            &self,
+            _spec: &str,
+        ) -> Pin<Box<dyn Future<Output = Result<ResolvedVersion, DownloadError>> + Send + '_>>
+        {
+            let version = self.version.clone();
+            Box::pin(async move { Ok(version) })
+        }
+
+        fn get_download_url(
+            &self,
+            _version: &ResolvedVersion,
+            _platform: &Platform,
+        ) -> Pin<Box<dyn Future<Output = Result<String, DownloadError>> + Send + '_>> {
+            Box::pin(async { Err(DownloadError::NotFound) })
+        }
+
+        fn download(
+            &self,
+            _version: &ResolvedVersion,
+            _platform: &Platform,
+            _progress_callback: Box<dyn Fn(u64, u64) + Send + Sync>,
+        ) -> Pin<Box<dyn Future<Output = Result<DownloadTarget, DownloadError>> + Send + '_>>
+        {
+            let path = self.archive.to_string_lossy().to_string();
+            Box::pin(async move { Ok(DownloadTarget::File(path)) })
+        }
+    }
+
+    async fn install_patch(
+        downloader: PatchDownloader,
+        config: &mut Config,
+        accept: bool,
+    ) -> String {
+        JavaInstaller::install_major_with(
+            &downloader,
+            "17",
+            config,
+            false,
+            false,
+            None,
+            &|_, _, _| accept,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn major_only_reuses_name_and_replaces_dir_on_newer_patch() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let mut config = Config::new();
+
+        let old_home = install_patch(
+            PatchDownloader::new(tmp.path(), "17.0.11+9"),
+            &mut config,
+            true,
+        )
+        .await;
+        assert!(old_home.ends_with("jdk-17.0.11+9"), "{old_home}");
+        let env = config.get_java_env("jdk17").unwrap().clone();
+        config.java_environments[0].java_opts = Some("-Xmx1g".to_string());
+        config.save().unwrap();
+        assert_eq!(
+            manifest::installed_version(&env).as_deref(),
+            Some("17.0.11+9")
+        );
+
+        // 同一补丁版本:什么都不做
+        let same = install_patch(
+            PatchDownloader::new(tmp.path(), "17.0.11+9"),
+            &mut config,
+            true,
+        )
+        .await;
+        assert_eq!(same, old_home);
+
+        // 拒绝升级:保持原样
+        let kept = install_patch(
+            PatchDownloader::new(tmp.path(), "17.0.12+7"),
+            &mut config,
+            false,
+        )
+        .await;
+        assert_eq!(kept, old_home);
+
+        let new_home = install_patch(
+            PatchDownloader::new(tmp.path(), "17.0.12+7"),
+            &mut config,
+            true,
+        )
+        .await;
+        assert!(new_home.ends_with("jdk-17.0.12+7"), "{new_home}");
+        assert!(crate::utils::validate_java_home(&new_home));
+        assert!(!Path::new(&old_home).exists());
+        let packages = crate::infrastructure::paths::tool_packages_dir("java").unwrap();
+        assert!(Path::new(&new_home).starts_with(packages.join("jdk17")));
+        assert!(!packages.join("jdk17.upgrade").exists());
+
+        let config = Config::load().unwrap();
+        assert_eq!(config.java_environments.len(), 1);
+        let env = config.get_java_env("jdk17").unwrap();
+        assert_eq!(env.java_home, new_home);
+        assert_eq!(env.java_opts.as_deref(), Some("-Xmx1g"));
+        assert_eq!(
+            manifest::installed_version(env).as_deref(),
+            Some("17.0.12+7")
+        );
+
+        // 解析到更旧的补丁(按字符串比较 "17.0.9" 反而更大):不降级,也不询问
+        let mut config = config;
+        let downgrade = JavaInstaller::install_major_with(
+            &PatchDownloader::new(tmp.path(), "17.0.9+9"),
+            "17",
+            &mut config,
+            false,
+            false,
+            None,
+            &|_, _, _| panic!("must not offer a downgrade"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(downgrade, new_home);
+        assert!(Path::new(&new_home).exists());
+        assert_eq!(
+            manifest::installed_version(config.get_java_env("jdk17").unwrap()).as_deref(),
+            Some("17.0.12+7")
+        );
+    }
+
+    #[tokio::test]
+    async fn major_only_refuses_to_replace_another_envs_dir() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let mut config = Config::new();
+
+        // jdk17 指向另一个环境名的安装目录
+        let shared_home = install_patch(
+            PatchDownloader::new(tmp.path(), "17.0.11+9"),
+            &mut config,
+            true,
+        )
+        .await;
+        let packages = crate::infrastructure::paths::tool_packages_dir("java").unwrap();
+        let other = packages.join("corp17");
+        fs::rename(packages.join("jdk17"), &other).unwrap();
+        let moved_home = other
+            .join(
+                Path::new(&shared_home)
+                    .strip_prefix(packages.join("jdk17"))
+                    .unwrap(),
+            )
+            .to_string_lossy()
+            .to_string();
+        config.java_environments[0].java_home = moved_home.clone();
+
+        let err = JavaInstaller::install_major_with(
+            &PatchDownloader::new(tmp.path(), "17.0.12+7"),
+            "17",
+            &mut config,
+            false,
+            false,
+            None,
+            &|_, _, _| true,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("remove or rename it first"), "{err}");
+        assert!(Path::new(&moved_home).exists());
+    }
+
+    #[test]
+    fn java_versions_compare_numerically() {
+        let key = |v| java_version_key(v).unwrap();
+        assert!(key("17.0.12+7") > key("17.0.9+9"));
+        assert!(key("17.0.11+10") > key("17.0.11+9"));
+        assert!(key("17.0.1+12") > key("17+35"));
+        assert_eq!(key("21+35"), key("21.0.0+35"));
+        assert_eq!(java_version_key("jdk17"), None);
+    }
+
+    /// `--retries` / `--timeout` 经 `install_downloader` 传给实际下载,不改配置文件。
+    #[tokio::test]
+    async fn install_downloader_applies_command_overrides_to_downloads() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let server = httpmock::MockServer::start();
+        let failing = server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/jdk.tar.gz");
+            then.status(503);
+        });
+        let mut config = Config::new();
+        config.download.retry_count = 2;
+        config.download.retry_delay_ms = 1;
+        config.mirrors.java = vec![crate::infrastructure::config::MirrorConfig {
+            name: "local".to_string(),
+            priority: 1,
+            base_url: server.base_url(),
+            url_template: "{base_url}/{filename}".to_string(),
+            enabled: true,
+        }];
+        config.save().unwrap();
+        let version = ResolvedVersion {
+            template_vars: TemplateVars {
+                filename: "jdk.tar.gz".to_string(),
+                ..TemplateVars::default()
+            },
+            ..stub_version("21.0.5+11", 21, true)
+        };
+        let download = |overrides: DownloadOverrides| {
+            let (downloader, _) = JavaInstaller::install_downloader(&config, &overrides).unwrap();
+            let version = version.clone();
+            async move {
+                downloader
+                    .download(&version, &Platform::current(), Box::new(|_, _| {}))
+                    .await
+            }
+        };
+
+        // 配置的重试次数:1 次 + 2 次重试
+        assert!(download(DownloadOverrides {
+            no_verify: true,
+            ..DownloadOverrides::default()
+        })
+        .await
+        .is_err());
+        failing.assert_hits(3);
+
+        // --retries 0:只请求一次
+        assert!(download(DownloadOverrides {
+            retries: Some(0),
+            no_verify: true,
+            ..DownloadOverrides::default()
+        })
+        .await
+        .is_err());
+        failing.assert_hits(4);
+        assert_eq!(Config::load().unwrap().download.retry_count, 2);
+    }
 }
@@ -0,0 +1,173 @@
+//! fnva 托管 JDK 的可复现清单(`fnva java manifest` / `manifest apply`)。
+//!
+//! 只收录安装在 `~/.fnva/packages/java` 下的环境;手动添加或扫描到的 JDK
+//! 无法在另一台机器上重新下载,不进清单。
+
+use crate::infrastructure::config::JavaEnvironment;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 清单文件(TOML,`[[java]]` 数组)。
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct JavaManifest {
+    #[serde(default)]
+    pub java: Vec<ManifestEntry>,
+}
+
+/// 单个 JDK:环境名 + 具体版本;`url` / `sha256` 仅供参考,
+/// `apply` 按版本重新解析,校验和以注册表为准。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub name: String,
+    pub version: String,
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+impl JavaManifest {
+    pub fn parse(content: &str) -> Result<Self, String> {
+        toml::from_str(content).map_err(|e| format!("Failed to parse manifest: {e}"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        Self::parse(&content)
+    }
+
+    pub fn to_toml(&self) -> Result<String, String> {
+        toml::to_string_pretty(self).map_err(|e| format!("Failed to serialize manifest: {e}"))
+    }
+}
+
+/// fnva 安装的环境(`java_home` 位于 `packages_dir` 之下)。
+pub fn managed_envs<'a>(
+    envs: &'a [JavaEnvironment],
+    packages_dir: &Path,
+) -> Vec<&'a JavaEnvironment> {
+    envs.iter()
+        .filter(|e| Path::new(&e.java_home).starts_with(packages_dir))
+        .collect()
+}
+
+/// 环境对应的具体版本:安装时记录的 `version`,没有记录(本地包 / 归档安装)时读 JDK 的
+/// `release` 文件;都没有时返回 `None`,环境名不能当版本用(`jdk17` 会被解析成最新的 17)。
+pub fn installed_version(env: &JavaEnvironment) -> Option<String> {
+    env.version.clone().or_else(|| {
+        let home = Path::new(&env.java_home);
+        super::info::release_field(home, "JAVA_RUNTIME_VERSION")
+            .or_else(|| super::info::release_field(home, "JAVA_VERSION"))
+    })
+}
+
+/// 逐条检查清单里的环境名(会成为安装目录名),返回所有不合法的条目。
+pub fn invalid_entry_names(manifest: &JavaManifest) -> Vec<String> {
+    manifest
+        .java
+        .iter()
+        .filter_map(|entry| {
+            crate::utils::validation::ValidationUtils::validate_environment_name(&entry.name)
+                .err()
+                .map(|e| format!("'{}': {e}", entry.name))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::config::EnvironmentSource;
+
+    fn env(name: &str, home: &str, version: Option<&str>) -> JavaEnvironment {
+        JavaEnvironment {
+            name: name.to_string(),
+            java_home: home.to_string(),
+            description: String::new(),
+            source: EnvironmentSource::Manual,
+            java_opts: None,
+            extra_paths: Vec::new(),
+            version: version.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn only_fnva_installed_envs_are_managed() {
+        let packages = Path::new("/home/u/.fnva/packages/java");
+        let envs = vec![
+            env("lts", "/home/u/.fnva/packages/java/lts", Some("21.0.5+11")),
+            env("corp", "/opt/jdk", None),
+            env("17", "/home/u/.fnva/packages/java/17/jdk-17", None),
+        ];
+        let managed = managed_envs(&envs, packages);
+        let names: Vec<_> = managed.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["lts", "17"]);
+    }
+
+    #[test]
+    fn installed_version_falls_back_to_release_file_never_to_name() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let home = tmp.path().to_str().unwrap();
+        assert_eq!(
+            installed_version(&env("jdk17", home, Some("17.0.11+9"))).as_deref(),
+            Some("17.0.11+9")
+        );
+        // 本地包安装没有记录版本,也没有 release 文件:未知,而不是环境名
+        assert_eq!(installed_version(&env("jdk17", home, None)), None);
+
+        std::fs::write(tmp.path().join("release"), "JAVA_VERSION=\"17.0.11\"\n").unwrap();
+        assert_eq!(
+            installed_version(&env("jdk17", home, None)).as_deref(),
+            Some("17.0.11")
+        );
+        std::fs::write(
+            tmp.path().join("release"),
+            "JAVA_VERSION=\"17.0.11\"\nJAVA_RUNTIME_VERSION=\"17.0.11+9\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            installed_version(&env("jdk17", home, None)).as_deref(),
+            Some("17.0.11+9")
+        );
+    }
+
+    #[test]
+    fn manifest_round_trips_and_tolerates_missing_fields() {
+        let manifest = JavaManifest {
+            java: vec![ManifestEntry {
+                name: "lts".to_string(),
+                version: "21.0.5+11".to_string(),
+                source: "adoptium".to_string(),
+                url: Some("https://example.com/jdk.tar.gz".to_string()),
+                sha256: None,
+            }],
+        };
+        let text = manifest.to_toml().unwrap();
+        assert!(text.contains("[[java]]"));
+        assert!(!text.contains("sha256"));
+        assert_eq!(JavaManifest::parse(&text).unwrap(), manifest);
+
+        let minimal = JavaManifest::parse(
+            "[[java]]\nname = \"17\"\nversion = \"17\"\nsource = \"adoptium\"\n",
+        )
+        .unwrap();
+        assert_eq!(minimal.java[0].url, None);
+        assert_eq!(JavaManifest::parse("").unwrap(), JavaManifest::default());
+    }
+
+    #[test]
+    fn rejects_entry_names_that_are_not_plain_directory_names() {
+        let manifest = JavaManifest::parse(
+            "[[java]]\nname = \"17\"\nversion = \"17\"\nsource = \"adoptium\"\n\
+             [[java]]\nname = \"..\"\nversion = \"21\"\nsource = \"adoptium\"\n\
+             [[java]]\nname = \"a/b\"\nversion = \"21\"\nsource = \"adoptium\"\n",
+        )
+        .unwrap();
+        let invalid = invalid_entry_names(&manifest);
+        assert_eq!(invalid.len(), 2, "{invalid:?}");
+        assert!(invalid[0].starts_with("'..'"), "{invalid:?}");
+        assert!(invalid[1].starts_with("'a/b'"), "{invalid:?}");
+    }
+}
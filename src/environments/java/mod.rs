@@ -1,8 +1,13 @@
+pub mod benchmark;
 pub mod downloader;
 pub mod environment_manager;
+pub mod info;
 pub mod installer;
+pub mod manifest;
 pub mod paths;
+pub mod project_version;
 pub mod scanner;
+pub mod shims;
 pub mod validator;
 pub mod version_discovery;
 
@@ -32,14 +32,8 @@ pub fn common_paths(extra: &[String]) -> Vec<String> {
         ]);
     }
 
-    if let Some(home) = dirs::home_dir() {
-        paths.push(
-            home.join(".fnva")
-                .join("packages")
-                .join("java")
-                .to_string_lossy()
-                .into_owned(),
-        );
+    if let Ok(dir) = crate::infrastructure::paths::tool_packages_dir("java") {
+        paths.push(dir.to_string_lossy().into_owned());
     }
 
     // 持久自定义(config)
@@ -0,0 +1,187 @@
+//! 项目级 Java 版本文件:从当前目录向上查找 `.java-version` / `.tool-versions`
+//! (asdf),把其中的版本标识模糊匹配到已配置的 Java 环境。
+//!
+//! 同一目录里 `.java-version` 优先;`.tool-versions` 的 `java` 行可列多个版本,
+//! 按顺序取第一个能匹配上的。
+
+use crate::infrastructure::config::JavaEnvironment;
+use std::path::{Path, PathBuf};
+
+const JAVA_VERSION_FILE: &str = ".java-version";
+const TOOL_VERSIONS_FILE: &str = ".tool-versions";
+
+/// 找到的版本文件及其中的候选版本标识(按优先级)。
+#[derive(Debug, PartialEq)]
+pub struct ProjectVersion {
+    pub file: PathBuf,
+    pub specs: Vec<String>,
+}
+
+/// 从 `start` 向上查找最近的、声明了 java 版本的版本文件。
+pub fn find_project_version(start: &Path) -> Option<ProjectVersion> {
+    for dir in start.ancestors() {
+        for (name, parse) in [
+            (
+                JAVA_VERSION_FILE,
+                parse_java_version as fn(&str) -> Vec<String>,
+            ),
+            (TOOL_VERSIONS_FILE, parse_tool_versions),
+        ] {
+            let file = dir.join(name);
+            let Ok(content) = std::fs::read_to_string(&file) else {
+                continue;
+            };
+            let specs = parse(&content);
+            if !specs.is_empty() {
+                return Some(ProjectVersion { file, specs });
+            }
+        }
+    }
+    None
+}
+
+/// `.java-version`:第一行非空、非注释内容即版本。
+fn parse_java_version(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|l| l.split('#').next().unwrap_or("").trim())
+        .find(|l| !l.is_empty())
+        .map(|l| vec![l.to_string()])
+        .unwrap_or_default()
+}
+
+/// `.tool-versions`:`<tool> <version> [<fallback>...]`,`#` 起为注释,
+/// 空白可为多个空格/制表符。只取 `java` 行,`system` 表示交给系统,跳过。
+pub fn parse_tool_versions(content: &str) -> Vec<String> {
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or("");
+        let mut fields = line.split_whitespace();
+        if fields.next() != Some("java") {
+            continue;
+        }
+        return fields
+            .filter(|v| *v != "system")
+            .map(str::to_string)
+            .collect();
+    }
+    Vec::new()
+}
+
+/// 把 asdf 风格的标识(`temurin-17.0.12+7`、`openjdk-21`、`path:/opt/jdk`、`17`)
+/// 匹配到已配置环境。优先级:名称完全相同 > 版本号相同 > 环境更具体
+/// (`17.0.12+7` 对 `17.0.12`)> 环境更宽泛(`jdk17` 对 `17.0.12`)。
+pub fn match_java_env<'a>(spec: &str, envs: &'a [JavaEnvironment]) -> Option<&'a JavaEnvironment> {
+    if let Some(path) = spec.strip_prefix("path:") {
+        let want = Path::new(path);
+        return envs.iter().find(|e| Path::new(&e.java_home) == want);
+    }
+    if let Some(env) = envs.iter().find(|e| e.name.eq_ignore_ascii_case(spec)) {
+        return Some(env);
+    }
+    let wanted = version_part(spec)?;
+    envs.iter()
+        .filter_map(|e| {
+            let have = version_part(&e.name)?;
+            let rank = if have == wanted {
+                0
+            } else if extends(have, wanted) {
+                1
+            } else if extends(wanted, have) {
+                2
+            } else {
+                return None;
+            };
+            Some((rank, e))
+        })
+        .min_by_key(|(rank, _)| *rank)
+        .map(|(_, e)| e)
+}
+
+/// 去掉发行版前缀(`temurin-` / `jdk` / `zulu-` …),返回从第一个数字开始的版本号。
+fn version_part(s: &str) -> Option<&str> {
+    s.find(|c: char| c.is_ascii_digit()).map(|i| &s[i..])
+}
+
+/// `long` 是否是 `short` 更具体的版本(`17.0.12+7` 扩展 `17.0.12`,`17` 不扩展 `1`)。
+fn extends(long: &str, short: &str) -> bool {
+    long.strip_prefix(short)
+        .is_some_and(|rest| rest.starts_with(['.', '+', '-', '_']))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::config::EnvironmentSource;
+
+    fn env(name: &str) -> JavaEnvironment {
+        JavaEnvironment {
+            name: name.to_string(),
+            java_home: format!("/opt/{name}"),
+            description: String::new(),
+            source: EnvironmentSource::Manual,
+            java_opts: None,
+            extra_paths: Vec::new(),
+            version: None,
+        }
+    }
+
+    #[test]
+    fn tool_versions_grammar() {
+        let content = "# team tools\r\nnodejs 20.11.0\njavascript 1\n\tjava   temurin-17.0.12+7  zulu-17 # pinned\r\nmaven 3.9.9\n";
+        assert_eq!(
+            parse_tool_versions(content),
+            ["temurin-17.0.12+7", "zulu-17"]
+        );
+        // system 跳过,后备版本保留
+        assert_eq!(parse_tool_versions("java system 21"), ["21"]);
+        // 注释掉的行、缺版本、没有 java 行
+        assert!(parse_tool_versions("# java 17\n").is_empty());
+        assert!(parse_tool_versions("java\n").is_empty());
+        assert!(parse_tool_versions("nodejs 20\n").is_empty());
+        // 只认第一条 java 行
+        assert_eq!(parse_tool_versions("java 17\njava 21\n"), ["17"]);
+    }
+
+    #[test]
+    fn java_version_file_skips_blank_and_comment_lines() {
+        assert_eq!(parse_java_version("\n# pinned\n  21.0.5 \n"), ["21.0.5"]);
+        assert!(parse_java_version("\n\n").is_empty());
+    }
+
+    #[test]
+    fn fuzzy_match_prefers_most_specific_env() {
+        let envs = vec![
+            env("jdk17"),
+            env("17.0.12+7"),
+            env("21.0.5+11"),
+            env("corp"),
+        ];
+        let name = |spec: &str| match_java_env(spec, &envs).map(|e| e.name.as_str());
+
+        assert_eq!(name("corp"), Some("corp"));
+        assert_eq!(name("temurin-17.0.12"), Some("17.0.12+7"));
+        assert_eq!(name("temurin-17.0.12+7"), Some("17.0.12+7"));
+        assert_eq!(name("openjdk-17.0.2"), Some("jdk17"));
+        assert_eq!(name("21"), Some("21.0.5+11"));
+        assert_eq!(name("path:/opt/corp"), Some("corp"));
+        // 1 不能匹配 17/21
+        assert_eq!(name("1"), None);
+        assert_eq!(name("zulu-11"), None);
+    }
+
+    #[test]
+    fn walk_finds_nearest_file_and_prefers_java_version() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let nested = tmp.path().join("a").join("b");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(tmp.path().join(".tool-versions"), "java temurin-21\n").unwrap();
+        std::fs::write(tmp.path().join("a").join(".tool-versions"), "nodejs 20\n").unwrap();
+
+        let found = find_project_version(&nested).unwrap();
+        assert_eq!(found.file, tmp.path().join(".tool-versions"));
+        assert_eq!(found.specs, ["temurin-21"]);
+
+        std::fs::write(tmp.path().join(".java-version"), "17\n").unwrap();
+        assert_eq!(find_project_version(&nested).unwrap().specs, ["17"]);
+    }
+}
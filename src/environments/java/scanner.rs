@@ -165,24 +165,20 @@ impl JavaScanner {
             .map_err(|e| format!("Failed to execute java -version: {e}"))?;
 
         if output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let lines: Vec<&str> = stderr.lines().collect();
-            if let Some(first_line) = lines.first() {
-                // 解析版本信息，例如："openjdk version "17.0.2" 2022-01-18"
-                if let Some(start) = first_line.find('"') {
-                    if let Some(end) = first_line.rfind('"') {
-                        let version = &first_line[start + 1..end];
-                        return Ok(Some(version.to_string()));
-                    }
-                }
-            }
+            // 版本信息通常在 stderr,少数 JVM 写到 stdout
+            let text = format!(
+                "{}\n{}",
+                String::from_utf8_lossy(&output.stderr),
+                String::from_utf8_lossy(&output.stdout)
+            );
+            return Ok(parse_version_output(&text));
         }
 
         Ok(None)
     }
 
     /// 检测供应商信息
-    fn detect_vendor(path: &str) -> Result<Option<String>, String> {
+    pub fn detect_vendor(path: &str) -> Result<Option<String>, String> {
         let path_lower = path.to_lowercase();
 
         if path_lower.contains("adoptium") || path_lower.contains("adoptopenjdk") {
@@ -205,10 +201,112 @@ impl JavaScanner {
     }
 }
 
+/// 从 `java -version` 的输出里取版本,依次尝试:
+/// 1. 任意一行的 `version "..."`(Temurin / OpenJ9 等标准格式,允许前面有 `Picked up ...`)
+/// 2. 以数字开头的引号内容(本地化输出,如 `openjdk 版本 "17.0.2"`)
+/// 3. 裸的 `1.8.0_191` / `17.0.2` 这类数字串
+/// 4. 都不行时返回第一行非空原文,至少不把环境当成没有版本
+pub fn parse_version_output(output: &str) -> Option<String> {
+    let lines: Vec<&str> = output
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let quoted_after = |line: &str, from: usize| -> Option<String> {
+        let rest = &line[from..];
+        let start = rest.find('"')? + 1;
+        let end = rest[start..].find('"')? + start;
+        Some(rest[start..end].trim().to_string()).filter(|v| !v.is_empty())
+    };
+
+    lines
+        .iter()
+        .find_map(|line| {
+            let at = line.find("version \"")?;
+            quoted_after(line, at)
+        })
+        .or_else(|| {
+            lines.iter().find_map(|line| {
+                let mut from = 0;
+                while let Some(v) = quoted_after(line, from) {
+                    if v.starts_with(|c: char| c.is_ascii_digit()) {
+                        return Some(v);
+                    }
+                    from += line[from..].find('"')? + 1;
+                    from += line[from..].find('"')? + 1;
+                }
+                None
+            })
+        })
+        .or_else(|| lines.iter().find_map(|line| bare_version(line)))
+        .or_else(|| lines.first().map(|l| l.to_string()))
+}
+
+/// 第一个形如 `\d+(\.\d+)+(_\d+)?` 的数字串
+fn bare_version(line: &str) -> Option<String> {
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let starts_token = i == 0 || !bytes[i - 1].is_ascii_alphanumeric();
+        if !(bytes[i].is_ascii_digit() && starts_token) {
+            i += 1;
+            continue;
+        }
+        let digits = |mut j: usize| {
+            while j < bytes.len() && bytes[j].is_ascii_digit() {
+                j += 1;
+            }
+            j
+        };
+        let mut end = digits(i);
+        let mut dots = 0;
+        while end + 1 < bytes.len() && bytes[end] == b'.' && bytes[end + 1].is_ascii_digit() {
+            end = digits(end + 1);
+            dots += 1;
+        }
+        if dots > 0 {
+            if end + 1 < bytes.len() && bytes[end] == b'_' && bytes[end + 1].is_ascii_digit() {
+                end = digits(end + 1);
+            }
+            return Some(line[i..end].to_string());
+        }
+        i = end;
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn parse_version_output_handles_vendor_and_localized_formats() {
+        let temurin = "openjdk version \"17.0.9\" 2023-10-17\n\
+            OpenJDK Runtime Environment Temurin-17.0.9+9 (build 17.0.9+9)\n\
+            OpenJDK 64-Bit Server VM Temurin-17.0.9+9 (build 17.0.9+9, mixed mode, sharing)\n";
+        assert_eq!(parse_version_output(temurin).as_deref(), Some("17.0.9"));
+
+        // OpenJ9 常在前面多出 JAVA_TOOL_OPTIONS 提示行
+        let openj9 = "Picked up JAVA_TOOL_OPTIONS: -Xshareclasses:name=app\n\
+            openjdk version \"11.0.20.1\" 2023-08-24\n\
+            IBM Semeru Runtime Open Edition 11.0.20.1 (build 11.0.20.1+1)\n\
+            Eclipse OpenJ9 VM 11.0.20.1 (build openj9-0.40.0, JRE 11 Linux amd64-64-Bit)\n";
+        assert_eq!(parse_version_output(openj9).as_deref(), Some("11.0.20.1"));
+
+        let localized = "openjdk 版本 \"21.0.2\" 2024-01-16\nOpenJDK 运行时环境\n";
+        assert_eq!(parse_version_output(localized).as_deref(), Some("21.0.2"));
+
+        let unquoted = "Java(TM) SE Runtime Environment (build 1.8.0_191-b12)\n";
+        assert_eq!(parse_version_output(unquoted).as_deref(), Some("1.8.0_191"));
+
+        let odd = "\nsome-jvm build x64\n";
+        assert_eq!(
+            parse_version_output(odd).as_deref(),
+            Some("some-jvm build x64")
+        );
+        assert_eq!(parse_version_output("\n  \n"), None);
+    }
+
     #[test]
     fn test_detect_vendor() {
         assert_eq!(
@@ -234,4 +332,30 @@ mod tests {
             "jdk17"
         );
     }
+
+    #[tokio::test]
+    async fn scan_includes_config_custom_paths() {
+        use crate::infrastructure::config::Config;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = crate::testutil::FnvaHomeGuard::new(tmp.path());
+
+        let share = tmp.path().join("share");
+        let jdk = share.join("corp-jdk-17");
+        let bin = jdk.join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        let java = if cfg!(windows) { "java.exe" } else { "java" };
+        std::fs::write(bin.join(java), "").unwrap();
+
+        let found = |hits: &[ScanHit]| {
+            hits.iter()
+                .any(|h| std::path::Path::new(&h.location) == jdk)
+        };
+        assert!(!found(&JavaScanner::scan_system(&[]).await.unwrap()));
+
+        let mut config = Config::new();
+        assert!(config.add_java_scan_path(&share.to_string_lossy()).unwrap());
+        config.save().unwrap();
+        assert!(found(&JavaScanner::scan_system(&[]).await.unwrap()));
+    }
 }
@@ -0,0 +1,185 @@
+//! `fnva java install-shims`:在指定目录生成 `java` / `javac` / `jar` 包装脚本。
+//!
+//! 包装脚本每次运行时调用 `fnva java which <tool>` 找到当前激活 JDK 里的真实程序再
+//! exec 过去,所以把该目录放进 PATH 后无需 shell 集成、也不改写 PATH 就能跟随切换。
+//! Unix 写 `#!/bin/sh` 脚本并加可执行位,Windows 写 `<tool>.cmd`。
+
+use crate::infrastructure::config::Config;
+use std::path::{Path, PathBuf};
+
+/// 生成包装脚本的工具
+pub const SHIM_TOOLS: &[&str] = &["java", "javac", "jar"];
+
+/// 包装脚本文件名
+pub fn shim_file_name(tool: &str, windows: bool) -> String {
+    if windows {
+        format!("{tool}.cmd")
+    } else {
+        tool.to_string()
+    }
+}
+
+/// `tool` 的包装脚本内容;`fnva` 为写入时 fnva 可执行文件的绝对路径。
+pub fn shim_script(fnva: &Path, tool: &str, windows: bool) -> String {
+    let fnva = fnva.to_string_lossy();
+    if windows {
+        format!(
+            "@echo off\r\n\
+             rem fnva shim: runs {tool} from the active Java environment\r\n\
+             setlocal\r\n\
+             set \"FNVA_SHIM_TARGET=\"\r\n\
+             for /f \"delims=\" %%i in ('call \"{fnva}\" java which {tool}') do set \"FNVA_SHIM_TARGET=%%i\"\r\n\
+             if not defined FNVA_SHIM_TARGET exit /b 127\r\n\
+             \"%FNVA_SHIM_TARGET%\" %*\r\n\
+             exit /b %ERRORLEVEL%\r\n",
+            fnva = fnva.replace('%', "%%")
+        )
+    } else {
+        format!(
+            "#!/bin/sh\n\
+             # fnva shim: runs {tool} from the active Java environment\n\
+             target=$('{fnva}' java which {tool}) || exit 127\n\
+             exec \"$target\" \"$@\"\n",
+            fnva = fnva.replace('\'', "'\\''")
+        )
+    }
+}
+
+/// 在 `dir` 写入全部包装脚本(已存在则覆盖),返回写入的文件。
+pub fn install_shims(dir: &Path, fnva: &Path, windows: bool) -> Result<Vec<PathBuf>, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let mut written = Vec::new();
+    for tool in SHIM_TOOLS {
+        let path = dir.join(shim_file_name(tool, windows));
+        std::fs::write(&path, shim_script(fnva, tool, windows))
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+                .map_err(|e| format!("Failed to make {} executable: {e}", path.display()))?;
+        }
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// 包装脚本运行时要 exec 的程序:`current` 环境(没有则取默认环境)里的 `bin/<tool>`。
+pub fn resolve_tool(config: &Config, current: Option<&str>, tool: &str) -> Result<PathBuf, String> {
+    let name = current
+        .or(config.default_java_env.as_deref())
+        .ok_or("No active or default Java environment; run `fnva java use <name>` or `fnva java default <name>`")?;
+    let env = config
+        .get_java_env(name)
+        .ok_or_else(|| format!("Java environment '{name}' not found"))?;
+    let bin = Path::new(&env.java_home).join("bin");
+    let path = if cfg!(windows) {
+        bin.join(format!("{tool}.exe"))
+    } else {
+        bin.join(tool)
+    };
+    if !path.is_file() {
+        return Err(format!(
+            "'{tool}' not found in Java environment '{name}' ({})",
+            bin.display()
+        ));
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::config::{EnvironmentSource, JavaEnvironment};
+
+    fn config_with(homes: &[(&str, &Path)]) -> Config {
+        let mut config = Config::new();
+        for (name, home) in homes {
+            config
+                .add_java_env(JavaEnvironment {
+                    name: name.to_string(),
+                    java_home: home.to_string_lossy().into_owned(),
+                    description: String::new(),
+                    source: EnvironmentSource::Manual,
+                    java_opts: None,
+                    extra_paths: Vec::new(),
+                    version: None,
+                })
+                .unwrap();
+        }
+        config
+    }
+
+    #[test]
+    fn scripts_call_back_into_fnva_for_each_tool() {
+        let fnva = Path::new("/opt/it's/fnva");
+        let sh = shim_script(fnva, "javac", false);
+        assert!(sh.starts_with("#!/bin/sh\n"), "{sh}");
+        assert!(
+            sh.contains("target=$('/opt/it'\\''s/fnva' java which javac) || exit 127"),
+            "{sh}"
+        );
+        assert!(sh.contains("exec \"$target\" \"$@\""), "{sh}");
+
+        let cmd = shim_script(Path::new(r"C:\Tools\100%\fnva.exe"), "jar", true);
+        assert!(
+            cmd.contains(r#"('call "C:\Tools\100%%\fnva.exe" java which jar')"#),
+            "{cmd}"
+        );
+        assert!(cmd.contains("\"%FNVA_SHIM_TARGET%\" %*"), "{cmd}");
+        assert_eq!(shim_file_name("jar", true), "jar.cmd");
+    }
+
+    #[test]
+    fn install_writes_every_shim() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let dir = tmp.path().join("shims");
+        let written = install_shims(&dir, Path::new("/usr/local/bin/fnva"), false).unwrap();
+        assert_eq!(
+            written,
+            SHIM_TOOLS.iter().map(|t| dir.join(t)).collect::<Vec<_>>()
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.join("java"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+    }
+
+    #[test]
+    fn resolves_current_then_default_environment() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let exe = |tool: &str| {
+            if cfg!(windows) {
+                format!("{tool}.exe")
+            } else {
+                tool.to_string()
+            }
+        };
+        let (jdk17, jdk21) = (tmp.path().join("jdk17"), tmp.path().join("jdk21"));
+        for home in [&jdk17, &jdk21] {
+            std::fs::create_dir_all(home.join("bin")).unwrap();
+            std::fs::write(home.join("bin").join(exe("java")), "").unwrap();
+        }
+        let mut config = config_with(&[("jdk17", &jdk17), ("jdk21", &jdk21)]);
+        assert!(resolve_tool(&config, None, "java").is_err());
+
+        config.default_java_env = Some("jdk21".to_string());
+        assert_eq!(
+            resolve_tool(&config, None, "java").unwrap(),
+            jdk21.join("bin").join(exe("java"))
+        );
+        assert_eq!(
+            resolve_tool(&config, Some("jdk17"), "java").unwrap(),
+            jdk17.join("bin").join(exe("java"))
+        );
+
+        let missing = resolve_tool(&config, Some("jdk17"), "javac").unwrap_err();
+        assert!(missing.contains("'javac' not found"), "{missing}");
+        assert!(resolve_tool(&config, Some("ghost"), "java").is_err());
+    }
+}
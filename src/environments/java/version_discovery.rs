@@ -21,6 +21,9 @@ use std::future::Future;
 use std::pin::Pin;
 
 const MIRROR_BASE: &str = "https://mirrors.tuna.tsinghua.edu.cn/Adoptium";
+/// `fnva java registry update` 的默认来源:仓库内维护的注册表。
+pub const DEFAULT_REGISTRY_URL: &str =
+    "https://raw.githubusercontent.com/Protagonistss/fnva/main/config/java_versions.toml";
 const CACHE_TTL_SECS: i64 = 86_400; // 24h
 /// 支持发现的主版本(倒序,最新优先)
 const SUPPORTED_MAJORS: &[u32] = &[25, 21, 17, 11, 8];
@@ -96,6 +99,140 @@ fn parse_filename(filename: &str, major: u32) -> Option<(String, String)> {
     }
 }
 
+/// 编译期嵌入的 `config/java_versions.toml`(RegistryEntry 格式)
+const EMBEDDED_REGISTRY: &str = include_str!("../../../config/java_versions.toml");
+
+/// 本地注册表路径:`java_versions_path` 配置优先,否则 `~/.fnva/cache/java_versions.toml`。
+pub fn local_registry_path() -> Option<std::path::PathBuf> {
+    let configured = crate::infrastructure::config::Config::load()
+        .ok()
+        .and_then(|c| c.java_versions_path);
+    match configured {
+        Some(p) => Some(std::path::PathBuf::from(p)),
+        None => crate::infrastructure::paths::java_versions_path().ok(),
+    }
+}
+
+/// 解析注册表 TOML(RegistryEntry 格式),只保留当前平台有资产的条目。
+///
+/// 可选的 `[versions.checksums]` 表按平台键给出资产 SHA-256,安装时据此校验。
+fn parse_registry(text: &str, platform: &Platform) -> Result<Vec<CachedVersion>, DiscoveryError> {
+    parse_registry_entries(text, Some(platform))
+}
+
+/// `platform` 为 `None` 时每个版本的每个平台资产各产出一条(`fnva java ls-remote --platforms`)。
+fn parse_registry_entries(
+    text: &str,
+    platform: Option<&Platform>,
+) -> Result<Vec<CachedVersion>, DiscoveryError> {
+    let parsed: toml::Value =
+        toml::from_str(text).map_err(|e| DiscoveryError::Parse(e.to_string()))?;
+    let versions = parsed
+        .get("versions")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| DiscoveryError::Parse("no versions in java_versions.toml".into()))?;
+    let plat_key = platform.map(|p| format!("{}-{}", p.os, p.arch));
+    let mut out = Vec::new();
+    for v in versions {
+        let (Some(version), Some(major)) = (
+            v.get("version").and_then(|x| x.as_str()),
+            v.get("major").and_then(|x| x.as_integer()),
+        ) else {
+            continue;
+        };
+        let tag = v
+            .get("tag_name")
+            .and_then(|x| x.as_str())
+            .unwrap_or("")
+            .to_string();
+        let Some(assets) = v.get("assets").and_then(|a| a.as_table()) else {
+            continue;
+        };
+        for (key, filename) in assets {
+            if plat_key.as_ref().is_some_and(|k| k != key) {
+                continue;
+            }
+            let (Some(filename), Some((os, arch))) = (filename.as_str(), key.split_once('-'))
+            else {
+                continue;
+            };
+            let sha256 = v
+                .get("checksums")
+                .and_then(|c| c.as_table())
+                .and_then(|c| c.get(key))
+                .and_then(|h| h.as_str())
+                .map(|h| h.trim().to_ascii_lowercase());
+            out.push(CachedVersion {
+                version: version.to_string(),
+                major: major as u32,
+                is_lts: AdoptiumDiscovery::is_lts(major as u32),
+                tag: tag.clone(),
+                filename: filename.to_string(),
+                os: os.to_string(),
+                arch: arch.to_string(),
+                sha256,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// 注册表中 `version` 在各平台的构建,按平台键排序。
+///
+/// 与离线兜底同源:优先本地注册表,其中没有该版本时查编译期嵌入的注册表;都没有时为空。
+pub fn registry_platform_builds(version: &str) -> Vec<(Platform, ResolvedVersion)> {
+    let builds_in = |text: &str| -> Vec<CachedVersion> {
+        let mut builds: Vec<CachedVersion> = parse_registry_entries(text, None)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|cv| cv.version == version)
+            .collect();
+        builds.sort_by(|a, b| (&a.os, &a.arch).cmp(&(&b.os, &b.arch)));
+        builds
+    };
+    let local = local_registry_path()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .map(|text| builds_in(&text))
+        .unwrap_or_default();
+    let builds = if local.is_empty() {
+        builds_in(EMBEDDED_REGISTRY)
+    } else {
+        local
+    };
+    builds
+        .iter()
+        .map(|cv| {
+            let platform = Platform {
+                os: cv.os.clone(),
+                arch: cv.arch.clone(),
+            };
+            (platform, AdoptiumDiscovery::make_resolved(cv))
+        })
+        .collect()
+}
+
+/// 从 `url` 拉取注册表,校验可解析后写入本地注册表路径,返回条目数。
+pub async fn update_registry(url: &str) -> Result<usize, String> {
+    let client = crate::infrastructure::remote::http::http_client();
+    let text = fetch_with_retry(&client, url)
+        .await
+        .map_err(|e| format!("Failed to fetch registry from {url}: {e}"))?;
+    let parsed: toml::Value =
+        toml::from_str(&text).map_err(|e| format!("Invalid registry TOML from {url}: {e}"))?;
+    let count = parsed
+        .get("versions")
+        .and_then(|v| v.as_array())
+        .map(|v| v.len())
+        .filter(|n| *n > 0)
+        .ok_or_else(|| format!("Registry from {url} has no [[versions]] entries"))?;
+    let path = local_registry_path().ok_or("Cannot resolve registry path")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&path, text).map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    Ok(count)
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 struct CachedVersion {
     version: String,
@@ -105,22 +242,44 @@ struct CachedVersion {
     filename: String,
     os: String,
     arch: String,
+    #[serde(default)]
+    sha256: Option<String>,
 }
 
 /// 清华 Adoptium 镜像目录动态发现:Java 版本来源。
 pub struct AdoptiumDiscovery {
     client: Client,
     platform: Platform,
+    base_url: String,
+    /// 本实例已加载的版本表:一次安装里 `find` 与 `list` 共用,避免重复抓取
+    /// (离线回退嵌入表时不写磁盘缓存,没有这层会把所有 major 再重试一遍)。
+    memo: std::sync::Mutex<Option<Vec<CachedVersion>>>,
+    /// 离线模式:不抓镜像目录,只用缓存与本地注册表 / 嵌入表
+    offline: bool,
 }
 
 impl AdoptiumDiscovery {
     pub fn new() -> Self {
+        Self::with_base_url(MIRROR_BASE)
+    }
+
+    /// 指定镜像根地址(测试用 mock server)。
+    pub fn with_base_url(base_url: &str) -> Self {
         Self {
-            client: Client::new(),
+            client: crate::infrastructure::remote::http::http_client(),
             platform: Platform::current(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            memo: std::sync::Mutex::new(None),
+            offline: crate::infrastructure::remote::offline::is_offline(),
         }
     }
 
+    /// 覆盖离线模式(默认取 `--offline` / `FNVA_OFFLINE`)。
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
     fn cache_path() -> Result<std::path::PathBuf, DiscoveryError> {
         crate::infrastructure::paths::cache_dir()
             .map(|d| d.join("java_versions.json"))
@@ -134,8 +293,10 @@ impl AdoptiumDiscovery {
     /// 抓单个 major 的 `/{major}/jdk/{arch}/{os}/` 目录 → filenames → 解析版本。
     async fn fetch_major(&self, major: u32) -> Result<Vec<CachedVersion>, DiscoveryError> {
         let url = format!(
-            "{MIRROR_BASE}/{major}/jdk/{}/{}/",
-            self.platform.arch, self.platform.os
+            "{}/{major}/jdk/{}/{}/",
+            self.base_url,
+            self.platform.arch,
+            Platform::adoptium_os(&self.platform.os)
         );
         let html = fetch_with_retry(&self.client, &url).await?;
 
@@ -164,6 +325,7 @@ impl AdoptiumDiscovery {
                     filename: name.to_string(),
                     os: self.platform.os.clone(),
                     arch: self.platform.arch.clone(),
+                    sha256: None,
                 });
             }
         }
@@ -172,6 +334,11 @@ impl AdoptiumDiscovery {
 
     /// 抓所有支持 major → 合并 + 写缓存。全部 major 失败则回退嵌入表。
     async fn fetch_and_cache(&self) -> Result<Vec<CachedVersion>, DiscoveryError> {
+        if self.offline {
+            return Err(DiscoveryError::Network(
+                crate::infrastructure::remote::offline::offline_error("Refreshing Java versions"),
+            ));
+        }
         let mut all = Vec::new();
         for &major in SUPPORTED_MAJORS {
             if let Ok(v) = self.fetch_major(major).await {
@@ -179,7 +346,7 @@ impl AdoptiumDiscovery {
             }
         }
         if all.is_empty() {
-            return Self::embedded_versions(&self.platform);
+            return Self::registry_versions(&self.platform);
         }
         all.sort_by(|a, b| b.major.cmp(&a.major).then(b.version.cmp(&a.version)));
         if let Ok(path) = Self::cache_path() {
@@ -188,61 +355,49 @@ impl AdoptiumDiscovery {
         Ok(all)
     }
 
-    /// TTL 内用缓存,否则抓取;抓取失败(离线)回退嵌入表。
+    /// 实例内已加载过则直接复用;否则 TTL 内用缓存,再否则抓取;抓取失败(离线)回退嵌入表。
     async fn load_versions(&self) -> Result<Vec<CachedVersion>, DiscoveryError> {
+        if let Some(memo) = self.memo.lock().ok().and_then(|m| m.clone()) {
+            return Ok(memo);
+        }
+        let versions = self.load_versions_uncached().await?;
+        if let Ok(mut memo) = self.memo.lock() {
+            *memo = Some(versions.clone());
+        }
+        Ok(versions)
+    }
+
+    async fn load_versions_uncached(&self) -> Result<Vec<CachedVersion>, DiscoveryError> {
         if let Ok(path) = Self::cache_path() {
             if let Some(cached) = CacheEntry::<CachedVersion>::read(&path, CACHE_TTL_SECS) {
                 return Ok(cached);
             }
         }
+        if self.offline {
+            return Self::registry_versions(&self.platform);
+        }
         match self.fetch_and_cache().await {
             Ok(v) => Ok(v),
-            Err(_) => Self::embedded_versions(&self.platform),
+            Err(_) => Self::registry_versions(&self.platform),
         }
     }
 
+    /// 离线兜底:优先读本地注册表(`fnva java registry update` 写入),
+    /// 不存在或解析失败时用编译期嵌入的 `config/java_versions.toml`。
+    fn registry_versions(platform: &Platform) -> Result<Vec<CachedVersion>, DiscoveryError> {
+        if let Some(text) = local_registry_path().and_then(|p| std::fs::read_to_string(p).ok()) {
+            if let Ok(v) = parse_registry(&text, platform) {
+                if !v.is_empty() {
+                    return Ok(v);
+                }
+            }
+        }
+        Self::embedded_versions(platform)
+    }
+
     /// 编译期嵌入兜底:解析 `config/java_versions.toml`(RegistryEntry 格式)。
     fn embedded_versions(platform: &Platform) -> Result<Vec<CachedVersion>, DiscoveryError> {
-        const EMBEDDED: &str = include_str!("../../../config/java_versions.toml");
-        let parsed: toml::Value =
-            toml::from_str(EMBEDDED).map_err(|e| DiscoveryError::Parse(e.to_string()))?;
-        let versions = parsed
-            .get("versions")
-            .and_then(|v| v.as_array())
-            .ok_or_else(|| DiscoveryError::Parse("no versions in java_versions.toml".into()))?;
-        let plat_key = format!("{}-{}", platform.os, platform.arch);
-        let mut out = Vec::new();
-        for v in versions {
-            let (Some(version), Some(major)) = (
-                v.get("version").and_then(|x| x.as_str()),
-                v.get("major").and_then(|x| x.as_integer()),
-            ) else {
-                continue;
-            };
-            let tag = v
-                .get("tag_name")
-                .and_then(|x| x.as_str())
-                .unwrap_or("")
-                .to_string();
-            let Some(filename) = v
-                .get("assets")
-                .and_then(|a| a.as_table())
-                .and_then(|a| a.get(&plat_key))
-                .and_then(|f| f.as_str())
-            else {
-                continue;
-            };
-            out.push(CachedVersion {
-                version: version.to_string(),
-                major: major as u32,
-                is_lts: Self::is_lts(major as u32),
-                tag,
-                filename: filename.to_string(),
-                os: platform.os.clone(),
-                arch: platform.arch.clone(),
-            });
-        }
-        Ok(out)
+        parse_registry(EMBEDDED_REGISTRY, platform)
     }
 
     fn make_resolved(cv: &CachedVersion) -> ResolvedVersion {
@@ -256,8 +411,10 @@ impl AdoptiumDiscovery {
                 major: Some(cv.major),
                 tag: Some(cv.tag.clone()),
                 filename: cv.filename.clone(),
-                os: cv.os.clone(),
+                // 镜像模板的 {os} 按 Adoptium 目录命名(macOS 为 mac)
+                os: Platform::adoptium_os(&cv.os).to_string(),
                 arch: cv.arch.clone(),
+                sha256: cv.sha256.clone(),
                 ..Default::default()
             },
         }
@@ -312,7 +469,13 @@ impl VersionDiscovery for AdoptiumDiscovery {
     }
 
     fn refresh(&self) -> Pin<Box<dyn Future<Output = Result<(), DiscoveryError>> + Send + '_>> {
-        Box::pin(async { self.fetch_and_cache().await.map(|_| ()) })
+        Box::pin(async {
+            let versions = self.fetch_and_cache().await?;
+            if let Ok(mut memo) = self.memo.lock() {
+                *memo = Some(versions);
+            }
+            Ok(())
+        })
     }
 }
 
@@ -375,4 +538,173 @@ mod tests {
         assert!(!v.is_empty());
         assert!(v.iter().any(|x| x.major == 21));
     }
+
+    #[test]
+    fn parse_registry_reads_platform_checksum() {
+        let platform = Platform {
+            os: "linux".into(),
+            arch: "x64".into(),
+        };
+        let text = r#"
+[[versions]]
+version = "21.0.11+10"
+major = 21
+lts = true
+tag_name = "jdk-21.0.11+10"
+[versions.assets]
+linux-x64 = "OpenJDK21U-jdk_x64_linux_hotspot_21.0.11_10.tar.gz"
+[versions.checksums]
+linux-x64 = "ABCDEF0123"
+"#;
+        let v = parse_registry(text, &platform).unwrap();
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0].sha256.as_deref(), Some("abcdef0123"));
+        let resolved = AdoptiumDiscovery::make_resolved(&v[0]);
+        assert_eq!(resolved.template_vars.sha256.as_deref(), Some("abcdef0123"));
+    }
+
+    #[test]
+    fn parse_registry_entries_lists_every_platform_without_filter() {
+        let text = r#"
+[[versions]]
+version = "21.0.11+10"
+major = 21
+tag_name = "jdk-21.0.11+10"
+[versions.assets]
+linux-x64 = "OpenJDK21U-jdk_x64_linux_hotspot_21.0.11_10.tar.gz"
+macos-aarch64 = "OpenJDK21U-jdk_aarch64_mac_hotspot_21.0.11_10.tar.gz"
+[versions.checksums]
+macos-aarch64 = "FEED"
+"#;
+        let all = parse_registry_entries(text, None).unwrap();
+        let keys: Vec<(String, String, Option<String>)> = all
+            .iter()
+            .map(|cv| (cv.os.clone(), cv.arch.clone(), cv.sha256.clone()))
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("linux".into(), "x64".into(), None),
+                ("macos".into(), "aarch64".into(), Some("feed".into())),
+            ]
+        );
+        // 平台过滤保持原行为:只留宿主平台
+        let linux = Platform {
+            os: "linux".into(),
+            arch: "x64".into(),
+        };
+        assert_eq!(parse_registry(text, &linux).unwrap().len(), 1);
+    }
+
+    /// 文件名与镜像目录都跟随宿主平台:Linux 取 linux 资产,macOS 取 mac 资产且
+    /// Adoptium 目录写作 `mac`(不是 `macos`)。
+    #[test]
+    fn registry_assets_and_mirror_path_follow_host_platform() {
+        let template = "{base_url}/{major}/jdk/{arch}/{os}/{filename}";
+        let resolve = |os: &str, arch: &str| {
+            let platform = Platform {
+                os: os.into(),
+                arch: arch.into(),
+            };
+            let v = AdoptiumDiscovery::embedded_versions(&platform).unwrap();
+            let cv = v.iter().find(|x| x.major == 21).unwrap();
+            let vars = AdoptiumDiscovery::make_resolved(cv).template_vars;
+            (
+                vars.filename.clone(),
+                TemplateVars::render(template, MIRROR_BASE, &vars),
+            )
+        };
+
+        let (filename, url) = resolve("linux", "x64");
+        assert!(filename.contains("_x64_linux_"), "{filename}");
+        assert!(url.contains("/21/jdk/x64/linux/"), "{url}");
+
+        let (filename, url) = resolve("macos", "aarch64");
+        assert!(filename.contains("_aarch64_mac_"), "{filename}");
+        assert!(url.contains("/21/jdk/aarch64/mac/"), "{url}");
+        assert!(!filename.contains("windows") && !url.contains("windows"));
+    }
+
+    #[tokio::test]
+    async fn mac_host_lists_adoptium_mac_directory() {
+        use httpmock::prelude::*;
+        let server = httpmock::MockServer::start();
+        let listing = server.mock(|when, then| {
+            when.method(GET).path("/21/jdk/aarch64/mac/");
+            then.status(200)
+                .body(r#"<a href="OpenJDK21U-jdk_aarch64_mac_hotspot_21.0.11_10.tar.gz">x</a>"#);
+        });
+
+        let mut discovery = AdoptiumDiscovery::with_base_url(&server.base_url());
+        discovery.platform = Platform {
+            os: "macos".into(),
+            arch: "aarch64".into(),
+        };
+        let versions = discovery.fetch_major(21).await.unwrap();
+        listing.assert();
+        assert_eq!(versions[0].version, "21.0.11+10");
+    }
+
+    #[tokio::test]
+    async fn update_registry_writes_local_file() {
+        use httpmock::prelude::*;
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = crate::testutil::FnvaHomeGuard::new(tmp.path());
+
+        let server = httpmock::MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/java_versions.toml");
+            then.status(200)
+                .body(include_str!("../../../config/java_versions.toml"));
+        });
+
+        let count = update_registry(&server.url("/java_versions.toml"))
+            .await
+            .unwrap();
+        assert!(count > 0);
+        assert!(local_registry_path().unwrap().exists());
+    }
+
+    #[tokio::test]
+    async fn find_then_list_fetches_each_major_once() {
+        use httpmock::prelude::*;
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = crate::testutil::FnvaHomeGuard::new(tmp.path());
+
+        // 空目录页:所有 major 都解析不出版本 → 回退嵌入表,不写磁盘缓存
+        let server = httpmock::MockServer::start();
+        let listing = server.mock(|when, then| {
+            when.method(GET);
+            then.status(200).body("<html></html>");
+        });
+
+        let discovery = AdoptiumDiscovery::with_base_url(&server.base_url());
+        discovery.find("lts").await.unwrap();
+        discovery.list().await.unwrap();
+
+        listing.assert_hits(SUPPORTED_MAJORS.len());
+    }
+
+    #[tokio::test]
+    async fn offline_lists_from_registry_without_requests() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = crate::testutil::FnvaHomeGuard::new(tmp.path());
+
+        let server = httpmock::MockServer::start();
+        let any = server.mock(|when, then| {
+            when.any_request();
+            then.status(200).body("<html></html>");
+        });
+
+        let discovery = AdoptiumDiscovery::with_base_url(&server.base_url()).offline(true);
+        let versions = discovery.list().await.unwrap();
+        assert!(!versions.is_empty());
+        discovery.find("lts").await.unwrap();
+        assert!(matches!(
+            discovery.refresh().await,
+            Err(DiscoveryError::Network(msg)) if msg.contains("offline mode")
+        ));
+
+        any.assert_hits(0);
+    }
 }
@@ -12,7 +12,7 @@ pub type MavenDownloader = GenericDownloader<MirrorDirectoryDiscovery>;
 impl MavenDownloader {
     pub fn new(mirrors: Vec<MirrorConfig>) -> Self {
         Self::with_file_name(
-            MirrorDirectoryDiscovery::new(),
+            MirrorDirectoryDiscovery::from_mirrors(&mirrors),
             mirrors,
             |version, mirror| format!("apache-maven-{}-{}.tar.gz", version.version, mirror),
         )
@@ -26,6 +26,7 @@ impl MavenInstaller {
         version_spec: &str,
         config: &mut Config,
         auto_switch: bool,
+        keep_archive: Option<&std::path::Path>,
     ) -> Result<String, String> {
         crate::cli::print::action(&format!("Installing maven {version_spec}"));
 
@@ -67,6 +68,7 @@ impl MavenInstaller {
             &platform,
             version_spec,
             &MAVEN_DESCRIPTOR,
+            keep_archive,
         )
         .await?;
 
@@ -125,12 +127,10 @@ impl MavenInstaller {
             return Ok(None); // 已在配置中,走正常流程(会提示已安装)
         }
         let fnva_dir = crate::infrastructure::paths::tool_packages_dir("maven")?;
-        let maven_home = fnva_dir.join(version_spec);
-        if maven_home.exists() {
-            let actual = locate_maven_home(&maven_home)?;
-            return Ok(Some(actual));
-        }
-        Ok(None)
+        generic::reuse_complete_install(
+            &generic::install_dir_for(&fnva_dir, version_spec)?,
+            &MAVEN_DESCRIPTOR,
+        )
     }
 
     /// 卸载 Maven 版本(仅限 fnva 管理的安装)
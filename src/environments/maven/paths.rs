@@ -35,13 +35,9 @@ pub fn common_paths(extra: &[String]) -> Vec<String> {
                 .into_owned(),
         );
         paths.push(home.join(".maven").to_string_lossy().into_owned());
-        paths.push(
-            home.join(".fnva")
-                .join("packages")
-                .join("maven")
-                .to_string_lossy()
-                .into_owned(),
-        );
+    }
+    if let Ok(dir) = crate::infrastructure::paths::tool_packages_dir("maven") {
+        paths.push(dir.to_string_lossy().into_owned());
     }
 
     // 持久自定义(config)
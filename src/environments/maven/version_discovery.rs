@@ -1,12 +1,15 @@
 //! Maven 版本发现:抓取 Apache archive 的 `maven-3/` 目录列表,解析出版本号。
 //!
-//! 清华镜像的 `maven-3/` 目录只保留最新版,不能用作 list 源;因此
-//! `discovery_url` 固定指向 apache archive(完整历史)。清华源只作下载加速镜像
-//! (见 `MavenInstaller` 的镜像配置)。
+//! 清华镜像的 `maven-3/` 目录只保留最新版,不能单独用作 list 源;因此
+//! apache archive(完整历史)始终是第一个来源,镜像目录只作补充。
+//!
+//! 配置了多个 Maven 镜像时,所有目录并发抓取、按版本号去重合并:每个来源各有
+//! 超时,某个镜像慢或被墙不会拖住整个列表,只要任一来源成功即可。
 //!
 //! 抓取结果缓存到 `~/.fnva/maven_versions.json`,带 24h TTL,支持
 //! `fnva maven refresh` 强制刷新。抓取失败时回退编译期嵌入的兜底列表。
 
+use crate::infrastructure::config::MirrorConfig;
 use crate::infrastructure::tool_protocol::fetch_with_retry;
 use crate::infrastructure::tool_protocol::template_vars::TemplateVars;
 use crate::infrastructure::tool_protocol::version_discovery::{
@@ -16,21 +19,53 @@ use crate::infrastructure::tool_protocol::CacheEntry;
 use reqwest::Client;
 use std::future::Future;
 use std::pin::Pin;
+use std::time::Duration;
 
 const ARCHIVE_URL: &str = "https://archive.apache.org/dist/maven/maven-3/";
 const CACHE_TTL_SECS: i64 = 86_400; // 24h
+/// 单个目录来源(含重试)的总时限;超时按该来源失败处理
+const SOURCE_TIMEOUT: Duration = Duration::from_secs(20);
 
 /// 镜像目录动态发现:Maven 版本来源。
 pub struct MirrorDirectoryDiscovery {
-    discovery_url: &'static str,
+    /// 目录来源,按优先级排列;apache archive 永远在首位(完整历史)
+    discovery_urls: Vec<String>,
     client: Client,
+    /// 离线模式:不抓目录,只用缓存与嵌入列表
+    offline: bool,
+    /// 单个来源的总时限
+    source_timeout: Duration,
 }
 
 impl MirrorDirectoryDiscovery {
     pub fn new() -> Self {
+        Self::with_sources(vec![ARCHIVE_URL.to_string()])
+    }
+
+    /// apache archive + 所有启用且配置了 base_url 的镜像目录。
+    pub fn from_mirrors(mirrors: &[MirrorConfig]) -> Self {
+        let mut urls = vec![ARCHIVE_URL.to_string()];
+        let mut enabled: Vec<&MirrorConfig> = mirrors
+            .iter()
+            .filter(|m| m.enabled && !m.base_url.is_empty())
+            .collect();
+        enabled.sort_by_key(|m| m.priority);
+        for m in enabled {
+            let url = format!("{}/", m.base_url.trim_end_matches('/'));
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        Self::with_sources(urls)
+    }
+
+    /// 指定目录来源(测试用 mock server)。
+    pub fn with_sources(discovery_urls: Vec<String>) -> Self {
         Self {
-            discovery_url: ARCHIVE_URL,
-            client: Client::new(),
+            discovery_urls,
+            client: crate::infrastructure::remote::http::http_client(),
+            offline: crate::infrastructure::remote::offline::is_offline(),
+            source_timeout: SOURCE_TIMEOUT,
         }
     }
 
@@ -73,11 +108,54 @@ impl MirrorDirectoryDiscovery {
                 .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
     }
 
+    /// 并发抓取所有来源并去重合并;每个来源最多等 `source_timeout`,
+    /// 全部来源失败(含超时)时返回第一个错误。
+    async fn fetch_merged(&self) -> Result<Vec<String>, DiscoveryError> {
+        let results =
+            futures_util::future::join_all(self.discovery_urls.iter().map(|url| async move {
+                tokio::time::timeout(self.source_timeout, fetch_with_retry(&self.client, url))
+                    .await
+                    .unwrap_or_else(|_| {
+                        Err(DiscoveryError::Network(format!(
+                            "Timed out fetching {url} after {}s",
+                            self.source_timeout.as_secs()
+                        )))
+                    })
+            }))
+            .await;
+        let mut first_err = None;
+        let mut merged: Vec<String> = Vec::new();
+        let mut any_ok = false;
+        for result in results {
+            match result {
+                Ok(html) => {
+                    any_ok = true;
+                    for v in Self::parse_directory_html(&html) {
+                        if !merged.contains(&v) {
+                            merged.push(v);
+                        }
+                    }
+                }
+                Err(e) => {
+                    first_err.get_or_insert(e);
+                }
+            }
+        }
+        match (any_ok, first_err) {
+            (false, Some(e)) => Err(e),
+            _ => Ok(merged),
+        }
+    }
+
     /// 抓取目录并写缓存。网络错误向上传播(由 `load_versions` / `refresh`
     /// 决定是否回退);抓到内容但解析为空则回退嵌入式列表。
     async fn fetch_and_cache(&self) -> Result<Vec<String>, DiscoveryError> {
-        let html = fetch_with_retry(&self.client, self.discovery_url).await?;
-        let mut versions = Self::parse_directory_html(&html);
+        if self.offline {
+            return Err(DiscoveryError::Network(
+                crate::infrastructure::remote::offline::offline_error("Refreshing Maven versions"),
+            ));
+        }
+        let mut versions = self.fetch_merged().await?;
         if versions.is_empty() {
             return Self::embedded_versions();
         }
@@ -89,7 +167,7 @@ impl MirrorDirectoryDiscovery {
         Ok(versions)
     }
 
-    /// TTL 内用本地缓存,否则抓取;抓取失败(离线)回退嵌入式列表。
+    /// TTL 内用本地缓存,否则抓取;抓取失败或离线模式回退嵌入式列表。
     async fn load_versions(&self) -> Result<Vec<String>, DiscoveryError> {
         if let Ok(path) = Self::cache_path() {
             if let Some(cached) = CacheEntry::<String>::read(&path, CACHE_TTL_SECS) {
@@ -237,6 +315,75 @@ mod tests {
         assert_eq!(v.first().map(String::as_str), Some("3.9.16"));
     }
 
+    #[tokio::test]
+    async fn fetch_merged_skips_failing_source() {
+        use httpmock::prelude::*;
+        let broken = httpmock::MockServer::start();
+        broken.mock(|when, then| {
+            when.method(GET);
+            then.status(503).body("Service Unavailable");
+        });
+        let healthy = httpmock::MockServer::start();
+        healthy.mock(|when, then| {
+            when.method(GET);
+            then.status(200)
+                .body(r#"<a href="3.9.16/">3.9.16/</a><a href="3.8.8/">3.8.8/</a>"#);
+        });
+
+        let discovery =
+            MirrorDirectoryDiscovery::with_sources(vec![broken.base_url(), healthy.base_url()]);
+        let v = discovery.fetch_merged().await.unwrap();
+        assert_eq!(v, vec!["3.9.16".to_string(), "3.8.8".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn fetch_merged_does_not_wait_for_stalled_source() {
+        use httpmock::prelude::*;
+        let stalled = httpmock::MockServer::start();
+        stalled.mock(|when, then| {
+            when.method(GET);
+            then.status(200)
+                .delay(Duration::from_secs(10))
+                .body(r#"<a href="3.0.0/">3.0.0/</a>"#);
+        });
+        let healthy = httpmock::MockServer::start();
+        healthy.mock(|when, then| {
+            when.method(GET);
+            then.status(200).body(r#"<a href="3.9.16/">3.9.16/</a>"#);
+        });
+
+        let mut discovery =
+            MirrorDirectoryDiscovery::with_sources(vec![stalled.base_url(), healthy.base_url()]);
+        discovery.source_timeout = Duration::from_millis(300);
+        let started = std::time::Instant::now();
+        let v = discovery.fetch_merged().await.unwrap();
+        assert_eq!(v, vec!["3.9.16".to_string()]);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn from_mirrors_keeps_archive_first_and_skips_disabled() {
+        let mirror = |name: &str, base_url: &str, enabled: bool| MirrorConfig {
+            name: name.into(),
+            priority: 1,
+            enabled,
+            base_url: base_url.into(),
+            url_template: String::new(),
+        };
+        let d = MirrorDirectoryDiscovery::from_mirrors(&[
+            mirror("tsinghua", "https://mirror.example/maven-3", true),
+            mirror("off", "https://off.example/maven-3", false),
+            mirror("archive", ARCHIVE_URL, true),
+        ]);
+        assert_eq!(
+            d.discovery_urls,
+            vec![
+                ARCHIVE_URL.to_string(),
+                "https://mirror.example/maven-3/".to_string()
+            ]
+        );
+    }
+
     #[test]
     fn supports_refresh_is_true() {
         assert!(MirrorDirectoryDiscovery::new().supports_refresh());
@@ -64,6 +64,26 @@ impl AppError {
         }
     }
 
+    /// 稳定的错误码(取底层错误的变体),供 `--json` 的错误输出使用。
+    pub fn code(&self) -> &'static str {
+        match self.root_cause() {
+            AppError::Io(_) => "io",
+            AppError::Environment { .. } => "environment",
+            AppError::Config { .. } => "config",
+            AppError::Network { .. } => "network",
+            AppError::Serialization(_) => "serialization",
+            AppError::Path { .. } => "path",
+            AppError::LockError { .. } => "lock",
+            AppError::VersionParse { .. } => "version_parse",
+            AppError::Installation { .. } => "installation",
+            AppError::ScriptGeneration { .. } => "script_generation",
+            AppError::NotFound { .. } => "not_found",
+            AppError::Permission { .. } => "permission",
+            AppError::Validation { .. } => "validation",
+            AppError::Internal { .. } | AppError::Context { .. } => "internal",
+        }
+    }
+
     /// 为错误附上操作上下文,返回包装后的 [`AppError`]。
     pub fn context(self, operation: impl Into<String>) -> Self {
         AppError::Context {
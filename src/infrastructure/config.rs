@@ -7,7 +7,7 @@ use std::path::PathBuf;
 pub const DEFAULT_SONNET_MODEL: &str = "claude-sonnet-4-5";
 
 /// 镜像配置（模板化 URL）
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct MirrorConfig {
     pub name: String,
     #[serde(default = "default_mirror_priority")]
@@ -97,19 +97,22 @@ fn default_java_mirrors() -> Vec<MirrorConfig> {
 }
 
 /// 配置文件结构
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
-    #[serde(default)]
+    #[serde(default, serialize_with = "sorted_by_name")]
     pub java_environments: Vec<JavaEnvironment>,
-    #[serde(default)]
+    #[serde(default, serialize_with = "sorted_by_name")]
     pub maven_environments: Vec<MavenEnvironment>,
-    #[serde(default)]
+    #[serde(default, serialize_with = "sorted_by_name")]
     pub cc_environments: Vec<CcEnvironment>,
     #[serde(default)]
     pub mirrors: MirrorsConfig,
     /// Java 版本注册表路径（可选，默认使用编译嵌入的版本）
     #[serde(default)]
     pub java_versions_path: Option<String>,
+    /// `fnva java registry update` 的注册表来源 URL（可选，默认仓库内的 java_versions.toml）
+    #[serde(default)]
+    pub java_registry_url: Option<String>,
     /// 下载配置
     #[serde(default)]
     pub download: DownloadConfig,
@@ -136,10 +139,74 @@ pub struct Config {
     /// 明确移除的 Java 环境名称（防止重新扫描添加）
     #[serde(default)]
     pub removed_java_names: Vec<String>,
+    /// 切换 Java 时如何清理 PATH 中旧的 Java bin 目录
+    #[serde(default)]
+    pub path_strategy: PathStrategy,
+    /// 切换到已激活的环境(无操作)时是否仍写入历史
+    #[serde(default)]
+    pub record_noop_switches: bool,
+    /// 切换历史最多保留的条数,超出时丢弃最旧的记录
+    #[serde(default = "default_history_limit")]
+    pub history_limit: usize,
+    /// 切换到新环境后执行的命令(经系统 shell),失败只警告
+    #[serde(default)]
+    pub on_switch_command: Option<String>,
+    /// 按环境覆盖 on_switch_command:`[on_switch_commands.java]` 下 `jdk17 = "..."`
+    #[serde(
+        default,
+        skip_serializing_if = "std::collections::HashMap::is_empty",
+        serialize_with = "sorted_nested_map"
+    )]
+    pub on_switch_commands:
+        std::collections::HashMap<String, std::collections::HashMap<String, String>>,
+    /// 系统层(补全默认值后),保存时据此去掉继承来的部分;无系统配置时为 `None`
+    #[serde(skip)]
+    pub(super) system_layer: Option<toml::Value>,
+}
+
+/// 默认保留的切换历史条数
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+fn default_history_limit() -> usize {
+    DEFAULT_HISTORY_LIMIT
+}
+
+/// [`Config::compact_java`] 做了哪些修改
+#[derive(Debug, Default, Serialize)]
+pub struct CompactReport {
+    /// `(环境名, 原路径, 整理后路径)`
+    pub normalized: Vec<(String, String, String)>,
+    /// `(删除的环境名, 保留的环境名)`
+    pub duplicates: Vec<(String, String)>,
+    /// 从 `removed_java_names` 去掉的名称
+    pub pruned_removed_names: Vec<String>,
+}
+
+impl CompactReport {
+    pub fn is_empty(&self) -> bool {
+        self.normalized.is_empty()
+            && self.duplicates.is_empty()
+            && self.pruned_removed_names.is_empty()
+    }
+}
+
+/// PATH 清理策略
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum PathStrategy {
+    /// 只移除上一次由 fnva 设置的 bin 目录(记录在 `FNVA_JAVA_BIN`)
+    #[serde(rename = "fnva_only")]
+    #[default]
+    FnvaOnly,
+    /// 移除 PATH 中所有包含 java/jdk 的条目
+    #[serde(rename = "aggressive")]
+    Aggressive,
+    /// 不清理,只把新的 bin 目录放到 PATH 最前面
+    #[serde(rename = "keep")]
+    Keep,
 }
 
 /// 下载配置
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DownloadConfig {
     /// 重试次数
     #[serde(default = "default_retry_count")]
@@ -156,6 +223,91 @@ pub struct DownloadConfig {
     /// 读取超时时间（秒）
     #[serde(default = "default_read_timeout_sec")]
     pub read_timeout_sec: u64,
+    /// 单次安装的总时限(秒,含所有镜像与重试);0(默认)表示不限,慢速网络下仍在下载时不会被中断
+    #[serde(default)]
+    pub install_timeout_sec: u64,
+    /// 额外信任的根证书(PEM/DER,企业代理 CA);`FNVA_CA_CERT` 环境变量优先
+    #[serde(default)]
+    pub ca_cert_path: Option<String>,
+    /// 跳过 TLS 证书校验(危险,仅作临时逃生口)
+    #[serde(default)]
+    pub danger_accept_invalid_certs: bool,
+    /// 额外校验发行方的 OpenPGP 分离签名,不符则安装失败
+    #[serde(default)]
+    pub verify_signatures: bool,
+    /// 签名校验使用的公钥(二进制或 ASCII-armored 格式)
+    #[serde(default)]
+    pub signature_key_path: Option<String>,
+    /// 解压 zip 的并行线程数;0 表示按 CPU 核数
+    #[serde(default)]
+    pub extract_threads: usize,
+}
+
+impl Default for DownloadConfig {
+    /// 与缺省字段的 serde 默认值一致(派生的 Default 会把超时设成 0)
+    fn default() -> Self {
+        Self {
+            retry_count: default_retry_count(),
+            retry_delay_ms: default_retry_delay_ms(),
+            exponential_backoff: default_exponential_backoff(),
+            connect_timeout_sec: default_connect_timeout_sec(),
+            read_timeout_sec: default_read_timeout_sec(),
+            install_timeout_sec: 0,
+            ca_cert_path: None,
+            danger_accept_invalid_certs: false,
+            verify_signatures: false,
+            signature_key_path: None,
+            extract_threads: 0,
+        }
+    }
+}
+
+/// `--retries` 的上限,避免 CI 里误写成极大值后长时间重试
+pub const MAX_DOWNLOAD_RETRIES: u32 = 20;
+
+/// 单次命令对下载行为的覆盖(`java install --timeout / --retries / --no-verify`),
+/// 随安装调用传入下载器,不写回配置文件。
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DownloadOverrides {
+    /// 连接与读取超时(秒)
+    pub timeout_sec: Option<u64>,
+    pub retries: Option<u32>,
+    /// 跳过 SHA-256 与签名校验
+    pub no_verify: bool,
+}
+
+impl DownloadOverrides {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.timeout_sec == Some(0) {
+            return Err("--timeout must be greater than 0".to_string());
+        }
+        if self.retries.is_some_and(|n| n > MAX_DOWNLOAD_RETRIES) {
+            return Err(format!("--retries must be at most {MAX_DOWNLOAD_RETRIES}"));
+        }
+        Ok(())
+    }
+}
+
+impl DownloadConfig {
+    /// 解压线程数:`extract_threads`,为 0 时取 CPU 核数
+    pub fn extract_workers(&self) -> usize {
+        match self.extract_threads {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        }
+    }
+
+    /// 叠加本次命令覆盖值后的配置
+    pub fn with_overrides(mut self, overrides: &DownloadOverrides) -> Self {
+        if let Some(secs) = overrides.timeout_sec {
+            self.connect_timeout_sec = secs;
+            self.read_timeout_sec = secs;
+        }
+        if let Some(retries) = overrides.retries {
+            self.retry_count = retries;
+        }
+        self
+    }
 }
 
 fn default_retry_count() -> u32 {
@@ -180,21 +332,18 @@ fn default_read_timeout_sec() -> u64 {
 
 /// 默认 CC 环境配置（仅保留一个 anthropic-cc 作为初始示例）
 fn default_cc_environments() -> Vec<CcEnvironment> {
+    // 与 `cc add-preset anthropic` 同源,只保留默认配置原有的描述,服务商由 base_url 推断
+    let preset = crate::environments::cc::presets::find_preset("anthropic")
+        .expect("built-in anthropic preset");
     vec![CcEnvironment {
-        name: "anthropic-cc".to_string(),
-        api_key: "${ANTHROPIC_API_KEY}".to_string(),
-        base_url: "https://api.anthropic.com".to_string(),
-        sonnet_model: DEFAULT_SONNET_MODEL.to_string(),
-        opus_model: Some("claude-opus-4-5".to_string()),
-        haiku_model: Some("claude-haiku-4-5".to_string()),
         description: "Anthropic Claude Code environment".to_string(),
-        api_timeout_ms: None,
-        extra_env: std::collections::HashMap::new(),
+        provider: None,
+        ..preset.instantiate("anthropic-cc", None)
     }]
 }
 
 /// Java 环境配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct JavaEnvironment {
     pub name: String,
     pub java_home: String,
@@ -203,6 +352,15 @@ pub struct JavaEnvironment {
     /// 环境来源：manual（手动添加）或 scanned（扫描发现）
     #[serde(default)]
     pub source: EnvironmentSource,
+    /// 切换时导出的 JAVA_OPTS（如 -Xmx2g -Dfile.encoding=UTF-8），未设置则清除
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub java_opts: Option<String>,
+    /// 切换时与 `bin` 一起加到 PATH 前面的额外目录(按顺序,位于 `bin` 之后)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_paths: Vec<String>,
+    /// fnva 下载安装时解析出的具体版本(如 `21.0.5+11`);手动添加、扫描或本地归档安装的为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
 }
 
 /// 环境来源
@@ -216,7 +374,7 @@ pub enum EnvironmentSource {
 }
 
 /// Maven 环境配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct MavenEnvironment {
     pub name: String,
     pub maven_home: String,
@@ -236,7 +394,7 @@ pub struct MavenEnvironment {
 }
 
 /// CC (Claude Code) 环境配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CcEnvironment {
     pub name: String,
     #[serde(default)]
@@ -255,8 +413,73 @@ pub struct CcEnvironment {
     #[serde(default)]
     pub api_timeout_ms: Option<String>,
     /// Extra environment variables to export verbatim (e.g. CLAUDE_CODE_AUTO_COMPACT_WINDOW)
-    #[serde(default)]
+    #[serde(default, serialize_with = "sorted_map")]
     pub extra_env: std::collections::HashMap<String, String>,
+    /// 服务商(`cc list --provider` 过滤用);不写则取 base_url 的主域名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+}
+
+// ─── 稳定的序列化顺序 ─────────────────────────────────────────────────
+//
+// 保存结果只取决于配置内容:环境按名称、HashMap 按键排序输出,
+// 多次保存同一份配置得到逐字节相同的文件,纳入版本管理时 diff 干净。
+
+/// 有名称的环境条目(按名称排序输出)
+trait Named {
+    fn name(&self) -> &str;
+}
+
+impl Named for JavaEnvironment {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Named for MavenEnvironment {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Named for CcEnvironment {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// 按名称排序后序列化;排序稳定,同名条目保持原有先后。
+fn sorted_by_name<T, S>(items: &[T], serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Named + Serialize,
+    S: serde::Serializer,
+{
+    let mut sorted: Vec<&T> = items.iter().collect();
+    sorted.sort_by(|a, b| a.name().cmp(b.name()));
+    serializer.collect_seq(sorted)
+}
+
+fn sorted_map<S: serde::Serializer>(
+    map: &std::collections::HashMap<String, String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(map.iter().collect::<std::collections::BTreeMap<_, _>>())
+}
+
+fn sorted_nested_map<S: serde::Serializer>(
+    map: &std::collections::HashMap<String, std::collections::HashMap<String, String>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(
+        map.iter()
+            .map(|(k, inner)| {
+                (
+                    k,
+                    inner.iter().collect::<std::collections::BTreeMap<_, _>>(),
+                )
+            })
+            .collect::<std::collections::BTreeMap<_, _>>(),
+    )
 }
 
 impl Default for Config {
@@ -265,6 +488,52 @@ impl Default for Config {
     }
 }
 
+/// 读取配置文件时短暂 IO 错误的最多尝试次数与首次退避
+const READ_ATTEMPTS: u32 = 4;
+const READ_BACKOFF_MS: u64 = 25;
+
+/// 读取配置文件;杀毒软件等短暂锁住文件(共享冲突)时带退避重试,其他错误立即返回。
+fn read_config_file(path: &std::path::Path) -> Result<String, String> {
+    read_with_retry(
+        || fs::read_to_string(path),
+        READ_ATTEMPTS,
+        std::time::Duration::from_millis(READ_BACKOFF_MS),
+    )
+    .map_err(|e| format!("Failed to read config file: {e}"))
+}
+
+fn read_with_retry(
+    mut read: impl FnMut() -> std::io::Result<String>,
+    attempts: u32,
+    backoff: std::time::Duration,
+) -> std::io::Result<String> {
+    let mut delay = backoff;
+    let mut attempt = 1;
+    loop {
+        match read() {
+            Err(e) if attempt < attempts && is_transient_io_error(&e) => {
+                tracing::debug!(attempt, error = %e, "config file busy, retrying");
+                std::thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// 可能很快自行消失的 IO 错误:Windows 的共享 / 锁冲突(32 / 33)、被信号打断等。
+fn is_transient_io_error(e: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+    if cfg!(windows) && matches!(e.raw_os_error(), Some(32 | 33)) {
+        return true;
+    }
+    matches!(
+        e.kind(),
+        ErrorKind::Interrupted | ErrorKind::WouldBlock | ErrorKind::ResourceBusy
+    )
+}
+
 impl Config {
     /// 创建默认配置
     pub fn new() -> Self {
@@ -275,6 +544,7 @@ impl Config {
             mirrors: MirrorsConfig::default(),
             download: DownloadConfig::default(),
             java_versions_path: None,
+            java_registry_url: None,
             current_java_env: None,
             default_java_env: None,
             current_maven_env: None,
@@ -283,13 +553,24 @@ impl Config {
             custom_java_scan_paths: Vec::new(),
             custom_maven_scan_paths: Vec::new(),
             removed_java_names: Vec::new(),
+            path_strategy: PathStrategy::default(),
+            record_noop_switches: false,
+            history_limit: DEFAULT_HISTORY_LIMIT,
+            on_switch_command: None,
+            on_switch_commands: std::collections::HashMap::new(),
+            system_layer: None,
         }
     }
 
-    /// 从文件加载配置
+    /// 从文件加载配置;存在系统配置时叠在其上(见 [`config_layers`](super::config_layers))
     pub fn load() -> Result<Self, String> {
         crate::infrastructure::paths::migrate_layout();
         let config_path = get_config_path()?;
+        tracing::debug!(path = %config_path.display(), "loading config");
+
+        if let Some(system) = super::config_layers::load_system_config()? {
+            return Self::load_layered(&config_path, system);
+        }
 
         if !config_path.exists() {
             // 如果配置文件不存在，创建默认配置
@@ -298,15 +579,38 @@ impl Config {
             return Ok(config);
         }
 
-        let content = fs::read_to_string(&config_path)
-            .map_err(|e| format!("Failed to read config file: {e}"))?;
+        let content = read_config_file(&config_path)?;
 
         toml::from_str(&content).map_err(|e| format!("Failed to parse config file: {e}"))
     }
 
+    /// 用户层合并到系统层之上;用户配置不存在时不写默认环境,直接沿用系统层。
+    fn load_layered(config_path: &std::path::Path, system: toml::Value) -> Result<Self, String> {
+        let mut merged = if config_path.exists() {
+            let content = read_config_file(config_path)?;
+            toml::from_str(&content).map_err(|e| format!("Failed to parse config file: {e}"))?
+        } else {
+            toml::Value::Table(toml::map::Map::new())
+        };
+        super::config_layers::merge_under(&mut merged, &system);
+
+        let normalized: Config = system
+            .try_into()
+            .map_err(|e| format!("Failed to parse system config: {e}"))?;
+        let mut config: Config = merged
+            .try_into()
+            .map_err(|e| format!("Failed to parse config file: {e}"))?;
+        config.system_layer = Some(
+            toml::Value::try_from(&normalized)
+                .map_err(|e| format!("Failed to serialize config: {e}"))?,
+        );
+        Ok(config)
+    }
+
     /// 保存配置到文件
     pub fn save(&self) -> Result<(), String> {
         let config_path = get_config_path()?;
+        tracing::debug!(path = %config_path.display(), "saving config");
 
         // 确保配置目录存在
         if let Some(parent) = config_path.parent() {
@@ -314,8 +618,17 @@ impl Config {
                 .map_err(|e| format!("Failed to create config directory: {e}"))?;
         }
 
-        let toml_content =
-            toml::to_string_pretty(self).map_err(|e| format!("Failed to serialize config: {e}"))?;
+        // 叠了系统层时只写用户自己的新增与覆盖,系统层文件从不写入
+        let toml_content = match &self.system_layer {
+            Some(system) => {
+                let mut user = toml::Value::try_from(self)
+                    .map_err(|e| format!("Failed to serialize config: {e}"))?;
+                super::config_layers::strip_inherited(&mut user, system);
+                toml::to_string_pretty(&user)
+            }
+            None => toml::to_string_pretty(self),
+        }
+        .map_err(|e| format!("Failed to serialize config: {e}"))?;
 
         fs::write(&config_path, toml_content)
             .map_err(|e| format!("Failed to write config file: {e}"))?;
@@ -323,8 +636,9 @@ impl Config {
         Ok(())
     }
 
-    /// 添加 Java 环境
-    pub fn add_java_env(&mut self, env: JavaEnvironment) -> Result<(), String> {
+    /// 添加 Java 环境(`java_home` 先整理,去掉末尾分隔符等)
+    pub fn add_java_env(&mut self, mut env: JavaEnvironment) -> Result<(), String> {
+        env.java_home = crate::utils::path::clean_home(&env.java_home);
         // 检查名称是否已存在
         if self.java_environments.iter().any(|e| e.name == env.name) {
             return Err(format!("Java environment '{}' already exists", env.name));
@@ -343,11 +657,158 @@ impl Config {
         Ok(())
     }
 
+    /// 切换到某个环境后要执行的命令:该环境自己的配置优先,其次全局 `on_switch_command`
+    pub fn on_switch_command_for(&self, env_type: &str, name: &str) -> Option<&str> {
+        self.on_switch_commands
+            .get(env_type)
+            .and_then(|m| m.get(name))
+            .or(self.on_switch_command.as_ref())
+            .map(String::as_str)
+            .filter(|c| !c.trim().is_empty())
+    }
+
     /// 获取 Java 环境
     pub fn get_java_env(&self, name: &str) -> Option<&JavaEnvironment> {
         self.java_environments.iter().find(|e| e.name == name)
     }
 
+    /// 添加自定义 Java 扫描路径;已存在(忽略首尾空白)时返回 `false`。
+    pub fn add_java_scan_path(&mut self, path: &str) -> Result<bool, String> {
+        let path = path.trim();
+        if path.is_empty() {
+            return Err("Scan path must not be empty".to_string());
+        }
+        if self.custom_java_scan_paths.iter().any(|p| p.trim() == path) {
+            return Ok(false);
+        }
+        self.custom_java_scan_paths.push(path.to_string());
+        Ok(true)
+    }
+
+    /// 移除自定义 Java 扫描路径;不存在时返回 `false`。
+    pub fn remove_java_scan_path(&mut self, path: &str) -> bool {
+        let original_len = self.custom_java_scan_paths.len();
+        self.custom_java_scan_paths
+            .retain(|p| p.trim() != path.trim());
+        self.custom_java_scan_paths.len() != original_len
+    }
+
+    /// 清理 `java_home` 已失效的扫描环境,返回 `(清理的 scanned, 失效但保留的 manual)`。
+    ///
+    /// Manual 环境只报告不删除;`dry_run` 时不修改配置。被清理的环境若是当前/默认
+    /// 环境,一并清空对应设置。
+    pub fn prune_stale_java_envs(
+        &mut self,
+        is_valid: impl Fn(&str) -> bool,
+        dry_run: bool,
+    ) -> (Vec<String>, Vec<String>) {
+        let (mut scanned, mut manual) = (Vec::new(), Vec::new());
+        for env in self
+            .java_environments
+            .iter()
+            .filter(|e| !is_valid(&e.java_home))
+        {
+            match env.source {
+                EnvironmentSource::Scanned => scanned.push(env.name.clone()),
+                EnvironmentSource::Manual => manual.push(env.name.clone()),
+            }
+        }
+        if !dry_run {
+            self.java_environments
+                .retain(|e| !scanned.contains(&e.name));
+            for slot in [&mut self.current_java_env, &mut self.default_java_env] {
+                if slot.as_ref().is_some_and(|n| scanned.contains(n)) {
+                    *slot = None;
+                }
+            }
+        }
+        (scanned, manual)
+    }
+
+    /// 整理 Java 配置(`fnva config compact`):
+    /// - `java_home` / `extra_paths` 统一分隔符、去掉多余的 `/`
+    /// - 名称只差大小写且指向同一目录的条目只留一个(优先 manual),
+    ///   当前 / 默认环境指向被删条目时改指保留的那个
+    /// - `removed_java_names` 去重,并去掉已重新存在的环境名
+    pub fn compact_java(&mut self, windows: bool) -> CompactReport {
+        let mut report = CompactReport::default();
+        for env in &mut self.java_environments {
+            let home = crate::utils::path::clean_home_path(&env.java_home, windows);
+            if home != env.java_home {
+                report
+                    .normalized
+                    .push((env.name.clone(), env.java_home.clone(), home.clone()));
+                env.java_home = home;
+            }
+            for path in &mut env.extra_paths {
+                *path = crate::utils::path::clean_home_path(path, windows);
+            }
+        }
+
+        let same_home = |a: &str, b: &str| {
+            if windows {
+                a.eq_ignore_ascii_case(b)
+            } else {
+                a == b
+            }
+        };
+        let mut kept: Vec<JavaEnvironment> = Vec::new();
+        for env in std::mem::take(&mut self.java_environments) {
+            let twin = kept.iter_mut().find(|k| {
+                k.name.eq_ignore_ascii_case(&env.name) && same_home(&k.java_home, &env.java_home)
+            });
+            match twin {
+                None => kept.push(env),
+                Some(twin) => {
+                    let (keep, drop) = if twin.source == EnvironmentSource::Scanned
+                        && env.source == EnvironmentSource::Manual
+                    {
+                        let scanned = std::mem::replace(twin, env);
+                        (twin.name.clone(), scanned.name)
+                    } else {
+                        (twin.name.clone(), env.name)
+                    };
+                    report.duplicates.push((drop, keep));
+                }
+            }
+        }
+        self.java_environments = kept;
+        for (dropped, kept) in &report.duplicates {
+            for slot in [&mut self.current_java_env, &mut self.default_java_env] {
+                if slot.as_deref() == Some(dropped.as_str()) {
+                    *slot = Some(kept.clone());
+                }
+            }
+        }
+
+        let mut seen = Vec::new();
+        for name in std::mem::take(&mut self.removed_java_names) {
+            if self.get_java_env(&name).is_some() || seen.contains(&name) {
+                report.pruned_removed_names.push(name);
+            } else {
+                seen.push(name);
+            }
+        }
+        self.removed_java_names = seen;
+        report
+    }
+
+    /// 以 `from` 为模板深拷贝出名为 `to` 的 Java 环境,返回新环境供调用方覆盖字段
+    pub fn copy_java_env(&mut self, from: &str, to: &str) -> Result<&mut JavaEnvironment, String> {
+        crate::utils::validation::ValidationUtils::validate_environment_name(to)?;
+        if self.get_java_env(to).is_some() {
+            return Err(format!("Java environment '{to}' already exists"));
+        }
+        let mut env = self
+            .get_java_env(from)
+            .cloned()
+            .ok_or_else(|| format!("Java environment '{from}' does not exist"))?;
+        env.name = to.to_string();
+        env.source = EnvironmentSource::Manual;
+        self.java_environments.push(env);
+        Ok(self.java_environments.last_mut().unwrap())
+    }
+
     /// 设置当前激活的 Java 环境
     pub fn set_current_java_env(&mut self, name: String) -> Result<(), String> {
         // 验证环境是否存在
@@ -483,6 +944,26 @@ impl Config {
         self.default_cc_env = None;
     }
 
+    /// 获取 CC 环境
+    pub fn get_cc_env(&self, name: &str) -> Option<&CcEnvironment> {
+        self.cc_environments.iter().find(|e| e.name == name)
+    }
+
+    /// 以 `from` 为模板深拷贝出名为 `to` 的 CC 环境(含 extra_env),返回新环境供调用方覆盖字段
+    pub fn copy_cc_env(&mut self, from: &str, to: &str) -> Result<&mut CcEnvironment, String> {
+        crate::utils::validation::ValidationUtils::validate_environment_name(to)?;
+        if self.get_cc_env(to).is_some() {
+            return Err(format!("CC environment '{to}' already exists"));
+        }
+        let mut env = self
+            .get_cc_env(from)
+            .cloned()
+            .ok_or_else(|| format!("CC environment '{from}' does not exist"))?;
+        env.name = to.to_string();
+        self.cc_environments.push(env);
+        Ok(self.cc_environments.last_mut().unwrap())
+    }
+
     /// 获取有效的 Java 环境（优先级：当前环境 → 默认环境）
     pub fn get_effective_java_env(&self) -> Option<&JavaEnvironment> {
         // 首先尝试获取当前环境
@@ -610,6 +1091,170 @@ pub fn get_config_dir() -> Result<PathBuf, String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn download_overrides_take_precedence_over_config() {
+        let config = DownloadConfig {
+            retry_count: 5,
+            connect_timeout_sec: 30,
+            read_timeout_sec: 300,
+            ..DownloadConfig::default()
+        };
+
+        let overridden = config.clone().with_overrides(&DownloadOverrides {
+            timeout_sec: Some(7),
+            retries: Some(0),
+            no_verify: false,
+        });
+        assert_eq!(overridden.connect_timeout_sec, 7);
+        assert_eq!(overridden.read_timeout_sec, 7);
+        assert_eq!(overridden.retry_count, 0);
+        assert_eq!(overridden.install_timeout_sec, config.install_timeout_sec);
+
+        // 只给其中一个时另一个保持配置值
+        let partial = config.clone().with_overrides(&DownloadOverrides {
+            retries: Some(9),
+            ..DownloadOverrides::default()
+        });
+        assert_eq!(partial.retry_count, 9);
+        assert_eq!(partial.read_timeout_sec, 300);
+
+        assert!(DownloadOverrides::default().validate().is_ok());
+        assert!(DownloadOverrides {
+            timeout_sec: Some(0),
+            ..DownloadOverrides::default()
+        }
+        .validate()
+        .is_err());
+        assert!(DownloadOverrides {
+            retries: Some(MAX_DOWNLOAD_RETRIES + 1),
+            ..DownloadOverrides::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_read_retries_transient_errors_but_not_missing_file() {
+        use std::io::{Error, ErrorKind};
+        let no_wait = std::time::Duration::ZERO;
+
+        let mut calls = 0;
+        let content = read_with_retry(
+            || {
+                calls += 1;
+                if calls < 3 {
+                    Err(Error::from(ErrorKind::ResourceBusy))
+                } else {
+                    Ok("history_limit = 5".to_string())
+                }
+            },
+            4,
+            no_wait,
+        )
+        .unwrap();
+        assert_eq!((calls, content.as_str()), (3, "history_limit = 5"));
+
+        // 一直被锁:尝试次数用尽后报错
+        let mut calls = 0;
+        let err = read_with_retry(
+            || {
+                calls += 1;
+                Err(Error::from(ErrorKind::ResourceBusy))
+            },
+            4,
+            no_wait,
+        )
+        .unwrap_err();
+        assert_eq!((calls, err.kind()), (4, ErrorKind::ResourceBusy));
+
+        // 非短暂错误不重试
+        let mut calls = 0;
+        assert!(read_with_retry(
+            || {
+                calls += 1;
+                Err(Error::from(ErrorKind::NotFound))
+            },
+            4,
+            no_wait,
+        )
+        .is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_load_fails_fast_on_parse_error() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _guard = crate::testutil::FnvaHomeGuard::new(tmp.path());
+        let path = get_config_path().unwrap();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "history_limit = [unterminated").unwrap();
+
+        // 解析失败不属于短暂 IO 错误,直接报告而非重试后的读取错误
+        let err = Config::load().unwrap_err();
+        assert!(err.starts_with("Failed to parse config file"), "{err}");
+    }
+
+    #[test]
+    fn test_save_is_byte_identical_regardless_of_insertion_order() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _guard = crate::testutil::FnvaHomeGuard::new(tmp.path());
+        let java = |name: &str| JavaEnvironment {
+            name: name.to_string(),
+            java_home: format!("/opt/{name}"),
+            description: String::new(),
+            source: EnvironmentSource::Manual,
+            java_opts: None,
+            extra_paths: Vec::new(),
+            version: None,
+        };
+        let build = |names: &[&str], keys: &[&str]| {
+            let mut config = Config::new();
+            for name in names {
+                config.add_java_env(java(name)).unwrap();
+            }
+            let mut cc = CcEnvironment {
+                name: "glm".to_string(),
+                api_key: String::new(),
+                base_url: String::new(),
+                sonnet_model: String::new(),
+                opus_model: None,
+                haiku_model: None,
+                description: String::new(),
+                api_timeout_ms: None,
+                extra_env: std::collections::HashMap::new(),
+                provider: None,
+            };
+            for key in keys {
+                cc.extra_env.insert(key.to_string(), format!("v-{key}"));
+                config
+                    .on_switch_commands
+                    .entry("java".to_string())
+                    .or_default()
+                    .insert(key.to_string(), format!("echo {key}"));
+            }
+            config.cc_environments.push(cc);
+            config
+        };
+        let saved = |config: Config| {
+            config.save().unwrap();
+            fs::read_to_string(get_config_path().unwrap()).unwrap()
+        };
+
+        let keys: Vec<String> = (0..16).map(|i| format!("K{i:02}")).collect();
+        let keys: Vec<&str> = keys.iter().map(String::as_str).collect();
+        let reversed: Vec<&str> = keys.iter().rev().copied().collect();
+        let first = saved(build(&["jdk8", "jdk21", "jdk17"], &keys));
+        let second = saved(build(&["jdk17", "jdk8", "jdk21"], &reversed));
+        assert_eq!(first, second);
+
+        let order: Vec<usize> = ["jdk17", "jdk21", "jdk8"]
+            .iter()
+            .map(|n| first.find(&format!("name = \"{n}\"")).unwrap())
+            .collect();
+        assert!(order.windows(2).all(|w| w[0] < w[1]), "{first}");
+        assert!(first.find("K00").unwrap() < first.find("K15").unwrap());
+    }
+
     #[test]
     fn test_resolve_env_var() {
         // 设置测试环境变量
@@ -624,6 +1269,127 @@ mod tests {
         env::remove_var("TEST_VAR");
     }
 
+    #[test]
+    fn test_prune_stale_java_envs_only_removes_scanned() {
+        let mut config = Config::new();
+        for (name, home, source) in [
+            ("gone-scanned", "/gone/a", EnvironmentSource::Scanned),
+            ("gone-manual", "/gone/b", EnvironmentSource::Manual),
+            ("ok-scanned", "/ok/c", EnvironmentSource::Scanned),
+        ] {
+            config
+                .add_java_env(JavaEnvironment {
+                    name: name.to_string(),
+                    java_home: home.to_string(),
+                    description: String::new(),
+                    source,
+                    java_opts: None,
+                    extra_paths: Vec::new(),
+                    version: None,
+                })
+                .unwrap();
+        }
+        config.default_java_env = Some("gone-scanned".to_string());
+        let is_valid = |home: &str| home.starts_with("/ok");
+
+        let dry = config.prune_stale_java_envs(is_valid, true);
+        assert_eq!(
+            dry,
+            (vec!["gone-scanned".into()], vec!["gone-manual".into()])
+        );
+        assert_eq!(config.java_environments.len(), 3);
+
+        config.prune_stale_java_envs(is_valid, false);
+        let names: Vec<_> = config
+            .java_environments
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect();
+        assert_eq!(names, ["gone-manual", "ok-scanned"]);
+        assert_eq!(config.default_java_env, None);
+    }
+
+    #[test]
+    fn test_compact_java_normalizes_dedupes_and_prunes() {
+        let mut config = Config::new();
+        let env = |name: &str, home: &str, source| JavaEnvironment {
+            name: name.to_string(),
+            java_home: home.to_string(),
+            description: String::new(),
+            source,
+            java_opts: None,
+            extra_paths: Vec::new(),
+            version: None,
+        };
+        config.java_environments = vec![
+            env("jdk17", "/opt//jdk17/", EnvironmentSource::Scanned),
+            env("JDK17", "/opt/jdk17", EnvironmentSource::Manual),
+            env("jdk21", "/opt/jdk21", EnvironmentSource::Manual),
+            // 同目录不同名是别名,不算重复
+            env("lts", "/opt/jdk21", EnvironmentSource::Manual),
+            // 同名不同目录也不算
+            env("JDK21", "/other/jdk21", EnvironmentSource::Scanned),
+        ];
+        config.current_java_env = Some("jdk17".to_string());
+        config.removed_java_names = vec!["jdk8".into(), "jdk21".into(), "jdk8".into()];
+
+        let report = config.compact_java(false);
+        assert_eq!(
+            report.normalized,
+            [(
+                "jdk17".to_string(),
+                "/opt//jdk17/".to_string(),
+                "/opt/jdk17".to_string()
+            )]
+        );
+        assert_eq!(
+            report.duplicates,
+            [("jdk17".to_string(), "JDK17".to_string())]
+        );
+        assert_eq!(report.pruned_removed_names, ["jdk21", "jdk8"]);
+
+        let envs: Vec<_> = config
+            .java_environments
+            .iter()
+            .map(|e| (e.name.as_str(), e.java_home.as_str(), e.source.clone()))
+            .collect();
+        assert_eq!(
+            envs,
+            [
+                ("JDK17", "/opt/jdk17", EnvironmentSource::Manual),
+                ("jdk21", "/opt/jdk21", EnvironmentSource::Manual),
+                ("lts", "/opt/jdk21", EnvironmentSource::Manual),
+                ("JDK21", "/other/jdk21", EnvironmentSource::Scanned),
+            ]
+        );
+        assert_eq!(config.current_java_env.as_deref(), Some("JDK17"));
+        assert_eq!(config.removed_java_names, ["jdk8"]);
+
+        // 再跑一次没有可做的
+        assert!(config.compact_java(false).is_empty());
+    }
+
+    #[test]
+    fn test_compact_java_windows_paths_compare_case_insensitively() {
+        let mut config = Config::new();
+        for (name, home) in [("jdk17", r"C:/Java\jdk17\"), ("Jdk17", r"c:\java\JDK17")] {
+            config.java_environments.push(JavaEnvironment {
+                name: name.to_string(),
+                java_home: home.to_string(),
+                description: String::new(),
+                source: EnvironmentSource::Manual,
+                java_opts: None,
+                extra_paths: vec!["D:/tools//bin".to_string()],
+                version: None,
+            });
+        }
+        let report = config.compact_java(true);
+        assert_eq!(report.duplicates.len(), 1);
+        assert_eq!(config.java_environments.len(), 1);
+        assert_eq!(config.java_environments[0].java_home, r"C:\Java\jdk17");
+        assert_eq!(config.java_environments[0].extra_paths, [r"D:\tools\bin"]);
+    }
+
     #[test]
     fn test_config_add_java_env() {
         let mut config = Config::new();
@@ -632,12 +1398,63 @@ mod tests {
             java_home: "/usr/lib/jvm/java-17".to_string(),
             description: "Test JDK".to_string(),
             source: EnvironmentSource::Manual,
+            java_opts: None,
+            extra_paths: Vec::new(),
+            version: None,
         };
 
         assert!(config.add_java_env(env.clone()).is_ok());
         assert!(config.add_java_env(env).is_err()); // 重复添加应该失败
     }
 
+    #[test]
+    fn test_add_java_env_strips_trailing_separator() {
+        let mut config = Config::new();
+        let (input, stored) = if cfg!(windows) {
+            ("C:/Java//jdk17\\", "C:\\Java\\jdk17")
+        } else {
+            ("/opt//jdk17/", "/opt/jdk17")
+        };
+        config
+            .add_java_env(JavaEnvironment {
+                name: "17".to_string(),
+                java_home: input.to_string(),
+                description: String::new(),
+                source: EnvironmentSource::Manual,
+                java_opts: None,
+                extra_paths: Vec::new(),
+                version: None,
+            })
+            .unwrap();
+        assert_eq!(config.get_java_env("17").unwrap().java_home, stored);
+    }
+
+    #[test]
+    fn test_copy_cc_env_clones_and_overrides_model() {
+        let mut config = Config::new();
+        config.cc_environments[0]
+            .extra_env
+            .insert("FOO".to_string(), "bar".to_string());
+
+        let copy = config.copy_cc_env("anthropic-cc", "anthropic-dev").unwrap();
+        copy.sonnet_model = "claude-sonnet-dev".to_string();
+
+        let src = config.get_cc_env("anthropic-cc").unwrap();
+        let dst = config.get_cc_env("anthropic-dev").unwrap();
+        assert_eq!(dst.base_url, src.base_url);
+        assert_eq!(dst.api_key, src.api_key);
+        assert_eq!(dst.extra_env.get("FOO").map(String::as_str), Some("bar"));
+        assert_eq!(dst.sonnet_model, "claude-sonnet-dev");
+        assert_ne!(src.sonnet_model, "claude-sonnet-dev");
+
+        // 目标已存在 / 源不存在 都应拒绝
+        assert!(config.copy_cc_env("anthropic-cc", "anthropic-dev").is_err());
+        assert!(config.copy_cc_env("ghost", "other").is_err());
+        for bad in ["", "  ", "a/b", ".."] {
+            assert!(config.copy_cc_env("anthropic-cc", bad).is_err(), "{bad:?}");
+        }
+    }
+
     #[test]
     fn test_config_add_maven_env() {
         let mut config = Config::new();
@@ -714,4 +1531,46 @@ mod tests {
         config.clear_default_maven_env();
         assert_eq!(config.default_maven_env, None);
     }
+
+    #[test]
+    fn test_config_full_toml_roundtrip() {
+        let original: Config = toml::from_str(
+            "default_java_env = \"jdk17\"\n\
+             default_cc_env = \"glm\"\n\
+             path_strategy = \"keep\"\n\
+             history_limit = 50\n\
+             on_switch_command = \"echo switched\"\n\
+             [download]\nretry_count = 5\n\
+             [[java_environments]]\nname = \"jdk17\"\njava_home = \"/opt/jdk17\"\n\
+             java_opts = \"-Xmx2g\"\nextra_paths = [\"/opt/tools\"]\n\
+             [[cc_environments]]\nname = \"glm\"\nbase_url = \"https://open.bigmodel.cn/api/anthropic\"\n\
+             provider = \"bigmodel\"\n\
+             [cc_environments.extra_env]\nCLAUDE_CODE_AUTO_COMPACT_WINDOW = \"100000\"\n\
+             [on_switch_commands.java]\njdk17 = \"echo 17\"\n",
+        )
+        .unwrap();
+        let first = toml::to_string_pretty(&original).unwrap();
+        let parsed: Config = toml::from_str(&first).unwrap();
+        assert_eq!(toml::to_string_pretty(&parsed).unwrap(), first);
+
+        assert_eq!(parsed.java_environments, original.java_environments);
+        assert_eq!(parsed.cc_environments, original.cc_environments);
+        assert_eq!(parsed.path_strategy, PathStrategy::Keep);
+        assert_eq!(parsed.download.retry_count, 5);
+        assert_eq!(parsed.history_limit, 50);
+        assert_eq!(parsed.on_switch_commands["java"]["jdk17"], "echo 17");
+    }
+
+    #[test]
+    fn test_java_scan_path_add_remove() {
+        let mut config = Config::new();
+        assert!(config.add_java_scan_path(" /mnt/jdks ").unwrap());
+        assert!(!config.add_java_scan_path("/mnt/jdks").unwrap());
+        assert!(config.add_java_scan_path("  ").is_err());
+        assert_eq!(config.custom_java_scan_paths, ["/mnt/jdks"]);
+
+        assert!(!config.remove_java_scan_path("/opt/other"));
+        assert!(config.remove_java_scan_path("/mnt/jdks"));
+        assert!(config.custom_java_scan_paths.is_empty());
+    }
 }
@@ -0,0 +1,333 @@
+//! `fnva config get/set/list`:按点分键读写标量设置。
+//!
+//! 只开放标量字段(字符串、数字、布尔、枚举);环境列表、镜像等结构化内容
+//! 仍由各自的子命令管理。可选字符串传空串即清除。写回用 `toml_edit` 只改动
+//! 这一项,文件里的注释、顺序和其他内容保持原样。
+
+use super::config::{Config, PathStrategy};
+
+/// 可读写的键,按 `config list` 的输出顺序排列。
+pub const SETTABLE_KEYS: &[&str] = &[
+    "default_java_env",
+    "default_maven_env",
+    "default_cc_env",
+    "java_versions_path",
+    "java_registry_url",
+    "path_strategy",
+    "record_noop_switches",
+    "history_limit",
+    "on_switch_command",
+    "download.retry_count",
+    "download.retry_delay_ms",
+    "download.exponential_backoff",
+    "download.connect_timeout_sec",
+    "download.read_timeout_sec",
+    "download.install_timeout_sec",
+    "download.ca_cert_path",
+    "download.danger_accept_invalid_certs",
+    "download.verify_signatures",
+    "download.signature_key_path",
+    "download.extract_threads",
+];
+
+fn unknown_key() -> String {
+    format!("Unknown key; settable keys: {}", SETTABLE_KEYS.join(", "))
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => Err(format!("Expected a boolean (true/false), got '{value}'")),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Expected a non-negative integer, got '{value}'"))
+}
+
+fn optional(value: &str) -> Option<String> {
+    Some(value.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+impl Config {
+    /// 读取设置的当前值;可选项未设置时为 `None`。
+    pub fn get_setting(&self, key: &str) -> Result<Option<String>, String> {
+        let d = &self.download;
+        let value = match key {
+            "default_java_env" => self.default_java_env.clone(),
+            "default_maven_env" => self.default_maven_env.clone(),
+            "default_cc_env" => self.default_cc_env.clone(),
+            "java_versions_path" => self.java_versions_path.clone(),
+            "java_registry_url" => self.java_registry_url.clone(),
+            "path_strategy" => Some(
+                match self.path_strategy {
+                    PathStrategy::FnvaOnly => "fnva_only",
+                    PathStrategy::Aggressive => "aggressive",
+                    PathStrategy::Keep => "keep",
+                }
+                .to_string(),
+            ),
+            "record_noop_switches" => Some(self.record_noop_switches.to_string()),
+            "history_limit" => Some(self.history_limit.to_string()),
+            "on_switch_command" => self.on_switch_command.clone(),
+            "download.retry_count" => Some(d.retry_count.to_string()),
+            "download.retry_delay_ms" => Some(d.retry_delay_ms.to_string()),
+            "download.exponential_backoff" => Some(d.exponential_backoff.to_string()),
+            "download.connect_timeout_sec" => Some(d.connect_timeout_sec.to_string()),
+            "download.read_timeout_sec" => Some(d.read_timeout_sec.to_string()),
+            "download.install_timeout_sec" => Some(d.install_timeout_sec.to_string()),
+            "download.ca_cert_path" => d.ca_cert_path.clone(),
+            "download.danger_accept_invalid_certs" => {
+                Some(d.danger_accept_invalid_certs.to_string())
+            }
+            "download.verify_signatures" => Some(d.verify_signatures.to_string()),
+            "download.signature_key_path" => d.signature_key_path.clone(),
+            "download.extract_threads" => Some(d.extract_threads.to_string()),
+            _ => return Err(unknown_key()),
+        };
+        Ok(value)
+    }
+
+    /// 按字段类型解析并写入设置;值不合法时不做任何修改。
+    pub fn set_setting(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let d = &mut self.download;
+        match key {
+            "default_java_env" => {
+                if let Some(name) = optional(value) {
+                    if self.get_java_env(&name).is_none() {
+                        return Err(format!("Java environment '{name}' does not exist"));
+                    }
+                }
+                self.default_java_env = optional(value);
+            }
+            "default_maven_env" => {
+                if let Some(name) = optional(value) {
+                    if self.get_maven_env(&name).is_none() {
+                        return Err(format!("Maven environment '{name}' does not exist"));
+                    }
+                }
+                self.default_maven_env = optional(value);
+            }
+            "default_cc_env" => {
+                if let Some(name) = optional(value) {
+                    if self.get_cc_env(&name).is_none() {
+                        return Err(format!("CC environment '{name}' does not exist"));
+                    }
+                }
+                self.default_cc_env = optional(value);
+            }
+            "java_versions_path" => self.java_versions_path = optional(value),
+            "java_registry_url" => {
+                if let Some(url) = optional(value) {
+                    crate::utils::validation::ValidationUtils::validate_url(&url)?;
+                }
+                self.java_registry_url = optional(value);
+            }
+            "path_strategy" => {
+                self.path_strategy = match value.trim() {
+                    "fnva_only" => PathStrategy::FnvaOnly,
+                    "aggressive" => PathStrategy::Aggressive,
+                    "keep" => PathStrategy::Keep,
+                    _ => {
+                        return Err(format!(
+                            "Expected 'fnva_only', 'aggressive' or 'keep', got '{value}'"
+                        ))
+                    }
+                }
+            }
+            "record_noop_switches" => self.record_noop_switches = parse_bool(value)?,
+            "history_limit" => {
+                let limit: usize = parse_number(value)?;
+                if limit == 0 {
+                    return Err("history_limit must be at least 1".to_string());
+                }
+                self.history_limit = limit;
+            }
+            "on_switch_command" => self.on_switch_command = optional(value),
+            "download.retry_count" => d.retry_count = parse_number(value)?,
+            "download.retry_delay_ms" => d.retry_delay_ms = parse_number(value)?,
+            "download.exponential_backoff" => d.exponential_backoff = parse_bool(value)?,
+            "download.connect_timeout_sec" => d.connect_timeout_sec = parse_number(value)?,
+            "download.read_timeout_sec" => d.read_timeout_sec = parse_number(value)?,
+            "download.install_timeout_sec" => d.install_timeout_sec = parse_number(value)?,
+            "download.ca_cert_path" => d.ca_cert_path = optional(value),
+            "download.danger_accept_invalid_certs" => {
+                d.danger_accept_invalid_certs = parse_bool(value)?
+            }
+            "download.verify_signatures" => d.verify_signatures = parse_bool(value)?,
+            "download.signature_key_path" => d.signature_key_path = optional(value),
+            "download.extract_threads" => d.extract_threads = parse_number(value)?,
+            _ => return Err(unknown_key()),
+        }
+        Ok(())
+    }
+
+    /// 把 `key` 的当前值写回 `config.toml`:只改这一项(未设置时删除),保留注释与
+    /// 其余内容;文件还不存在时整体保存。
+    pub fn save_setting(&self, key: &str) -> Result<(), String> {
+        let path = super::config::get_config_path()?;
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return self.save();
+        };
+        let mut doc: toml_edit::DocumentMut = content
+            .parse()
+            .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
+        set_in_document(&mut doc, key, self.get_typed_setting(key)?);
+        std::fs::write(&path, doc.to_string())
+            .map_err(|e| format!("Failed to write config file: {e}"))
+    }
+
+    /// 按序列化后的类型取值(数字、布尔保持原类型),供写回 TOML。
+    fn get_typed_setting(&self, key: &str) -> Result<Option<toml::Value>, String> {
+        if !SETTABLE_KEYS.contains(&key) {
+            return Err(unknown_key());
+        }
+        let value =
+            toml::Value::try_from(self).map_err(|e| format!("Failed to serialize config: {e}"))?;
+        Ok(key
+            .split('.')
+            .try_fold(&value, |v, part| v.get(part))
+            .cloned())
+    }
+}
+
+/// 在文档中设置点分键 `key`;`None` 时删除。缺少的父表按需创建。
+fn set_in_document(doc: &mut toml_edit::DocumentMut, key: &str, value: Option<toml::Value>) {
+    let (parents, leaf) = match key.rsplit_once('.') {
+        Some((parents, leaf)) => (Some(parents), leaf),
+        None => (None, key),
+    };
+    let mut table = doc.as_table_mut();
+    for part in parents.into_iter().flat_map(|p| p.split('.')) {
+        if value.is_none() && !table.contains_table(part) {
+            return;
+        }
+        let entry = table.entry(part).or_insert_with(toml_edit::table);
+        let Some(child) = entry.as_table_mut() else {
+            return;
+        };
+        table = child;
+    }
+    let edit_value = |value: toml::Value| -> Option<toml_edit::Value> {
+        Some(match value {
+            toml::Value::String(s) => s.into(),
+            toml::Value::Integer(i) => i.into(),
+            toml::Value::Float(f) => f.into(),
+            toml::Value::Boolean(b) => b.into(),
+            _ => return None,
+        })
+    };
+    match value.and_then(edit_value) {
+        Some(value) => {
+            // 保留原有的行尾注释等装饰
+            match table.get_mut(leaf).and_then(toml_edit::Item::as_value_mut) {
+                Some(existing) => {
+                    let decor = existing.decor().clone();
+                    *existing = value;
+                    *existing.decor_mut() = decor;
+                }
+                None => {
+                    table.insert(leaf, toml_edit::value(value));
+                }
+            }
+        }
+        None => {
+            table.remove(leaf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_listed_key_can_be_read() {
+        let config = Config::new();
+        for key in SETTABLE_KEYS {
+            assert!(config.get_setting(key).is_ok(), "{key}");
+        }
+        assert_eq!(
+            config
+                .get_setting("download.retry_count")
+                .unwrap()
+                .as_deref(),
+            Some("3")
+        );
+        assert_eq!(config.get_setting("java_registry_url").unwrap(), None);
+    }
+
+    #[test]
+    fn set_parses_by_type_and_rejects_bad_keys_and_values() {
+        let mut config = Config::new();
+        config.set_setting("download.retry_count", "7").unwrap();
+        config
+            .set_setting("download.verify_signatures", "yes")
+            .unwrap();
+        config.set_setting("path_strategy", "aggressive").unwrap();
+        config.set_setting("history_limit", "20").unwrap();
+        assert_eq!(config.download.retry_count, 7);
+        assert!(config.download.verify_signatures);
+        assert_eq!(config.path_strategy, PathStrategy::Aggressive);
+        assert_eq!(config.history_limit, 20);
+
+        assert!(config.set_setting("download.retry_count", "-1").is_err());
+        assert!(config.set_setting("record_noop_switches", "maybe").is_err());
+        assert!(config.set_setting("path_strategy", "nuke").is_err());
+        config.set_setting("path_strategy", "keep").unwrap();
+        assert_eq!(config.path_strategy, PathStrategy::Keep);
+        assert!(config.set_setting("history_limit", "0").is_err());
+        assert!(config.set_setting("default_java_env", "ghost").is_err());
+        assert!(config.set_setting("default_maven_env", "ghost").is_err());
+        assert!(config.set_setting("default_cc_env", "ghost").is_err());
+        config.set_setting("default_cc_env", "").unwrap();
+        let err = config.set_setting("download.nope", "1").unwrap_err();
+        assert!(err.contains("download.retry_count"));
+        assert!(config.set_setting("java_environments", "x").is_err());
+        // 失败的写入不改动原值
+        assert_eq!(config.download.retry_count, 7);
+
+        config
+            .set_setting("download.ca_cert_path", "/etc/ca.pem")
+            .unwrap();
+        config.set_setting("download.ca_cert_path", "").unwrap();
+        assert_eq!(config.download.ca_cert_path, None);
+    }
+
+    #[test]
+    fn save_setting_edits_only_that_key_and_keeps_comments() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = crate::testutil::FnvaHomeGuard::new(tmp.path());
+        let path = super::super::config::get_config_path().unwrap();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            &path,
+            "# 我的配置\nhistory_limit = 50 # 保留五十条\n\n[download]\n# 重试\nretry_count = 3\n",
+        )
+        .unwrap();
+
+        let mut config = Config::load().unwrap();
+        config.set_setting("download.retry_count", "7").unwrap();
+        config.save_setting("download.retry_count").unwrap();
+        config.set_setting("on_switch_command", "true").unwrap();
+        config.save_setting("on_switch_command").unwrap();
+        config.set_setting("history_limit", "20").unwrap();
+        config.save_setting("history_limit").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "# 我的配置\nhistory_limit = 20 # 保留五十条\non_switch_command = \"true\"\n\n\
+             [download]\n# 重试\nretry_count = 7\n"
+        );
+
+        config.set_setting("on_switch_command", "").unwrap();
+        config.save_setting("on_switch_command").unwrap();
+        let reloaded = Config::load().unwrap();
+        assert_eq!(reloaded.on_switch_command, None);
+        assert_eq!(reloaded.download.retry_count, 7);
+        assert_eq!(reloaded.history_limit, 20);
+    }
+}
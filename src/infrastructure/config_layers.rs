@@ -0,0 +1,187 @@
+//! 团队共享的只读系统配置,叠在用户配置之下。
+//!
+//! 系统层(`FNVA_SYSTEM_CONFIG`,默认 `/etc/fnva/config.toml`,Windows 为
+//! `%ProgramData%\fnva\config.toml`)只读不写。加载时按键合并:用户写了的键
+//! 以用户为准,没写的取系统层;带 `name` 的表数组(各类环境、镜像)按名称
+//! 合并,同名条目用户优先;扫描路径等其余列表取并集。合并规则见
+//! [`config_merge`](super::config_merge)。保存时去掉与系统层相同的部分,只把
+//! 用户自己的新增与覆盖写回 `~/.fnva/config.toml`。
+//!
+//! 系统层的环境与扫描路径可以在用户层覆盖或补充,但删不掉:删除后下次加载会重新出现。
+
+use super::config_merge::{merge_values, MergeStrategy};
+use std::path::PathBuf;
+use toml::Value;
+
+/// 系统配置路径的环境变量(优先于默认位置)
+pub const SYSTEM_CONFIG_ENV_VAR: &str = "FNVA_SYSTEM_CONFIG";
+
+/// 系统配置的位置;未设置环境变量时取平台默认路径。
+pub fn system_config_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(SYSTEM_CONFIG_ENV_VAR).filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    if cfg!(target_os = "windows") {
+        std::env::var_os("ProgramData").map(|d| PathBuf::from(d).join("fnva").join("config.toml"))
+    } else {
+        Some(PathBuf::from("/etc/fnva/config.toml"))
+    }
+}
+
+/// 读取系统配置;文件不存在返回 `None`,存在但无法解析则报错。
+pub fn load_system_config() -> Result<Option<Value>, String> {
+    let Some(path) = system_config_path().filter(|p| p.is_file()) else {
+        return Ok(None);
+    };
+    tracing::debug!(path = %path.display(), "loading system config");
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read system config {}: {e}", path.display()))?;
+    toml::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse system config {}: {e}", path.display()))
+}
+
+/// 把系统层合并到用户层之下:用户没写的键取系统层,表递归合并,带 `name` 的
+/// 表数组追加用户层没有的同名条目,其余数组取并集。与导入时的
+/// [`KeepExisting`](MergeStrategy::KeepExisting) 合并是同一套规则。
+pub fn merge_under(user: &mut Value, system: &Value) {
+    // KeepExisting 从不报冲突
+    let _ = merge_values(user, system, MergeStrategy::KeepExisting);
+}
+
+/// 去掉用户层中与系统层(已按 `Config` 补全默认值)相同的部分,留下用户自己的内容。
+pub fn strip_inherited(user: &mut Value, system: &Value) {
+    let (Value::Table(user), Value::Table(system)) = (user, system) else {
+        return;
+    };
+    user.retain(|key, value| {
+        let Some(base) = system.get(key) else {
+            return true;
+        };
+        if value == base {
+            return false;
+        }
+        match (value, base) {
+            (value @ Value::Table(_), Value::Table(_)) => strip_inherited(value, base),
+            (Value::Array(items), Value::Array(base_items)) => {
+                items.retain(|item| !base_items.contains(item));
+            }
+            _ => {}
+        }
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(text: &str) -> Value {
+        toml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn merge_prefers_user_entries_by_name_and_fills_missing_keys() {
+        let mut user = value(
+            r#"
+default_cc_env = "mine"
+[[cc_environments]]
+name = "corp"
+sonnet_model = "user-model"
+[[cc_environments]]
+name = "mine"
+[download]
+retry_count = 5
+"#,
+        );
+        let system = value(
+            r#"
+default_cc_env = "corp"
+java_registry_url = "https://corp.example/java_versions.toml"
+[[cc_environments]]
+name = "corp"
+sonnet_model = "corp-model"
+[[cc_environments]]
+name = "corp-fast"
+[download]
+retry_count = 1
+ca_cert_path = "/etc/ssl/corp.pem"
+"#,
+        );
+        merge_under(&mut user, &system);
+
+        assert_eq!(user["default_cc_env"].as_str(), Some("mine"));
+        assert_eq!(
+            user["java_registry_url"].as_str(),
+            Some("https://corp.example/java_versions.toml")
+        );
+        let names: Vec<_> = user["cc_environments"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["corp", "mine", "corp-fast"]);
+        assert_eq!(
+            user["cc_environments"][0]["sonnet_model"].as_str(),
+            Some("user-model")
+        );
+        assert_eq!(user["download"]["retry_count"].as_integer(), Some(5));
+        assert_eq!(
+            user["download"]["ca_cert_path"].as_str(),
+            Some("/etc/ssl/corp.pem")
+        );
+    }
+
+    #[test]
+    fn strip_keeps_only_user_additions_and_overrides() {
+        let system = value(
+            r#"
+history_limit = 100
+[[cc_environments]]
+name = "corp"
+sonnet_model = "corp-model"
+[download]
+retry_count = 1
+"#,
+        );
+        let mut user = value(
+            r#"
+history_limit = 100
+[[cc_environments]]
+name = "corp"
+sonnet_model = "corp-model"
+[[cc_environments]]
+name = "mine"
+[download]
+retry_count = 1
+read_timeout_sec = 60
+"#,
+        );
+        strip_inherited(&mut user, &system);
+
+        assert!(user.get("history_limit").is_none());
+        let envs = user["cc_environments"].as_array().unwrap();
+        assert_eq!(envs.len(), 1);
+        assert_eq!(envs[0]["name"].as_str(), Some("mine"));
+        assert!(user["download"].get("retry_count").is_none());
+        assert_eq!(user["download"]["read_timeout_sec"].as_integer(), Some(60));
+    }
+
+    #[test]
+    fn plain_lists_are_unioned_on_load_and_only_user_items_are_saved() {
+        let system = value("custom_java_scan_paths = [\"/opt/corp\"]\n");
+        let mut user = value("custom_java_scan_paths = [\"/home/me/jdks\"]\n");
+        merge_under(&mut user, &system);
+        assert_eq!(
+            user["custom_java_scan_paths"],
+            value("v = [\"/home/me/jdks\", \"/opt/corp\"]")["v"]
+        );
+
+        strip_inherited(&mut user, &system);
+        assert_eq!(
+            user["custom_java_scan_paths"],
+            value("v = [\"/home/me/jdks\"]")["v"]
+        );
+    }
+}
@@ -0,0 +1,330 @@
+//! 两份配置在 TOML 层的结构化合并(导入、系统配置叠加共用)。
+//!
+//! 只有对方文件里实际写了的键参与合并:反序列化后的 `Config` 已分不清"未写"与
+//! "写了默认值",所以合并在 [`toml::Value`] 上进行。表递归合并,带 `name` 的表数组
+//! (各类环境、镜像)按名称合并,扫描路径等其余数组取并集,标量按策略取舍。
+
+use super::config::Config;
+use toml::Value;
+
+/// 同名条目或取值不同的标量如何处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// 以传入的配置为准
+    OverwriteByName,
+    /// 保留已有的值,只补充缺少的条目(系统配置叠加即按此合并)
+    KeepExisting,
+    /// 出现任何冲突即报错,且不做任何修改
+    ErrorOnConflict,
+}
+
+/// 单次合并的上下文:策略与收集到的冲突
+struct Merger {
+    strategy: MergeStrategy,
+    conflicts: Vec<String>,
+}
+
+impl Merger {
+    /// 两边都有值且不同时决定是否采用对方的值
+    fn take_theirs(&mut self, ours: &Value, theirs: &Value, what: impl FnOnce() -> String) -> bool {
+        if covers(ours, theirs) {
+            return false;
+        }
+        match self.strategy {
+            MergeStrategy::OverwriteByName => true,
+            MergeStrategy::KeepExisting => false,
+            MergeStrategy::ErrorOnConflict => {
+                self.conflicts.push(what());
+                false
+            }
+        }
+    }
+
+    /// 合并同一键下的两个值;`path` 为点分键,用于冲突描述
+    fn value(&mut self, path: &str, ours: &mut Value, theirs: &Value) {
+        match (ours, theirs) {
+            (Value::Table(ours), Value::Table(theirs)) => {
+                for (key, theirs) in theirs {
+                    let path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{path}.{key}")
+                    };
+                    match ours.get_mut(key) {
+                        Some(ours) => self.value(&path, ours, theirs),
+                        None => {
+                            ours.insert(key.clone(), theirs.clone());
+                        }
+                    }
+                }
+            }
+            (Value::Array(ours), Value::Array(theirs)) if is_named(ours) && is_named(theirs) => {
+                self.named(path, ours, theirs)
+            }
+            (Value::Array(ours), Value::Array(theirs)) => {
+                for item in theirs {
+                    if !ours.contains(item) {
+                        ours.push(item.clone());
+                    }
+                }
+            }
+            (ours, theirs) => {
+                if self.take_theirs(ours, theirs, || format!("setting '{path}'")) {
+                    *ours = theirs.clone();
+                }
+            }
+        }
+    }
+
+    /// 按名称合并:同名条目按策略整条处理(原位替换),新条目按对方顺序追加
+    fn named(&mut self, path: &str, ours: &mut Vec<Value>, theirs: &[Value]) {
+        for item in theirs {
+            match ours.iter().position(|o| name_of(o) == name_of(item)) {
+                Some(i) => {
+                    let what = || format!("{path} '{}'", name_of(item).unwrap_or_default());
+                    if self.take_theirs(&ours[i], item, what) {
+                        ours[i] = item.clone();
+                    }
+                }
+                None => ours.push(item.clone()),
+            }
+        }
+    }
+}
+
+/// `theirs` 写了的每个键在 `ours` 里都取相同的值(未写的字段不算差异)
+fn covers(ours: &Value, theirs: &Value) -> bool {
+    match (ours, theirs) {
+        (Value::Table(ours), Value::Table(theirs)) => theirs
+            .iter()
+            .all(|(key, theirs)| ours.get(key).is_some_and(|ours| covers(ours, theirs))),
+        _ => ours == theirs,
+    }
+}
+
+fn name_of(value: &Value) -> Option<&str> {
+    value.get("name")?.as_str()
+}
+
+fn is_named(items: &[Value]) -> bool {
+    items.iter().all(|i| name_of(i).is_some())
+}
+
+/// 把 `theirs` 合并进 `ours`。
+///
+/// `ErrorOnConflict` 下列出全部冲突后返回错误,`ours` 保持不变;其余策略不会失败。
+pub fn merge_values(
+    ours: &mut Value,
+    theirs: &Value,
+    strategy: MergeStrategy,
+) -> Result<(), String> {
+    let mut merger = Merger {
+        strategy,
+        conflicts: Vec::new(),
+    };
+    let mut merged = ours.clone();
+    merger.value("", &mut merged, theirs);
+    if !merger.conflicts.is_empty() {
+        return Err(format!(
+            "Config merge conflicts: {}",
+            merger.conflicts.join(", ")
+        ));
+    }
+    *ours = merged;
+    Ok(())
+}
+
+impl Config {
+    /// 把另一份配置文件的内容(`other`,未补全默认值)合并进当前配置。
+    ///
+    /// 失败时当前配置保持不变。
+    pub fn merge(&mut self, other: &Value, strategy: MergeStrategy) -> Result<(), String> {
+        let mut merged =
+            Value::try_from(&*self).map_err(|e| format!("Failed to serialize config: {e}"))?;
+        merge_values(&mut merged, other, strategy)?;
+        let mut config: Config = merged
+            .try_into()
+            .map_err(|e| format!("Invalid merged config: {e}"))?;
+        config.system_layer = self.system_layer.take();
+        *self = config;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::config::DEFAULT_HISTORY_LIMIT;
+
+    fn value(text: &str) -> Value {
+        toml::from_str(text).unwrap()
+    }
+
+    fn config_with(envs: &[(&str, &str)]) -> Config {
+        let mut text = String::new();
+        for (name, home) in envs {
+            text.push_str(&format!(
+                "[[java_environments]]\nname = \"{name}\"\njava_home = \"{home}\"\n"
+            ));
+        }
+        let mut config = Config::new();
+        config.java_environments = toml::from_str::<Config>(&text).unwrap().java_environments;
+        config
+    }
+
+    fn homes(config: &Config) -> Vec<(&str, &str)> {
+        config
+            .java_environments
+            .iter()
+            .map(|e| (e.name.as_str(), e.java_home.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn disjoint_sets_are_unioned_under_every_strategy() {
+        for strategy in [
+            MergeStrategy::OverwriteByName,
+            MergeStrategy::KeepExisting,
+            MergeStrategy::ErrorOnConflict,
+        ] {
+            let mut ours = config_with(&[("jdk17", "/opt/jdk17")]);
+            ours.custom_java_scan_paths = vec!["/a".into(), "/b".into()];
+            let theirs = value(
+                r#"
+default_maven_env = "mvn3"
+custom_java_scan_paths = ["/b", "/c"]
+[[java_environments]]
+name = "jdk21"
+java_home = "/opt/jdk21"
+[[cc_environments]]
+name = "glm"
+api_key = "${GLM_API_KEY}"
+base_url = "https://open.bigmodel.cn/api/anthropic"
+sonnet_model = "glm-4.6"
+"#,
+            );
+
+            ours.merge(&theirs, strategy).unwrap();
+            assert_eq!(
+                homes(&ours),
+                [("jdk17", "/opt/jdk17"), ("jdk21", "/opt/jdk21")],
+                "{strategy:?}"
+            );
+            assert_eq!(ours.custom_java_scan_paths, ["/a", "/b", "/c"]);
+            let cc: Vec<_> = ours
+                .cc_environments
+                .iter()
+                .map(|e| e.name.as_str())
+                .collect();
+            assert_eq!(cc, ["anthropic-cc", "glm"]);
+            assert_eq!(ours.default_maven_env.as_deref(), Some("mvn3"));
+        }
+    }
+
+    #[test]
+    fn overwrite_by_name_replaces_in_place_and_takes_only_their_written_keys() {
+        let mut ours = config_with(&[("jdk17", "/old/17"), ("jdk8", "/opt/8")]);
+        ours.default_java_env = Some("jdk8".to_string());
+        ours.history_limit = 20;
+        let theirs = value(
+            r#"
+default_java_env = "jdk17"
+[[java_environments]]
+name = "jdk17"
+java_home = "/new/17"
+[download]
+retry_count = 9
+"#,
+        );
+
+        ours.merge(&theirs, MergeStrategy::OverwriteByName).unwrap();
+        assert_eq!(homes(&ours), [("jdk17", "/new/17"), ("jdk8", "/opt/8")]);
+        assert_eq!(ours.default_java_env.as_deref(), Some("jdk17"));
+        assert_eq!(ours.download.retry_count, 9);
+        // 对方没写的标量不会被默认值覆盖
+        assert_eq!(ours.history_limit, 20);
+        assert_ne!(ours.history_limit, DEFAULT_HISTORY_LIMIT);
+    }
+
+    #[test]
+    fn keep_existing_only_fills_gaps() {
+        let mut ours = config_with(&[("jdk17", "/old/17")]);
+        ours.history_limit = 20;
+        let theirs = value(
+            r#"
+default_java_env = "jdk21"
+history_limit = 50
+[[java_environments]]
+name = "jdk17"
+java_home = "/new/17"
+[[java_environments]]
+name = "jdk21"
+java_home = "/opt/21"
+[on_switch_commands.java]
+jdk21 = "echo 21"
+"#,
+        );
+
+        ours.merge(&theirs, MergeStrategy::KeepExisting).unwrap();
+        assert_eq!(homes(&ours), [("jdk17", "/old/17"), ("jdk21", "/opt/21")]);
+        assert_eq!(ours.default_java_env.as_deref(), Some("jdk21"));
+        assert_eq!(ours.history_limit, 20);
+        assert_eq!(ours.on_switch_commands["java"]["jdk21"], "echo 21");
+    }
+
+    #[test]
+    fn error_on_conflict_lists_every_conflict_and_leaves_config_untouched() {
+        let mut ours = config_with(&[("jdk17", "/old/17")]);
+        ours.download.retry_count = 5;
+        ours.history_limit = 20;
+        ours.on_switch_commands
+            .entry("java".to_string())
+            .or_default()
+            .insert("jdk17".to_string(), "echo old".to_string());
+        let theirs = value(
+            r#"
+[[java_environments]]
+name = "jdk17"
+java_home = "/new/17"
+[[java_environments]]
+name = "jdk21"
+java_home = "/opt/21"
+[download]
+retry_count = 3
+[on_switch_commands.java]
+jdk17 = "echo new"
+"#,
+        );
+
+        let err = ours
+            .merge(&theirs, MergeStrategy::ErrorOnConflict)
+            .unwrap_err();
+        assert!(err.contains("java_environments 'jdk17'"), "{err}");
+        assert!(err.contains("download.retry_count"), "{err}");
+        assert!(err.contains("on_switch_commands.java.jdk17"), "{err}");
+        // 对方没写的键(history_limit 等)不算冲突
+        assert!(!err.contains("history_limit"), "{err}");
+        assert_eq!(homes(&ours), [("jdk17", "/old/17")]);
+        assert_eq!(ours.on_switch_commands["java"]["jdk17"], "echo old");
+    }
+
+    #[test]
+    fn error_on_conflict_accepts_identical_entries() {
+        let mut ours = config_with(&[("jdk17", "/opt/17")]);
+        ours.history_limit = 20;
+        let theirs = value(
+            r#"
+[[java_environments]]
+name = "jdk17"
+java_home = "/opt/17"
+[[java_environments]]
+name = "jdk21"
+java_home = "/opt/21"
+"#,
+        );
+        ours.merge(&theirs, MergeStrategy::ErrorOnConflict).unwrap();
+        assert_eq!(homes(&ours), [("jdk17", "/opt/17"), ("jdk21", "/opt/21")]);
+        assert_eq!(ours.mirrors.java.len(), Config::new().mirrors.java.len());
+        assert_eq!(ours.history_limit, 20);
+    }
+}
@@ -0,0 +1,192 @@
+//! 配置的语义检查:能解析、但内容自相矛盾的配置(`config validate` / `doctor` 使用)。
+//!
+//! 结构错误由反序列化直接报错;这里只找手改配置后常见的问题,以警告返回,不修改配置。
+
+use super::config::Config;
+use crate::core::environment_manager::EnvironmentType;
+use serde::Serialize;
+
+/// 一条语义警告
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConfigWarning {
+    /// `default_<type>_env` 指向不存在的环境
+    DanglingDefault {
+        env_type: EnvironmentType,
+        name: String,
+    },
+    /// `[on_switch_commands.<key>]` 的 key 不是已知的环境类型
+    UnknownHookType { key: String },
+    /// 同一类型下有多个同名环境(只有第一个能被选中)
+    DuplicateName {
+        env_type: EnvironmentType,
+        name: String,
+        count: usize,
+    },
+}
+
+impl std::fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigWarning::DanglingDefault { env_type, name } => write!(
+                f,
+                "default_{env_type}_env = '{name}', but no {env_type} environment has that name"
+            ),
+            ConfigWarning::UnknownHookType { key } => write!(
+                f,
+                "[on_switch_commands.{key}]: unknown environment type (expected java, maven or cc)"
+            ),
+            ConfigWarning::DuplicateName {
+                env_type,
+                name,
+                count,
+            } => write!(
+                f,
+                "{count} {env_type} environments are named '{name}'; only the first one is used"
+            ),
+        }
+    }
+}
+
+impl Config {
+    /// 语义检查,按 默认环境 → 钩子 → 重名 的顺序返回警告;没有问题时为空。
+    pub fn validate_semantics(&self) -> Vec<ConfigWarning> {
+        let names = [
+            (
+                EnvironmentType::Java,
+                self.default_java_env.as_deref(),
+                self.java_environments
+                    .iter()
+                    .map(|e| e.name.as_str())
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                EnvironmentType::Maven,
+                self.default_maven_env.as_deref(),
+                self.maven_environments
+                    .iter()
+                    .map(|e| e.name.as_str())
+                    .collect(),
+            ),
+            (
+                EnvironmentType::Cc,
+                self.default_cc_env.as_deref(),
+                self.cc_environments
+                    .iter()
+                    .map(|e| e.name.as_str())
+                    .collect(),
+            ),
+        ];
+        let mut warnings = Vec::new();
+
+        for (env_type, default, envs) in &names {
+            if let Some(name) = default.filter(|d| !d.is_empty() && !envs.contains(d)) {
+                warnings.push(ConfigWarning::DanglingDefault {
+                    env_type: *env_type,
+                    name: name.to_string(),
+                });
+            }
+        }
+
+        let mut hook_keys: Vec<&String> = self
+            .on_switch_commands
+            .keys()
+            .filter(|k| !matches!(k.as_str(), "java" | "maven" | "cc"))
+            .collect();
+        hook_keys.sort();
+        warnings.extend(
+            hook_keys
+                .into_iter()
+                .map(|key| ConfigWarning::UnknownHookType { key: key.clone() }),
+        );
+
+        for (env_type, _, envs) in &names {
+            let mut reported: Vec<&str> = Vec::new();
+            for name in envs {
+                let count = envs.iter().filter(|n| *n == name).count();
+                if count > 1 && !reported.contains(name) {
+                    reported.push(name);
+                    warnings.push(ConfigWarning::DuplicateName {
+                        env_type: *env_type,
+                        name: name.to_string(),
+                        count,
+                    });
+                }
+            }
+        }
+
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml: &str) -> Config {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn valid_config_has_no_warnings() {
+        let config = parse(
+            "default_java_env = \"jdk17\"\n\
+             [[java_environments]]\nname = \"jdk17\"\njava_home = \"/opt/jdk17\"\n\
+             [on_switch_commands.java]\njdk17 = \"echo 17\"\n",
+        );
+        assert_eq!(config.validate_semantics(), []);
+    }
+
+    #[test]
+    fn reports_dangling_default() {
+        let config = parse(
+            "default_java_env = \"jdk21\"\ndefault_cc_env = \"glm\"\n\
+             [[java_environments]]\nname = \"jdk17\"\njava_home = \"/opt/jdk17\"\n",
+        );
+        assert_eq!(
+            config.validate_semantics(),
+            [
+                ConfigWarning::DanglingDefault {
+                    env_type: EnvironmentType::Java,
+                    name: "jdk21".to_string(),
+                },
+                ConfigWarning::DanglingDefault {
+                    env_type: EnvironmentType::Cc,
+                    name: "glm".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_unknown_hook_type() {
+        let config = parse("[on_switch_commands.node]\nv20 = \"echo 20\"\n");
+        let warnings = config.validate_semantics();
+        assert_eq!(
+            warnings,
+            [ConfigWarning::UnknownHookType {
+                key: "node".to_string()
+            }]
+        );
+        assert!(warnings[0].to_string().contains("on_switch_commands.node"));
+    }
+
+    #[test]
+    fn reports_duplicate_names_once_per_name() {
+        let config = parse(
+            "[[java_environments]]\nname = \"jdk17\"\njava_home = \"/opt/a\"\n\
+             [[java_environments]]\nname = \"jdk17\"\njava_home = \"/opt/b\"\n\
+             [[java_environments]]\nname = \"jdk17\"\njava_home = \"/opt/c\"\n\
+             [[maven_environments]]\nname = \"jdk17\"\nmaven_home = \"/opt/mvn\"\n",
+        );
+        // 不同类型同名不算重复
+        assert_eq!(
+            config.validate_semantics(),
+            [ConfigWarning::DuplicateName {
+                env_type: EnvironmentType::Java,
+                name: "jdk17".to_string(),
+                count: 3,
+            }]
+        );
+    }
+}
@@ -3,7 +3,9 @@
 //! 工具差异(安装子目录、home 定位方式)由 [`ToolDescriptor`] 参数化。
 //! Java / Maven 的 installer 都调本模块的 [`download_and_install`]。
 
-use crate::infrastructure::installer::utils::{create_progress_bar, extract_tar_gz, extract_zip};
+use crate::infrastructure::installer::utils::{
+    create_progress_bar, extract_tar_gz, extract_tar_xz, extract_zip,
+};
 use crate::infrastructure::remote::platform::Platform;
 use crate::infrastructure::remote::DownloadTarget;
 use crate::infrastructure::tool_protocol::{ResolvedVersion, ToolDescriptor, ToolDownloader};
@@ -14,13 +16,18 @@ use std::path::{Path, PathBuf};
 /// 通用安装骨架:下载 → 解压到 `~/.fnva/{install_subdir}/{env_name}` → 定位 home。
 ///
 /// 返回最终的 home 目录字符串(已通过 `descriptor.home_validator` 校验)。
+/// `keep_archive` 给出目录时,安装成功后把归档连同 `.sha256` 校验文件保留到该目录;
+/// 否则删除下载缓存里的这份归档(`--keep-archive` 才保留)。
+#[tracing::instrument(skip_all, fields(tool = descriptor.id, version = %version.version, env_name = %env_name))]
 pub async fn download_and_install(
     downloader: &dyn ToolDownloader,
     version: &ResolvedVersion,
     platform: &Platform,
     env_name: &str,
     descriptor: &ToolDescriptor,
+    keep_archive: Option<&Path>,
 ) -> Result<String, String> {
+    crate::infrastructure::installer::interrupt::install_interrupt_handler();
     let pb = create_progress_bar().unwrap_or_else(|_| fallback_spinner());
     let pb_clone = pb.clone();
 
@@ -51,16 +58,62 @@ pub async fn download_and_install(
         }
     };
 
+    tracing::debug!(archive = %file_path.display(), "download finished, installing");
     let home = install_archive(&file_path, env_name, descriptor)?;
-    if !(descriptor.home_validator)(&home) {
-        return Err(format!(
-            "{} installation verification failed",
-            descriptor.display_name
-        ));
+    match keep_archive {
+        Some(dir) => {
+            let kept = keep_archive_file(&file_path, dir).await?;
+            crate::cli::print::step("Archive", &kept.display().to_string());
+        }
+        None => discard_cached_archive(&file_path),
     }
     Ok(home)
 }
 
+/// 安装成功后删除下载缓存里的归档及其 `.sha256`;不在缓存目录下的文件不是 fnva 的,不动。
+fn discard_cached_archive(archive: &Path) {
+    use crate::infrastructure::remote::download::checksum_sidecar;
+
+    let in_cache =
+        crate::infrastructure::paths::downloads_dir().is_ok_and(|dir| archive.starts_with(dir));
+    if !in_cache {
+        return;
+    }
+    for path in [archive.to_path_buf(), checksum_sidecar(archive)] {
+        if let Err(e) = fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!(path = %path.display(), error = %e, "failed to remove cached archive");
+            }
+        }
+    }
+}
+
+/// 把已校验的归档保留到 `dir`(与下载缓存同目录时原地保留),并写 sha256sum 格式的
+/// `<file>.sha256`;之后命中下载缓存时据此校验,损坏则重下。
+pub async fn keep_archive_file(archive: &Path, dir: &Path) -> Result<PathBuf, String> {
+    use crate::infrastructure::remote::download::{checksum_sidecar, file_sha256};
+
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create archive dir: {e}"))?;
+    let file_name = archive
+        .file_name()
+        .ok_or_else(|| format!("Invalid archive path: {}", archive.display()))?;
+    let kept = dir.join(file_name);
+    let same_file = match (archive.canonicalize(), kept.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    };
+    if !same_file {
+        fs::copy(archive, &kept).map_err(|e| format!("Failed to keep archive: {e}"))?;
+    }
+    let hash = file_sha256(&kept).await?;
+    fs::write(
+        checksum_sidecar(&kept),
+        format!("{hash}  {}\n", file_name.to_string_lossy()),
+    )
+    .map_err(|e| format!("Failed to write checksum file: {e}"))?;
+    Ok(kept)
+}
+
 fn fallback_spinner() -> ProgressBar {
     let pb = ProgressBar::new_spinner();
     pb.set_style(
@@ -72,8 +125,52 @@ fn fallback_spinner() -> ProgressBar {
     pb
 }
 
-/// 解压归档到 `~/.fnva/{install_subdir}/{env_name}`,再用 `descriptor.locate_home`
-/// 定位实际 home。
+/// 离线安装:直接解压本地归档(不发任何网络请求),定位并校验 home。
+pub fn install_local_archive(
+    archive_path: &Path,
+    env_name: &str,
+    descriptor: &ToolDescriptor,
+) -> Result<String, String> {
+    if !archive_path.is_file() {
+        return Err(format!("Archive not found: {}", archive_path.display()));
+    }
+    crate::infrastructure::installer::interrupt::install_interrupt_handler();
+    install_archive(archive_path, env_name, descriptor)
+}
+
+/// 安装完成标记:解压并校验通过后才写入,缺失即视为中断的半成品。
+pub const INSTALL_COMPLETE_MARKER: &str = ".fnva-complete";
+
+/// 复用 `install_dir` 下已解压好的安装。
+///
+/// 有完成标记且 home 校验通过时返回 home;目录存在但不完整(解压中断、
+/// 被手动改坏)则清理掉并返回 `None`,交由调用方重新下载/解压。
+pub fn reuse_complete_install(
+    install_dir: &Path,
+    descriptor: &ToolDescriptor,
+) -> Result<Option<String>, String> {
+    if !install_dir.exists() {
+        return Ok(None);
+    }
+    if install_dir.join(INSTALL_COMPLETE_MARKER).is_file() {
+        if let Ok(home) = (descriptor.locate_home)(install_dir) {
+            if (descriptor.home_validator)(&home) {
+                return Ok(Some(home));
+            }
+        }
+    }
+    crate::cli::print::warn(&format!(
+        "Incomplete {} install at {}, re-extracting",
+        descriptor.display_name,
+        install_dir.display()
+    ));
+    fs::remove_dir_all(install_dir)
+        .map_err(|e| format!("Failed to remove incomplete install: {e}"))?;
+    Ok(None)
+}
+
+/// 解压归档到 `~/.fnva/{install_subdir}/{env_name}`,用 `descriptor.locate_home`
+/// 定位并校验实际 home,成功后写入完成标记。
 fn install_archive(
     archive_path: &Path,
     env_name: &str,
@@ -83,15 +180,164 @@ fn install_archive(
 
     fs::create_dir_all(&fnva_dir).map_err(|e| format!("Failed to create install dir: {e}"))?;
 
-    let install_dir = fnva_dir.join(env_name);
+    let install_dir = install_dir_for(&fnva_dir, env_name)?;
+    // 残留目录只可能是上次中断的半成品(完整安装已被 reuse_complete_install 复用)
+    if install_dir.exists() {
+        fs::remove_dir_all(&install_dir)
+            .map_err(|e| format!("Failed to remove stale install dir: {e}"))?;
+    }
+    // 出错或被 Ctrl-C 中断时删除,不留半成品目录
+    let in_progress = crate::infrastructure::installer::interrupt::track(&install_dir);
     fs::create_dir_all(&install_dir).map_err(|e| format!("Failed to create version dir: {e}"))?;
 
-    if archive_path.to_str().unwrap().ends_with(".zip") {
-        extract_zip(archive_path, &install_dir)?;
+    // 只按扩展名判断格式;非 UTF-8 的文件名同样可用
+    let archive_name = archive_path
+        .file_name()
+        .ok_or_else(|| format!("Invalid archive path: {}", archive_path.display()))?
+        .to_string_lossy();
+    if archive_name.ends_with(".msi") {
+        install_msi_package(archive_path, &install_dir)?;
+    } else if archive_name.ends_with(".zip") {
+        let workers = crate::infrastructure::config::Config::load()
+            .map(|c| c.download.extract_workers())
+            .unwrap_or(1);
+        extract_zip(archive_path, &install_dir, workers)?;
+    } else if archive_name.ends_with(".tar.xz") || archive_name.ends_with(".txz") {
+        extract_tar_xz(archive_path, &install_dir)?;
     } else {
         extract_tar_gz(archive_path, &install_dir)?;
     }
 
     let actual_home = (descriptor.locate_home)(&install_dir)?;
+    if !(descriptor.home_validator)(&actual_home) {
+        return Err(format!(
+            "{} installation verification failed",
+            descriptor.display_name
+        ));
+    }
+    fs::write(install_dir.join(INSTALL_COMPLETE_MARKER), "")
+        .map_err(|e| format!("Failed to write install marker: {e}"))?;
+    in_progress.finish();
     Ok(actual_home)
 }
+
+/// `env_name` 对应的安装目录 `root/<env_name>`。
+///
+/// 目录会被整个删除重建,名称必须是单个普通路径分量(非空、无分隔符、不是 `.` / `..`),
+/// 拼接结果也必须仍直接位于 `root` 下。
+pub fn install_dir_for(root: &Path, env_name: &str) -> Result<PathBuf, String> {
+    let mut components = Path::new(env_name).components();
+    let single_normal = matches!(
+        (components.next(), components.next()),
+        (Some(std::path::Component::Normal(_)), None)
+    );
+    if !single_normal || env_name.contains(['/', '\\']) {
+        return Err(format!(
+            "Invalid environment name '{env_name}': must be a single directory name"
+        ));
+    }
+    let dir = root.join(env_name);
+    if dir.parent() != Some(root) {
+        return Err(format!(
+            "Install directory {} escapes {}",
+            dir.display(),
+            root.display()
+        ));
+    }
+    Ok(dir)
+}
+
+/// MSI 只能在 Windows 上用 msiexec 静默安装;其他平台直接报错而不是当 tar 解压。
+#[cfg(target_os = "windows")]
+fn install_msi_package(msi_path: &Path, install_dir: &Path) -> Result<(), String> {
+    crate::infrastructure::installer::utils::install_msi(msi_path, install_dir)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn install_msi_package(_msi_path: &Path, _install_dir: &Path) -> Result<(), String> {
+    Err("MSI packages can only be installed on Windows".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuse_complete_install_cleans_partial_and_keeps_marked() {
+        use crate::environments::java::installer::JAVA_DESCRIPTOR;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let java = if cfg!(windows) { "java.exe" } else { "java" };
+
+        // 中断的解压:没有完成标记,即便 bin/java 已写出也不复用
+        let partial = tmp.path().join("partial");
+        fs::create_dir_all(partial.join("jdk/bin")).unwrap();
+        fs::write(partial.join("jdk/bin").join(java), b"").unwrap();
+        assert_eq!(
+            reuse_complete_install(&partial, &JAVA_DESCRIPTOR).unwrap(),
+            None
+        );
+        assert!(!partial.exists());
+
+        let complete = tmp.path().join("complete");
+        fs::create_dir_all(complete.join("jdk/bin")).unwrap();
+        fs::write(complete.join("jdk/bin").join(java), b"").unwrap();
+        fs::write(complete.join(INSTALL_COMPLETE_MARKER), "").unwrap();
+        let home = reuse_complete_install(&complete, &JAVA_DESCRIPTOR)
+            .unwrap()
+            .unwrap();
+        assert!(home.ends_with("jdk"), "{home}");
+    }
+
+    #[test]
+    fn install_dir_accepts_only_a_single_plain_name() {
+        let root = Path::new("/home/u/.fnva/packages/java");
+        assert_eq!(install_dir_for(root, "jdk17").unwrap(), root.join("jdk17"));
+        assert_eq!(
+            install_dir_for(root, "21.0.2+13").unwrap(),
+            root.join("21.0.2+13")
+        );
+        for bad in ["", ".", "..", "a/b", "../java", "a\\b", "/etc"] {
+            assert!(install_dir_for(root, bad).is_err(), "{bad:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn keep_archive_copies_file_and_writes_checksum() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let archive = tmp.path().join("jdk.tar.gz");
+        fs::write(&archive, b"abc").unwrap();
+        let dir = tmp.path().join("kept");
+
+        let kept = keep_archive_file(&archive, &dir).await.unwrap();
+        assert_eq!(fs::read(&kept).unwrap(), b"abc");
+        let sidecar = fs::read_to_string(dir.join("jdk.tar.gz.sha256")).unwrap();
+        assert_eq!(
+            sidecar,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  jdk.tar.gz\n"
+        );
+
+        // 原地保留:不复制,只补校验文件
+        let again = keep_archive_file(&kept, &dir).await.unwrap();
+        assert_eq!(again, kept);
+    }
+
+    #[test]
+    fn discard_removes_only_archives_in_download_cache() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = crate::testutil::FnvaHomeGuard::new(tmp.path());
+        let cache = crate::infrastructure::paths::downloads_dir().unwrap();
+        fs::create_dir_all(&cache).unwrap();
+        let cached = cache.join("jdk.tar.gz");
+        fs::write(&cached, b"abc").unwrap();
+        fs::write(cache.join("jdk.tar.gz.sha256"), "x  jdk.tar.gz\n").unwrap();
+        let local = tmp.path().join("jdk.tar.gz");
+        fs::write(&local, b"abc").unwrap();
+
+        discard_cached_archive(&cached);
+        discard_cached_archive(&local);
+        assert!(!cached.exists());
+        assert!(!cache.join("jdk.tar.gz.sha256").exists());
+        assert!(local.exists());
+    }
+}
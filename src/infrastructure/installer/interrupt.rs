@@ -0,0 +1,159 @@
+//! 安装中途 Ctrl-C 的清理。
+//!
+//! 正在解压的安装目录在开始时登记为 [`InProgress`],完成后注销。安装路径上注册的
+//! Ctrl-C 处理在独立任务里运行(同步解压期间也能响应):删除所有仍在登记中的路径,
+//! 再以 130 退出。出错返回或 panic 时,`InProgress` 的 Drop 同样会删除半成品。
+//! 下载临时文件(`*.downloading`)及其续传记录不登记,中断后保留,下次接着下。
+
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once};
+
+/// 进行中的路径登记表;进程内用全局的一份,测试可另建独立实例。
+pub struct Registry {
+    paths: Mutex<Vec<PathBuf>>,
+}
+
+static REGISTRY: Registry = Registry::new();
+
+impl Registry {
+    pub const fn new() -> Self {
+        Self {
+            paths: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// 登记 `path` 为进行中;返回的守卫未 [`finish`](InProgress::finish) 就被丢弃时删除它。
+    pub fn track(&'static self, path: &Path) -> InProgress {
+        self.lock().push(path.to_path_buf());
+        InProgress {
+            registry: self,
+            path: path.to_path_buf(),
+            armed: true,
+        }
+    }
+
+    /// 删除所有进行中的路径(中断时调用),返回实际删掉的路径。
+    pub fn remove_all(&self) -> Vec<PathBuf> {
+        let paths = std::mem::take(&mut *self.lock());
+        paths.into_iter().filter(|p| remove_path(p)).collect()
+    }
+
+    fn untrack(&self, path: &Path) {
+        let mut paths = self.lock();
+        if let Some(i) = paths.iter().position(|p| p == path) {
+            paths.remove(i);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<PathBuf>> {
+        self.paths.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 进行中的文件 / 目录。
+pub struct InProgress {
+    registry: &'static Registry,
+    path: PathBuf,
+    armed: bool,
+}
+
+impl InProgress {
+    /// 已完成:注销登记,保留路径。
+    pub fn finish(mut self) {
+        self.armed = false;
+        self.registry.untrack(&self.path);
+    }
+}
+
+impl Drop for InProgress {
+    fn drop(&mut self) {
+        if self.armed {
+            self.registry.untrack(&self.path);
+            remove_path(&self.path);
+        }
+    }
+}
+
+/// 在全局登记表中登记进行中的路径。
+pub fn track(path: &Path) -> InProgress {
+    REGISTRY.track(path)
+}
+
+/// 注册 Ctrl-C 处理(每个进程一次);不在 tokio 运行时里时跳过。
+pub fn install_interrupt_handler() {
+    static INSTALLED: Once = Once::new();
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    INSTALLED.call_once(|| {
+        handle.spawn(async {
+            if tokio::signal::ctrl_c().await.is_err() {
+                return;
+            }
+            let removed = REGISTRY.remove_all();
+            crate::cli::print::warn(&format!(
+                "Interrupted; removed {} partial install dir(s), partial downloads will resume",
+                removed.len()
+            ));
+            std::process::exit(130);
+        });
+    });
+}
+
+fn remove_path(path: &Path) -> bool {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path).is_ok()
+    } else {
+        std::fs::remove_file(path).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interrupt_removes_partially_extracted_dir() {
+        let registry: &'static Registry = Box::leak(Box::new(Registry::new()));
+        let tmp = tempfile::TempDir::new().unwrap();
+        let install_dir = tmp.path().join("packages").join("java").join("21");
+        let finished = tmp.path().join("done");
+
+        let dir_guard = registry.track(&install_dir);
+        std::fs::create_dir_all(install_dir.join("jdk-21").join("bin")).unwrap();
+        std::fs::write(install_dir.join("jdk-21").join("release"), "partial").unwrap();
+        std::fs::create_dir_all(&finished).unwrap();
+        registry.track(&finished).finish();
+
+        // 解压进行到一半时收到 Ctrl-C
+        let removed = registry.remove_all();
+        assert_eq!(removed, std::slice::from_ref(&install_dir));
+        assert!(!install_dir.exists());
+        assert!(finished.exists());
+        // 之后守卫被丢弃也不会出错
+        drop(dir_guard);
+    }
+
+    #[test]
+    fn dropped_guard_removes_path_but_finished_one_keeps_it() {
+        let registry: &'static Registry = Box::leak(Box::new(Registry::new()));
+        let tmp = tempfile::TempDir::new().unwrap();
+        let failed = tmp.path().join("failed");
+        let ok = tmp.path().join("ok");
+        std::fs::create_dir_all(&failed).unwrap();
+        std::fs::create_dir_all(&ok).unwrap();
+
+        drop(registry.track(&failed));
+        registry.track(&ok).finish();
+
+        assert!(!failed.exists());
+        assert!(ok.exists());
+        assert!(registry.remove_all().is_empty());
+    }
+}
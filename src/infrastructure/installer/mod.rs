@@ -1,3 +1,4 @@
 pub mod generic;
+pub mod interrupt;
 pub mod utils;
 pub use utils::*;
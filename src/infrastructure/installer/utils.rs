@@ -11,39 +11,119 @@ pub fn create_progress_bar() -> Result<ProgressBar, AppError> {
             .map_err(|e| AppError::Internal {
                 message: format!("Failed to create progress bar style: {e}")
             })?
-            .progress_chars("━╸ ")
+            .progress_chars(crate::cli::print::glyph("━╸ ", "=> "))
     );
     Ok(pb)
 }
 
-pub fn extract_zip(zip_path: &Path, dest_dir: &Path) -> Result<(), String> {
-    let file = fs::File::open(zip_path).map_err(|e| format!("Failed to open ZIP file: {e}"))?;
-    let mut archive =
-        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read ZIP file: {e}"))?;
+/// 解压 zip 到 `dest_dir`:先串行建好全部目录,再由 `workers` 个线程(各自打开一份
+/// 归档)并行写文件;Unix 上按条目记录的权限恢复文件与目录的 mode(`bin/java` 等的可执行位)。
+pub fn extract_zip(zip_path: &Path, dest_dir: &Path, workers: usize) -> Result<(), String> {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    let open = || -> Result<zip::ZipArchive<fs::File>, String> {
+        let file = fs::File::open(zip_path).map_err(|e| format!("Failed to open ZIP file: {e}"))?;
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read ZIP file: {e}"))
+    };
+
+    let mut archive = open()?;
+    // (条目下标, 目标路径, unix 权限)
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
     for i in 0..archive.len() {
-        let mut file = archive
+        let entry = archive
             .by_index(i)
             .map_err(|e| format!("Failed to read ZIP entry: {e}"))?;
         // enclosed_name 对含 `..` / 绝对路径等不安全条目返回 None,跳过以防 zip-slip。
-        let Some(rel) = file.enclosed_name() else {
+        let Some(rel) = entry.enclosed_name() else {
             continue;
         };
         let outpath = dest_dir.join(rel);
-        if file.is_dir() {
-            fs::create_dir_all(&outpath).map_err(|e| format!("Failed to create directory: {e}"))?;
+        let dir = if entry.is_dir() {
+            Some(outpath.as_path())
+        } else {
+            outpath.parent()
+        };
+        if let Some(dir) = dir.filter(|d| !d.exists()) {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory: {e}"))?;
+        }
+        if entry.is_dir() {
+            dirs.push((outpath, entry.unix_mode()));
         } else {
-            if let Some(p) = outpath.parent() {
-                if !p.exists() {
-                    fs::create_dir_all(p)
-                        .map_err(|e| format!("Failed to create parent directory: {e}"))?;
-                }
+            files.push((i, outpath, entry.unix_mode()));
+        }
+    }
+    drop(archive);
+
+    let next = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let worker = || -> Result<(), String> {
+        let mut archive = open()?;
+        while !failed.load(Ordering::Relaxed) {
+            let Some((index, outpath, mode)) = files.get(next.fetch_add(1, Ordering::Relaxed))
+            else {
+                break;
+            };
+            let written = write_zip_entry(&mut archive, *index, outpath, *mode);
+            if written.is_err() {
+                failed.store(true, Ordering::Relaxed);
+                return written;
+            }
+        }
+        Ok(())
+    };
+
+    let written = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers.clamp(1, files.len().max(1)))
+            .map(|_| scope.spawn(worker))
+            .collect();
+        // 先等所有线程结束,再报告第一个错误
+        let mut result = Ok(());
+        for handle in handles {
+            let joined = handle
+                .join()
+                .unwrap_or_else(|_| Err("ZIP extraction worker panicked".to_string()));
+            if result.is_ok() {
+                result = joined;
             }
-            let mut outfile =
-                fs::File::create(&outpath).map_err(|e| format!("Failed to create file: {e}"))?;
-            std::io::copy(&mut file, &mut outfile)
-                .map_err(|e| format!("Failed to write file: {e}"))?;
         }
+        result
+    });
+    written?;
+
+    // 目录最后处理:只读目录若先设好会挡住文件写入;属主保留 rwx,以便日后卸载删除
+    for (dir, mode) in dirs.iter().rev() {
+        set_unix_mode(dir, mode.map(|m| m | 0o700))?;
+    }
+    Ok(())
+}
+
+fn write_zip_entry(
+    archive: &mut zip::ZipArchive<fs::File>,
+    index: usize,
+    outpath: &Path,
+    mode: Option<u32>,
+) -> Result<(), String> {
+    let mut entry = archive
+        .by_index(index)
+        .map_err(|e| format!("Failed to read ZIP entry: {e}"))?;
+    let mut outfile =
+        fs::File::create(outpath).map_err(|e| format!("Failed to create file: {e}"))?;
+    std::io::copy(&mut entry, &mut outfile).map_err(|e| format!("Failed to write file: {e}"))?;
+    drop(outfile);
+    set_unix_mode(outpath, mode)
+}
+
+/// 按 zip 条目记录的 mode 设置权限(只取权限位,忽略 setuid 等);非 Unix 或无记录时不做处理。
+fn set_unix_mode(path: &Path, mode: Option<u32>) -> Result<(), String> {
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(mode & 0o777))
+            .map_err(|e| format!("Failed to set permissions on {}: {e}", path.display()))?;
     }
+    #[cfg(not(unix))]
+    let _ = (path, mode);
     Ok(())
 }
 
@@ -70,6 +150,61 @@ pub fn extract_tar_gz(tar_path: &Path, dest_dir: &Path) -> Result<(), String> {
     Ok(())
 }
 
+/// `.tar.xz`:xz2 解压后经管道交给系统 `tar`(部分平台的 tar 不支持 `-J`)。
+pub fn extract_tar_xz(tar_path: &Path, dest_dir: &Path) -> Result<(), String> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let dest_dir_str =
+        safe_path_to_str(dest_dir).map_err(|e| format!("Dest path conversion failed: {e}"))?;
+    let file = fs::File::open(tar_path).map_err(|e| format!("Failed to open archive: {e}"))?;
+    let mut decoder = xz2::read::XzDecoder::new(std::io::BufReader::new(file));
+
+    let mut child = std::process::Command::new("tar")
+        .args(["-xf", "-", "-C", dest_dir_str, "--strip-components=1"])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute extract command: {e}"))?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let copied = std::io::copy(&mut decoder, &mut stdin).and_then(|_| stdin.flush());
+    drop(stdin);
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to execute extract command: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Extraction failed: {stderr}"));
+    }
+    copied.map_err(|e| format!("Failed to decompress xz archive: {e}"))?;
+    Ok(())
+}
+
+/// 用 `msiexec /i <file> /qn TARGETDIR=<dir>` 静默安装 MSI 到 `dest_dir`。
+///
+/// 厂商 MSI 往往在 TARGETDIR 下再套一层目录,home 定位交给调用方的 `locate_home`。
+#[cfg(target_os = "windows")]
+pub fn install_msi(msi_path: &Path, dest_dir: &Path) -> Result<(), String> {
+    let msi_path_str =
+        safe_path_to_str(msi_path).map_err(|e| format!("Path conversion failed: {e}"))?;
+    let dest_dir_str =
+        safe_path_to_str(dest_dir).map_err(|e| format!("Dest path conversion failed: {e}"))?;
+
+    let target_dir = format!("TARGETDIR={dest_dir_str}");
+
+    let output = std::process::Command::new("msiexec")
+        .args(["/i", msi_path_str, "/qn", target_dir.as_str()])
+        .output()
+        .map_err(|e| format!("Failed to execute msiexec: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "MSI installation failed (msiexec exit code {:?})",
+            output.status.code()
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -77,6 +212,42 @@ mod tests {
     use zip::write::SimpleFileOptions;
     use zip::ZipWriter;
 
+    #[test]
+    fn extract_tar_xz_strips_top_level_dir() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let src = tmp.path().join("src");
+        fs::create_dir_all(src.join("jdk-21/bin")).unwrap();
+        fs::write(src.join("jdk-21/bin/java"), b"#!/bin/sh\n").unwrap();
+        fs::write(src.join("jdk-21/release"), b"JAVA_VERSION=\"21\"\n").unwrap();
+        let tar = tmp.path().join("jdk.tar");
+        let status = std::process::Command::new("tar")
+            .args([
+                "-cf",
+                tar.to_str().unwrap(),
+                "-C",
+                src.to_str().unwrap(),
+                "jdk-21",
+            ])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let archive = tmp.path().join("jdk.tar.xz");
+        let mut encoder = xz2::write::XzEncoder::new(fs::File::create(&archive).unwrap(), 6);
+        std::io::copy(&mut fs::File::open(&tar).unwrap(), &mut encoder).unwrap();
+        encoder.finish().unwrap();
+
+        let dest = tmp.path().join("out");
+        fs::create_dir_all(&dest).unwrap();
+        extract_tar_xz(&archive, &dest).unwrap();
+        assert_eq!(fs::read(dest.join("bin/java")).unwrap(), b"#!/bin/sh\n");
+        assert!(dest.join("release").is_file());
+
+        // 不是 xz 数据
+        let bogus = tmp.path().join("bogus.tar.xz");
+        fs::write(&bogus, b"not xz").unwrap();
+        assert!(extract_tar_xz(&bogus, &dest).is_err());
+    }
+
     /// 构造一个 zip 文件,内含 `entries`(名字 → 内容)。
     fn build_zip(path: &Path, entries: &[(&str, &[u8])]) {
         let file = fs::File::create(path).unwrap();
@@ -98,7 +269,7 @@ mod tests {
         let dest = tmp.path().join("out");
         fs::create_dir_all(&dest).unwrap();
 
-        extract_zip(&zip_path, &dest).unwrap();
+        extract_zip(&zip_path, &dest, 2).unwrap();
 
         assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "hello");
         assert_eq!(
@@ -107,6 +278,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn extract_zip_in_parallel_writes_every_file_and_keeps_exec_bits() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let zip_path = tmp.path().join("jdk.zip");
+        let mut zip = ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        zip.add_directory("jdk/lib/", SimpleFileOptions::default())
+            .unwrap();
+        for i in 0..200 {
+            zip.start_file(format!("jdk/lib/f{i}.txt"), SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(format!("file {i}").as_bytes()).unwrap();
+        }
+        zip.start_file(
+            "jdk/bin/java",
+            SimpleFileOptions::default().unix_permissions(0o755),
+        )
+        .unwrap();
+        zip.write_all(b"#!/bin/sh\n").unwrap();
+        zip.finish().unwrap();
+
+        for workers in [1, 8] {
+            let dest = tmp.path().join(format!("out{workers}"));
+            fs::create_dir_all(&dest).unwrap();
+            extract_zip(&zip_path, &dest, workers).unwrap();
+            for i in 0..200 {
+                assert_eq!(
+                    fs::read_to_string(dest.join(format!("jdk/lib/f{i}.txt"))).unwrap(),
+                    format!("file {i}")
+                );
+            }
+            assert_eq!(fs::read(dest.join("jdk/bin/java")).unwrap(), b"#!/bin/sh\n");
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = fs::metadata(dest.join("jdk/bin/java"))
+                    .unwrap()
+                    .permissions()
+                    .mode();
+                assert_eq!(mode & 0o777, 0o755);
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn extract_zip_restores_executable_bit_on_bin_java() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let zip_path = tmp.path().join("jdk.zip");
+        let mut zip = ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        zip.add_directory(
+            "jdk-17/bin/",
+            SimpleFileOptions::default().unix_permissions(0o750),
+        )
+        .unwrap();
+        zip.start_file(
+            "jdk-17/bin/java",
+            SimpleFileOptions::default().unix_permissions(0o4755),
+        )
+        .unwrap();
+        zip.write_all(b"#!/bin/sh\n").unwrap();
+        zip.start_file(
+            "jdk-17/release",
+            SimpleFileOptions::default().unix_permissions(0o644),
+        )
+        .unwrap();
+        zip.write_all(b"JAVA_VERSION=\"17\"\n").unwrap();
+        zip.finish().unwrap();
+
+        let dest = tmp.path().join("out");
+        fs::create_dir_all(&dest).unwrap();
+        extract_zip(&zip_path, &dest, 2).unwrap();
+
+        let mode = |p: &str| fs::metadata(dest.join(p)).unwrap().permissions().mode() & 0o7777;
+        // setuid 位被丢弃,只保留权限位
+        assert_eq!(mode("jdk-17/bin/java"), 0o755);
+        assert_eq!(mode("jdk-17/release"), 0o644);
+        assert_eq!(mode("jdk-17/bin"), 0o750);
+    }
+
     #[test]
     fn extract_zip_rejects_path_traversal_entries() {
         // zip-slip:恶意条目含 `..`,`enclosed_name()` 返回 None 被跳过,
@@ -121,7 +373,7 @@ mod tests {
         let dest = tmp.path().join("out");
         fs::create_dir_all(&dest).unwrap();
 
-        extract_zip(&zip_path, &dest).unwrap();
+        extract_zip(&zip_path, &dest, 2).unwrap();
 
         // 正常条目照常解压
         assert_eq!(fs::read_to_string(dest.join("ok.txt")).unwrap(), "safe");
@@ -0,0 +1,64 @@
+//! 诊断日志(`tracing`)初始化。
+//!
+//! 日志只写 stderr,且默认关闭,不会混进 `eval "$(fnva ...)"` 消费的 stdout 脚本。
+//! 级别来源优先级:`--log-level` > `FNVA_LOG` 环境变量 > off。
+
+use tracing_subscriber::EnvFilter;
+
+/// 日志级别环境变量名(支持 `EnvFilter` 语法,如 `debug` / `fnva=trace`)
+pub const LOG_ENV_VAR: &str = "FNVA_LOG";
+
+/// 解析最终生效的过滤指令。
+fn resolve_directive(cli_level: Option<&str>) -> String {
+    cli_level
+        .map(str::to_string)
+        .or_else(|| std::env::var(LOG_ENV_VAR).ok())
+        .filter(|s| !s.trim().is_empty())
+        .unwrap_or_else(|| "off".to_string())
+}
+
+/// 生效的过滤器;级别非法时退回 off 并提示。
+fn build_filter(cli_level: Option<&str>) -> EnvFilter {
+    let directive = resolve_directive(cli_level);
+    EnvFilter::try_new(&directive).unwrap_or_else(|e| {
+        crate::cli::print::warn(&format!("Invalid log level '{directive}': {e}"));
+        EnvFilter::new("off")
+    })
+}
+
+/// 安装全局 subscriber;级别非法不影响命令本身执行。
+pub fn init(cli_level: Option<&str>) {
+    let filter = build_filter(cli_level);
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_level_overrides_env_and_default_is_off() {
+        assert_eq!(resolve_directive(Some("debug")), "debug");
+        assert_eq!(resolve_directive(Some("  ")), "off");
+    }
+
+    #[test]
+    fn test_env_var_sets_filter() {
+        let _lock = crate::testutil::env_lock();
+        std::env::set_var(LOG_ENV_VAR, "fnva=trace");
+        let from_env = build_filter(None).to_string();
+        let overridden = build_filter(Some("warn")).to_string();
+        std::env::set_var(LOG_ENV_VAR, "not a [valid filter");
+        let invalid = build_filter(None).to_string();
+        std::env::remove_var(LOG_ENV_VAR);
+        let unset = build_filter(None).to_string();
+
+        assert_eq!(from_env, "fnva=trace");
+        assert_eq!(overridden, "warn");
+        assert_eq!(invalid, "off");
+        assert_eq!(unset, "off");
+    }
+}
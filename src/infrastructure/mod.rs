@@ -1,5 +1,10 @@
 pub mod config;
+pub mod config_keys;
+pub mod config_layers;
+pub mod config_merge;
+pub mod config_semantics;
 pub mod installer;
+pub mod logging;
 pub mod paths;
 pub mod remote;
 pub mod scanner;
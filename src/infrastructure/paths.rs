@@ -9,22 +9,62 @@
 //! - `packages/<tool>/<name>/`  安装的工具(持久)
 
 use std::path::PathBuf;
+use std::sync::RwLock;
 
 const FNVA_DIR: &str = ".fnva";
 
-fn home() -> Result<PathBuf, String> {
-    // FNVA_HOME 优先(主要用于测试隔离到临时目录);未设置时回落到用户主目录。
-    if let Ok(custom) = std::env::var("FNVA_HOME") {
-        if !custom.is_empty() {
-            return Ok(PathBuf::from(custom));
-        }
+/// `--config-dir` 指定的数据目录,启动时设置一次,所有路径 helper 都从它派生。
+static CONFIG_DIR_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// 覆盖 fnva 数据目录(替代 `~/.fnva`);传 `None` 恢复默认。
+pub fn set_config_dir(dir: Option<PathBuf>) {
+    if let Ok(mut guard) = CONFIG_DIR_OVERRIDE.write() {
+        *guard = dir;
+    }
+}
+
+fn non_empty(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// 数据目录的解析顺序(`--config-dir` 之外):
+/// 1. `$FNVA_HOME/.fnva`(测试隔离、自定义位置)
+/// 2. `~/.fnva`
+/// 3. `$XDG_CONFIG_HOME/fnva`(没有主目录的容器 / 沙箱)
+///
+/// 都没有时报错,不退回系统临时目录:那里所有用户可写,别人放进去的
+/// `config.toml`(如 `on_switch_command`)会被当成自己的配置执行。
+fn resolve_fnva_dir(
+    fnva_home: Option<PathBuf>,
+    home: Option<PathBuf>,
+    xdg_config_home: Option<PathBuf>,
+) -> Result<PathBuf, String> {
+    if let Some(dir) = fnva_home.or(home) {
+        return Ok(dir.join(FNVA_DIR));
     }
-    dirs::home_dir().ok_or_else(|| "Cannot get user home directory".to_string())
+    let dir = xdg_config_home
+        .ok_or_else(|| {
+            "Cannot determine the home directory; set FNVA_HOME (or XDG_CONFIG_HOME) \
+             to choose where fnva keeps its data"
+                .to_string()
+        })?
+        .join("fnva");
+    tracing::debug!(dir = %dir.display(), "no home directory, using XDG_CONFIG_HOME");
+    Ok(dir)
 }
 
-/// `~/.fnva`
+/// `~/.fnva`(或 `--config-dir` 指定的目录;无主目录时见 [`resolve_fnva_dir`])
 pub fn fnva_dir() -> Result<PathBuf, String> {
-    Ok(home()?.join(FNVA_DIR))
+    if let Some(dir) = CONFIG_DIR_OVERRIDE.read().ok().and_then(|g| g.clone()) {
+        return Ok(dir);
+    }
+    resolve_fnva_dir(
+        non_empty("FNVA_HOME"),
+        dirs::home_dir(),
+        non_empty("XDG_CONFIG_HOME"),
+    )
 }
 
 /// `~/.fnva/config.toml`
@@ -158,15 +198,23 @@ fn clean_aliyun_mirror() {
     }
 }
 
-/// Remove interrupted download leftovers (`*.downloading`).
+/// Remove interrupted download leftovers (`*.downloading`), except those that
+/// still have a `*.fnva-part.json` record and can be resumed.
 fn clean_downloading() {
+    use crate::infrastructure::remote::partial;
     let Ok(dir) = downloads_dir() else { return };
+    let resumable: Vec<PathBuf> = partial::list(&dir)
+        .iter()
+        .map(|(archive, _)| partial::partial_file(archive))
+        .collect();
     let Ok(entries) = std::fs::read_dir(&dir) else {
         return;
     };
     for entry in entries.flatten() {
         let path = entry.path();
-        if path.extension().and_then(|e| e.to_str()) == Some("downloading") {
+        if path.extension().and_then(|e| e.to_str()) == Some("downloading")
+            && !resumable.contains(&path)
+        {
             let _ = std::fs::remove_file(&path);
         }
     }
@@ -248,4 +296,72 @@ mod tests {
         let after_second = std::fs::read_to_string(history_path().unwrap()).unwrap();
         assert_eq!(after_first, after_second);
     }
+
+    #[test]
+    fn fnva_dir_falls_back_without_home() {
+        let p = |s: &str| Some(PathBuf::from(s));
+        assert_eq!(
+            resolve_fnva_dir(p("/fh"), p("/home/u"), p("/xdg")).unwrap(),
+            PathBuf::from("/fh/.fnva")
+        );
+        assert_eq!(
+            resolve_fnva_dir(p("/fh"), None, p("/xdg")).unwrap(),
+            PathBuf::from("/fh/.fnva")
+        );
+        assert_eq!(
+            resolve_fnva_dir(None, p("/home/u"), p("/xdg")).unwrap(),
+            PathBuf::from("/home/u/.fnva")
+        );
+        assert_eq!(
+            resolve_fnva_dir(None, None, p("/xdg")).unwrap(),
+            PathBuf::from("/xdg/fnva")
+        );
+        // 不退回共享的临时目录
+        let err = resolve_fnva_dir(None, None, None).unwrap_err();
+        assert!(err.contains("FNVA_HOME"), "{err}");
+    }
+
+    #[test]
+    fn every_path_helper_resolves_under_fnva_home() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let base = tmp.path().join(FNVA_DIR);
+        for path in [
+            fnva_dir(),
+            config_path(),
+            state_dir(),
+            current_envs_path(),
+            history_path(),
+            cache_dir(),
+            downloads_dir(),
+            maven_versions_path(),
+            java_versions_path(),
+            packages_dir(),
+            tool_packages_dir("java"),
+        ] {
+            let path = path.unwrap();
+            assert!(path.starts_with(&base), "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn config_dir_override_isolates_state() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let dir_a = tmp.path().join("profile-a");
+        let dir_b = tmp.path().join("profile-b");
+
+        set_config_dir(Some(dir_a.clone()));
+        assert_eq!(config_path().unwrap(), dir_a.join("config.toml"));
+        assert!(tool_packages_dir("java").unwrap().starts_with(&dir_a));
+        migrate_layout();
+        std::fs::write(history_path().unwrap(), "[[history]]\n").unwrap();
+
+        set_config_dir(Some(dir_b.clone()));
+        assert!(cache_dir().unwrap().starts_with(&dir_b));
+        assert!(!history_path().unwrap().exists());
+
+        set_config_dir(None);
+        assert_eq!(fnva_dir().unwrap(), tmp.path().join(FNVA_DIR));
+    }
 }
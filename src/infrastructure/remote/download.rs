@@ -1,3 +1,4 @@
+use super::partial::{self, PartState};
 use futures_util::StreamExt;
 use reqwest::Client;
 use sha2::{Digest, Sha256};
@@ -7,13 +8,155 @@ use tokio::io::AsyncWriteExt;
 /// HTTP User-Agent，版本随 Cargo.toml 自动同步。
 const USER_AGENT: &str = concat!("fnva/", env!("CARGO_PKG_VERSION"));
 
-/// 错误类型：用于区分临时错误和永久错误
-#[derive(Debug, Clone, PartialEq)]
-pub enum ErrorType {
-    /// 临时错误（网络问题、超时等，可以重试）
-    Transient(String),
-    /// 永久错误（404、403等，不应重试）
-    Permanent(String),
+/// 下载失败分类:决定面向用户的提示,以及是否值得在同一来源内重试。
+///
+/// DNS / 连接 / TLS 失败换个时间重试同一地址几乎不会好转,直接交给上层换镜像;
+/// 超时和 5xx 往往是暂时的,按配置重试。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// 域名解析失败(通常是没有网络或 DNS 配置问题)
+    Dns,
+    /// TLS 握手 / 证书校验失败
+    Tls,
+    /// 连接被拒绝或目标不可达
+    Connect,
+    /// 连接或读取超时
+    Timeout,
+    /// HTTP 4xx
+    Client(u16),
+    /// HTTP 5xx
+    Server(u16),
+    /// 写本地文件失败
+    Io,
+    /// 其他(读流中断等)
+    Other,
+}
+
+impl FailureKind {
+    pub fn from_status(code: u16) -> Self {
+        if code >= 500 {
+            FailureKind::Server(code)
+        } else {
+            FailureKind::Client(code)
+        }
+    }
+
+    /// 按 reqwest 的错误标志和 source 链上的消息分类(hyper 的 DNS / rustls 的证书
+    /// 错误没有独立类型,只能看消息)。
+    pub fn from_reqwest(err: &reqwest::Error) -> Self {
+        // 顶层消息带 URL(文件名可能含 ssl 等字样),只看底层 source 链
+        let mut chain = Vec::new();
+        let mut source = std::error::Error::source(err);
+        while let Some(e) = source {
+            chain.push(e.to_string());
+            source = e.source();
+        }
+        Self::classify(
+            err.status().map(|s| s.as_u16()),
+            err.is_timeout(),
+            err.is_connect(),
+            &chain,
+        )
+    }
+
+    fn classify(status: Option<u16>, is_timeout: bool, is_connect: bool, chain: &[String]) -> Self {
+        if let Some(code) = status {
+            return Self::from_status(code);
+        }
+        let text = chain.join(": ").to_lowercase();
+        if text.contains("dns error") || text.contains("failed to lookup address") {
+            FailureKind::Dns
+        } else if ["certificate", "tls", "ssl", "handshake"]
+            .iter()
+            .any(|k| text.contains(k))
+        {
+            FailureKind::Tls
+        } else if is_timeout {
+            FailureKind::Timeout
+        } else if is_connect {
+            FailureKind::Connect
+        } else {
+            FailureKind::Other
+        }
+    }
+
+    /// 是否在同一来源内重试;否则立即放弃该来源(由上层轮换镜像)。
+    pub fn retry_same_source(self) -> bool {
+        matches!(
+            self,
+            FailureKind::Timeout
+                | FailureKind::Server(_)
+                | FailureKind::Client(408 | 429)
+                | FailureKind::Other
+        )
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            FailureKind::Dns => "DNS resolution failed".to_string(),
+            FailureKind::Tls => "TLS handshake failed".to_string(),
+            FailureKind::Connect => "Connection failed".to_string(),
+            FailureKind::Timeout => "Connection timed out".to_string(),
+            FailureKind::Client(404) => "Resource not found (HTTP 404)".to_string(),
+            FailureKind::Client(code @ (401 | 403)) => format!("Access denied (HTTP {code})"),
+            FailureKind::Client(code) => format!("Request rejected (HTTP {code})"),
+            FailureKind::Server(code) => format!("Server error (HTTP {code})"),
+            FailureKind::Io => "Failed to write download".to_string(),
+            FailureKind::Other => "Download interrupted".to_string(),
+        }
+    }
+
+    /// 给用户的排查建议
+    pub fn hint(self) -> &'static str {
+        match self {
+            FailureKind::Dns => {
+                "check your internet connection and DNS settings (or set HTTPS_PROXY)"
+            }
+            FailureKind::Tls => {
+                "behind a TLS-intercepting proxy? set FNVA_CA_CERT or download.ca_cert_path"
+            }
+            FailureKind::Connect => {
+                "the mirror may be down or blocked; check firewall/proxy settings"
+            }
+            FailureKind::Timeout => {
+                "the network is slow; raise download.connect_timeout_sec / read_timeout_sec"
+            }
+            FailureKind::Client(_) => {
+                "the mirror doesn't have this file; try another mirror or version"
+            }
+            FailureKind::Server(_) => {
+                "the mirror is having trouble; try again later or another mirror"
+            }
+            FailureKind::Io => "check free disk space and permissions on ~/.fnva/cache",
+            FailureKind::Other => "the connection dropped mid-download; try again",
+        }
+    }
+}
+
+/// 单次下载尝试的失败:分类 + 原始信息。
+#[derive(Debug)]
+struct AttemptError {
+    kind: FailureKind,
+    detail: String,
+}
+
+impl AttemptError {
+    fn new(kind: FailureKind, detail: impl ToString) -> Self {
+        Self {
+            kind,
+            detail: detail.to_string(),
+        }
+    }
+
+    fn from_reqwest(err: reqwest::Error) -> Self {
+        Self::new(FailureKind::from_reqwest(&err), err)
+    }
+}
+
+impl std::fmt::Display for AttemptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.kind.label(), self.detail)
+    }
 }
 
 /// 下载选项
@@ -65,38 +208,8 @@ impl DownloadOptions {
     }
 }
 
-/// 判断错误类型
-fn classify_error(error: &str, status_code: Option<u16>) -> ErrorType {
-    // 根据状态码判断
-    if let Some(code) = status_code {
-        match code {
-            404 | 403 | 401 => {
-                return ErrorType::Permanent(format!(
-                    "Resource not found or access denied (HTTP {code})"
-                ))
-            }
-            500..=599 => return ErrorType::Transient(format!("Server error (HTTP {code})")),
-            _ => {}
-        }
-    }
-
-    // 根据错误消息判断
-    let error_lower = error.to_lowercase();
-    if error_lower.contains("not found") || error_lower.contains("404") {
-        ErrorType::Permanent("Resource not found".to_string())
-    } else if error_lower.contains("timeout") || error_lower.contains("timed out") {
-        ErrorType::Transient("Connection timed out".to_string())
-    } else if error_lower.contains("network") || error_lower.contains("connection") {
-        ErrorType::Transient("Network connection issue".to_string())
-    } else if error_lower.contains("dns") || error_lower.contains("resolve") {
-        ErrorType::Transient("DNS resolution failed".to_string())
-    } else {
-        ErrorType::Transient(error.to_string())
-    }
-}
-
-/// 验证文件哈希
-async fn verify_file_sha256(path: &Path, expected: &str) -> Result<(), String> {
+/// 计算文件 SHA-256(小写十六进制)
+pub async fn file_sha256(path: &Path) -> Result<String, String> {
     let mut file = tokio::fs::File::open(path)
         .await
         .map_err(|e| e.to_string())?;
@@ -112,8 +225,12 @@ async fn verify_file_sha256(path: &Path, expected: &str) -> Result<(), String> {
         hasher.update(&buffer[0..n]);
     }
 
-    let result = hasher.finalize();
-    let actual = hex::encode(result);
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// 验证文件哈希
+async fn verify_file_sha256(path: &Path, expected: &str) -> Result<(), String> {
+    let actual = file_sha256(path).await?;
 
     if actual.eq_ignore_ascii_case(expected) {
         Ok(())
@@ -124,11 +241,28 @@ async fn verify_file_sha256(path: &Path, expected: &str) -> Result<(), String> {
     }
 }
 
+/// 归档旁的校验文件 `<file>.sha256`(sha256sum 格式,`--keep-archive` 写入)。
+pub fn checksum_sidecar(archive: &Path) -> std::path::PathBuf {
+    let mut name = archive.as_os_str().to_os_string();
+    name.push(".sha256");
+    std::path::PathBuf::from(name)
+}
+
+/// 读取校验文件里的哈希(首个字段);不存在或为空返回 `None`。
+fn read_checksum_sidecar(archive: &Path) -> Option<String> {
+    std::fs::read_to_string(checksum_sidecar(archive))
+        .ok()?
+        .split_whitespace()
+        .next()
+        .map(str::to_string)
+}
+
 /// 从配置加载下载选项
 pub fn load_download_options() -> DownloadOptions {
-    crate::infrastructure::config::Config::load()
-        .map(|config| DownloadOptions::from_config(&config.download))
-        .unwrap_or_else(|_| DownloadOptions::default())
+    let config = crate::infrastructure::config::Config::load()
+        .map(|config| config.download)
+        .unwrap_or_default();
+    DownloadOptions::from_config(&config)
 }
 
 pub async fn download_to_file(
@@ -141,6 +275,7 @@ pub async fn download_to_file(
     download_to_file_with_options(client, url, file_path, progress, options).await
 }
 
+#[tracing::instrument(skip_all, fields(url = %url))]
 pub async fn download_to_file_with_options(
     client: &Client,
     url: &str,
@@ -148,12 +283,21 @@ pub async fn download_to_file_with_options(
     progress: impl Fn(u64, u64),
     options: DownloadOptions,
 ) -> Result<(), String> {
+    crate::infrastructure::remote::offline::ensure_online(&format!("Downloading {url}"))?;
     let mut attempts = 0;
-    let mut last_status_code: Option<u16> = None;
 
     loop {
         attempts += 1;
-        match download_to_file_internal(client, url, file_path, &progress).await {
+        tracing::debug!(attempt = attempts, path = %file_path.display(), "downloading");
+        match download_to_file_internal(
+            client,
+            url,
+            file_path,
+            options.expected_sha256.as_deref(),
+            &progress,
+        )
+        .await
+        {
             Ok(_) => {
                 if let Some(expected) = &options.expected_sha256 {
                     if let Err(e) = verify_file_sha256(file_path, expected).await {
@@ -181,37 +325,23 @@ pub async fn download_to_file_with_options(
                 return Ok(());
             }
             Err(e) => {
-                // 尝试从错误消息中提取状态码
-                if e.contains("status code:") {
-                    if let Some(code_str) = e.split("status code:").nth(1) {
-                        if let Ok(code) = code_str
-                            .split_whitespace()
-                            .next()
-                            .unwrap_or("")
-                            .parse::<u16>()
-                        {
-                            last_status_code = Some(code);
-                        }
-                    }
-                }
-
+                tracing::warn!(attempt = attempts, error = %e, "download attempt failed");
                 // 尝试删除可能未完成的文件
                 let _ = tokio::fs::remove_file(file_path).await;
 
-                let error_type = classify_error(&e, last_status_code);
-
-                // 永久错误不重试
-                if let ErrorType::Permanent(msg) = error_type {
-                    return Err(format!("{msg}: {e} (URL: {url})"));
+                // DNS / 连接 / TLS / 4xx 重试同一来源无益,交给上层换镜像
+                if !e.kind.retry_same_source() {
+                    return Err(format!("{e} (URL: {url})\n  hint: {}", e.kind.hint()));
                 }
 
                 if attempts > options.retry_count {
                     return Err(format!(
-                        "Download failed (retried {} times): {}. URL: {}, file: {}",
+                        "Download failed (retried {} times): {}. URL: {}, file: {}\n  hint: {}",
                         options.retry_count,
                         e,
                         url,
-                        file_path.display()
+                        file_path.display(),
+                        e.kind.hint()
                     ));
                 }
 
@@ -229,71 +359,124 @@ pub async fn download_to_file_with_options(
     }
 }
 
+/// 写入 `*.downloading` 临时文件,完成后改名为目标文件。
+///
+/// 旁边的 [`PartState`] 记录与当前 URL / 总大小一致时带 `Range` 续传;读流中断或
+/// Ctrl-C 时保留临时文件和记录,供下一次尝试(或下一次运行)接着下。
 async fn download_to_file_internal(
     client: &Client,
     url: &str,
     file_path: &Path,
+    expected_sha256: Option<&str>,
     progress: &impl Fn(u64, u64),
-) -> Result<(), String> {
-    let response = client
-        .get(url)
-        .header("User-Agent", USER_AGENT)
-        .send()
-        .await
-        .map_err(|e| {
-            let error_msg = e.to_string();
-            if error_msg.contains("timeout") {
-                format!("Connection timed out: {error_msg}")
-            } else if error_msg.contains("dns") || error_msg.contains("resolve") {
-                format!("DNS resolution failed: {error_msg}")
-            } else {
-                format!("Network request failed: {error_msg} (URL: {url})")
-            }
-        })?;
+) -> Result<(), AttemptError> {
+    let temp_path = partial::partial_file(file_path);
+    let previous = PartState::load(file_path);
+    let offset = previous
+        .as_ref()
+        .and_then(|state| state.resume_offset(url, &temp_path));
+
+    let mut request = client.get(url).header("User-Agent", USER_AGENT);
+    if let Some(offset) = offset {
+        request = request.header(reqwest::header::RANGE, format!("bytes={offset}-"));
+    }
+    let response = request.send().await.map_err(AttemptError::from_reqwest)?;
 
     let status = response.status();
     if !status.is_success() {
-        return Err(format!(
-            "Server returned status code: {status} (URL: {url})"
+        return Err(AttemptError::new(
+            FailureKind::from_status(status.as_u16()),
+            format!("server returned {status}"),
         ));
     }
 
-    let total_size = response.content_length().unwrap_or(0);
-    let mut downloaded = 0u64;
+    // 服务器忽略 Range(200)或返回的范围 / 总大小与记录不符时从头下载
+    let resumed = match (offset, &previous) {
+        (Some(offset), Some(prev)) if status == reqwest::StatusCode::PARTIAL_CONTENT => response
+            .headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(partial::parse_content_range)
+            .filter(|&range| range == (offset, prev.expected_size))
+            .map(|_| (offset, prev.expected_size)),
+        _ => None,
+    };
+    let (mut downloaded, total_size) =
+        resumed.unwrap_or((0, response.content_length().unwrap_or(0)));
+    match resumed {
+        Some((offset, _)) => tracing::debug!(offset, "resuming download"),
+        None if offset.is_some() => tracing::debug!("server refused resume, restarting"),
+        None => {}
+    }
     let mut stream = response.bytes_stream();
 
-    // 使用临时文件
-    let temp_path = file_path.with_extension("downloading");
-    let mut file = tokio::fs::File::create(&temp_path)
-        .await
-        .map_err(|e| format!("Failed to create file: {e}"))?;
+    // 临时文件和记录不登记为中断清理对象:Ctrl-C 后保留,下次运行接着下
+    let mut file = if resumed.is_some() {
+        tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(&temp_path)
+            .await
+    } else {
+        tokio::fs::File::create(&temp_path).await
+    }
+    .map_err(|e| AttemptError::new(FailureKind::Io, format!("create file: {e}")))?;
+
+    let mut state = PartState {
+        url: url.to_string(),
+        expected_size: total_size,
+        bytes_written: downloaded,
+        sha256: expected_sha256.map(str::to_string),
+    };
+    let save = |state: &PartState| {
+        if let Err(e) = state.save(file_path) {
+            tracing::debug!(error = %e, "failed to write download state");
+        }
+    };
+    save(&state);
 
     while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| format!("Failed to read data: {e}"))?;
-        downloaded += chunk.len() as u64;
-        progress(downloaded, total_size);
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                // 读流中断:记下已写字节,保留临时文件以便续传
+                let _ = file.flush().await;
+                state.bytes_written = downloaded;
+                save(&state);
+                return Err(AttemptError::from_reqwest(e));
+            }
+        };
         file.write_all(&chunk)
             .await
-            .map_err(|e| format!("Failed to write file: {e}"))?;
+            .map_err(|e| AttemptError::new(FailureKind::Io, format!("write file: {e}")))?;
+        downloaded += chunk.len() as u64;
+        progress(downloaded, total_size);
+        if downloaded - state.bytes_written >= partial::SAVE_EVERY_BYTES {
+            state.bytes_written = downloaded;
+            save(&state);
+        }
     }
 
     file.flush()
         .await
-        .map_err(|e| format!("Failed to flush file: {e}"))?;
+        .map_err(|e| AttemptError::new(FailureKind::Io, format!("flush file: {e}")))?;
     drop(file); // 关闭文件
 
     // 重命名为目标文件
     tokio::fs::rename(&temp_path, file_path)
         .await
-        .map_err(|e| format!("Failed to rename file: {e}"))?;
+        .map_err(|e| AttemptError::new(FailureKind::Io, format!("rename file: {e}")))?;
+    // 下载完成,记录不再需要
+    PartState::remove(file_path);
 
     Ok(())
 }
 
+#[tracing::instrument(skip_all, fields(url = %url, file_name = %file_name))]
 pub async fn download_with_cache(
     client: &Client,
     url: &str,
     file_name: &str,
+    options: DownloadOptions,
     progress_callback: Box<dyn Fn(u64, u64) + Send + Sync>,
 ) -> Result<crate::infrastructure::remote::DownloadTarget, String> {
     crate::cli::print::step("Source", url);
@@ -305,8 +488,22 @@ pub async fn download_with_cache(
 
     let file_path = cache_dir.join(file_name);
 
+    // 缓存文件与注册表 / 保留时记录的校验和不符时丢弃重下
+    let cached_expected = options
+        .expected_sha256
+        .clone()
+        .or_else(|| read_checksum_sidecar(&file_path));
+    if let (Some(expected), true) = (cached_expected, file_path.exists()) {
+        if let Err(e) = verify_file_sha256(&file_path, &expected).await {
+            tracing::warn!(path = %file_path.display(), error = %e, "cached file checksum mismatch");
+            let _ = tokio::fs::remove_file(&file_path).await;
+            let _ = tokio::fs::remove_file(checksum_sidecar(&file_path)).await;
+        }
+    }
+
     if let Ok(metadata) = tokio::fs::metadata(&file_path).await {
         if metadata.len() > 0 {
+            tracing::debug!(path = %file_path.display(), "download cache hit");
             crate::cli::print::step(
                 "Status",
                 &format!("Using cached file ({} MB)", metadata.len() / (1024 * 1024)),
@@ -323,7 +520,7 @@ pub async fn download_with_cache(
         }
     }
 
-    download_to_file(client, url, &file_path, progress_callback).await?;
+    download_to_file_with_options(client, url, &file_path, progress_callback, options).await?;
 
     let file_size = tokio::fs::metadata(&file_path)
         .await
@@ -344,3 +541,231 @@ pub async fn download_with_cache(
             .to_string(),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(msgs: &[&str]) -> Vec<String> {
+        msgs.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn classify_status_codes() {
+        assert_eq!(
+            FailureKind::classify(Some(404), false, false, &[]),
+            FailureKind::Client(404)
+        );
+        assert_eq!(
+            FailureKind::classify(Some(503), false, false, &[]),
+            FailureKind::Server(503)
+        );
+        assert!(!FailureKind::Client(404).retry_same_source());
+        assert!(FailureKind::Client(429).retry_same_source());
+        assert!(FailureKind::Server(503).retry_same_source());
+    }
+
+    #[test]
+    fn classify_dns_and_tls_from_source_chain() {
+        let dns = chain(&[
+            "client error (Connect)",
+            "dns error: failed to lookup address information: Name or service not known",
+        ]);
+        // DNS 失败 reqwest 也会标记 is_connect,需先于 Connect 判断
+        assert_eq!(
+            FailureKind::classify(None, false, true, &dns),
+            FailureKind::Dns
+        );
+        let tls = chain(&[
+            "client error (Connect)",
+            "error:0A000086:SSL routines:tls_post_process_server_certificate:certificate verify failed",
+        ]);
+        assert_eq!(
+            FailureKind::classify(None, false, true, &tls),
+            FailureKind::Tls
+        );
+        for kind in [FailureKind::Dns, FailureKind::Tls, FailureKind::Connect] {
+            assert!(!kind.retry_same_source(), "{kind:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn classify_real_connect_refused_and_timeout() {
+        // 绑定后立即释放端口 → 连接被拒绝
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let err = Client::new()
+            .get(format!("http://127.0.0.1:{port}/jdk.tar.gz"))
+            .send()
+            .await
+            .unwrap_err();
+        assert_eq!(FailureKind::from_reqwest(&err), FailureKind::Connect);
+
+        // 接受连接但从不响应 → 超时
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let err = Client::builder()
+            .timeout(std::time::Duration::from_millis(200))
+            .build()
+            .unwrap()
+            .get(format!("http://{addr}/"))
+            .send()
+            .await
+            .unwrap_err();
+        assert_eq!(FailureKind::from_reqwest(&err), FailureKind::Timeout);
+        drop(listener);
+    }
+
+    #[tokio::test]
+    async fn resumes_from_matching_part_state_and_deletes_it() {
+        use httpmock::prelude::*;
+        let server = MockServer::start();
+        let resume = server.mock(|when, then| {
+            when.method(GET)
+                .path("/jdk.tar.gz")
+                .header("range", "bytes=4-");
+            then.status(206)
+                .header("content-range", "bytes 4-9/10")
+                .body("456789");
+        });
+        let url = server.url("/jdk.tar.gz");
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let archive = tmp.path().join("jdk.tar.gz");
+        std::fs::write(partial::partial_file(&archive), "0123").unwrap();
+        PartState {
+            url: url.clone(),
+            expected_size: 10,
+            bytes_written: 4,
+            sha256: None,
+        }
+        .save(&archive)
+        .unwrap();
+
+        let options = DownloadOptions {
+            retry_count: 0,
+            ..DownloadOptions::default()
+        };
+        download_to_file_with_options(&Client::new(), &url, &archive, |_, _| {}, options)
+            .await
+            .unwrap();
+
+        resume.assert();
+        assert_eq!(std::fs::read_to_string(&archive).unwrap(), "0123456789");
+        assert!(!partial::part_sidecar(&archive).exists());
+        assert!(!partial::partial_file(&archive).exists());
+    }
+
+    #[tokio::test]
+    async fn restarts_when_part_state_is_for_another_url() {
+        use httpmock::prelude::*;
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/jdk.tar.gz");
+            then.status(200).body("0123456789");
+        });
+        let url = server.url("/jdk.tar.gz");
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let archive = tmp.path().join("jdk.tar.gz");
+        std::fs::write(partial::partial_file(&archive), "xxxx").unwrap();
+        PartState {
+            url: server.url("/other.tar.gz"),
+            expected_size: 10,
+            bytes_written: 4,
+            sha256: None,
+        }
+        .save(&archive)
+        .unwrap();
+
+        let options = DownloadOptions {
+            retry_count: 0,
+            ..DownloadOptions::default()
+        };
+        download_to_file_with_options(&Client::new(), &url, &archive, |_, _| {}, options)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read_to_string(&archive).unwrap(), "0123456789");
+        assert!(!partial::part_sidecar(&archive).exists());
+    }
+
+    #[tokio::test]
+    async fn interrupted_download_keeps_partial_and_resumes() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        // 第一个连接只发前半段就挂起(模拟下载中途 Ctrl-C);之后的连接按 Range 补齐
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/jdk.tar.gz", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for (i, stream) in listener.incoming().enumerate() {
+                let mut stream = stream.unwrap();
+                let mut request = [0u8; 1024];
+                let n = stream.read(&mut request).unwrap();
+                let request = String::from_utf8_lossy(&request[..n]).to_ascii_lowercase();
+                if i == 0 {
+                    stream
+                        .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 10\r\n\r\n01234")
+                        .unwrap();
+                    std::thread::spawn(move || {
+                        std::thread::sleep(std::time::Duration::from_secs(30));
+                        drop(stream);
+                    });
+                } else {
+                    assert!(request.contains("range: bytes=5-"), "{request}");
+                    stream
+                        .write_all(
+                            b"HTTP/1.1 206 Partial Content\r\ncontent-range: bytes 5-9/10\r\n\
+                              content-length: 5\r\n\r\n56789",
+                        )
+                        .unwrap();
+                }
+            }
+        });
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let archive = tmp.path().join("jdk.tar.gz");
+        let options = || DownloadOptions {
+            retry_count: 0,
+            ..DownloadOptions::default()
+        };
+
+        let task = tokio::spawn({
+            let (url, archive) = (url.clone(), archive.clone());
+            async move {
+                download_to_file_with_options(&Client::new(), &url, &archive, |_, _| {}, options())
+                    .await
+            }
+        });
+        let temp_path = partial::partial_file(&archive);
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while std::fs::metadata(&temp_path).map_or(0, |m| m.len()) < 5 {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "download never started"
+            );
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        // 中断:任务被取消,进行中的状态全部丢弃
+        task.abort();
+        let _ = task.await;
+
+        assert_eq!(std::fs::read_to_string(&temp_path).unwrap(), "01234");
+        let state = PartState::load(&archive).expect("sidecar kept after interrupt");
+        assert_eq!(
+            (state.url.as_str(), state.expected_size),
+            (url.as_str(), 10)
+        );
+
+        download_to_file_with_options(&Client::new(), &url, &archive, |_, _| {}, options())
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&archive).unwrap(), "0123456789");
+        assert!(!partial::part_sidecar(&archive).exists());
+        assert!(!temp_path.exists());
+    }
+}
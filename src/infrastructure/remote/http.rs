@@ -0,0 +1,212 @@
+//! 共享的 reqwest 客户端构建:统一注入下载相关的 TLS / 超时配置。
+//!
+//! 同一运行时内共用一个懒加载的 `Client`(内部是 Arc,clone 共享连接池),
+//! 多次版本查询 / 镜像探测 / 下载复用同一批 TLS 连接。代理沿用 reqwest 默认,
+//! 读取 `HTTP(S)_PROXY` / `NO_PROXY` 环境变量。
+//!
+//! 企业 TLS 拦截代理会用自签 CA 重签证书,reqwest 默认拒绝。这里按
+//! `FNVA_CA_CERT` 环境变量 > `download.ca_cert_path` 的顺序加载额外根证书
+//! (PEM,失败再按 DER 解析);`download.danger_accept_invalid_certs` 是最后手段,
+//! 开启时每个进程都会在 stderr 醒目警告。
+
+use crate::infrastructure::config::{Config, DownloadConfig};
+use reqwest::{Certificate, Client, ClientBuilder};
+use std::sync::{Arc, Mutex, Once, Weak};
+use std::time::Duration;
+use tokio::runtime;
+
+/// 额外根证书路径的环境变量(优先于配置)
+pub const CA_CERT_ENV_VAR: &str = "FNVA_CA_CERT";
+
+/// 按配置生成 builder,调用方可再追加 timeout 等选项。
+pub fn client_builder(config: &DownloadConfig) -> Result<ClientBuilder, String> {
+    let mut builder = Client::builder().user_agent(concat!("fnva/", env!("CARGO_PKG_VERSION")));
+    // 旧版本写出的配置里超时可能是 0,按不限时处理
+    if config.connect_timeout_sec > 0 {
+        builder = builder.connect_timeout(Duration::from_secs(config.connect_timeout_sec));
+    }
+    if config.read_timeout_sec > 0 {
+        builder = builder.read_timeout(Duration::from_secs(config.read_timeout_sec));
+    }
+    if let Some(cert) = extra_root_cert(config)? {
+        builder = builder.add_root_certificate(cert);
+    }
+    if config.danger_accept_invalid_certs {
+        warn_insecure_once();
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    Ok(builder)
+}
+
+/// 按运行时缓存的客户端。
+struct SharedClient {
+    runtime: runtime::Id,
+    /// 运行时关闭时随挂起的任务一起释放(运行时 id 可能被复用,不能只比 id)
+    alive: Weak<()>,
+    client: Client,
+}
+
+/// 共享的客户端,首次调用时按配置构建。
+///
+/// 池里的连接挂在建立它的 tokio 运行时上,运行时关闭后(如各个 `#[tokio::test]`)
+/// 旧连接就不能再用,所以只在同一个仍在运行的运行时里复用;不在运行时里调用时每次新建。
+pub fn http_client() -> Client {
+    static SHARED: Mutex<Option<SharedClient>> = Mutex::new(None);
+
+    let Ok(handle) = runtime::Handle::try_current() else {
+        return build_http_client();
+    };
+    let mut shared = SHARED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(s) = &*shared {
+        if s.runtime == handle.id() && s.alive.strong_count() > 0 {
+            return s.client.clone();
+        }
+    }
+    let client = build_http_client();
+    let token = Arc::new(());
+    let alive = Arc::downgrade(&token);
+    handle.spawn(async move {
+        let _token = token;
+        std::future::pending::<()>().await
+    });
+    *shared = Some(SharedClient {
+        runtime: handle.id(),
+        alive,
+        client: client.clone(),
+    });
+    client
+}
+
+/// 按给定的下载配置构建独立的客户端(如带 `--timeout` 覆盖的安装),不进共享缓存。
+pub fn client_for(config: &DownloadConfig) -> Result<Client, String> {
+    client_builder(config)?.build().map_err(|e| e.to_string())
+}
+
+/// 读取配置构建客户端;证书加载失败时警告并退回默认客户端,不中断命令。
+fn build_http_client() -> Client {
+    let config = Config::load().map(|c| c.download).unwrap_or_default();
+    match client_for(&config) {
+        Ok(client) => client,
+        Err(e) => {
+            crate::cli::print::warn(&format!("Ignoring TLS settings: {e}"));
+            Client::new()
+        }
+    }
+}
+
+/// 加载额外根证书;未配置时返回 `None`。
+fn extra_root_cert(config: &DownloadConfig) -> Result<Option<Certificate>, String> {
+    let path = std::env::var(CA_CERT_ENV_VAR)
+        .ok()
+        .filter(|p| !p.is_empty())
+        .or_else(|| config.ca_cert_path.clone());
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    let bytes =
+        std::fs::read(&path).map_err(|e| format!("Failed to read CA certificate {path}: {e}"))?;
+    Certificate::from_pem(&bytes)
+        .or_else(|_| Certificate::from_der(&bytes))
+        .map(Some)
+        .map_err(|e| format!("Invalid CA certificate {path}: {e}"))
+}
+
+fn warn_insecure_once() {
+    static WARNED: Once = Once::new();
+    WARNED.call_once(|| {
+        crate::cli::print::warn(
+            "TLS certificate verification is DISABLED (download.danger_accept_invalid_certs); \
+             downloads can be tampered with",
+        );
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_CA_PEM: &str = "-----BEGIN CERTIFICATE-----
+MIIBhTCCASugAwIBAgIUbKuoP7+QvCZjyRMX2ux0Esr1i3EwCgYIKoZIzj0EAwIw
+FzEVMBMGA1UEAwwMZm52YS10ZXN0LWNhMCAXDTI2MTAxNjA4MTMwN1oYDzIxMjYw
+OTIyMDgxMzA3WjAXMRUwEwYDVQQDDAxmbnZhLXRlc3QtY2EwWTATBgcqhkjOPQIB
+BggqhkjOPQMBBwNCAASlIdmjCzRIiCFLq2M9Thr9um656FHGNF5D6K3vikQHXbsQ
+0ZFT24MIe7NDVub/CruPrJnj/gtvrB/o3M96gvZyo1MwUTAdBgNVHQ4EFgQUWk6f
+CVq59Dr+Tl3rlkYTjsugeqUwHwYDVR0jBBgwFoAUWk6fCVq59Dr+Tl3rlkYTjsug
+eqUwDwYDVR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNIADBFAiEA72M05QMy4Pw1
+JRQkx3Bwq429Etetoj2s7am6Sfos7gwCIFnGWG55hLEjV60Gp6shJj66uwfX01Ho
+1OD0t93OMwXO
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn custom_ca_cert_is_loaded_into_client() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("corp-ca.pem");
+        std::fs::write(&path, TEST_CA_PEM).unwrap();
+        let config = DownloadConfig {
+            ca_cert_path: Some(path.to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+
+        assert!(extra_root_cert(&config).unwrap().is_some());
+        client_builder(&config).unwrap().build().unwrap();
+    }
+
+    /// 两次 `http_client()` 共用连接池:对 keep-alive 服务器只建立一条 TCP 连接。
+    #[tokio::test]
+    async fn shared_client_reuses_connections() {
+        use std::io::{Read, Write};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let counter = accepted.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                counter.fetch_add(1, Ordering::SeqCst);
+                std::thread::spawn(move || {
+                    let mut buf = [0u8; 4096];
+                    let mut pending = Vec::new();
+                    while let Ok(n) = stream.read(&mut buf) {
+                        if n == 0 {
+                            break;
+                        }
+                        pending.extend_from_slice(&buf[..n]);
+                        while let Some(end) = pending.windows(4).position(|w| w == b"\r\n\r\n") {
+                            pending.drain(..end + 4);
+                            let _ =
+                                stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok");
+                        }
+                    }
+                });
+            }
+        });
+
+        for _ in 0..2 {
+            let body = http_client()
+                .get(&url)
+                .send()
+                .await
+                .unwrap()
+                .text()
+                .await
+                .unwrap();
+            assert_eq!(body, "ok");
+        }
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn invalid_ca_cert_reports_path() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("missing.pem");
+        let config = DownloadConfig {
+            ca_cert_path: Some(path.to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+        let err = client_builder(&config).err().unwrap();
+        assert!(err.contains("missing.pem"));
+    }
+}
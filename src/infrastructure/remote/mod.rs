@@ -1,8 +1,14 @@
 pub mod cache;
 pub mod download;
+pub mod http;
 pub mod java_downloader;
 pub mod mirror_utils;
+pub mod network_cache;
+pub mod offline;
+pub mod partial;
 pub mod platform;
+pub mod self_update;
+pub mod signature;
 
 pub use platform::Platform;
 
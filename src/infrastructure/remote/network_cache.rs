@@ -0,0 +1,136 @@
+//! 镜像连通性探测结果的短期缓存 `~/.fnva/network_cache.json`。
+//!
+//! `fnva doctor --network` 每次探测后写入;带 `--cached` 时,同一 URL 的结果在
+//! [`NETWORK_CACHE_TTL_SECS`] 内直接复用,不发请求,避免频繁调用时反复打镜像。
+
+use super::cache::CacheEntry;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+/// 缓存有效期(秒)
+pub const NETWORK_CACHE_TTL_SECS: u64 = 300;
+
+/// 一次镜像探测的结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProbeResult {
+    pub url: String,
+    /// 收到响应时的 HTTP 状态码
+    pub status: Option<u16>,
+    /// 请求失败的原因
+    pub error: Option<String>,
+}
+
+/// 缓存文件路径
+pub fn cache_file() -> Result<PathBuf, String> {
+    Ok(crate::infrastructure::paths::fnva_dir()?.join("network_cache.json"))
+}
+
+/// 读取 `url` 未过期的缓存结果;文件缺失、损坏、过期或 URL 不同都返回 `None`。
+pub fn load_fresh(path: &Path, url: &str) -> Option<CacheEntry<ProbeResult>> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let entry: CacheEntry<ProbeResult> = serde_json::from_str(&content).ok()?;
+    (entry.is_valid() && entry.data.url == url).then_some(entry)
+}
+
+/// 写入缓存;失败只影响下次能否复用,静默忽略。
+pub fn save(path: &Path, result: &ProbeResult) {
+    let entry = CacheEntry::new(result.clone(), NETWORK_CACHE_TTL_SECS);
+    if let Ok(json) = serde_json::to_string_pretty(&entry) {
+        let _ = std::fs::write(path, json);
+    }
+}
+
+/// `cached` 且有新鲜结果时直接返回(第二项为缓存的秒龄);否则调用 `probe` 并写入缓存。
+pub async fn probe_with_cache<F, Fut>(
+    path: &Path,
+    url: &str,
+    cached: bool,
+    probe: F,
+) -> (ProbeResult, Option<u64>)
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = ProbeResult>,
+{
+    if cached {
+        if let Some(entry) = load_fresh(path, url) {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            return (entry.data, Some(now.saturating_sub(entry.timestamp)));
+        }
+    }
+    let result = probe().await;
+    save(path, &result);
+    (result, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    const URL: &str = "https://mirror.example/";
+
+    fn reachable() -> ProbeResult {
+        ProbeResult {
+            url: URL.to_string(),
+            status: Some(200),
+            error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn cached_call_reuses_fresh_result_without_probing() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("network_cache.json");
+        let probes = AtomicU32::new(0);
+        let probe = || async {
+            probes.fetch_add(1, Ordering::SeqCst);
+            reachable()
+        };
+
+        // 没有缓存:即使 --cached 也要探测,并写入缓存
+        let (first, age) = probe_with_cache(&path, URL, true, probe).await;
+        assert_eq!((first, age), (reachable(), None));
+        assert_eq!(probes.load(Ordering::SeqCst), 1);
+
+        let (second, age) = probe_with_cache(&path, URL, true, probe).await;
+        assert_eq!(second, reachable());
+        assert!(age.is_some());
+        assert_eq!(
+            probes.load(Ordering::SeqCst),
+            1,
+            "second call hit the network"
+        );
+
+        // 不带 --cached 或换了 URL:重新探测
+        probe_with_cache(&path, URL, false, probe).await;
+        probe_with_cache(&path, "https://other.example/", true, || async {
+            probes.fetch_add(1, Ordering::SeqCst);
+            reachable()
+        })
+        .await;
+        assert_eq!(probes.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn expired_or_corrupt_cache_is_ignored() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("network_cache.json");
+        let stale = CacheEntry {
+            data: reachable(),
+            timestamp: 0,
+            ttl: NETWORK_CACHE_TTL_SECS,
+        };
+        std::fs::write(&path, serde_json::to_string(&stale).unwrap()).unwrap();
+        assert!(load_fresh(&path, URL).is_none());
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(load_fresh(&path, URL).is_none());
+
+        save(&path, &reachable());
+        assert_eq!(load_fresh(&path, URL).unwrap().data, reachable());
+    }
+}
@@ -0,0 +1,38 @@
+//! 离线模式(`--offline` / `FNVA_OFFLINE=1`):保证 fnva 不发起任何网络请求。
+//!
+//! 命令入口对需要联网的操作(安装、刷新、注册表更新、镜像探测)先调用
+//! [`ensure_online`] 给出明确报错;版本发现只读缓存与本地注册表 / 嵌入表,
+//! 目录抓取与文件下载再各兜一层底,遗漏的路径也不会真正发出请求。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 开启离线模式的环境变量(`1` / `true`)
+pub const OFFLINE_ENV_VAR: &str = "FNVA_OFFLINE";
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// 由 `--offline` 全局参数设置。
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// `--offline` 或 `FNVA_OFFLINE=1` 任一开启即为离线模式。
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+        || std::env::var(OFFLINE_ENV_VAR)
+            .is_ok_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true"))
+}
+
+/// 离线模式下拒绝需要联网的操作。
+pub fn ensure_online(what: &str) -> Result<(), String> {
+    if is_offline() {
+        return Err(offline_error(what));
+    }
+    Ok(())
+}
+
+pub fn offline_error(what: &str) -> String {
+    format!(
+        "{what} needs network access, but fnva is in offline mode (--offline / {OFFLINE_ENV_VAR})"
+    )
+}
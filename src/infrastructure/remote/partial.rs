@@ -0,0 +1,154 @@
+//! 断点续传的进度记录 `<archive>.fnva-part.json`。
+//!
+//! 下载期间与临时文件(`*.downloading`)并存,定期写入 URL、总大小、已写字节数和
+//! 预期校验和;下一次下载同一文件时据此决定是否带 `Range` 续传,完成后删除。
+//! `fnva doctor` 也靠它列出卡住的下载。
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 已写字节每增加这么多就刷新一次记录
+pub const SAVE_EVERY_BYTES: u64 = 4 * 1024 * 1024;
+
+const SUFFIX: &str = ".fnva-part.json";
+
+/// 一次未完成下载的状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartState {
+    pub url: String,
+    /// 服务器给出的总大小;0 表示未知(不能续传)
+    pub expected_size: u64,
+    pub bytes_written: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+/// `archive` 对应的记录文件路径
+pub fn part_sidecar(archive: &Path) -> PathBuf {
+    let mut name = archive.as_os_str().to_os_string();
+    name.push(SUFFIX);
+    PathBuf::from(name)
+}
+
+/// 记录文件对应的下载目标;不是记录文件时返回 `None`。
+pub fn archive_of(sidecar: &Path) -> Option<PathBuf> {
+    let name = sidecar.to_str()?.strip_suffix(SUFFIX)?;
+    Some(PathBuf::from(name))
+}
+
+/// 下载目标的临时文件(与 `download_to_file` 写入的路径一致)
+pub fn partial_file(archive: &Path) -> PathBuf {
+    archive.with_extension("downloading")
+}
+
+impl PartState {
+    /// 读取 `archive` 的记录;不存在或损坏时返回 `None`。
+    pub fn load(archive: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(part_sidecar(archive)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&self, archive: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(part_sidecar(archive), json)
+    }
+
+    pub fn remove(archive: &Path) {
+        let _ = std::fs::remove_file(part_sidecar(archive));
+    }
+
+    /// 续传起点:URL 相同、总大小已知,且临时文件不短于记录的字节数、不超过总大小。
+    ///
+    /// 记录是定期刷新的,临时文件可能比记录多写了一些,从文件实际长度接着下。
+    pub fn resume_offset(&self, url: &str, partial: &Path) -> Option<u64> {
+        if self.url != url || self.expected_size == 0 {
+            return None;
+        }
+        let len = std::fs::metadata(partial).ok()?.len();
+        (len > 0 && len >= self.bytes_written && len < self.expected_size).then_some(len)
+    }
+}
+
+/// 目录下所有未完成下载的记录(下载目标 + 状态),按路径排序。
+pub fn list(dir: &Path) -> Vec<(PathBuf, PartState)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut parts: Vec<_> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let archive = archive_of(&entry.path())?;
+            let state = PartState::load(&archive)?;
+            Some((archive, state))
+        })
+        .collect();
+    parts.sort_by(|a, b| a.0.cmp(&b.0));
+    parts
+}
+
+/// 解析 `Content-Range: bytes start-end/total`,返回 `(start, total)`。
+pub fn parse_content_range(value: &str) -> Option<(u64, u64)> {
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let (start, _) = range.split_once('-')?;
+    Some((start.trim().parse().ok()?, total.trim().parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(bytes_written: u64) -> PartState {
+        PartState {
+            url: "https://mirror.example/jdk-17.tar.gz".to_string(),
+            expected_size: 100,
+            bytes_written,
+            sha256: Some("abc123".to_string()),
+        }
+    }
+
+    #[test]
+    fn sidecar_is_created_validated_and_deleted() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let archive = tmp.path().join("jdk-17.tar.gz");
+        let partial = partial_file(&archive);
+        assert_eq!(
+            part_sidecar(&archive),
+            tmp.path().join("jdk-17.tar.gz.fnva-part.json")
+        );
+        assert_eq!(archive_of(&part_sidecar(&archive)), Some(archive.clone()));
+
+        state(40).save(&archive).unwrap();
+        assert_eq!(PartState::load(&archive), Some(state(40)));
+        assert_eq!(list(tmp.path()), vec![(archive.clone(), state(40))]);
+
+        let saved = state(40);
+        // 临时文件缺失或比记录短:不续传
+        assert_eq!(saved.resume_offset(&saved.url, &partial), None);
+        std::fs::write(&partial, [0u8; 30]).unwrap();
+        assert_eq!(saved.resume_offset(&saved.url, &partial), None);
+        // 比记录多写了一些:从实际长度续传
+        std::fs::write(&partial, [0u8; 45]).unwrap();
+        assert_eq!(saved.resume_offset(&saved.url, &partial), Some(45));
+        // URL 变了或总大小未知:不续传
+        assert_eq!(
+            saved.resume_offset("https://other/jdk.tar.gz", &partial),
+            None
+        );
+        let unknown = PartState {
+            expected_size: 0,
+            ..state(40)
+        };
+        assert_eq!(unknown.resume_offset(&unknown.url, &partial), None);
+
+        PartState::remove(&archive);
+        assert_eq!(PartState::load(&archive), None);
+        assert!(list(tmp.path()).is_empty());
+    }
+
+    #[test]
+    fn parses_content_range() {
+        assert_eq!(parse_content_range("bytes 45-99/100"), Some((45, 100)));
+        assert_eq!(parse_content_range("bytes 45-99/*"), None);
+        assert_eq!(parse_content_range("items 0-1/2"), None);
+    }
+}
@@ -44,6 +44,14 @@ impl Platform {
         }
     }
 
+    /// Adoptium 目录与文件名里的操作系统名:macOS 写作 `mac`,其余与 `os` 相同。
+    pub fn adoptium_os(os: &str) -> &str {
+        match os {
+            "macos" => "mac",
+            other => other,
+        }
+    }
+
     /// 生成 key 供下载 URL 查找使用。
     pub fn key(&self) -> String {
         format!("{}-{}", self.os, self.arch)
@@ -0,0 +1,137 @@
+//! `fnva self update-check`:查询 GitHub 最新发布,与编译时版本比较。
+//!
+//! 只报告,不下载;请求走共享 HTTP 客户端(代理 / CA 配置同下载),
+//! 离线模式下直接拒绝。
+
+use serde::{Deserialize, Serialize};
+
+/// GitHub 最新发布接口
+pub const RELEASES_API_URL: &str =
+    "https://api.github.com/repos/Protagonistss/fnva/releases/latest";
+
+/// 覆盖发布接口地址的环境变量(镜像 / 测试用)
+pub const RELEASES_URL_ENV_VAR: &str = "FNVA_RELEASES_URL";
+
+/// 当前编译的版本
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+}
+
+/// 检查结果
+#[derive(Debug, Serialize)]
+pub struct UpdateCheck {
+    pub current: String,
+    pub latest: String,
+    pub update_available: bool,
+    pub url: String,
+}
+
+/// 发布接口地址:环境变量优先。
+pub fn releases_url() -> String {
+    std::env::var(RELEASES_URL_ENV_VAR)
+        .ok()
+        .filter(|u| !u.trim().is_empty())
+        .unwrap_or_else(|| RELEASES_API_URL.to_string())
+}
+
+/// 拉取最新发布并与 `current` 比较。
+pub async fn check_for_update(url: &str, current: &str) -> Result<UpdateCheck, String> {
+    super::offline::ensure_online("Checking for fnva updates")?;
+    let resp = super::http::http_client()
+        .get(url)
+        .header("Accept", "application/vnd.github+json")
+        .timeout(std::time::Duration::from_secs(15))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to query {url}: {e}"))?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(format!("Failed to query {url}: HTTP {status}"));
+    }
+    let release: Release = resp
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected release data from {url}: {e}"))?;
+    let latest = release.tag_name.trim_start_matches('v').to_string();
+    Ok(UpdateCheck {
+        current: current.to_string(),
+        update_available: is_newer(&latest, current),
+        latest,
+        url: release.html_url,
+    })
+}
+
+/// `latest` 是否比 `current` 新:按点分数字逐段比较,预发布后缀(`-rc.1`)忽略。
+fn is_newer(latest: &str, current: &str) -> bool {
+    let parts = |v: &str| -> Vec<u64> {
+        v.trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect()
+    };
+    let (mut latest, mut current) = (parts(latest), parts(current));
+    let len = latest.len().max(current.len());
+    latest.resize(len, 0);
+    current.resize(len, 0);
+    latest > current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+
+    #[test]
+    fn compares_dotted_versions_numerically() {
+        assert!(is_newer("0.0.100", "0.0.90"));
+        assert!(is_newer("v0.1", "0.0.90"));
+        assert!(!is_newer("0.0.90", "0.0.90"));
+        assert!(!is_newer("0.0.89", "0.0.90"));
+        assert!(!is_newer("0.0.90-rc.1", "0.0.90"));
+    }
+
+    #[tokio::test]
+    async fn reports_newer_release_from_mocked_api() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/releases/latest");
+            then.status(200).json_body(serde_json::json!({
+                "tag_name": "v0.1.0",
+                "html_url": "https://github.com/Protagonistss/fnva/releases/tag/v0.1.0",
+                "assets": []
+            }));
+        });
+        let check = check_for_update(&server.url("/releases/latest"), "0.0.90")
+            .await
+            .unwrap();
+        assert!(check.update_available);
+        assert_eq!(check.latest, "0.1.0");
+        assert!(check.url.ends_with("/tag/v0.1.0"));
+
+        let up_to_date = check_for_update(&server.url("/releases/latest"), "0.1.0")
+            .await
+            .unwrap();
+        assert!(!up_to_date.update_available);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_response_is_an_error() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/releases/latest");
+            then.status(403)
+                .json_body(serde_json::json!({ "message": "API rate limit exceeded" }));
+        });
+        let err = check_for_update(&server.url("/releases/latest"), "0.0.90")
+            .await
+            .unwrap_err();
+        assert!(err.contains("403"), "{err}");
+    }
+}
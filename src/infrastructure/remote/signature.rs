@@ -0,0 +1,154 @@
+//! 下载归档的 OpenPGP 分离签名校验(`download.verify_signatures`)。
+//!
+//! 在 SHA-256 之外再校验发行方签名:从归档 URL 旁下载 `<url><suffix>`(Temurin 为
+//! `.sig`),用 `download.signature_key_path` 指定的公钥在进程内验证,不依赖 GnuPG。
+//! 公钥可以是二进制(`gpg --export <key-id>`)或 ASCII-armored 格式;只信任这一把公钥,
+//! 不读取用户 `~/.gnupg` 里的其它密钥。
+
+use crate::infrastructure::config::DownloadConfig;
+use pgp::composed::{Deserializable, DetachedSignature, SignedPublicKey};
+use reqwest::Client;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// 归档旁的签名文件 `<file><suffix>`。
+pub fn signature_sidecar(archive: &Path, suffix: &str) -> PathBuf {
+    let mut name = archive.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// `config` 开启签名校验时,下载 `archive_url` 的签名并校验本地归档;未开启直接返回。
+///
+/// 签名不符时删除归档(避免下次命中缓存)并返回错误,调用方应中止安装而不是换镜像。
+pub async fn verify_download(
+    client: &Client,
+    config: &DownloadConfig,
+    archive_url: &str,
+    archive: &Path,
+    suffix: &str,
+) -> Result<(), String> {
+    if !config.verify_signatures {
+        return Ok(());
+    }
+    let key = signature_key(config)?;
+
+    let sig_url = format!("{archive_url}{suffix}");
+    let sig_path = signature_sidecar(archive, suffix);
+    let bytes = client
+        .get(&sig_url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to download signature {sig_url}: {e}"))?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to download signature {sig_url}: {e}"))?;
+    tokio::fs::write(&sig_path, &bytes)
+        .await
+        .map_err(|e| format!("Failed to write {}: {e}", sig_path.display()))?;
+
+    if let Err(e) = verify_detached(archive, &sig_path, &key) {
+        let _ = tokio::fs::remove_file(archive).await;
+        let _ = tokio::fs::remove_file(&sig_path).await;
+        return Err(e);
+    }
+    crate::cli::print::step("Signature", "verified");
+    Ok(())
+}
+
+fn signature_key(config: &DownloadConfig) -> Result<PathBuf, String> {
+    let path = config.signature_key_path.as_deref().ok_or_else(|| {
+        "download.verify_signatures requires download.signature_key_path".to_string()
+    })?;
+    std::fs::canonicalize(path).map_err(|e| format!("Failed to read signature key {path}: {e}"))
+}
+
+/// 校验 `archive` 的分离签名 `signature`,只信任 `key` 中的公钥(主钥或任一子钥)。
+pub fn verify_detached(archive: &Path, signature: &Path, key: &Path) -> Result<(), String> {
+    let key = open(key).and_then(|r| {
+        SignedPublicKey::from_reader_single(r)
+            .map(|(k, _)| k)
+            .map_err(|e| format!("Failed to parse signature key {}: {e}", key.display()))
+    })?;
+    let sig = open(signature).and_then(|r| {
+        DetachedSignature::from_reader_single(r)
+            .map(|(s, _)| s)
+            .map_err(|e| format!("Failed to parse signature {}: {e}", signature.display()))
+    })?;
+
+    let verified = |verifier: &dyn Fn(File) -> pgp::errors::Result<()>| {
+        open(archive).map(|f| verifier(f).is_ok())
+    };
+    if verified(&|f| sig.signature.verify(&key, f))? {
+        return Ok(());
+    }
+    for subkey in &key.public_subkeys {
+        if verified(&|f| sig.signature.verify(subkey, f))? {
+            return Ok(());
+        }
+    }
+    Err(format!(
+        "Signature verification failed for {}: BAD signature (not made by the configured key, or the file was modified)",
+        archive.display()
+    ))
+}
+
+fn open(path: &Path) -> Result<File, String> {
+    File::open(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("signature")
+            .join(name)
+    }
+
+    #[test]
+    fn detached_signature_accepts_original_and_rejects_tampered_archive() {
+        let key = fixture("key.gpg");
+        verify_detached(
+            &fixture("archive.tar.gz"),
+            &fixture("archive.tar.gz.sig"),
+            &key,
+        )
+        .expect("fixture signature should verify");
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let archive = tmp.path().join("archive.tar.gz");
+        let sig = signature_sidecar(&archive, ".sig");
+        let mut bytes = std::fs::read(fixture("archive.tar.gz")).unwrap();
+        bytes.extend_from_slice(b"tampered");
+        std::fs::write(&archive, bytes).unwrap();
+        std::fs::copy(fixture("archive.tar.gz.sig"), &sig).unwrap();
+        let err = verify_detached(&archive, &sig, &key).unwrap_err();
+        assert!(err.contains("BAD signature"), "{err}");
+    }
+
+    #[test]
+    fn armored_key_is_accepted() {
+        verify_detached(
+            &fixture("archive.tar.gz"),
+            &fixture("archive.tar.gz.sig"),
+            &fixture("key.asc"),
+        )
+        .expect("armored key should verify");
+    }
+
+    #[test]
+    fn unrelated_file_is_not_a_valid_key() {
+        let err = verify_detached(
+            &fixture("archive.tar.gz"),
+            &fixture("archive.tar.gz.sig"),
+            &fixture("archive.tar.gz"),
+        )
+        .unwrap_err();
+        assert!(err.contains("Failed to parse signature key"), "{err}");
+    }
+}
@@ -5,19 +5,22 @@
 
 use crate::core::presentation::ScanHit;
 use crate::utils::path::normalize_path;
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::path::Path;
 
 /// 遍历候选根目录,收集有效安装。
 ///
 /// 对每个 root:若 root 本身就是有效安装(`is_valid` 为真)直接收;否则 `read_dir`
-/// 遍历其子目录,对每个子目录做同样校验。用 `normalize_path` + `HashSet` 按路径去重。
+/// 遍历其子目录,对每个子目录做同样校验。用 `normalize_path`(解析符号链接)按实际
+/// 目录去重;同一目录既有真实路径又有符号链接(如 sdkman 的 `current`)时保留符号链接,
+/// 名称和路径对用户更稳定。
 pub fn scan_directory_roots(
     roots: &[String],
     is_valid: impl Fn(&Path) -> bool,
     make_hit: impl Fn(&Path) -> Result<ScanHit, String>,
 ) -> Vec<ScanHit> {
-    let mut seen: HashSet<String> = HashSet::new();
+    // 实际目录 → (out 中的下标, 是否经符号链接找到)
+    let mut seen: HashMap<String, (usize, bool)> = HashMap::new();
     let mut out = Vec::new();
 
     for root in roots {
@@ -39,14 +42,65 @@ pub fn scan_directory_roots(
             if !is_valid(&cand) {
                 continue;
             }
-            let path_str = cand.to_string_lossy().to_string();
-            if seen.insert(normalize_path(&path_str)) {
-                if let Ok(hit) = make_hit(&cand) {
-                    out.push(hit);
+            let target = normalize_path(&cand.to_string_lossy());
+            let is_link = cand.is_symlink();
+            match seen.get(&target) {
+                None => {
+                    if let Ok(hit) = make_hit(&cand) {
+                        seen.insert(target, (out.len(), is_link));
+                        out.push(hit);
+                    }
                 }
+                Some(&(index, false)) if is_link => {
+                    if let Ok(hit) = make_hit(&cand) {
+                        out[index] = hit;
+                        seen.insert(target, (index, true));
+                    }
+                }
+                Some(_) => {}
             }
         }
     }
 
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(p: &Path) -> Result<ScanHit, String> {
+        Ok(ScanHit {
+            name: p.file_name().unwrap().to_string_lossy().to_string(),
+            location: p.to_string_lossy().to_string(),
+            detail: String::new(),
+            import_cmd: String::new(),
+        })
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_install_is_listed_once_under_the_link_name() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let candidates = tmp.path().join("candidates");
+        let real = candidates.join("17.0.9-tem");
+        std::fs::create_dir_all(real.join("bin")).unwrap();
+        std::os::unix::fs::symlink(&real, candidates.join("current")).unwrap();
+        let other = tmp.path().join("jdk21");
+        std::fs::create_dir_all(other.join("bin")).unwrap();
+
+        let roots = [
+            candidates.to_string_lossy().to_string(),
+            // 同一安装再经真实路径作为 root 出现一次
+            real.to_string_lossy().to_string(),
+            other.to_string_lossy().to_string(),
+        ];
+        let hits = scan_directory_roots(&roots, |p| p.join("bin").is_dir(), hit);
+        let names: Vec<&str> = hits.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(names, ["current", "jdk21"]);
+        assert_eq!(
+            hits[0].location,
+            candidates.join("current").to_string_lossy()
+        );
+    }
+}
@@ -76,7 +76,7 @@ impl CurrentEnvsFile {
         map
     }
 
-    /// Persist to disk.
+    /// Persist to disk (atomically).
     fn save(&self) -> Result<(), String> {
         let path = Self::path()?;
         if let Some(parent) = path.parent() {
@@ -84,7 +84,8 @@ impl CurrentEnvsFile {
         }
         let content = toml::to_string_pretty(self)
             .map_err(|e| format!("Failed to serialize current_envs: {e}"))?;
-        fs::write(&path, content).map_err(|e| format!("Failed to write current_envs.toml: {e}"))?;
-        Ok(())
+        // shell hook 随时可能读取,原子替换避免读到半截文件
+        crate::utils::path::write_atomic(&path, content.as_bytes())
+            .map_err(|e| format!("Failed to write current_envs.toml: {e}"))
     }
 }
@@ -0,0 +1,78 @@
+//! dotenv 输出:把环境变量表渲染成严格的 `KEY=VALUE` 行,供 CI / Docker 消费。
+//!
+//! 与 shell 脚本不同,dotenv 没有 `export`,值含空格、`=`、`#`、引号等特殊字符时
+//! 必须加双引号并转义;`$` 原样保留,由加载方按 `${VAR}` 插值(与切换脚本语义一致)。
+
+/// 渲染 dotenv 文本(每行一个变量,末尾换行)。
+pub fn format_dotenv(vars: &[(String, String)]) -> String {
+    let mut out = String::new();
+    for (key, value) in vars {
+        out.push_str(key);
+        out.push('=');
+        out.push_str(&quote_value(value));
+        out.push('\n');
+    }
+    out
+}
+
+fn is_plain(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:@+,%".contains(c))
+}
+
+fn quote_value(value: &str) -> String {
+    if is_plain(value) {
+        return value.to_string();
+    }
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn plain_values_are_unquoted() {
+        let out = format_dotenv(&vars(&[("JAVA_HOME", "/opt/jdk-21"), ("N", "1")]));
+        assert_eq!(out, "JAVA_HOME=/opt/jdk-21\nN=1\n");
+    }
+
+    #[test]
+    fn spaces_and_equals_are_quoted() {
+        let out = format_dotenv(&vars(&[
+            ("JAVA_HOME", "C:/Program Files/Java/jdk-21"),
+            ("TOKEN", "abc=def=="),
+        ]));
+        assert_eq!(
+            out,
+            "JAVA_HOME=\"C:/Program Files/Java/jdk-21\"\nTOKEN=\"abc=def==\"\n"
+        );
+    }
+
+    #[test]
+    fn quotes_backslashes_and_newlines_are_escaped() {
+        let out = format_dotenv(&vars(&[("V", "a \"b\"\\c\nd"), ("E", "")]));
+        assert_eq!(out, "V=\"a \\\"b\\\"\\\\c\\nd\"\nE=\"\"\n");
+    }
+}
@@ -1,4 +1,5 @@
 pub mod current_envs;
+pub mod dotenv;
 pub mod platform;
 pub mod script_factory;
 pub mod script_strategy;
@@ -19,6 +19,56 @@ pub enum ShellType {
     Unknown,
 }
 
+impl ShellType {
+    /// 可以通过 `--shell` 指定的类型(不含 `Unknown`)
+    pub const ALL: [ShellType; 5] = [
+        ShellType::Bash,
+        ShellType::Zsh,
+        ShellType::Fish,
+        ShellType::PowerShell,
+        ShellType::Cmd,
+    ];
+
+    /// 规范的小写名称,与 `FromStr` 互为逆运算
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShellType::Bash => "bash",
+            ShellType::Zsh => "zsh",
+            ShellType::Fish => "fish",
+            ShellType::PowerShell => "powershell",
+            ShellType::Cmd => "cmd",
+            ShellType::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for ShellType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ShellType {
+    type Err = String;
+
+    /// 不区分大小写;另接受 `pwsh` / `ps1` / `ps` 与带 `.exe` 后缀的写法。
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_ascii_lowercase();
+        let name = lower.strip_suffix(".exe").unwrap_or(&lower);
+        match name {
+            "bash" => Ok(ShellType::Bash),
+            "zsh" => Ok(ShellType::Zsh),
+            "fish" => Ok(ShellType::Fish),
+            "powershell" | "pwsh" | "ps1" | "ps" => Ok(ShellType::PowerShell),
+            "cmd" => Ok(ShellType::Cmd),
+            _ => {
+                let valid: Vec<&str> = ShellType::ALL.iter().map(ShellType::as_str).collect();
+                Err(format!("Unknown shell '{s}'. Valid: {}", valid.join(", ")))
+            }
+        }
+    }
+}
+
 /// 获取当前操作系统类型
 pub fn get_os_type() -> OsType {
     match env::consts::OS {
@@ -181,4 +231,33 @@ mod tests {
         assert!(escaped.contains("path/with"));
         assert!(escaped.contains("spaces"));
     }
+
+    #[test]
+    fn shell_type_round_trips_through_display_and_from_str() {
+        for shell in ShellType::ALL {
+            assert_eq!(shell.to_string().parse::<ShellType>(), Ok(shell));
+            assert_eq!(
+                shell.to_string().to_uppercase().parse::<ShellType>(),
+                Ok(shell)
+            );
+        }
+        for (alias, shell) in [
+            ("pwsh", ShellType::PowerShell),
+            ("ps1", ShellType::PowerShell),
+            ("ps", ShellType::PowerShell),
+            ("PowerShell.exe", ShellType::PowerShell),
+            ("pwsh.exe", ShellType::PowerShell),
+            ("cmd.exe", ShellType::Cmd),
+            (" Zsh ", ShellType::Zsh),
+        ] {
+            assert_eq!(alias.parse::<ShellType>(), Ok(shell), "{alias}");
+        }
+        assert_eq!(ShellType::Unknown.to_string(), "unknown");
+        let err = "unknown".parse::<ShellType>().unwrap_err();
+        assert!(
+            err.contains("Valid: bash, zsh, fish, powershell, cmd"),
+            "{err}"
+        );
+        assert!("tcsh".parse::<ShellType>().is_err());
+    }
 }
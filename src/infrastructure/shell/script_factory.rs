@@ -21,7 +21,7 @@ impl ScriptFactory {
         // 注册所有策略
         strategies.insert(ShellType::PowerShell, Arc::new(PowerShellStrategy::new()?));
         strategies.insert(ShellType::Bash, Arc::new(BashStrategy::new()?));
-        strategies.insert(ShellType::Zsh, Arc::new(BashStrategy::new()?)); // Zsh使用相同的Bash策略
+        strategies.insert(ShellType::Zsh, Arc::new(BashStrategy::zsh()?));
         strategies.insert(ShellType::Fish, Arc::new(FishStrategy::new()?));
         strategies.insert(ShellType::Cmd, Arc::new(CmdStrategy::new()?));
 
@@ -37,7 +37,7 @@ impl ScriptFactory {
             .get(&shell_type)
             .cloned()
             .ok_or_else(|| AppError::ScriptGeneration {
-                shell_type: format!("{shell_type:?}"),
+                shell_type: shell_type.to_string(),
                 reason: "Unsupported shell type".to_string(),
             })
     }
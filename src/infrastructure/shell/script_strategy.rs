@@ -37,6 +37,57 @@ fn build_maven_opts_value(config: &Value) -> String {
     parts.join(" ")
 }
 
+/// 把 JAVA_HOME 与要加到 PATH 前面的目录写入模板数据:`java_paths` 是
+/// `bin` 加上环境的 `extra_paths`(按配置顺序),`java_path` 是用目标 shell 的
+/// PATH 分隔符拼好的整段,同时记入 `FNVA_JAVA_BIN` 供下次切换时整段移除。
+fn insert_java_paths(data: &mut Value, config: &Value, windows: bool) {
+    let Some(java_home) = config.get("java_home").and_then(|v| v.as_str()) else {
+        return;
+    };
+    let (home, bin) = crate::utils::path::home_and_bin(java_home, windows);
+    let mut paths = vec![bin.clone()];
+    if let Some(extra) = config.get("extra_paths").and_then(|v| v.as_array()) {
+        paths.extend(
+            extra
+                .iter()
+                .filter_map(|p| p.as_str())
+                .filter(|p| !p.trim().is_empty())
+                .map(|p| crate::utils::path::clean_home_path(p, windows)),
+        );
+    }
+    data["java_home"] = json!(home);
+    data["java_bin"] = json!(bin);
+    data["java_path"] = json!(paths.join(if windows { ";" } else { ":" }));
+    data["java_paths"] = json!(paths);
+}
+
+/// 把环境的 JAVA_OPTS 写入模板数据:`java_opts_value` 已按目标 shell 转义,
+/// 模板里用 `{{{ }}}` 原样输出(bash/fish/PowerShell 为带引号的字面量,
+/// CMD 为 `set "..."` 内部的内容)。
+fn insert_java_opts(data: &mut Value, config: &Value, shell: ShellType) {
+    let opts = config
+        .get("java_opts")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty());
+    data["has_java_opts"] = json!(opts.is_some());
+    if let Some(opts) = opts {
+        data["java_opts_value"] = json!(quote_for_shell(shell, opts));
+    }
+}
+
+/// 按 shell 规则把任意字符串变成字面量,`$`、引号、反斜杠都不会被展开。
+fn quote_for_shell(shell: ShellType, value: &str) -> String {
+    match shell {
+        ShellType::Fish => format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'")),
+        ShellType::PowerShell => format!("'{}'", value.replace('\'', "''")),
+        // 批处理里 % 会被展开,其余字符在 set "..." 的引号内保持原样
+        ShellType::Cmd => value.replace('%', "%%"),
+        ShellType::Bash | ShellType::Zsh | ShellType::Unknown => {
+            format!("'{}'", value.replace('\'', "'\\''"))
+        }
+    }
+}
+
 /// 脚本生成策略接口
 pub trait ScriptGenerationStrategy: Send + Sync {
     /// 生成环境切换脚本
@@ -163,14 +214,13 @@ impl ScriptGenerationStrategy for PowerShellStrategy {
 
         // 添加特定环境类型的数据
         if env_type == EnvironmentType::Java {
-            if let Some(java_home) = config.get("java_home").and_then(|v| v.as_str()) {
-                data["java_home"] = json!(java_home);
-                data["java_bin"] = json!(format!("{}\\bin", java_home));
-            }
+            insert_java_paths(&mut data, config, true);
+            insert_java_opts(&mut data, config, ShellType::PowerShell);
         } else if env_type == EnvironmentType::Maven {
             if let Some(maven_home) = config.get("maven_home").and_then(|v| v.as_str()) {
-                data["maven_home"] = json!(maven_home);
-                data["maven_bin"] = json!(format!("{}\\bin", maven_home));
+                let (home, bin) = crate::utils::path::home_and_bin(maven_home, true);
+                data["maven_home"] = json!(home);
+                data["maven_bin"] = json!(bin);
             }
             // 构建最终的 MAVEN_OPTS（合并用户设置 + local_repo + settings_file）
             let opts_value = build_maven_opts_value(config);
@@ -188,6 +238,7 @@ impl ScriptGenerationStrategy for PowerShellStrategy {
         let data = json!({
             "current_envs": current_envs,
             "shell_type": "PowerShell",
+            "fnva_version": env!("CARGO_PKG_VERSION"),
         });
 
         self.template_engine.render("powershell_integration", &data)
@@ -198,15 +249,25 @@ impl ScriptGenerationStrategy for PowerShellStrategy {
     }
 }
 
-/// Bash/Zsh 脚本生成策略
+/// Bash/Zsh 脚本生成策略:切换脚本两者共用,集成脚本按 `shell` 注册各自的提示符钩子
 pub struct BashStrategy {
     template_engine: Arc<TemplateEngine>,
+    shell: ShellType,
 }
 
 impl BashStrategy {
     pub fn new() -> Result<Self, AppError> {
         Ok(Self {
             template_engine: Arc::new(TemplateEngine::new()?),
+            shell: ShellType::Bash,
+        })
+    }
+
+    /// zsh 专用:集成脚本用 `precmd_functions` 而不是 `PROMPT_COMMAND`
+    pub fn zsh() -> Result<Self, AppError> {
+        Ok(Self {
+            shell: ShellType::Zsh,
+            ..Self::new()?
         })
     }
 }
@@ -231,14 +292,13 @@ impl ScriptGenerationStrategy for BashStrategy {
         });
 
         if env_type == EnvironmentType::Java {
-            if let Some(java_home) = config.get("java_home").and_then(|v| v.as_str()) {
-                data["java_home"] = json!(java_home);
-                data["java_bin"] = json!(format!("{}/bin", java_home));
-            }
+            insert_java_paths(&mut data, config, false);
+            insert_java_opts(&mut data, config, self.shell);
         } else if env_type == EnvironmentType::Maven {
             if let Some(maven_home) = config.get("maven_home").and_then(|v| v.as_str()) {
-                data["maven_home"] = json!(maven_home);
-                data["maven_bin"] = json!(format!("{}/bin", maven_home));
+                let (home, bin) = crate::utils::path::home_and_bin(maven_home, false);
+                data["maven_home"] = json!(home);
+                data["maven_bin"] = json!(bin);
             }
             // 构建最终的 MAVEN_OPTS（合并用户设置 + local_repo + settings_file）
             let opts_value = build_maven_opts_value(config);
@@ -255,14 +315,16 @@ impl ScriptGenerationStrategy for BashStrategy {
     ) -> Result<String, AppError> {
         let data = json!({
             "current_envs": current_envs,
-            "shell_type": "Bash/Zsh",
+            "shell_type": self.shell.to_string(),
+            "zsh": self.shell == ShellType::Zsh,
+            "fnva_version": env!("CARGO_PKG_VERSION"),
         });
 
         self.template_engine.render("bash_integration", &data)
     }
 
     fn shell_type(&self) -> ShellType {
-        ShellType::Bash
+        self.shell
     }
 }
 
@@ -299,14 +361,13 @@ impl ScriptGenerationStrategy for FishStrategy {
         });
 
         if env_type == EnvironmentType::Java {
-            if let Some(java_home) = config.get("java_home").and_then(|v| v.as_str()) {
-                data["java_home"] = json!(java_home);
-                data["java_bin"] = json!(format!("{}/bin", java_home));
-            }
+            insert_java_paths(&mut data, config, false);
+            insert_java_opts(&mut data, config, ShellType::Fish);
         } else if env_type == EnvironmentType::Maven {
             if let Some(maven_home) = config.get("maven_home").and_then(|v| v.as_str()) {
-                data["maven_home"] = json!(maven_home);
-                data["maven_bin"] = json!(format!("{}/bin", maven_home));
+                let (home, bin) = crate::utils::path::home_and_bin(maven_home, false);
+                data["maven_home"] = json!(home);
+                data["maven_bin"] = json!(bin);
             }
             // 构建最终的 MAVEN_OPTS（合并用户设置 + local_repo + settings_file）
             let opts_value = build_maven_opts_value(config);
@@ -324,6 +385,7 @@ impl ScriptGenerationStrategy for FishStrategy {
         let data = json!({
             "current_envs": current_envs,
             "shell_type": "Fish",
+            "fnva_version": env!("CARGO_PKG_VERSION"),
         });
 
         self.template_engine.render("fish_integration", &data)
@@ -367,14 +429,13 @@ impl ScriptGenerationStrategy for CmdStrategy {
         });
 
         if env_type == EnvironmentType::Java {
-            if let Some(java_home) = config.get("java_home").and_then(|v| v.as_str()) {
-                data["java_home"] = json!(java_home);
-                data["java_bin"] = json!(format!("{}\\bin", java_home));
-            }
+            insert_java_paths(&mut data, config, true);
+            insert_java_opts(&mut data, config, ShellType::Cmd);
         } else if env_type == EnvironmentType::Maven {
             if let Some(maven_home) = config.get("maven_home").and_then(|v| v.as_str()) {
-                data["maven_home"] = json!(maven_home);
-                data["maven_bin"] = json!(format!("{}\\bin", maven_home));
+                let (home, bin) = crate::utils::path::home_and_bin(maven_home, true);
+                data["maven_home"] = json!(home);
+                data["maven_bin"] = json!(bin);
             }
             // 构建最终的 MAVEN_OPTS（合并用户设置 + local_repo + settings_file）
             let opts_value = build_maven_opts_value(config);
@@ -392,6 +453,7 @@ impl ScriptGenerationStrategy for CmdStrategy {
         let data = json!({
             "current_envs": current_envs,
             "shell_type": "CMD",
+            "fnva_version": env!("CARGO_PKG_VERSION"),
         });
 
         self.template_engine.render("cmd_integration", &data)
@@ -560,6 +622,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_java_bin_is_clean_for_trailing_and_mixed_separators() {
+        let posix = json!({ "java_home": "/opt//jdk17/" });
+        for strategy in [
+            Box::new(BashStrategy::new().unwrap()) as Box<dyn ScriptGenerationStrategy>,
+            Box::new(FishStrategy::new().unwrap()),
+        ] {
+            let script = strategy
+                .generate_switch_script(EnvironmentType::Java, "jdk17", &posix)
+                .unwrap();
+            assert!(script.contains("\"/opt/jdk17/bin\""), "{script}");
+            assert!(script.contains("\"/opt/jdk17\""), "{script}");
+            assert!(
+                !script.contains("opt//") && !script.contains("jdk17//"),
+                "{script}"
+            );
+        }
+
+        // 模板对反斜杠做了转义,所以期望值里每个 `\` 都成对出现
+        let windows = json!({ "java_home": "C:/Java\\jdk17\\" });
+        for strategy in [
+            Box::new(PowerShellStrategy::new().unwrap()) as Box<dyn ScriptGenerationStrategy>,
+            Box::new(CmdStrategy::new().unwrap()),
+        ] {
+            let script = strategy
+                .generate_switch_script(EnvironmentType::Java, "jdk17", &windows)
+                .unwrap();
+            assert!(script.contains("C:\\\\Java\\\\jdk17\\\\bin"), "{script}");
+            assert!(!script.contains("jdk17\\\\\\\\bin"), "{script}");
+            assert!(!script.contains("C:/"), "{script}");
+        }
+    }
+
     #[test]
     fn test_maven_strategy() {
         let strategy = BashStrategy::new().unwrap();
@@ -581,4 +676,499 @@ mod tests {
             "should derive maven_bin: {script}"
         );
     }
+
+    #[test]
+    fn test_java_path_strategy_fnva_only_vs_aggressive() {
+        let strategy = BashStrategy::new().unwrap();
+
+        // 默认(fnva_only):只移除 FNVA_JAVA_BIN 记录的目录
+        let config = json!({ "java_home": "/usr/lib/jvm/java-17" });
+        let script = strategy
+            .generate_switch_script(EnvironmentType::Java, "jdk17", &config)
+            .unwrap();
+        assert!(script.contains("${PATH//${FNVA_JAVA_BIN}:/}"), "{script}");
+        assert!(!script.contains("java|jdk"), "{script}");
+
+        // aggressive:移除所有 java/jdk 条目
+        let config = json!({ "java_home": "/usr/lib/jvm/java-17", "aggressive_path": true });
+        let script = strategy
+            .generate_switch_script(EnvironmentType::Java, "jdk17", &config)
+            .unwrap();
+        assert!(script.contains("grep -viE 'java|jdk'"), "{script}");
+        assert!(!script.contains("${PATH//${FNVA_JAVA_BIN}:/}"), "{script}");
+        assert!(script.contains("export FNVA_JAVA_BIN="), "{script}");
+    }
+
+    #[test]
+    fn test_java_keep_path_skips_cleaning_in_every_shell() {
+        // keep 优先于 aggressive:四种 shell 都只剩前置 bin 目录这一步
+        let config = json!({
+            "java_home": "/opt/jdk17",
+            "aggressive_path": true,
+            "keep_path": true,
+        });
+        let cases = [
+            (
+                Box::new(BashStrategy::new().unwrap()) as Box<dyn ScriptGenerationStrategy>,
+                r#"export PATH="$FNVA_JAVA_BIN:$PATH""#,
+            ),
+            (
+                Box::new(FishStrategy::new().unwrap()),
+                "set -gx PATH $FNVA_JAVA_BIN $PATH",
+            ),
+            (
+                Box::new(PowerShellStrategy::new().unwrap()),
+                r#"$env:PATH = $env:FNVA_JAVA_BIN + ";" + $env:PATH"#,
+            ),
+            (
+                Box::new(CmdStrategy::new().unwrap()),
+                r#"set "PATH=%FNVA_JAVA_BIN%;%PATH%""#,
+            ),
+        ];
+        for (strategy, prepend) in cases {
+            let script = strategy
+                .generate_switch_script(EnvironmentType::Java, "jdk17", &config)
+                .unwrap();
+            assert!(script.contains(prepend), "{script}");
+            for cleaning in [
+                "java|jdk",
+                "java jdk",
+                "${PATH//",
+                "contains -i",
+                "-notcontains",
+                "%%PATH:",
+                "_FNVA_NEWPATH",
+            ] {
+                assert!(!script.contains(cleaning), "{cleaning}: {script}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_extra_paths_follow_bin_with_shell_separator() {
+        let posix = json!({
+            "java_home": "/opt/graalvm",
+            "extra_paths": ["/opt/graalvm/lib/svm/bin", "/opt/tools/"],
+        });
+        let bash = BashStrategy::new()
+            .unwrap()
+            .generate_switch_script(EnvironmentType::Java, "graal", &posix)
+            .unwrap();
+        assert!(
+            bash.contains(
+                r#"export FNVA_JAVA_BIN="/opt/graalvm/bin:/opt/graalvm/lib/svm/bin:/opt/tools""#
+            ),
+            "{bash}"
+        );
+        assert!(
+            bash.contains(r#"export PATH="$FNVA_JAVA_BIN:$PATH""#),
+            "{bash}"
+        );
+
+        let fish = FishStrategy::new()
+            .unwrap()
+            .generate_switch_script(EnvironmentType::Java, "graal", &posix)
+            .unwrap();
+        assert!(
+            fish.contains(
+                r#"set -gx FNVA_JAVA_BIN "/opt/graalvm/bin" "/opt/graalvm/lib/svm/bin" "/opt/tools""#
+            ),
+            "{fish}"
+        );
+        assert!(fish.contains("set -gx PATH $FNVA_JAVA_BIN $PATH"), "{fish}");
+
+        let windows = json!({ "java_home": "C:\\graalvm", "extra_paths": ["C:/tools"] });
+        for strategy in [
+            Box::new(PowerShellStrategy::new().unwrap()) as Box<dyn ScriptGenerationStrategy>,
+            Box::new(CmdStrategy::new().unwrap()),
+        ] {
+            let script = strategy
+                .generate_switch_script(EnvironmentType::Java, "graal", &windows)
+                .unwrap();
+            assert!(
+                script.contains("C:\\\\graalvm\\\\bin;C:\\\\tools"),
+                "{script}"
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_bash_extra_paths_prepend_and_are_removed_on_next_switch() {
+        let strategy = BashStrategy::new().unwrap();
+        let render = |config: &Value| {
+            strategy
+                .generate_switch_script(EnvironmentType::Java, "jdk", config)
+                .unwrap()
+        };
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path().join("graal.sh"),
+            render(&json!({ "java_home": "/opt/graal", "extra_paths": ["/opt/svm"] })),
+        )
+        .unwrap();
+        std::fs::write(
+            tmp.path().join("jdk17.sh"),
+            render(&json!({ "java_home": "/opt/jdk17" })),
+        )
+        .unwrap();
+        let run = |script: &str| {
+            let out = std::process::Command::new("bash")
+                .current_dir(tmp.path())
+                .arg("-c")
+                .arg(script)
+                .env("_FNVA_QUIET", "1")
+                .env("PATH", "/usr/bin:/bin")
+                .output()
+                .unwrap();
+            assert!(out.status.success());
+            String::from_utf8(out.stdout).unwrap()
+        };
+        assert_eq!(
+            run(r#"source ./graal.sh; printf '%s' "$PATH""#),
+            "/opt/graal/bin:/opt/svm:/usr/bin:/bin"
+        );
+        assert_eq!(
+            run(r#"source ./graal.sh; source ./jdk17.sh; printf '%s' "$PATH""#),
+            "/opt/jdk17/bin:/usr/bin:/bin"
+        );
+    }
+
+    #[test]
+    fn test_java_opts_only_emitted_when_configured_and_quoted_per_shell() {
+        let opts = r#"-Xmx2g -Dmsg="it's $HOME" -Dp=50%"#;
+        let with = json!({ "java_home": "/opt/jdk", "java_opts": opts });
+        let without = json!({ "java_home": "/opt/jdk" });
+        let strategies: Vec<(Box<dyn ScriptGenerationStrategy>, String)> = vec![
+            (
+                Box::new(BashStrategy::new().unwrap()),
+                r#"export JAVA_OPTS='-Xmx2g -Dmsg="it'\''s $HOME" -Dp=50%'"#.to_string(),
+            ),
+            (
+                Box::new(FishStrategy::new().unwrap()),
+                r#"set -gx JAVA_OPTS '-Xmx2g -Dmsg="it\'s $HOME" -Dp=50%'"#.to_string(),
+            ),
+            (
+                Box::new(PowerShellStrategy::new().unwrap()),
+                r#"$env:JAVA_OPTS = '-Xmx2g -Dmsg="it''s $HOME" -Dp=50%'"#.to_string(),
+            ),
+            (
+                Box::new(CmdStrategy::new().unwrap()),
+                r#"set "JAVA_OPTS=-Xmx2g -Dmsg="it's $HOME" -Dp=50%%""#.to_string(),
+            ),
+        ];
+        for (strategy, expected) in strategies {
+            let script = strategy
+                .generate_switch_script(EnvironmentType::Java, "jdk17", &with)
+                .unwrap();
+            assert!(script.contains(&expected), "{expected}\n{script}");
+            assert!(script.contains("FNVA_JAVA_OPTS"), "{script}");
+
+            let script = strategy
+                .generate_switch_script(EnvironmentType::Java, "jdk17", &without)
+                .unwrap();
+            assert!(!script.contains("-Xmx2g"), "{script}");
+        }
+    }
+
+    /// 真实 bash 里 source 切换脚本:JAVA_OPTS 原样还原;切到未配置的环境时
+    /// 只清除 fnva 设置的值,用户自己的 JAVA_OPTS 保留。
+    #[cfg(unix)]
+    #[test]
+    fn test_bash_java_opts_round_trip() {
+        let strategy = BashStrategy::new().unwrap();
+        let opts = r#"-Dmsg="it's $HOME" -Dcmd=`id` \n"#;
+        let render = |config: &Value| {
+            strategy
+                .generate_switch_script(EnvironmentType::Java, "jdk17", config)
+                .unwrap()
+        };
+        let with = render(&json!({ "java_home": "/opt/jdk", "java_opts": opts }));
+        let without = render(&json!({ "java_home": "/opt/jdk" }));
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("with.sh"), with).unwrap();
+        std::fs::write(tmp.path().join("without.sh"), without).unwrap();
+        let run = |script: &str| {
+            let out = std::process::Command::new("bash")
+                .current_dir(tmp.path())
+                .arg("-c")
+                .arg(script)
+                .env("_FNVA_QUIET", "1")
+                .output()
+                .unwrap();
+            assert!(out.status.success());
+            String::from_utf8(out.stdout).unwrap()
+        };
+        assert_eq!(run(r#"source ./with.sh; printf '%s' "$JAVA_OPTS""#), opts);
+        assert_eq!(
+            run(r#"source ./with.sh; source ./without.sh; printf '%s' "${JAVA_OPTS-unset}""#),
+            "unset"
+        );
+        assert_eq!(
+            run(r#"export JAVA_OPTS=mine; source ./without.sh; printf '%s' "$JAVA_OPTS""#),
+            "mine"
+        );
+    }
+
+    /// 在 bash 里执行渲染出的集成脚本,用记录参数的假 fnva 观察 wrapper 请求的 shell。
+    #[cfg(unix)]
+    fn wrapper_shell_arg(zsh_version: Option<&str>, command: &str) -> String {
+        use std::os::unix::fs::PermissionsExt;
+
+        let strategy = BashStrategy::new().unwrap();
+        let script = strategy
+            .generate_integration_script(&HashMap::new())
+            .unwrap();
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let args_file = tmp.path().join("args");
+        let stub = tmp.path().join("fnva");
+        std::fs::write(
+            &stub,
+            format!("#!/bin/sh\necho \"$@\" > '{}'\n", args_file.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let integration = tmp.path().join("integration.sh");
+        std::fs::write(&integration, script).unwrap();
+
+        let mut cmd = std::process::Command::new("bash");
+        cmd.arg("-c")
+            .arg(format!(
+                "source '{}' >/dev/null; fnva {command}",
+                integration.display()
+            ))
+            .env("HOME", tmp.path())
+            .env(
+                "PATH",
+                format!(
+                    "{}:{}",
+                    tmp.path().display(),
+                    std::env::var("PATH").unwrap()
+                ),
+            )
+            .env_remove("ZSH_VERSION");
+        if let Some(v) = zsh_version {
+            cmd.env("ZSH_VERSION", v);
+        }
+        assert!(cmd.status().unwrap().success());
+        std::fs::read_to_string(args_file).unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_bash_integration_requests_running_shell() {
+        assert_eq!(
+            wrapper_shell_arg(None, "java use jdk17").trim(),
+            "java use jdk17 --shell bash"
+        );
+        assert_eq!(
+            wrapper_shell_arg(Some("5.9"), "java use jdk17").trim(),
+            "java use jdk17 --shell zsh"
+        );
+        // --print-path / --list-only 的输出不是脚本,原样透传
+        for zsh_version in [None, Some("5.9")] {
+            assert_eq!(
+                wrapper_shell_arg(zsh_version, "java use jdk17 --print-path").trim(),
+                "java use jdk17 --print-path"
+            );
+            assert_eq!(
+                wrapper_shell_arg(zsh_version, "java use --list-only").trim(),
+                "java use --list-only"
+            );
+        }
+    }
+
+    #[test]
+    fn test_fish_and_powershell_wrappers_pass_non_script_output_through() {
+        let powershell = PowerShellStrategy::new()
+            .unwrap()
+            .generate_integration_script(&HashMap::new())
+            .unwrap();
+        let guard = powershell
+            .lines()
+            .find(|l| l.contains(r#"($args[1] -eq "use")"#))
+            .unwrap();
+        for flag in ["--print-path", "--list-only"] {
+            assert!(
+                guard.contains(&format!(r#"-not ($args -contains "{flag}")"#)),
+                "{guard}"
+            );
+        }
+
+        let fish = FishStrategy::new()
+            .unwrap()
+            .generate_integration_script(&HashMap::new())
+            .unwrap();
+        let guard = fish
+            .lines()
+            .find(|l| l.contains(r#"test $argv[2] = "use""#))
+            .unwrap();
+        for flag in ["--print-path", "--list-only"] {
+            assert!(
+                guard.contains(&format!("not contains -- {flag} $argv")),
+                "{guard}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_zsh_integration_registers_precmd_hook() {
+        let zsh = BashStrategy::zsh()
+            .unwrap()
+            .generate_integration_script(&HashMap::new())
+            .unwrap();
+        assert!(
+            zsh.contains("precmd_functions+=(_fnva_first_prompt)"),
+            "{zsh}"
+        );
+        assert!(!zsh.contains("PROMPT_COMMAND"), "{zsh}");
+        assert!(zsh.contains("_fnva_shell=zsh"), "{zsh}");
+        assert!(!zsh.contains("ZSH_VERSION"), "{zsh}");
+
+        // bash 版本保留运行时判断,被 zsh source 时同样走 precmd_functions
+        let bash = BashStrategy::new()
+            .unwrap()
+            .generate_integration_script(&HashMap::new())
+            .unwrap();
+        assert!(
+            bash.contains(r#"PROMPT_COMMAND="_fnva_first_prompt;${PROMPT_COMMAND:-}""#),
+            "{bash}"
+        );
+        assert!(bash.contains("ZSH_VERSION"), "{bash}");
+
+        let factory = crate::infrastructure::shell::ScriptFactory::new().unwrap();
+        assert_eq!(
+            factory.get_strategy(ShellType::Zsh).unwrap().shell_type(),
+            ShellType::Zsh
+        );
+    }
+
+    /// 交互式 bash(含不显示提示符的 `bash -ic`)启动时立即恢复,只恢复一次;
+    /// PROMPT_COMMAND 上的一次性钩子只把 Java bin 放回 PATH 最前并摘掉自己,不再调用 fnva。
+    #[cfg(unix)]
+    #[test]
+    fn test_bash_interactive_autoload_runs_once_and_first_prompt_only_fixes_path() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = BashStrategy::new()
+            .unwrap()
+            .generate_integration_script(&HashMap::new())
+            .unwrap();
+        let tmp = tempfile::TempDir::new().unwrap();
+        let calls = tmp.path().join("calls");
+        let stub = tmp.path().join("fnva");
+        std::fs::write(
+            &stub,
+            format!("#!/bin/sh\necho \"$@\" >> '{}'\n", calls.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let integration = tmp.path().join("integration.sh");
+        std::fs::write(&integration, script).unwrap();
+
+        // 恢复后 rc 又往 PATH 前面加了目录,把 Java bin 挤到了后面
+        let out = std::process::Command::new("bash")
+            .arg("-ic")
+            .arg(format!(
+                "PROMPT_COMMAND=user_hook; source '{}'; \
+                 printf '%s|' \"$(cat '{}' 2>/dev/null)\" \"$PROMPT_COMMAND\"; \
+                 export FNVA_JAVA_BIN=/opt/jdk/bin; PATH=\"/rc/bin:/opt/jdk/bin:$PATH\"; \
+                 _fnva_first_prompt; \
+                 printf '%s|%s|%s' \"$(cat '{}')\" \"$PROMPT_COMMAND\" \"$(printf %s \"$PATH\" | cut -d: -f1-3)\"",
+                integration.display(),
+                calls.display(),
+                calls.display()
+            ))
+            .env("HOME", tmp.path())
+            .env(
+                "PATH",
+                format!(
+                    "{}:{}",
+                    tmp.path().display(),
+                    std::env::var("PATH").unwrap()
+                ),
+            )
+            .env_remove("ZSH_VERSION")
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out.stdout).unwrap(),
+            format!(
+                "cc switch-default --shell bash|_fnva_first_prompt;user_hook|\
+                 cc switch-default --shell bash|user_hook|/opt/jdk/bin:/rc/bin:{}",
+                tmp.path().display()
+            )
+        );
+    }
+
+    /// 启动时的自动恢复:没有记录 cc 时调用 `cc switch-default`,并执行它给出的脚本。
+    #[cfg(unix)]
+    #[test]
+    fn test_bash_autoload_switches_to_default_cc_only_when_unrecorded() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = BashStrategy::new()
+            .unwrap()
+            .generate_integration_script(&HashMap::new())
+            .unwrap();
+        let run = |state: Option<&str>| {
+            let tmp = tempfile::TempDir::new().unwrap();
+            let calls = tmp.path().join("calls");
+            let stub = tmp.path().join("fnva");
+            std::fs::write(
+                &stub,
+                format!(
+                    "#!/bin/sh\necho \"$@\" >> '{}'\n\
+                     [ \"$2\" = switch-default ] && echo 'export FNVA_CURRENT_CC=glm'\n\
+                     exit 0\n",
+                    calls.display()
+                ),
+            )
+            .unwrap();
+            std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+            if let Some(state) = state {
+                let dir = tmp.path().join(".fnva").join("state");
+                std::fs::create_dir_all(&dir).unwrap();
+                std::fs::write(dir.join("current_envs.toml"), state).unwrap();
+            }
+            let integration = tmp.path().join("integration.sh");
+            std::fs::write(&integration, &script).unwrap();
+            let out = std::process::Command::new("bash")
+                .arg("-c")
+                .arg(format!(
+                    "source '{}'; printf '%s' \"${{FNVA_CURRENT_CC-unset}}\"",
+                    integration.display()
+                ))
+                .env("HOME", tmp.path())
+                .env(
+                    "PATH",
+                    format!(
+                        "{}:{}",
+                        tmp.path().display(),
+                        std::env::var("PATH").unwrap()
+                    ),
+                )
+                .env_remove("ZSH_VERSION")
+                .env_remove("FNVA_CURRENT_CC")
+                .output()
+                .unwrap();
+            assert!(out.status.success());
+            (
+                std::fs::read_to_string(calls).unwrap_or_default(),
+                String::from_utf8(out.stdout).unwrap(),
+            )
+        };
+
+        let (calls, stdout) = run(None);
+        assert_eq!(calls, "cc switch-default --shell bash\n");
+        assert!(stdout.contains("fnva active: cc glm"), "{stdout}");
+        assert!(stdout.ends_with("glm"), "{stdout}");
+
+        let (calls, _) = run(Some("java = \"jdk17\"\ncc = \"kimi\"\n"));
+        assert!(calls.contains("java use jdk17 --shell bash"), "{calls}");
+        assert!(calls.contains("cc use kimi --shell bash"), "{calls}");
+        assert!(!calls.contains("switch-default"), "{calls}");
+    }
 }
@@ -19,8 +19,11 @@ const MAX_ATTEMPTS: u32 = 3;
 ///
 /// - 发送失败(`send` 抛错)重试 3 次后返回 `Network` 错误;
 /// - 响应体读取失败则继续重试(与原实现一致),最终返回空字符串;
-/// - 成功则返回响应体文本。
+/// - 成功则返回响应体文本;
+/// - 离线模式下不发请求,直接返回 `Network` 错误。
 pub async fn fetch_with_retry(client: &Client, url: &str) -> Result<String, DiscoveryError> {
+    crate::infrastructure::remote::offline::ensure_online(&format!("Fetching {url}"))
+        .map_err(DiscoveryError::Network)?;
     let mut attempts = 0;
     let mut text = String::new();
     while attempts < MAX_ATTEMPTS {
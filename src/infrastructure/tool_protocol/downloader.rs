@@ -35,4 +35,9 @@ pub trait ToolDownloader: Send + Sync {
         platform: &Platform,
         progress_callback: Box<dyn Fn(u64, u64) + Send + Sync>,
     ) -> Pin<Box<dyn Future<Output = Result<DownloadTarget, DownloadError>> + Send + '_>>;
+
+    /// 最近一次来源失败的原因;安装超时被取消时用它说明卡在哪里。
+    fn last_error(&self) -> Option<String> {
+        None
+    }
 }
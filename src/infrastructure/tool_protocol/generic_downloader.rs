@@ -4,14 +4,17 @@
 //! `get_download_url` / `download` 实现 —— 这四个方法对两类工具完全一致,唯一差异是
 //! `download` 时缓存文件名的拼法,通过构造时传入的闭包注入。
 
-use crate::infrastructure::config::MirrorConfig;
-use crate::infrastructure::remote::download::download_with_cache;
+use crate::infrastructure::config::{Config, DownloadConfig, MirrorConfig};
+use crate::infrastructure::remote::download::{download_with_cache, DownloadOptions};
 use crate::infrastructure::remote::java_downloader::{DownloadError, DownloadTarget};
 use crate::infrastructure::remote::platform::Platform;
+use crate::infrastructure::remote::signature::verify_download;
 use std::future::Future;
+use std::path::Path;
 use std::pin::Pin;
+use std::sync::{Arc, Mutex};
 
-use super::{MirrorResolver, ResolvedVersion, ToolDownloader, VersionDiscovery};
+use super::{MirrorResolver, ResolveError, ResolvedVersion, ToolDownloader, VersionDiscovery};
 
 type FileNameFn = Box<dyn Fn(&ResolvedVersion, &str) -> String + Send + Sync>;
 
@@ -20,6 +23,18 @@ pub struct GenericDownloader<D: VersionDiscovery> {
     discovery: D,
     resolver: MirrorResolver,
     file_name: FileNameFn,
+    /// 发行方签名文件相对归档 URL 的后缀(如 `.sig`);`None` 表示该工具不做签名校验
+    signature_suffix: Option<&'static str>,
+    /// 发行方在归档旁发布的 SHA-256 文件后缀(如 `.sha256.txt`);版本本身没带校验和时据此补上
+    checksum_suffix: Option<&'static str>,
+    /// 最近一次镜像失败的原因(整体超时取消下载时仍可读到)
+    last_error: Mutex<Option<String>>,
+    /// 是否校验 SHA-256 与发行方签名(`install --no-verify` 关闭)
+    verify: bool,
+    /// 版本没有公布 SHA-256 时拒绝下载(而不是不校验地继续)
+    require_checksum: bool,
+    /// 下载设置(重试 / 超时 / 签名);`None` 时每次下载读取配置文件
+    download_config: Option<DownloadConfig>,
 }
 
 impl<D: VersionDiscovery> GenericDownloader<D> {
@@ -33,9 +48,55 @@ impl<D: VersionDiscovery> GenericDownloader<D> {
             discovery,
             resolver: MirrorResolver::new(mirrors),
             file_name: Box::new(file_name),
+            signature_suffix: None,
+            checksum_suffix: None,
+            last_error: Mutex::new(None),
+            verify: true,
+            require_checksum: false,
+            download_config: None,
         }
     }
 
+    /// 声明发行方在归档旁发布 `<url><suffix>` 分离签名,
+    /// `download.verify_signatures` 开启时据此校验。
+    pub fn with_signature_suffix(mut self, suffix: &'static str) -> Self {
+        self.signature_suffix = Some(suffix);
+        self
+    }
+
+    /// 声明发行方在归档旁发布 `<url><suffix>` 校验文件(sha256sum 格式);
+    /// 版本发现没有给出 SHA-256 时从镜像上取。
+    pub fn with_checksum_suffix(mut self, suffix: &'static str) -> Self {
+        self.checksum_suffix = Some(suffix);
+        self
+    }
+
+    /// 要求每个版本都有 SHA-256;缺失时报错并提示 `--no-verify`,而不是静默跳过校验。
+    pub fn requiring_checksum(mut self) -> Self {
+        self.require_checksum = true;
+        self
+    }
+
+    /// 使用调用方给出的下载设置(已叠加 `--timeout` / `--retries` 等覆盖),
+    /// 镜像探测与下载都改用按它构建的客户端。
+    pub fn with_download_config(mut self, config: DownloadConfig) -> Result<Self, String> {
+        let client = crate::infrastructure::remote::http::client_for(&config)?;
+        self.resolver = self.resolver.with_client(client);
+        self.download_config = Some(config);
+        Ok(self)
+    }
+
+    /// 跳过 SHA-256 与签名校验(`install --no-verify`);解压后的结构校验不受影响。
+    pub fn without_verification(mut self) -> Self {
+        self.verify = false;
+        self
+    }
+
+    /// 该版本在各启用镜像上的下载 URL(按优先级,不探测可用性)。
+    pub fn candidate_urls(&self, version: &ResolvedVersion) -> Vec<(String, String)> {
+        self.resolver.candidate_urls(&version.template_vars)
+    }
+
     /// 强制重新拉取远端版本缓存(若发现策略支持)。
     pub async fn refresh(&self) -> Result<(), DownloadError> {
         self.discovery
@@ -45,6 +106,30 @@ impl<D: VersionDiscovery> GenericDownloader<D> {
     }
 }
 
+impl<D: VersionDiscovery> GenericDownloader<D> {
+    /// 按镜像优先级取归档旁的 `<url><checksum_suffix>`,返回第一个合法的 SHA-256。
+    async fn published_checksum(&self, candidates: &[(String, String)]) -> Option<String> {
+        let suffix = self.checksum_suffix?;
+        for (name, url) in candidates {
+            let url = format!("{url}{suffix}");
+            let text = match self.resolver.client().get(&url).send().await {
+                Ok(resp) if resp.status().is_success() => resp.text().await.ok(),
+                _ => None,
+            };
+            let sha256 = text
+                .as_deref()
+                .and_then(|t| t.split_whitespace().next())
+                .filter(|h| h.len() == 64 && h.chars().all(|c| c.is_ascii_hexdigit()))
+                .map(str::to_lowercase);
+            if sha256.is_some() {
+                tracing::debug!(mirror = %name, url = %url, "using published checksum");
+                return sha256;
+            }
+        }
+        None
+    }
+}
+
 impl<D: VersionDiscovery> ToolDownloader for GenericDownloader<D> {
     fn list_available_versions(
         &self,
@@ -94,16 +179,263 @@ impl<D: VersionDiscovery> ToolDownloader for GenericDownloader<D> {
         let vars = version.template_vars.clone();
         let version_clone = version.clone();
         Box::pin(async move {
-            let url = self
-                .resolver
-                .resolve(&vars)
-                .await
-                .map_err(|e| DownloadError::from(e.to_string()))?;
             let mirror_name = self.resolver.first_mirror_name().to_string();
             let file_name = (self.file_name)(&version_clone, &mirror_name);
-            download_with_cache(self.resolver.client(), &url, &file_name, progress_callback)
+            let progress: Arc<dyn Fn(u64, u64) + Send + Sync> = Arc::from(progress_callback);
+            let candidates = self.resolver.candidate_urls(&vars);
+            let sha256 = match (&vars.sha256, self.verify) {
+                (_, false) => None,
+                (Some(sha256), true) => Some(sha256.clone()),
+                (None, true) => self.published_checksum(&candidates).await,
+            };
+            if self.verify && self.require_checksum && sha256.is_none() {
+                return Err(DownloadError::from(format!(
+                    "No SHA-256 checksum is published for {} ({}); refusing to install it unverified. \
+                     Re-run with --no-verify to install anyway",
+                    version_clone.display, vars.filename
+                )));
+            }
+            // 读不到配置时报错,不能退回默认值而悄悄跳过签名校验
+            let download_config = match &self.download_config {
+                Some(config) => config.clone(),
+                None => Config::load().map_err(DownloadError::from)?.download,
+            };
+
+            // 按优先级逐个镜像下载;某个来源失败(DNS/连接/4xx 或重试耗尽)就换下一个
+            let mut last_err = ResolveError::AllUnavailable.to_string();
+            for (name, url) in candidates {
+                let progress = Arc::clone(&progress);
+                let options = DownloadOptions {
+                    expected_sha256: sha256.clone(),
+                    ..DownloadOptions::from_config(&download_config)
+                };
+                match download_with_cache(
+                    self.resolver.client(),
+                    &url,
+                    &file_name,
+                    options,
+                    Box::new(move |done, total| progress(done, total)),
+                )
                 .await
-                .map_err(|e| DownloadError::from(e.to_string()))
+                {
+                    Ok(target) => {
+                        if let (Some(suffix), DownloadTarget::File(path), true) =
+                            (self.signature_suffix, &target, self.verify)
+                        {
+                            // 签名不符不换镜像:归档已过 SHA-256,说明发行内容本身有问题
+                            verify_download(
+                                self.resolver.client(),
+                                &download_config,
+                                &url,
+                                Path::new(path),
+                                suffix,
+                            )
+                            .await
+                            .map_err(DownloadError::from)?;
+                        }
+                        return Ok(target);
+                    }
+                    Err(e) => {
+                        crate::cli::print::warn(&format!("Mirror {name} failed: {e}"));
+                        *self.last_error.lock().unwrap() = Some(format!("mirror {name}: {e}"));
+                        last_err = e;
+                    }
+                }
+            }
+            Err(DownloadError::from(last_err))
+        })
+    }
+
+    fn last_error(&self) -> Option<String> {
+        self.last_error.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::tool_protocol::{DiscoveryError, TemplateVars};
+    use crate::testutil::FnvaHomeGuard;
+
+    struct NoDiscovery;
+
+    impl VersionDiscovery for NoDiscovery {
+        fn list(
+            &self,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<ResolvedVersion>, DiscoveryError>> + Send + '_>>
+        {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+
+        fn find(
+            &self,
+            spec: &str,
+        ) -> Pin<Box<dyn Future<Output = Result<ResolvedVersion, DiscoveryError>> + Send + '_>>
+        {
+            let spec = spec.to_string();
+            Box::pin(async move { Err(DiscoveryError::NotFound(spec)) })
+        }
+    }
+
+    #[tokio::test]
+    async fn no_verify_skips_checksum_that_would_reject_download() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let mut config = Config::new();
+        config.download.retry_count = 0;
+        config.save().unwrap();
+
+        let server = httpmock::MockServer::start();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/jdk.tar.gz");
+            then.status(200).body("payload");
+        });
+        let mirrors = vec![MirrorConfig {
+            name: "local".to_string(),
+            priority: 1,
+            base_url: server.base_url(),
+            url_template: "{base_url}/{filename}".to_string(),
+            enabled: true,
+        }];
+        let version = ResolvedVersion {
+            version: "21".to_string(),
+            major: Some(21),
+            is_lts: true,
+            display: "JDK 21".to_string(),
+            template_vars: TemplateVars {
+                filename: "jdk.tar.gz".to_string(),
+                sha256: Some("00".repeat(32)),
+                ..TemplateVars::default()
+            },
+        };
+        let downloader = |verify: bool, file: &'static str| {
+            let d = GenericDownloader::with_file_name(NoDiscovery, mirrors.clone(), move |_, _| {
+                file.to_string()
+            });
+            if verify {
+                d
+            } else {
+                d.without_verification()
+            }
+        };
+        let platform = Platform::current();
+
+        let Err(err) = downloader(true, "checked.tar.gz")
+            .download(&version, &platform, Box::new(|_, _| {}))
+            .await
+        else {
+            panic!("checksum mismatch should fail the download");
+        };
+        assert!(format!("{err}").contains("SHA256 mismatch"), "{err}");
+
+        let target = downloader(false, "unchecked.tar.gz")
+            .download(&version, &platform, Box::new(|_, _| {}))
+            .await
+            .unwrap();
+        let DownloadTarget::File(path) = target else {
+            panic!("expected a file");
+        };
+        assert_eq!(std::fs::read(path).unwrap(), b"payload");
+    }
+
+    #[tokio::test]
+    async fn missing_checksum_is_rejected_unless_no_verify() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let mut config = Config::new();
+        config.download.retry_count = 0;
+        config.save().unwrap();
+
+        let server = httpmock::MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/jdk.tar.gz");
+            then.status(200).body("payload");
+        });
+        let mirrors = vec![MirrorConfig {
+            name: "local".to_string(),
+            priority: 1,
+            base_url: server.base_url(),
+            url_template: "{base_url}/{filename}".to_string(),
+            enabled: true,
+        }];
+        let version = ResolvedVersion {
+            version: "21".to_string(),
+            major: Some(21),
+            is_lts: true,
+            display: "JDK 21".to_string(),
+            template_vars: TemplateVars {
+                filename: "jdk.tar.gz".to_string(),
+                sha256: None,
+                ..TemplateVars::default()
+            },
+        };
+        let downloader = || {
+            GenericDownloader::with_file_name(NoDiscovery, mirrors.clone(), |_, _| {
+                "unpublished.tar.gz".to_string()
+            })
+            .requiring_checksum()
+        };
+        let platform = Platform::current();
+
+        let Err(err) = downloader()
+            .download(&version, &platform, Box::new(|_, _| {}))
+            .await
+        else {
+            panic!("a version without a checksum should not be downloaded");
+        };
+        let err = format!("{err}");
+        assert!(err.contains("No SHA-256 checksum"), "{err}");
+        assert!(err.contains("--no-verify"), "{err}");
+        mock.assert_hits(0);
+
+        downloader()
+            .without_verification()
+            .download(&version, &platform, Box::new(|_, _| {}))
+            .await
+            .unwrap();
+        mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn unreadable_config_fails_instead_of_skipping_signature_check() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let _g = FnvaHomeGuard::new(tmp.path());
+        let config_path = crate::infrastructure::paths::config_path().unwrap();
+        std::fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+        std::fs::write(&config_path, "[download\nverify_signatures = true\n").unwrap();
+
+        let server = httpmock::MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/jdk.tar.gz");
+            then.status(200).body("payload");
+        });
+        let mirrors = vec![MirrorConfig {
+            name: "local".to_string(),
+            priority: 1,
+            base_url: server.base_url(),
+            url_template: "{base_url}/{filename}".to_string(),
+            enabled: true,
+        }];
+        let version = ResolvedVersion {
+            version: "21".to_string(),
+            major: Some(21),
+            is_lts: true,
+            display: "JDK 21".to_string(),
+            template_vars: TemplateVars {
+                filename: "jdk.tar.gz".to_string(),
+                ..TemplateVars::default()
+            },
+        };
+        let Err(err) = GenericDownloader::with_file_name(NoDiscovery, mirrors, |_, _| {
+            "signed.tar.gz".to_string()
         })
+        .with_signature_suffix(".sig")
+        .download(&version, &Platform::current(), Box::new(|_, _| {}))
+        .await
+        else {
+            panic!("a broken config must not fall back to unverified defaults");
+        };
+        assert!(format!("{err}").contains("config"), "{err}");
+        mock.assert_hits(0);
     }
 }
@@ -37,11 +37,17 @@ impl MirrorResolver {
     pub fn new(mut mirrors: Vec<MirrorConfig>) -> Self {
         mirrors.sort_by_key(|m| m.priority);
         Self {
-            client: Client::new(),
+            client: crate::infrastructure::remote::http::http_client(),
             mirrors,
         }
     }
 
+    /// 换用指定的 HTTP 客户端(如按本次命令的超时覆盖构建的客户端)
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
     /// 底层 HTTP 客户端(供下载器复用同一连接池)
     pub fn client(&self) -> &Client {
         &self.client
@@ -55,6 +61,20 @@ impl MirrorResolver {
             .unwrap_or("unknown")
     }
 
+    /// 按 priority 渲染所有启用镜像的 `(镜像名, URL)`,不做探测(供列表/审计输出)。
+    pub fn candidate_urls(&self, vars: &TemplateVars) -> Vec<(String, String)> {
+        self.mirrors
+            .iter()
+            .filter(|m| m.enabled)
+            .map(|m| {
+                (
+                    m.name.clone(),
+                    TemplateVars::render(&m.url_template, &m.base_url, vars),
+                )
+            })
+            .collect()
+    }
+
     /// 按 priority 遍历启用的镜像 → 渲染 → HEAD 探测(5s)→ 返回首个可用 URL。
     pub async fn resolve(&self, vars: &TemplateVars) -> Result<String, ResolveError> {
         for mirror in &self.mirrors {
@@ -69,3 +89,45 @@ impl MirrorResolver {
         Err(ResolveError::AllUnavailable)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mirror(name: &str, priority: u32, enabled: bool) -> MirrorConfig {
+        MirrorConfig {
+            name: name.to_string(),
+            priority,
+            base_url: format!("https://{name}.example"),
+            url_template: "{base_url}/{version}/{filename}".to_string(),
+            enabled,
+        }
+    }
+
+    #[test]
+    fn candidate_urls_follow_priority_and_skip_disabled() {
+        let resolver = MirrorResolver::new(vec![
+            mirror("b", 2, true),
+            mirror("off", 0, false),
+            mirror("a", 1, true),
+        ]);
+        let vars = TemplateVars {
+            version: "3.9.9".to_string(),
+            filename: "pkg.tar.gz".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolver.candidate_urls(&vars),
+            vec![
+                (
+                    "a".to_string(),
+                    "https://a.example/3.9.9/pkg.tar.gz".to_string()
+                ),
+                (
+                    "b".to_string(),
+                    "https://b.example/3.9.9/pkg.tar.gz".to_string()
+                ),
+            ]
+        );
+    }
+}
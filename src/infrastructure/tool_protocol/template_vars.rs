@@ -15,10 +15,12 @@ pub struct TemplateVars {
     pub tag: Option<String>,
     /// `{filename}` —— 已按平台解析好的最终文件名
     pub filename: String,
-    /// `{os}` —— linux / macos / windows
+    /// `{os}` —— linux / macos / windows(Java 按 Adoptium 目录命名,macOS 为 mac)
     pub os: String,
     /// `{arch}` —— x64 / aarch64
     pub arch: String,
+    /// 注册表发布的资产 SHA-256(可选);有值时下载后校验
+    pub sha256: Option<String>,
     /// 额外自由变量(供未来扩展,如 {classifier})
     pub extra: HashMap<String, String>,
 }
@@ -6,31 +6,52 @@ use std::process;
 
 #[tokio::main]
 async fn main() {
-    let cli =
-        Cli::from_arg_matches(&Cli::command().get_matches()).expect("Failed to parse arguments");
+    let matches = Cli::command().get_matches();
+    let json = fnva::cli::json_requested(&matches);
+    let cli = Cli::from_arg_matches(&matches).expect("Failed to parse arguments");
+    // 必须在任何路径被解析之前设置(CommandHandler::new 会做布局迁移)
+    fnva::infrastructure::paths::set_config_dir(cli.config_dir.clone());
+    fnva::infrastructure::logging::init(cli.log_level.as_deref());
+    fnva::infrastructure::remote::offline::set_offline(cli.offline);
 
     let mut handler = match CommandHandler::new() {
         Ok(handler) => handler,
         Err(e) => {
-            report_error(&e);
+            report_error(&e, json);
             process::exit(1);
         }
     };
 
     if let Err(e) = handler.handle_command(cli.command).await {
-        report_error(&e);
+        report_error(&e, json);
         process::exit(1);
     }
 }
 
 /// 按错误类型打印用户友好的错误信息 + 一条修复建议。
-fn report_error(e: &AppError) {
+///
+/// 请求了 JSON 输出时改为向 stderr 写一行 `{"error":{"code":...,"message":...}}`。
+fn report_error(e: &AppError, json: bool) {
+    if json {
+        let envelope = serde_json::json!({
+            "error": { "code": e.code(), "message": e.to_string() }
+        });
+        eprintln!("{envelope}");
+        return;
+    }
     print::failure("Command failed", Some(&e.to_string()));
+    let offline = fnva::infrastructure::remote::offline::is_offline();
     let hint: &str = match e.root_cause() {
+        AppError::Network { .. } | AppError::Installation { .. } if offline => {
+            "fnva is in offline mode; drop --offline / unset FNVA_OFFLINE to allow network access."
+        }
         AppError::NotFound { .. } => "Run `fnva <type> list` to see available environment names.",
         AppError::Network { .. } => {
             "Check mirror URLs / proxy in ~/.fnva/config.toml, or your network connection."
         }
+        AppError::Installation { .. } => {
+            "Run `fnva doctor --network` to check mirror reachability, then retry."
+        }
         AppError::Permission { .. } => "Check ownership and permissions of ~/.fnva.",
         AppError::Config { .. } => "Inspect / fix ~/.fnva/config.toml (or run `fnva config sync`).",
         _ => return,
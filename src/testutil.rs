@@ -9,6 +9,11 @@ use std::sync::{Mutex, OnceLock};
 
 static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
 
+/// 占用全局环境变量锁;改 `FNVA_HOME` 以外的进程环境变量的测试也用它串行。
+pub fn env_lock() -> std::sync::MutexGuard<'static, ()> {
+    LOCK.get_or_init(|| Mutex::new(())).lock().unwrap()
+}
+
 /// RAII guard:构造时把 `FNVA_HOME` 指向 `dir`,drop 时还原。
 /// 持有期间占用全局锁,使依赖 `FNVA_HOME` 的测试串行执行。
 pub struct FnvaHomeGuard {
@@ -17,7 +22,7 @@ pub struct FnvaHomeGuard {
 
 impl FnvaHomeGuard {
     pub fn new(dir: &Path) -> Self {
-        let lock = LOCK.get_or_init(|| Mutex::new(())).lock().unwrap();
+        let lock = env_lock();
         std::env::set_var("FNVA_HOME", dir);
         Self { _lock: lock }
     }
@@ -26,5 +31,6 @@ impl FnvaHomeGuard {
 impl Drop for FnvaHomeGuard {
     fn drop(&mut self) {
         std::env::remove_var("FNVA_HOME");
+        crate::infrastructure::paths::set_config_dir(None);
     }
 }
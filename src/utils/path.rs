@@ -1,5 +1,31 @@
 use std::path::Path;
 
+/// 目录下所有文件的总字节数(不跟随符号链接,读不到的条目忽略)
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// 先写同目录的临时文件再 rename 覆盖,读者只会看到旧内容或完整的新内容。
+pub fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_os_string();
+    tmp.push(format!(".tmp{}", std::process::id()));
+    let tmp = std::path::PathBuf::from(tmp);
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
+
 /// 标准化路径格式，处理反斜杠和大小写问题
 pub fn normalize_path(path: &str) -> String {
     let p = Path::new(path);
@@ -8,3 +34,140 @@ pub fn normalize_path(path: &str) -> String {
         Err(_) => p.to_string_lossy().replace('\\', "/"),
     }
 }
+
+/// 整理环境主目录:去掉首尾空白、合并重复分隔符、去掉末尾分隔符(根目录保留)。
+///
+/// `windows` 时 `/` 统一成 `\`,并保留 UNC 前缀 `\\`;否则只处理 `/`,
+/// 反斜杠在 Unix 上是合法的文件名字符,不做改动。
+pub fn clean_home_path(path: &str, windows: bool) -> String {
+    let sep = if windows { '\\' } else { '/' };
+    let path = path.trim();
+    let unified = if windows {
+        path.replace('/', "\\")
+    } else {
+        path.to_string()
+    };
+    let (prefix, rest) = match unified.strip_prefix(r"\\") {
+        Some(rest) if windows => (r"\\", rest),
+        _ => ("", unified.as_str()),
+    };
+
+    let mut out = String::from(prefix);
+    for c in rest.chars() {
+        if c == sep && out.len() > prefix.len() && out.ends_with(sep) {
+            continue;
+        }
+        out.push(c);
+    }
+    // `/` 与 `C:\` 是根目录,末尾分隔符不能去
+    let is_root = |s: &str| {
+        s.len() == prefix.len() + 1 || (windows && s.len() == 3 && s.as_bytes()[1] == b':')
+    };
+    while out.ends_with(sep) && !is_root(&out) {
+        out.pop();
+    }
+    out
+}
+
+/// 按当前平台整理环境主目录(写入配置前调用)。
+pub fn clean_home(path: &str) -> String {
+    clean_home_path(path, cfg!(windows))
+}
+
+/// 相对路径按 `cwd` 拼成绝对路径,并在词法上去掉 `.` / `..`(不解析符号链接);
+/// 绝对路径原样返回。
+pub fn absolutize_against(path: &Path, cwd: &Path) -> std::path::PathBuf {
+    use std::path::Component;
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    let mut out = cwd.to_path_buf();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// 用户在命令行给出的路径:相对当前目录转成绝对路径,不存在时报错。
+pub fn resolve_input_path(path: &str) -> Result<String, String> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err("path must not be empty".to_string());
+    }
+    let cwd = std::env::current_dir().map_err(|e| format!("cannot read current directory: {e}"))?;
+    let resolved = absolutize_against(Path::new(trimmed), &cwd);
+    if !resolved.exists() {
+        return Err(format!("path does not exist: {}", resolved.display()));
+    }
+    Ok(resolved.to_string_lossy().into_owned())
+}
+
+/// 切换脚本用的 `(主目录, bin 目录)`,按目标 shell 的分隔符拼接。
+pub fn home_and_bin(home: &str, windows: bool) -> (String, String) {
+    let home = clean_home_path(home, windows);
+    let sep = if windows { '\\' } else { '/' };
+    let bin = if home.ends_with(sep) {
+        format!("{home}bin")
+    } else {
+        format!("{home}{sep}bin")
+    };
+    (home, bin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_home_strips_trailing_and_duplicate_separators() {
+        assert_eq!(clean_home_path("/opt/jdk17/", false), "/opt/jdk17");
+        assert_eq!(clean_home_path(" /opt//jdk17/// ", false), "/opt/jdk17");
+        assert_eq!(clean_home_path("/", false), "/");
+        assert_eq!(
+            clean_home_path(r"C:/Program Files\Java//jdk17\", true),
+            r"C:\Program Files\Java\jdk17"
+        );
+        assert_eq!(clean_home_path(r"C:\", true), r"C:\");
+        assert_eq!(
+            clean_home_path(r"\\server\share\\jdk\", true),
+            r"\\server\share\jdk"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn relative_paths_resolve_against_cwd() {
+        let cwd = Path::new("/home/dev/project");
+        assert_eq!(
+            absolutize_against(Path::new("./jdk17"), cwd),
+            Path::new("/home/dev/project/jdk17")
+        );
+        assert_eq!(
+            absolutize_against(Path::new("../tools/./jdk17"), cwd),
+            Path::new("/home/dev/tools/jdk17")
+        );
+        assert_eq!(
+            absolutize_against(Path::new("/opt/jdk17"), cwd),
+            Path::new("/opt/jdk17")
+        );
+    }
+
+    #[test]
+    fn bin_dir_uses_target_shell_separator() {
+        assert_eq!(
+            home_and_bin("/opt/jdk17/", false),
+            ("/opt/jdk17".to_string(), "/opt/jdk17/bin".to_string())
+        );
+        assert_eq!(
+            home_and_bin(r"D:/jdk\17\", true),
+            (r"D:\jdk\17".to_string(), r"D:\jdk\17\bin".to_string())
+        );
+        assert_eq!(home_and_bin("/", false).1, "/bin");
+    }
+}
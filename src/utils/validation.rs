@@ -1,6 +1,9 @@
 use std::path::Path;
 
 /// 验证 Java HOME 路径是否有效
+///
+/// 目录与 `bin/java` 都可以是符号链接(如 sdkman 的 `current`),按链接目标判断;
+/// 悬空链接视为无效。
 pub fn validate_java_home(java_home: &str) -> bool {
     let java_path = Path::new(java_home);
 
@@ -25,16 +28,30 @@ pub fn validate_java_home(java_home: &str) -> bool {
     java_exe.exists()
 }
 
+/// 判断 Java HOME 是否为 JDK(含 `bin/javac`);只有 `bin/java` 的是 JRE。
+pub fn is_jdk(java_home: &str) -> bool {
+    let javac = if cfg!(target_os = "windows") {
+        "javac.exe"
+    } else {
+        "javac"
+    };
+    Path::new(java_home).join("bin").join(javac).exists()
+}
+
 /// 验证工具
 pub struct ValidationUtils;
 
 impl ValidationUtils {
     /// 验证环境名称是否有效
     pub fn validate_environment_name(name: &str) -> Result<(), String> {
-        if name.is_empty() {
+        if name.trim().is_empty() {
             return Err("Environment name cannot be empty".to_string());
         }
 
+        if name.trim() != name {
+            return Err("Environment name cannot start or end with whitespace".to_string());
+        }
+
         if name.len() > 100 {
             return Err("Environment name too long (max 100 characters)".to_string());
         }
@@ -198,11 +215,54 @@ mod tests {
         assert!(!validate_java_home("/nonexistent/path"));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_validate_java_home_follows_symlinks() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let real = tmp.path().join("17.0.9-tem");
+        std::fs::create_dir_all(real.join("bin")).unwrap();
+        std::fs::write(tmp.path().join("java-real"), "").unwrap();
+        std::os::unix::fs::symlink(tmp.path().join("java-real"), real.join("bin/java")).unwrap();
+        let current = tmp.path().join("current");
+        std::os::unix::fs::symlink(&real, &current).unwrap();
+        assert!(validate_java_home(&current.to_string_lossy()));
+
+        let dangling = tmp.path().join("dangling");
+        std::os::unix::fs::symlink(tmp.path().join("gone"), &dangling).unwrap();
+        assert!(!validate_java_home(&dangling.to_string_lossy()));
+    }
+
+    #[test]
+    fn test_is_jdk_distinguishes_jdk_from_jre() {
+        let (java, javac) = if cfg!(target_os = "windows") {
+            ("java.exe", "javac.exe")
+        } else {
+            ("java", "javac")
+        };
+        let tmp = tempfile::TempDir::new().unwrap();
+        let jdk = tmp.path().join("jdk");
+        let jre = tmp.path().join("jre");
+        for home in [&jdk, &jre] {
+            std::fs::create_dir_all(home.join("bin")).unwrap();
+            std::fs::write(home.join("bin").join(java), "").unwrap();
+        }
+        std::fs::write(jdk.join("bin").join(javac), "").unwrap();
+
+        let (jdk, jre) = (jdk.to_string_lossy(), jre.to_string_lossy());
+        assert!(validate_java_home(&jdk) && validate_java_home(&jre));
+        assert!(is_jdk(&jdk));
+        assert!(!is_jdk(&jre));
+    }
+
     #[test]
     fn test_validate_environment_name() {
         assert!(ValidationUtils::validate_environment_name("valid_name").is_ok());
         assert!(ValidationUtils::validate_environment_name("invalid/name").is_err());
         assert!(ValidationUtils::validate_environment_name("").is_err());
+        assert!(ValidationUtils::validate_environment_name("  ").is_err());
+        assert!(ValidationUtils::validate_environment_name(" jdk17").is_err());
+        assert!(ValidationUtils::validate_environment_name("..").is_err());
+        assert!(ValidationUtils::validate_environment_name("a\\b").is_err());
     }
 
     #[test]
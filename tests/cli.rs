@@ -19,6 +19,21 @@ fn version_flag_prints_version() {
         .stdout(predicate::str::contains(env!("CARGO_PKG_VERSION")));
 }
 
+#[test]
+fn fnva_home_takes_priority_over_home() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tempfile::TempDir::new().unwrap();
+    fnva_cmd()
+        .env("HOME", home.path())
+        .env("USERPROFILE", home.path())
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "list"])
+        .assert()
+        .success();
+    assert!(tmp.path().join(".fnva").join("config.toml").exists());
+    assert!(!home.path().join(".fnva").exists());
+}
+
 #[test]
 fn java_list_on_empty_config_succeeds() {
     let tmp = tempfile::TempDir::new().unwrap();
@@ -83,3 +98,1737 @@ fn doctor_runs_and_reports_checks() {
         .assert()
         .stdout(predicate::str::contains("fnva doctor"));
 }
+
+#[test]
+fn doctor_only_warns_about_config_problems_that_validate_rejects() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva/config.toml"),
+        "default_java_env = \"ghost\"\n",
+    )
+    .unwrap();
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["config", "validate"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("ghost"));
+
+    let output = fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .arg("doctor")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Config: default_java_env = 'ghost'"),
+        "{stderr}"
+    );
+    assert!(!stdout.contains("problem(s)") && !stderr.contains("problem(s)"));
+}
+
+#[test]
+fn config_dir_flag_keeps_profiles_isolated() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dir_a = tmp.path().join("a");
+    let dir_b = tmp.path().join("b");
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .arg("--config-dir")
+        .arg(&dir_a)
+        .args([
+            "cc",
+            "add",
+            "--name",
+            "only-in-a",
+            "-k",
+            "sk-test",
+            "-u",
+            "https://api.example.com",
+        ])
+        .assert()
+        .success();
+    assert!(dir_a.join("config.toml").exists());
+    assert!(!tmp.path().join(".fnva").exists());
+
+    // 全局参数也可放在子命令之后
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "list", "--config-dir"])
+        .arg(&dir_b)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("only-in-a").not());
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .arg("--config-dir")
+        .arg(&dir_a)
+        .args(["cc", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("only-in-a"));
+}
+
+#[test]
+fn java_current_quiet_without_env_exits_1_silently() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "current", "--quiet"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn cc_copy_clones_env_with_model_override() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args([
+            "cc",
+            "copy",
+            "anthropic-cc",
+            "anthropic-dev",
+            "--model",
+            "dev-model",
+        ])
+        .assert()
+        .success();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "list"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("anthropic-dev").and(predicate::str::contains("dev-model")),
+        );
+    // 目标已存在时拒绝
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "copy", "anthropic-cc", "anthropic-dev"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn fnva_ascii_forces_ascii_only_list_output() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .env("FNVA_ASCII", "1")
+        .args(["cc", "list"])
+        .assert()
+        .success()
+        .stdout(predicate::function(|s: &str| s.is_ascii()));
+}
+
+#[test]
+fn cc_env_file_quotes_special_values() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args([
+            "cc",
+            "copy",
+            "anthropic-cc",
+            "spaced",
+            "--api-key",
+            "sk key=abc==",
+        ])
+        .assert()
+        .success();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "env-file", "spaced"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "ANTHROPIC_AUTH_TOKEN=\"sk key=abc==\"\n",
+        ));
+
+    let out = tmp.path().join("cc.env");
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "env-file", "spaced", "-o", out.to_str().unwrap()])
+        .assert()
+        .success();
+    let written = std::fs::read_to_string(&out).unwrap();
+    assert!(written
+        .lines()
+        .all(|l| l.contains('=') && !l.starts_with("export")));
+}
+
+#[test]
+fn java_use_print_path_prints_bin_dir_only() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path().join("jdk-17");
+    let bin = home.join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let java = if cfg!(windows) { "java.exe" } else { "java" };
+    std::fs::write(bin.join(java), b"").unwrap();
+
+    // 直接写配置:`java add` 会执行 java -version,假的 bin/java 过不了
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva").join("config.toml"),
+        format!(
+            "[[java_environments]]\nname = \"17\"\njava_home = {:?}\ndescription = \"\"\n",
+            home.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "use", "17", "--print-path"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", bin.display()));
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "use", "ghost", "--print-path"])
+        .assert()
+        .failure()
+        .stdout("");
+}
+
+#[test]
+fn no_color_keeps_list_current_and_history_free_of_ansi_codes() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    for args in [&["cc", "list"][..], &["java", "current"], &["history"]] {
+        fnva_cmd()
+            .env("FNVA_HOME", tmp.path())
+            .env("NO_COLOR", "1")
+            .args(args)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\x1b[").not());
+    }
+}
+
+#[test]
+fn config_path_follows_config_dir_override() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let dir = tmp.path().join("profile");
+    let out = fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .arg("--config-dir")
+        .arg(&dir)
+        .args(["config", "path", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["config_dir"], dir.display().to_string());
+    assert_eq!(
+        json["config_file"],
+        dir.join("config.toml").display().to_string()
+    );
+    assert_eq!(
+        json["java_packages_dir"],
+        dir.join("packages").join("java").display().to_string()
+    );
+}
+
+#[test]
+fn config_set_get_and_list_scalar_settings() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["config", "set", "download.retry_count", "7"])
+        .assert()
+        .success();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["config", "get", "download.retry_count"])
+        .assert()
+        .success()
+        .stdout("7\n");
+
+    let config_path = tmp.path().join(".fnva").join("config.toml");
+    let config: toml::Value =
+        toml::from_str(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(config["download"]["retry_count"].as_integer(), Some(7));
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["config", "set", "download.retry_count", "many"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("non-negative integer"));
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["config", "set", "no.such.key", "1"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("settable keys"));
+
+    let out = fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["config", "list", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["download.retry_count"], "7");
+    assert_eq!(json["history_limit"], "100");
+    assert!(json["java_registry_url"].is_null());
+}
+
+#[test]
+fn history_limit_caps_entries_and_clear_empties_them() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    for name in ["k1", "k2"] {
+        fnva_cmd()
+            .env("FNVA_HOME", tmp.path())
+            .args(["cc", "add", "--name", name, "--api-key", "sk-test"])
+            .args(["--base-url", "https://cc.invalid"])
+            .assert()
+            .success();
+    }
+    let config = tmp.path().join(".fnva").join("config.toml");
+    let content = std::fs::read_to_string(&config).unwrap();
+    std::fs::write(
+        &config,
+        content.replace("history_limit = 100", "history_limit = 2"),
+    )
+    .unwrap();
+
+    for name in ["k1", "k2", "k1"] {
+        fnva_cmd()
+            .env("FNVA_HOME", tmp.path())
+            .args(["cc", "use", name, "--shell", "bash"])
+            .assert()
+            .success();
+    }
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["history", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"count\": 2"));
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["history", "clear", "--type", "cc"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cleared 2 cc history entries"));
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["history", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"count\": 0"));
+}
+
+#[test]
+fn java_set_java_opts_is_exported_on_use_and_cleared_by_unset() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path().join("jdk-17");
+    let bin = home.join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let java = if cfg!(windows) { "java.exe" } else { "java" };
+    std::fs::write(bin.join(java), b"").unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva").join("config.toml"),
+        format!(
+            "[[java_environments]]\nname = \"17\"\njava_home = {:?}\ndescription = \"\"\n",
+            home.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    let use_bash = || {
+        fnva_cmd()
+            .env("FNVA_HOME", tmp.path())
+            .args(["java", "use", "17", "--shell", "bash"])
+            .assert()
+            .success()
+    };
+    use_bash().stdout(predicate::str::contains("export JAVA_OPTS=").not());
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "set", "17", "--java-opts", "-Xmx2g -Dmsg='hi'"])
+        .assert()
+        .success();
+    use_bash().stdout(predicate::str::contains(
+        "export JAVA_OPTS='-Xmx2g -Dmsg='\\''hi'\\'''\n",
+    ));
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "env-file", "17"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("JAVA_OPTS="));
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "set", "17", "--unset-java-opts"])
+        .assert()
+        .success();
+    use_bash().stdout(predicate::str::contains("export JAVA_OPTS=").not());
+}
+
+#[test]
+fn java_set_extra_paths_prepends_them_after_bin() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path().join("graalvm");
+    let bin = home.join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let java = if cfg!(windows) { "java.exe" } else { "java" };
+    std::fs::write(bin.join(java), b"").unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva").join("config.toml"),
+        format!(
+            "[[java_environments]]\nname = \"graal\"\njava_home = {:?}\n",
+            home.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "set", "graal", "--extra-path", "/opt/svm/bin"])
+        .args(["--extra-path", "/opt/tools"])
+        .assert()
+        .success();
+    let prefix = format!("{}:/opt/svm/bin:/opt/tools", bin.display());
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "use", "graal", "--shell", "bash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "export FNVA_JAVA_BIN=\"{prefix}\""
+        )));
+    // dotenv 不展开 ${PATH}:只输出字面值变量
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "env-file", "graal"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("JAVA_HOME="))
+        .stdout(predicate::str::contains("PATH=").not());
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "set", "graal", "--clear-extra-paths"])
+        .assert()
+        .success();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "use", "graal", "--shell", "bash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("/opt/tools").not());
+}
+
+#[test]
+fn java_use_dash_toggles_to_previous_env() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let java = if cfg!(windows) { "java.exe" } else { "java" };
+    let mut config = String::new();
+    for name in ["11", "17"] {
+        let home = tmp.path().join(format!("jdk-{name}"));
+        std::fs::create_dir_all(home.join("bin")).unwrap();
+        std::fs::write(home.join("bin").join(java), b"").unwrap();
+        config.push_str(&format!(
+            "[[java_environments]]\nname = \"{name}\"\njava_home = {:?}\ndescription = \"\"\n",
+            home.to_str().unwrap()
+        ));
+    }
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(tmp.path().join(".fnva").join("config.toml"), config).unwrap();
+
+    let java_use = |name: &str| {
+        fnva_cmd()
+            .env("FNVA_HOME", tmp.path())
+            .env_remove("JAVA_HOME")
+            .args(["java", "use", name, "--shell", "bash"])
+            .assert()
+    };
+    java_use("-")
+        .failure()
+        .stderr(predicate::str::contains("No previous java environment"));
+
+    java_use("11").success();
+    java_use("-")
+        .failure()
+        .stderr(predicate::str::contains("No previous java environment"));
+    java_use("17").success();
+    java_use("-")
+        .success()
+        .stdout(predicate::str::contains("FNVA_CURRENT_JAVA=\"11\""));
+    java_use("-")
+        .success()
+        .stdout(predicate::str::contains("FNVA_CURRENT_JAVA=\"17\""));
+}
+
+#[test]
+fn java_info_reports_release_metadata_as_json() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path().join("jdk-17");
+    let bin = home.join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    for tool in ["java", "javac"] {
+        let tool = if cfg!(windows) {
+            format!("{tool}.exe")
+        } else {
+            tool.to_string()
+        };
+        std::fs::write(bin.join(tool), b"").unwrap();
+    }
+    std::fs::write(
+        home.join("release"),
+        "IMPLEMENTOR=\"Azul Systems, Inc.\"\nJAVA_VERSION=\"17.0.12\"\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva").join("config.toml"),
+        format!(
+            "default_java_env = \"17\"\n[[java_environments]]\nname = \"17\"\njava_home = {:?}\ndescription = \"\"\n",
+            home.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    let out = fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "info", "17", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let info: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(info["version"], "17.0.12");
+    assert_eq!(info["vendor"], "Azul Systems, Inc.");
+    assert_eq!(info["is_default"], true);
+    assert_eq!(info["valid"], true);
+    assert_eq!(info["is_jdk"], true);
+    assert_eq!(info["managed"], false);
+    assert!(info.get("size_bytes").is_none());
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "info", "17"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Version     : 17.0.12"));
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "info", "ghost"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not found").or(predicate::str::contains("Not found")));
+}
+
+#[test]
+fn cc_list_filters_by_provider_and_model() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva").join("config.toml"),
+        r#"
+[[cc_environments]]
+name = "claude"
+base_url = "https://api.anthropic.com"
+sonnet_model = "claude-sonnet-4-5"
+
+[[cc_environments]]
+name = "kimi"
+base_url = "https://api.moonshot.cn/anthropic"
+sonnet_model = "kimi-k2"
+
+[[cc_environments]]
+name = "ds-gateway"
+provider = "deepseek"
+base_url = "https://llm.corp.example/v1"
+sonnet_model = "deepseek-chat"
+haiku_model = "deepseek-lite"
+"#,
+    )
+    .unwrap();
+
+    let list = |args: &[&str]| {
+        let out = fnva_cmd()
+            .env("FNVA_HOME", tmp.path())
+            .args(["cc", "list", "--json"])
+            .args(args)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let mut names: Vec<String> = json["environments"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["name"].as_str().unwrap().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+    assert_eq!(list(&[]), ["claude", "ds-gateway", "kimi"]);
+    assert_eq!(list(&["--provider", "Moonshot"]), ["kimi"]);
+    assert_eq!(list(&["--provider", "deepseek"]), ["ds-gateway"]);
+    assert_eq!(list(&["--model-contains", "LITE"]), ["ds-gateway"]);
+    assert!(list(&["--provider", "anthropic", "--model-contains", "kimi"]).is_empty());
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "list", "--provider", "anthropic"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("claude")
+                .and(predicate::str::contains("kimi").not())
+                .and(predicate::str::contains("ds-gateway").not()),
+        );
+}
+
+#[test]
+fn cc_add_preset_instantiates_catalog_entry() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let out = fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "ls-remote", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let presets: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert!(presets
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|p| p["name"] == "glm"));
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "add-preset", "glm"])
+        .assert()
+        .success();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "add-preset", "kimi", "--name", "work"])
+        .args(["--api-key", "sk-test-0123456789"])
+        .assert()
+        .success();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "add-preset", "nope"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("fnva cc presets"));
+
+    let config_path = tmp.path().join(".fnva").join("config.toml");
+    let config: toml::Value =
+        toml::from_str(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+    let envs = config["cc_environments"].as_array().unwrap();
+    let find = |name: &str| {
+        envs.iter()
+            .find(|e| e["name"].as_str() == Some(name))
+            .unwrap()
+    };
+    let glm = find("glm-cc");
+    assert_eq!(glm["api_key"].as_str(), Some("${GLM_API_KEY}"));
+    assert_eq!(
+        glm["base_url"].as_str(),
+        Some("https://open.bigmodel.cn/api/anthropic")
+    );
+    assert_eq!(glm["provider"].as_str(), Some("bigmodel"));
+    let work = find("work");
+    assert_eq!(work["api_key"].as_str(), Some("sk-test-0123456789"));
+    assert_eq!(work["provider"].as_str(), Some("moonshot"));
+}
+
+#[test]
+fn cc_set_edits_model_in_place_and_round_trips() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    let config_path = tmp.path().join(".fnva").join("config.toml");
+    std::fs::write(
+        &config_path,
+        r#"
+[[cc_environments]]
+name = "first"
+base_url = "https://api.anthropic.com"
+sonnet_model = "claude-sonnet-4-5"
+
+[[cc_environments]]
+name = "kimi"
+api_key = "${MOONSHOT_API_KEY}"
+base_url = "https://api.moonshot.cn/anthropic"
+sonnet_model = "kimi-k2"
+"#,
+    )
+    .unwrap();
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "set", "kimi", "model", "kimi-k2-turbo"])
+        .assert()
+        .success();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "set", "kimi", "base_url", "not-a-url"])
+        .assert()
+        .failure();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "set", "kimi", "name", "other"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("settable fields"));
+
+    let config: toml::Value =
+        toml::from_str(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+    let envs = config["cc_environments"].as_array().unwrap();
+    // 原地修改:顺序与其余字段不变
+    assert_eq!(envs[0]["name"].as_str(), Some("first"));
+    assert_eq!(envs[1]["name"].as_str(), Some("kimi"));
+    assert_eq!(envs[1]["sonnet_model"].as_str(), Some("kimi-k2-turbo"));
+    assert_eq!(
+        envs[1]["base_url"].as_str(),
+        Some("https://api.moonshot.cn/anthropic")
+    );
+    assert_eq!(envs[1]["api_key"].as_str(), Some("${MOONSHOT_API_KEY}"));
+}
+
+#[test]
+fn env_export_script_keeps_or_resolves_secret_references() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva").join("config.toml"),
+        r#"
+[[cc_environments]]
+name = "team"
+api_key = "${FNVA_EXPORT_TEST_KEY}"
+base_url = "https://api.anthropic.com"
+sonnet_model = "claude-sonnet-4-5"
+
+[[cc_environments]]
+name = "literal"
+api_key = "sk-literal-456"
+base_url = "https://api.anthropic.com"
+sonnet_model = "claude-sonnet-4-5"
+"#,
+    )
+    .unwrap();
+    let script = tmp.path().join("activate-team.sh");
+    let export = |resolve: bool| {
+        let mut cmd = fnva_cmd();
+        cmd.env("FNVA_HOME", tmp.path())
+            .env("FNVA_EXPORT_TEST_KEY", "sk-team-123")
+            .args(["env", "export-script", "cc", "team"])
+            .arg(&script)
+            .args(["--shell", "bash"]);
+        if resolve {
+            cmd.arg("--resolve-secrets");
+        }
+        cmd.assert().success();
+        std::fs::read_to_string(&script).unwrap()
+    };
+
+    let kept = export(false);
+    assert!(kept.contains("export ANTHROPIC_AUTH_TOKEN=\"${FNVA_EXPORT_TEST_KEY}\""));
+    assert!(!kept.contains("sk-team-123"));
+
+    let resolved = export(true);
+    assert!(resolved.contains("export ANTHROPIC_AUTH_TOKEN=\"sk-team-123\""));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&script).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .env_remove("FNVA_EXPORT_TEST_KEY")
+        .args(["env", "export-script", "cc", "team"])
+        .arg(&script)
+        .args(["--shell", "bash", "--resolve-secrets"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("FNVA_EXPORT_TEST_KEY"));
+
+    // 配置里直接写的密钥:不加 --resolve-secrets 也只允许本人读写
+    let literal = tmp.path().join("activate-literal.sh");
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["env", "export-script", "cc", "literal"])
+        .arg(&literal)
+        .args(["--shell", "bash"])
+        .assert()
+        .success();
+    assert!(std::fs::read_to_string(&literal)
+        .unwrap()
+        .contains("sk-literal-456"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&literal).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn offline_mode_refuses_install_but_lists_from_registry() {
+    let tmp = tempfile::TempDir::new().unwrap();
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["--offline", "java", "install", "21"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("offline mode"));
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .env("FNVA_OFFLINE", "1")
+        .args(["java", "registry", "update"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("offline mode"));
+
+    let out = fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .env("FNVA_OFFLINE", "1")
+        .args(["java", "ls-remote", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert!(!json.as_array().unwrap().is_empty());
+}
+
+#[test]
+fn ls_remote_platforms_lists_every_published_platform() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let out = fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .env("FNVA_OFFLINE", "1")
+        .args(["java", "ls-remote", "21", "--platforms", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let builds = json.as_array().unwrap();
+    let platforms: Vec<&str> = builds
+        .iter()
+        .map(|b| b["platform"].as_str().unwrap())
+        .collect();
+    for key in ["linux-x64", "linux-aarch64", "macos-aarch64", "windows-x64"] {
+        assert!(platforms.contains(&key), "{platforms:?}");
+    }
+    assert!(builds.iter().all(|b| b["version"] == builds[0]["version"]));
+    let windows = builds
+        .iter()
+        .find(|b| b["platform"] == "windows-x64")
+        .unwrap();
+    assert!(windows["filename"].as_str().unwrap().ends_with(".zip"));
+    assert!(windows["download_urls"][0]["url"]
+        .as_str()
+        .unwrap()
+        .contains("/x64/windows/"));
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "ls-remote", "--platforms"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn install_explain_lists_mirror_urls_in_order_without_downloading() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva").join("config.toml"),
+        r#"
+[[mirrors.java]]
+name = "fallback"
+priority = 5
+base_url = "https://fallback.example/jdk"
+url_template = "{base_url}/{filename}"
+
+[[mirrors.java]]
+name = "off"
+priority = 0
+enabled = false
+base_url = "https://off.example"
+url_template = "{base_url}/{filename}"
+
+[[mirrors.java]]
+name = "corp"
+priority = 1
+base_url = "https://corp.example/jdk"
+url_template = "{base_url}/{major}/{filename}"
+"#,
+    )
+    .unwrap();
+
+    let out = fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["--offline", "java", "install", "21", "--explain"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(out).unwrap();
+    let lines: Vec<_> = stdout
+        .lines()
+        .filter(|l| l.starts_with(|c: char| c.is_ascii_digit()))
+        .collect();
+    assert_eq!(lines.len(), 2, "{stdout}");
+    assert!(
+        lines[0].starts_with("1. corp") && lines[0].contains("https://corp.example/jdk/21/"),
+        "{stdout}"
+    );
+    assert!(
+        lines[1].starts_with("2. fallback") && lines[1].contains("https://fallback.example/jdk/"),
+        "{stdout}"
+    );
+    assert!(!tmp
+        .path()
+        .join(".fnva")
+        .join("packages")
+        .join("java")
+        .join("21")
+        .exists());
+}
+
+#[test]
+fn self_update_check_reports_newer_release_and_respects_offline() {
+    use httpmock::prelude::*;
+    let tmp = tempfile::TempDir::new().unwrap();
+    let server = MockServer::start();
+    let releases = server.mock(|when, then| {
+        when.method(GET).path("/repos/fnva/releases/latest");
+        then.status(200).json_body(serde_json::json!({
+            "tag_name": "v999.0.0",
+            "html_url": "https://github.com/Protagonistss/fnva/releases/tag/v999.0.0"
+        }));
+    });
+
+    let out = fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .env(
+            "FNVA_RELEASES_URL",
+            server.url("/repos/fnva/releases/latest"),
+        )
+        .args(["self", "update-check", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["update_available"], true);
+    assert_eq!(json["latest"], "999.0.0");
+    assert_eq!(json["current"], env!("CARGO_PKG_VERSION"));
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .env(
+            "FNVA_RELEASES_URL",
+            server.url("/repos/fnva/releases/latest"),
+        )
+        .args(["--offline", "self", "update-check"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("offline mode"));
+    releases.assert_hits(1);
+}
+
+#[test]
+fn system_config_is_layered_under_user_config() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let system_path = tmp.path().join("system.toml");
+    let system = r#"
+default_cc_env = "corp"
+
+[[cc_environments]]
+name = "corp"
+api_key = "${CORP_API_KEY}"
+base_url = "https://llm.corp.example/anthropic"
+sonnet_model = "corp-sonnet"
+"#;
+    std::fs::write(&system_path, system).unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    let user_path = tmp.path().join(".fnva").join("config.toml");
+    std::fs::write(
+        &user_path,
+        r#"
+[[cc_environments]]
+name = "mine"
+base_url = "https://api.anthropic.com"
+sonnet_model = "claude-sonnet-4-5"
+"#,
+    )
+    .unwrap();
+
+    let cmd = || {
+        let mut cmd = fnva_cmd();
+        cmd.env("FNVA_HOME", tmp.path())
+            .env("FNVA_SYSTEM_CONFIG", &system_path);
+        cmd
+    };
+    let models = || {
+        let out = cmd()
+            .args(["cc", "list", "--json"])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let mut envs: Vec<(String, String)> = json["environments"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| {
+                (
+                    e["name"].as_str().unwrap().to_string(),
+                    e["version"].as_str().unwrap_or_default().to_string(),
+                )
+            })
+            .collect();
+        envs.sort();
+        envs
+    };
+    assert_eq!(
+        models(),
+        [
+            ("corp".to_string(), "corp-sonnet".to_string()),
+            ("mine".to_string(), "claude-sonnet-4-5".to_string())
+        ]
+    );
+
+    cmd()
+        .args(["cc", "set", "corp", "model", "my-override"])
+        .assert()
+        .success();
+    assert_eq!(models()[0], ("corp".to_string(), "my-override".to_string()));
+
+    // 系统层原样不动;用户层只多了覆盖项,没有抄入系统层的其余内容
+    assert_eq!(std::fs::read_to_string(&system_path).unwrap(), system);
+    let user: toml::Value = toml::from_str(&std::fs::read_to_string(&user_path).unwrap()).unwrap();
+    assert!(user.get("default_cc_env").is_none());
+    let names: Vec<_> = user["cc_environments"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["name"].as_str().unwrap())
+        .collect();
+    // 保存时按名称排序
+    assert_eq!(names, ["corp", "mine"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn java_benchmark_times_each_env_and_reports_broken_ones() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempfile::TempDir::new().unwrap();
+    let bin = tmp.path().join("jdk").join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    std::fs::write(bin.join("java"), "#!/bin/sh\nexit 0\n").unwrap();
+    std::fs::set_permissions(bin.join("java"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva").join("config.toml"),
+        format!(
+            "[[java_environments]]\nname = \"ok\"\njava_home = \"{}\"\ndescription = \"\"\nsource = \"manual\"\n\n\
+             [[java_environments]]\nname = \"gone\"\njava_home = \"/nonexistent/jdk\"\ndescription = \"\"\nsource = \"manual\"\n",
+            tmp.path().join("jdk").display()
+        ),
+    )
+    .unwrap();
+
+    let out = fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "benchmark", "--runs", "2", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json[0]["name"], "ok");
+    assert_eq!(json[0]["runs"], 2);
+    assert_eq!(json[1]["name"], "gone");
+    assert!(json[1]["error"].is_string());
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "benchmark", "missing"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn cc_use_json_masks_environment_but_keeps_script_evaluable() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva").join("config.toml"),
+        r#"
+[[cc_environments]]
+name = "kimi"
+api_key = "sk-moonshot-0123456789abcd"
+base_url = "https://api.moonshot.cn/anthropic"
+sonnet_model = "kimi-k2"
+
+[[cc_environments]]
+name = "claude"
+api_key = "${ANTHROPIC_API_KEY}"
+base_url = "https://api.anthropic.com"
+sonnet_model = "claude-sonnet-4-5"
+"#,
+    )
+    .unwrap();
+
+    let use_json_for = |name: &str, extra: &[&str]| {
+        let out = fnva_cmd()
+            .env("FNVA_HOME", tmp.path())
+            .args(["cc", "use", name, "--shell", "bash", "--json"])
+            .args(extra)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        serde_json::from_slice::<serde_json::Value>(&out).unwrap()
+    };
+    let use_json = |extra: &[&str]| use_json_for("kimi", extra);
+    let json = use_json(&[]);
+    assert_eq!(json["success"], true);
+    let env = &json["environment"];
+    assert_eq!(env["name"], "kimi");
+    assert_eq!(env["provider"], "moonshot");
+    assert_eq!(env["base_url"], "https://api.moonshot.cn/anthropic");
+    assert_eq!(env["sonnet_model"], "kimi-k2");
+    assert_eq!(env["api_key"], "****abcd");
+
+    let revealed = use_json(&["--reveal"]);
+    assert_eq!(
+        revealed["environment"]["api_key"],
+        "sk-moonshot-0123456789abcd"
+    );
+
+    // 脚本不打码:与 `--shell` 输出逐字节一致,`${VAR}` 引用原样保留,eval 后拿到真实的值
+    let json = use_json_for("claude", &[]);
+    let script = json["script"].as_str().unwrap();
+    assert!(script.contains("${ANTHROPIC_API_KEY}"), "{script}");
+    assert!(!script.contains("****"), "{script}");
+    let shell_script = fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "use", "claude", "--shell", "bash"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    assert_eq!(script, String::from_utf8(shell_script).unwrap());
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "use", "kimi", "--reveal"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn cc_current_check_reports_provider_status_and_skips_offline() {
+    use httpmock::prelude::*;
+    let tmp = tempfile::TempDir::new().unwrap();
+    let server = MockServer::start();
+    let accepted = server.mock(|when, then| {
+        when.method(GET)
+            .path("/v1/models")
+            .header("x-api-key", "sk-good-0123456789");
+        then.status(200)
+            .json_body(serde_json::json!({ "data": [] }));
+    });
+    let rejected = server.mock(|when, then| {
+        when.method(GET)
+            .path("/v1/models")
+            .header("x-api-key", "sk-bad-0123456789");
+        then.status(401);
+    });
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    let mut config = String::new();
+    for (name, key) in [("good", "sk-good-0123456789"), ("bad", "sk-bad-0123456789")] {
+        config.push_str(&format!(
+            "[[cc_environments]]\nname = \"{name}\"\napi_key = \"{key}\"\nbase_url = \"{}\"\nsonnet_model = \"m\"\n",
+            server.base_url()
+        ));
+    }
+    std::fs::write(tmp.path().join(".fnva").join("config.toml"), config).unwrap();
+    // 当前环境由 ANTHROPIC_* 变量匹配出来
+    let check = |key: &str| {
+        let mut cmd = fnva_cmd();
+        cmd.env("FNVA_HOME", tmp.path())
+            .env("ANTHROPIC_AUTH_TOKEN", key)
+            .env("ANTHROPIC_BASE_URL", server.base_url())
+            .args(["cc", "current", "--check"]);
+        cmd
+    };
+
+    check("sk-good-0123456789")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("reachable and authenticated"));
+    check("sk-bad-0123456789")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("HTTP 401"));
+    let out = check("sk-good-0123456789")
+        .arg("--json")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(json["name"], "good");
+    assert_eq!(json["check"]["status"], 200);
+
+    check("sk-good-0123456789")
+        .arg("--offline")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("offline"));
+    accepted.assert_hits(2);
+    rejected.assert_hits(1);
+}
+
+#[test]
+fn structured_status_line_goes_to_side_channel() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let home = tmp.path().join("jdk-17");
+    std::fs::create_dir_all(home.join("bin")).unwrap();
+    let java = if cfg!(windows) { "java.exe" } else { "java" };
+    std::fs::write(home.join("bin").join(java), b"").unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva").join("config.toml"),
+        format!(
+            "[[java_environments]]\nname = \"17\"\njava_home = {:?}\n\n[[java_environments]]\nname = \"gone\"\njava_home = \"/nonexistent/jdk\"\n",
+            home.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    let out = fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .env("FNVA_STRUCTURED_STATUS", "1")
+        .args(["java", "use", "17", "--shell", "bash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("export JAVA_HOME="))
+        .stdout(predicate::str::contains("\"success\"").not())
+        .get_output()
+        .clone();
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    let line = stderr
+        .lines()
+        .find(|l| l.starts_with('{'))
+        .expect("structured status line");
+    let status: serde_json::Value = serde_json::from_str(line).unwrap();
+    assert_eq!(status["name"], "17");
+    assert_eq!(status["env_type"], "Java");
+    assert_eq!(status["success"], true);
+
+    // 未开启时 stderr 不出现状态行
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "use", "17", "--shell", "bash"])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("\"success\"").not());
+
+    #[cfg(unix)]
+    {
+        let status_file = tmp.path().join("status.json");
+        let bin = assert_cmd::cargo::cargo_bin("fnva");
+        let out = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!(
+                "'{}' java use gone --shell bash 3>'{}'",
+                bin.display(),
+                status_file.display()
+            ))
+            .env("FNVA_HOME", tmp.path())
+            .env("FNVA_STRUCTURED_STATUS", "3")
+            .output()
+            .unwrap();
+        assert!(!out.status.success());
+        let status: serde_json::Value =
+            serde_json::from_str(std::fs::read_to_string(&status_file).unwrap().trim()).unwrap();
+        assert_eq!(status["name"], "gone");
+        assert_eq!(status["success"], false);
+        assert!(status["error"].as_str().is_some());
+    }
+}
+
+#[test]
+fn config_compact_rewrites_messy_java_entries() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let fnva = tmp.path().join(".fnva");
+    std::fs::create_dir_all(&fnva).unwrap();
+    std::fs::write(
+        fnva.join("config.toml"),
+        r#"removed_java_names = ["jdk21", "jdk8"]
+default_java_env = "jdk17"
+
+[[java_environments]]
+name = "jdk17"
+java_home = "/opt//jdk17/"
+source = "scanned"
+
+[[java_environments]]
+name = "JDK17"
+java_home = "/opt/jdk17"
+source = "manual"
+
+[[java_environments]]
+name = "jdk21"
+java_home = "/opt/jdk21"
+"#,
+    )
+    .unwrap();
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["config", "compact", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("jdk17 (kept JDK17)"))
+        .stdout(predicate::str::contains("jdk21"));
+    let untouched = std::fs::read_to_string(fnva.join("config.toml")).unwrap();
+    assert!(untouched.contains("/opt//jdk17/"));
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["config", "compact"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 duplicate(s) removed"));
+    let compacted: toml::Value =
+        toml::from_str(&std::fs::read_to_string(fnva.join("config.toml")).unwrap()).unwrap();
+    let names: Vec<_> = compacted["java_environments"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["JDK17", "jdk21"]);
+    assert_eq!(compacted["default_java_env"].as_str(), Some("JDK17"));
+    assert_eq!(
+        compacted["removed_java_names"].as_array().unwrap(),
+        &[toml::Value::from("jdk8")]
+    );
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["config", "compact"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing to compact"));
+}
+
+#[test]
+fn java_use_without_name_lists_candidates_when_not_interactive() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva").join("config.toml"),
+        "[[java_environments]]\nname = \"jdk11\"\njava_home = \"/opt/jdk11\"\n\n\
+         [[java_environments]]\nname = \"jdk17\"\njava_home = \"/opt/jdk17\"\n",
+    )
+    .unwrap();
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .current_dir(tmp.path())
+        .args(["java", "use", "--list-only"])
+        .assert()
+        .success()
+        .stdout("jdk11\njdk17\n");
+
+    // 测试里 stdin 不是终端:不弹选择,报错并列出可用名称
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .current_dir(tmp.path())
+        .args(["java", "use", "--shell", "bash"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("available: jdk11, jdk17"));
+}
+
+#[cfg(unix)]
+#[test]
+fn java_add_stores_relative_home_as_absolute_path() {
+    use std::os::unix::fs::PermissionsExt;
+    let tmp = tempfile::TempDir::new().unwrap();
+    let project = tmp.path().join("project");
+    let bin = project.join("jdk17").join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    let java = bin.join("java");
+    std::fs::write(
+        &java,
+        "#!/bin/sh\necho 'openjdk version \"17.0.2\" 2022-01-18' >&2\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&java, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let project = project.canonicalize().unwrap();
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .current_dir(&project)
+        .args(["java", "add", "--name", "work", "--home", "./jdk17"])
+        .assert()
+        .success();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .current_dir(&project)
+        .args(["java", "add", "--name", "ghost", "--home", "./missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("path does not exist"))
+        .stderr(predicate::str::contains(
+            project.join("missing").to_str().unwrap(),
+        ));
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .current_dir(&project)
+        .args(["java", "scan-path", "add", "../shared"])
+        .assert()
+        .success();
+
+    let config: toml::Value = toml::from_str(
+        &std::fs::read_to_string(tmp.path().join(".fnva").join("config.toml")).unwrap(),
+    )
+    .unwrap();
+    let envs = config["java_environments"].as_array().unwrap();
+    assert_eq!(envs.len(), 1);
+    assert_eq!(
+        envs[0]["java_home"].as_str().unwrap(),
+        project.join("jdk17").to_str().unwrap()
+    );
+    assert_eq!(
+        config["custom_java_scan_paths"][0].as_str().unwrap(),
+        tmp.path()
+            .canonicalize()
+            .unwrap()
+            .join("shared")
+            .to_str()
+            .unwrap()
+    );
+}
+
+#[test]
+fn cc_switch_default_prints_script_or_nothing() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    let config = tmp.path().join(".fnva").join("config.toml");
+    let envs = r#"
+[[cc_environments]]
+name = "kimi"
+base_url = "https://api.moonshot.cn/anthropic"
+api_key = "sk-test"
+sonnet_model = "kimi-k2"
+"#;
+    std::fs::write(&config, envs).unwrap();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "switch-default", "--shell", "bash"])
+        .assert()
+        .success()
+        .stdout("");
+
+    std::fs::write(&config, format!("default_cc_env = \"kimi\"\n{envs}")).unwrap();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["cc", "switch-default", "--shell", "bash"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("export FNVA_CURRENT_CC=\"kimi\""))
+        .stdout(predicate::str::contains(
+            "export ANTHROPIC_BASE_URL=\"https://api.moonshot.cn/anthropic\"",
+        ));
+}
+
+#[test]
+fn java_use_record_only_writes_state_without_a_script() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva").join("config.toml"),
+        "[[java_environments]]\nname = \"jdk17\"\njava_home = \"/opt/jdk17\"\n",
+    )
+    .unwrap();
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "use", "jdk17", "--record-only", "--shell", "bash"])
+        .assert()
+        .success()
+        .stdout("");
+    let state = std::fs::read_to_string(
+        tmp.path()
+            .join(".fnva")
+            .join("state")
+            .join("current_envs.toml"),
+    )
+    .unwrap();
+    assert!(state.contains("java = \"jdk17\""), "{state}");
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "use", "ghost", "--record-only"])
+        .assert()
+        .failure()
+        .stdout("");
+}
+
+#[test]
+fn install_rejects_out_of_range_timeout_and_retries() {
+    let tmp = tempfile::TempDir::new().unwrap();
+
+    for (flag, value, message) in [
+        ("--timeout", "0", "--timeout must be greater than 0"),
+        ("--retries", "21", "--retries must be at most 20"),
+    ] {
+        fnva_cmd()
+            .env("FNVA_HOME", tmp.path())
+            .args(["--offline", "java", "install", "21", flag, value])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(message));
+    }
+    // 合法值通过校验,之后才因离线被拒
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args([
+            "--offline",
+            "java",
+            "install",
+            "21",
+            "--timeout",
+            "5",
+            "--retries",
+            "0",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("offline mode"));
+}
+
+#[test]
+fn export_shell_env_reports_drift_from_session() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let state = tmp.path().join(".fnva").join("state");
+    std::fs::create_dir_all(&state).unwrap();
+    std::fs::write(state.join("current_envs.toml"), "java = \"jdk17\"\n").unwrap();
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .env("FNVA_CURRENT_JAVA", "jdk11")
+        .env_remove("FNVA_CURRENT_MAVEN")
+        .env_remove("FNVA_CURRENT_CC")
+        .args(["export", "shell-env"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("FNVA_CURRENT_JAVA"))
+        .stdout(predicate::str::contains(
+            "drift: session says 'jdk17', shell has FNVA_CURRENT_JAVA='jdk11'",
+        ));
+}
+
+#[test]
+fn config_validate_reports_semantic_problems() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva").join("config.toml"),
+        "default_java_env = \"jdk21\"\n\
+         [[java_environments]]\nname = \"jdk17\"\njava_home = \"/opt/a\"\n\
+         [[java_environments]]\nname = \"jdk17\"\njava_home = \"/opt/b\"\n\
+         [on_switch_commands.node]\nv20 = \"echo 20\"\n",
+    )
+    .unwrap();
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["config", "validate"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("default_java_env = 'jdk21'"))
+        .stderr(predicate::str::contains("on_switch_commands.node"))
+        .stderr(predicate::str::contains(
+            "2 java environments are named 'jdk17'",
+        ))
+        .stderr(predicate::str::contains("3 problem(s) found"));
+}
+
+#[test]
+fn failing_command_with_json_prints_json_error_envelope() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let output = fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "info", "ghost", "--json"])
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let envelope: serde_json::Value = serde_json::from_str(stderr.trim())
+        .unwrap_or_else(|e| panic!("stderr is not JSON ({e}): {stderr}"));
+    let message = envelope["error"]["message"].as_str().unwrap();
+    assert!(message.contains("ghost"), "{envelope}");
+    assert_eq!(envelope["error"]["code"], "not_found", "{envelope}");
+
+    // 不带 --json 时仍是普通文本
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "info", "ghost"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Command failed"));
+}
+
+#[cfg(unix)]
+#[test]
+fn java_shims_run_the_active_jdk() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempfile::TempDir::new().unwrap();
+    let jdk = tmp.path().join("jdk17");
+    std::fs::create_dir_all(jdk.join("bin")).unwrap();
+    let java = jdk.join("bin").join("java");
+    std::fs::write(&java, "#!/bin/sh\necho \"fake java $*\"\n").unwrap();
+    std::fs::set_permissions(&java, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva").join("config.toml"),
+        format!(
+            "default_java_env = \"jdk17\"\n[[java_environments]]\nname = \"jdk17\"\njava_home = \"{}\"\n",
+            jdk.display()
+        ),
+    )
+    .unwrap();
+    let shims = tmp.path().join("shims");
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["java", "install-shims"])
+        .arg(&shims)
+        .assert()
+        .success();
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .env_remove("JAVA_HOME")
+        .args(["java", "which"])
+        .assert()
+        .success()
+        .stdout(format!("{}\n", java.display()));
+
+    let out = std::process::Command::new(shims.join("java"))
+        .args(["-version", "x y"])
+        .env("FNVA_HOME", tmp.path())
+        .env_remove("JAVA_HOME")
+        .output()
+        .unwrap();
+    assert!(out.status.success(), "{out:?}");
+    assert_eq!(
+        String::from_utf8_lossy(&out.stdout),
+        "fake java -version x y\n"
+    );
+
+    // 当前环境里没有 javac:包装脚本以 127 退出
+    let out = std::process::Command::new(shims.join("javac"))
+        .env("FNVA_HOME", tmp.path())
+        .env_remove("JAVA_HOME")
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(127));
+}
+
+#[test]
+fn cc_models_respects_offline_mode() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join(".fnva")).unwrap();
+    std::fs::write(
+        tmp.path().join(".fnva").join("config.toml"),
+        "[[cc_environments]]\nname = \"glm\"\napi_key = \"sk-test-0123456789\"\n\
+         base_url = \"https://open.bigmodel.cn/api/anthropic\"\nsonnet_model = \"glm-4.6\"\n",
+    )
+    .unwrap();
+
+    fnva_cmd()
+        .env("FNVA_HOME", tmp.path())
+        .args(["--offline", "cc", "models", "glm"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("offline"))
+        .stderr(predicate::str::contains("sk-test").not());
+}
+
+#[test]
+fn env_switch_works_with_or_without_all_defaults_flag() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    for args in [
+        &["env", "switch", "--shell", "bash"][..],
+        &["env", "switch", "--all-defaults", "--shell", "bash"][..],
+    ] {
+        fnva_cmd()
+            .env("FNVA_HOME", tmp.path())
+            .args(args)
+            .assert()
+            .success();
+    }
+}
+
+#[test]
+fn java_install_from_archive_rejects_path_like_names() {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let packages = tmp.path().join(".fnva").join("packages");
+    std::fs::create_dir_all(packages.join("java").join("keep")).unwrap();
+    let archive = tmp.path().join("jdk.zip");
+    std::fs::write(&archive, b"not a zip").unwrap();
+    for name in ["..", ".", "a/b", "../java", ""] {
+        fnva_cmd()
+            .env("FNVA_HOME", tmp.path())
+            .args(["java", "install", "--from-archive"])
+            .arg(&archive)
+            .args(["--name", name])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("name"));
+    }
+    assert!(packages.join("java").join("keep").is_dir());
+}